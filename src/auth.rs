@@ -0,0 +1,158 @@
+use axum::{
+    Json, async_trait,
+    extract::FromRequestParts,
+    http::{StatusCode, request::Parts},
+    response::{IntoResponse, Response},
+};
+use serde_json::json;
+
+/// Header carrying the authenticated user's id, set by the gateway in front of the API.
+pub const USER_ID_HEADER: &str = "x-user-id";
+/// Header carrying the authenticated user's role (`player`, `editor`, `reviewer`, `moderator`,
+/// `admin`).
+pub const USER_ROLE_HEADER: &str = "x-user-role";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    Player,
+    Editor,
+    /// Signs off on content (doctrinal wording) before it is published; does not edit it.
+    Reviewer,
+    /// Triages community submissions; has no access to content itself.
+    Moderator,
+    Admin,
+}
+
+impl Role {
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "player" => Some(Self::Player),
+            "editor" => Some(Self::Editor),
+            "reviewer" => Some(Self::Reviewer),
+            "moderator" => Some(Self::Moderator),
+            "admin" => Some(Self::Admin),
+            _ => None,
+        }
+    }
+
+    pub fn can_edit(self) -> bool {
+        matches!(self, Self::Editor | Self::Admin)
+    }
+
+    pub fn can_review(self) -> bool {
+        matches!(self, Self::Reviewer | Self::Admin)
+    }
+
+    pub fn can_moderate(self) -> bool {
+        matches!(self, Self::Moderator | Self::Admin)
+    }
+
+    /// Content staff may see unpublished content and its history.
+    pub fn is_staff(self) -> bool {
+        self.can_edit() || self.can_review()
+    }
+}
+
+/// Identity of the caller as asserted by the upstream gateway. Requests without headers are
+/// anonymous players.
+#[derive(Debug, Clone)]
+pub struct Caller {
+    pub user_id: Option<String>,
+    pub role: Role,
+}
+
+impl Caller {
+    pub fn require_editor(&self) -> Result<(), Forbidden> {
+        if self.role.can_edit() {
+            Ok(())
+        } else {
+            Err(Forbidden("editor role required"))
+        }
+    }
+
+    pub fn require_moderator(&self) -> Result<(), Forbidden> {
+        if self.role.can_moderate() {
+            Ok(())
+        } else {
+            Err(Forbidden("moderator role required"))
+        }
+    }
+
+    /// The signed-in user's id; anonymous callers are rejected.
+    pub fn require_user(&self) -> Result<&str, Forbidden> {
+        self.user_id.as_deref().ok_or(Forbidden("sign-in required"))
+    }
+
+    pub fn require_staff(&self) -> Result<(), Forbidden> {
+        if self.role.is_staff() {
+            Ok(())
+        } else {
+            Err(Forbidden("editor or reviewer role required"))
+        }
+    }
+
+    pub fn require_admin(&self) -> Result<(), Forbidden> {
+        if self.role == Role::Admin {
+            Ok(())
+        } else {
+            Err(Forbidden("admin role required"))
+        }
+    }
+}
+
+/// Rejection for callers whose role does not allow the operation.
+#[derive(Debug)]
+pub struct Forbidden(pub &'static str);
+
+impl IntoResponse for Forbidden {
+    fn into_response(self) -> Response {
+        (StatusCode::FORBIDDEN, Json(json!({ "error": self.0 }))).into_response()
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for Caller
+where
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let header = |name: &str| {
+            parts
+                .headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+        };
+
+        let user_id = header(USER_ID_HEADER).map(str::to_owned);
+        let role = match header(USER_ROLE_HEADER) {
+            Some(value) => Role::parse(value).ok_or_else(|| {
+                (StatusCode::BAD_REQUEST, Json(json!({ "error": "unknown role" }))).into_response()
+            })?,
+            None => Role::Player,
+        };
+
+        Ok(Self {
+            user_id,
+            role,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Role;
+
+    #[test]
+    fn parses_roles_case_insensitively() {
+        assert_eq!(Role::parse("Editor"), Some(Role::Editor));
+        assert_eq!(Role::parse(" admin "), Some(Role::Admin));
+        assert_eq!(Role::parse("pope"), None);
+        assert!(!Role::Player.can_edit());
+        assert!(Role::Reviewer.is_staff() && !Role::Reviewer.can_edit());
+        assert!(Role::Moderator.can_moderate() && !Role::Moderator.is_staff());
+    }
+}
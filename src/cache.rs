@@ -0,0 +1,304 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use axum::body::Bytes;
+use serde::Serialize;
+use tracing::debug;
+
+use crate::config::AppConfig;
+
+/// In-process cache of serialized JSON response bodies, keyed by endpoint and resolved locale
+/// (for example `eras:list:es`). Entries expire after a fixed TTL and the oldest entries are
+/// evicted once `max_entries` or `max_bytes` of bodies is reached; a body larger than `max_bytes`
+/// on its own is never cached. A TTL or size of zero disables caching entirely.
+#[derive(Clone)]
+pub struct ResponseCache {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    ttl: Duration,
+    max_entries: usize,
+    max_bytes: usize,
+    entries: Mutex<Entries>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    evictions: AtomicU64,
+    invalidations: AtomicU64,
+}
+
+/// The cached entries and the total size of their bodies.
+#[derive(Default)]
+struct Entries {
+    map: HashMap<String, Entry>,
+    bytes: usize,
+}
+
+impl Entries {
+    fn remove(&mut self, key: &str) -> Option<Entry> {
+        let entry = self.map.remove(key)?;
+        self.bytes -= entry.body.len();
+        Some(entry)
+    }
+
+    fn retain(&mut self, mut keep: impl FnMut(&str, &Entry) -> bool) {
+        let mut bytes = self.bytes;
+        self.map.retain(|key, entry| {
+            let kept = keep(key, entry);
+            if !kept {
+                bytes -= entry.body.len();
+            }
+            kept
+        });
+        self.bytes = bytes;
+    }
+}
+
+struct Entry {
+    body: Bytes,
+    stored_at: Instant,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CacheStats {
+    pub enabled: bool,
+    pub ttl_secs: u64,
+    pub max_entries: usize,
+    pub max_bytes: usize,
+    pub entries: usize,
+    pub bytes: usize,
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+    pub invalidations: u64,
+}
+
+impl ResponseCache {
+    pub fn new(ttl: Duration, max_entries: usize, max_bytes: usize) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                ttl,
+                max_entries,
+                max_bytes,
+                entries: Mutex::new(Entries::default()),
+                hits: AtomicU64::new(0),
+                misses: AtomicU64::new(0),
+                evictions: AtomicU64::new(0),
+                invalidations: AtomicU64::new(0),
+            }),
+        }
+    }
+
+    pub fn from_config(cfg: &AppConfig) -> Self {
+        Self::new(Duration::from_secs(cfg.cache_ttl_secs), cfg.cache_max_entries, cfg.cache_max_bytes)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.inner.ttl.is_zero() && self.inner.max_entries > 0 && self.inner.max_bytes > 0
+    }
+
+    pub fn get(&self, key: &str) -> Option<Bytes> {
+        if !self.is_enabled() {
+            return None;
+        }
+
+        let mut entries = self.lock();
+        let hit = match entries.map.get(key) {
+            Some(entry) if entry.stored_at.elapsed() < self.inner.ttl => Some(entry.body.clone()),
+            Some(_) => {
+                entries.remove(key);
+                None
+            }
+            None => None,
+        };
+        drop(entries);
+
+        if hit.is_some() {
+            self.inner.hits.fetch_add(1, Ordering::Relaxed);
+            debug!(key, "response cache hit");
+        } else {
+            self.inner.misses.fetch_add(1, Ordering::Relaxed);
+            debug!(key, "response cache miss");
+        }
+        hit
+    }
+
+    pub fn insert(&self, key: String, body: Bytes) {
+        if !self.is_enabled() {
+            return;
+        }
+        if body.len() > self.inner.max_bytes {
+            debug!(key, bytes = body.len(), "response too large to cache");
+            return;
+        }
+
+        let mut entries = self.lock();
+        entries.remove(&key);
+        let fits = |entries: &Entries| {
+            entries.map.len() < self.inner.max_entries
+                && entries.bytes + body.len() <= self.inner.max_bytes
+        };
+        if !fits(&entries) {
+            let ttl = self.inner.ttl;
+            let before = entries.map.len();
+            entries.retain(|_, entry| entry.stored_at.elapsed() < ttl);
+            let mut evicted = (before - entries.map.len()) as u64;
+
+            while !fits(&entries) {
+                let Some(oldest) =
+                    entries.map.iter().min_by_key(|(_, entry)| entry.stored_at).map(|(k, _)| k.clone())
+                else {
+                    break;
+                };
+                entries.remove(&oldest);
+                evicted += 1;
+            }
+            self.inner.evictions.fetch_add(evicted, Ordering::Relaxed);
+        }
+
+        entries.bytes += body.len();
+        entries.map.insert(
+            key,
+            Entry {
+                body,
+                stored_at: Instant::now(),
+            },
+        );
+    }
+
+    /// Drops every entry whose key starts with `prefix`. An empty prefix clears the cache.
+    pub fn invalidate_prefix(&self, prefix: &str) -> usize {
+        let mut entries = self.lock();
+        let before = entries.map.len();
+        entries.retain(|key, _| !key.starts_with(prefix));
+        let removed = before - entries.map.len();
+        drop(entries);
+
+        self.inner.invalidations.fetch_add(1, Ordering::Relaxed);
+        debug!(prefix, removed, "response cache invalidated");
+        removed
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        let entries = self.lock();
+        let (count, bytes) = (entries.map.len(), entries.bytes);
+        drop(entries);
+        CacheStats {
+            enabled: self.is_enabled(),
+            ttl_secs: self.inner.ttl.as_secs(),
+            max_entries: self.inner.max_entries,
+            max_bytes: self.inner.max_bytes,
+            entries: count,
+            bytes,
+            hits: self.inner.hits.load(Ordering::Relaxed),
+            misses: self.inner.misses.load(Ordering::Relaxed),
+            evictions: self.inner.evictions.load(Ordering::Relaxed),
+            invalidations: self.inner.invalidations.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Serializes `value` and stores it under `key`, returning the encoded body.
+    pub fn store<T: Serialize>(&self, key: String, value: &T) -> serde_json::Result<Bytes> {
+        let body = Bytes::from(serde_json::to_vec(value)?);
+        self.insert(key, body.clone());
+        Ok(body)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Entries> {
+        self.inner.entries.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use axum::body::Bytes;
+
+    use super::ResponseCache;
+
+    #[test]
+    fn counts_hits_and_misses() {
+        let cache = ResponseCache::new(Duration::from_secs(60), 8, 1024);
+        assert!(cache.get("eras:list:en").is_none());
+        cache.insert("eras:list:en".into(), Bytes::from_static(b"[]"));
+        assert_eq!(cache.get("eras:list:en").as_deref(), Some(&b"[]"[..]));
+
+        let stats = cache.stats();
+        assert_eq!(stats.hits, 1);
+        assert_eq!(stats.misses, 1);
+        assert_eq!(stats.entries, 1);
+    }
+
+    #[test]
+    fn evicts_oldest_entry_when_full() {
+        let cache = ResponseCache::new(Duration::from_secs(60), 2, 1024);
+        cache.insert("a".into(), Bytes::from_static(b"1"));
+        cache.insert("b".into(), Bytes::from_static(b"2"));
+        cache.insert("c".into(), Bytes::from_static(b"3"));
+
+        assert!(cache.get("a").is_none());
+        assert!(cache.get("b").is_some());
+        assert!(cache.get("c").is_some());
+        assert_eq!(cache.stats().evictions, 1);
+    }
+
+    #[test]
+    fn expired_entries_are_misses() {
+        let cache = ResponseCache::new(Duration::from_millis(1), 8, 1024);
+        cache.insert("ui:levels".into(), Bytes::from_static(b"{}"));
+        std::thread::sleep(Duration::from_millis(5));
+        assert!(cache.get("ui:levels").is_none());
+    }
+
+    #[test]
+    fn invalidates_by_prefix() {
+        let cache = ResponseCache::new(Duration::from_secs(60), 8, 1024);
+        cache.insert("eras:list:en".into(), Bytes::from_static(b"[]"));
+        cache.insert("eras:era:creation:en".into(), Bytes::from_static(b"{}"));
+        cache.insert("ui:levels".into(), Bytes::from_static(b"{}"));
+
+        assert_eq!(cache.invalidate_prefix("eras:"), 2);
+        assert!(cache.get("ui:levels").is_some());
+        assert_eq!(cache.stats().entries, 1);
+    }
+
+    #[test]
+    fn zero_ttl_disables_cache() {
+        let cache = ResponseCache::new(Duration::ZERO, 8, 1024);
+        cache.insert("a".into(), Bytes::from_static(b"1"));
+        assert!(cache.get("a").is_none());
+        assert_eq!(cache.stats().misses, 0);
+    }
+
+    #[test]
+    fn evicts_oldest_entries_over_byte_budget() {
+        let cache = ResponseCache::new(Duration::from_secs(60), 8, 10);
+        cache.insert("a".into(), Bytes::from_static(b"1234"));
+        cache.insert("b".into(), Bytes::from_static(b"5678"));
+        cache.insert("c".into(), Bytes::from_static(b"9012"));
+
+        assert!(cache.get("a").is_none());
+        assert!(cache.get("b").is_some());
+        assert!(cache.get("c").is_some());
+        assert_eq!(cache.stats().bytes, 8);
+        assert_eq!(cache.stats().evictions, 1);
+    }
+
+    #[test]
+    fn skips_bodies_larger_than_the_budget() {
+        let cache = ResponseCache::new(Duration::from_secs(60), 8, 4);
+        cache.insert("a".into(), Bytes::from_static(b"1"));
+        cache.insert("big".into(), Bytes::from_static(b"12345"));
+
+        assert!(cache.get("big").is_none());
+        assert!(cache.get("a").is_some());
+        assert_eq!(cache.stats().evictions, 0);
+    }
+}
@@ -0,0 +1,268 @@
+use axum::{
+    Json,
+    body::Bytes,
+    http::{HeaderMap, HeaderValue, StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Utc};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+
+use crate::auth::{USER_ID_HEADER, USER_ROLE_HEADER};
+
+/// Cache validators attached to a `200 OK` JSON response.
+#[derive(Debug, Default)]
+pub struct Validators {
+    pub etag: String,
+    pub last_modified: Option<DateTime<Utc>>,
+    pub vary_accept_language: bool,
+    pub vary_user: bool,
+    pub vary_role: bool,
+    pub private: bool,
+}
+
+impl Validators {
+    /// Strong validator computed from the response body and the language it was rendered in, so
+    /// the same payload served for two languages never shares an ETag.
+    pub fn for_body(body: &[u8], lang: Option<&str>) -> Self {
+        Self {
+            etag: strong_etag(&[lang.unwrap_or_default().as_bytes(), body]),
+            ..Self::default()
+        }
+    }
+
+    pub fn with_etag(etag: String) -> Self {
+        Self {
+            etag,
+            ..Self::default()
+        }
+    }
+
+    pub fn last_modified(mut self, value: Option<DateTime<Utc>>) -> Self {
+        self.last_modified = value;
+        self
+    }
+
+    pub fn vary_accept_language(mut self, vary: bool) -> Self {
+        self.vary_accept_language = vary;
+        self
+    }
+
+    /// For endpoints whose body depends on `X-User-Id`: always sends `Vary: x-user-id`, and marks
+    /// the caller's own (personalized) body `Cache-Control: private` so shared caches skip it.
+    pub fn per_user(mut self, personalized: bool) -> Self {
+        self.vary_user = true;
+        self.private = personalized;
+        self
+    }
+
+    /// For endpoints that give staff a fuller body than other callers: always sends
+    /// `Vary: x-user-role`, and marks the staff body `Cache-Control: private`.
+    pub fn per_role(mut self, staff: bool) -> Self {
+        self.vary_role = true;
+        self.private = self.private || staff;
+        self
+    }
+}
+
+pub fn strong_etag(parts: &[&[u8]]) -> String {
+    format!("\"{}\"", content_hash(parts))
+}
+
+/// Hex digest (128 bits of SHA-256) over length-prefixed parts.
+pub fn content_hash(parts: &[&[u8]]) -> String {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update((part.len() as u64).to_be_bytes());
+        hasher.update(part);
+    }
+    hex::encode(&hasher.finalize()[..16])
+}
+
+/// Returns `304 Not Modified` when the request's `If-None-Match` (or, failing that,
+/// `If-Modified-Since`) matches the validators; otherwise a `200 OK` JSON response carrying them.
+pub fn respond(request: &HeaderMap, body: Bytes, validators: Validators) -> Response {
+    let mut response = if is_not_modified(request, &validators) {
+        StatusCode::NOT_MODIFIED.into_response()
+    } else {
+        (StatusCode::OK, [(header::CONTENT_TYPE, "application/json")], body).into_response()
+    };
+    apply_validators(response.headers_mut(), &validators);
+    response
+}
+
+pub fn is_not_modified(request: &HeaderMap, validators: &Validators) -> bool {
+    match request.get(header::IF_NONE_MATCH) {
+        Some(value) => etag_list_matches(value, &validators.etag, true),
+        None => match (request.get(header::IF_MODIFIED_SINCE), validators.last_modified) {
+            (Some(value), Some(last_modified)) => parse_http_date(value)
+                .is_some_and(|since| last_modified.timestamp() <= since.timestamp()),
+            _ => false,
+        },
+    }
+}
+
+pub fn apply_validators(headers: &mut HeaderMap, validators: &Validators) {
+    if let Ok(value) = HeaderValue::from_str(&validators.etag) {
+        headers.insert(header::ETAG, value);
+    }
+    if let Some(value) =
+        validators.last_modified.and_then(|dt| HeaderValue::from_str(&format_http_date(dt)).ok())
+    {
+        headers.insert(header::LAST_MODIFIED, value);
+    }
+    if validators.vary_accept_language {
+        headers.append(header::VARY, HeaderValue::from_static("Accept-Language"));
+    }
+    if validators.vary_user {
+        headers.append(header::VARY, HeaderValue::from_static(USER_ID_HEADER));
+    }
+    if validators.vary_role {
+        headers.append(header::VARY, HeaderValue::from_static(USER_ROLE_HEADER));
+    }
+    if validators.private {
+        headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("private"));
+    }
+}
+
+/// `If-Match` check for write endpoints: an absent header always passes, `*` passes for any
+/// existing representation, otherwise one of the listed strong ETags must equal `current`.
+pub fn if_match_allows(request: &HeaderMap, current: &str) -> bool {
+    match request.get(header::IF_MATCH) {
+        Some(value) => etag_list_matches(value, current, false),
+        None => true,
+    }
+}
+
+pub fn precondition_failed() -> Response {
+    (
+        StatusCode::PRECONDITION_FAILED,
+        Json(json!({ "error": "resource was modified; refetch and retry with the current ETag" })),
+    )
+        .into_response()
+}
+
+fn etag_list_matches(value: &HeaderValue, current: &str, weak: bool) -> bool {
+    let Ok(value) = value.to_str() else {
+        return false;
+    };
+    value.split(',').map(str::trim).any(|candidate| {
+        if candidate == "*" {
+            return true;
+        }
+        match candidate.strip_prefix("W/") {
+            Some(tag) => weak && tag == current,
+            None => candidate == current,
+        }
+    })
+}
+
+fn format_http_date(value: DateTime<Utc>) -> String {
+    value.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+}
+
+fn parse_http_date(value: &HeaderValue) -> Option<DateTime<Utc>> {
+    let value = value.to_str().ok()?;
+    DateTime::parse_from_rfc2822(value).ok().map(|dt| dt.with_timezone(&Utc))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::Bytes,
+        http::{HeaderMap, HeaderValue, StatusCode, header},
+    };
+    use chrono::{TimeZone, Utc};
+
+    use super::{Validators, if_match_allows, respond};
+
+    #[test]
+    fn etag_depends_on_language() {
+        let en = Validators::for_body(b"[]", Some("en"));
+        let es = Validators::for_body(b"[]", Some("es"));
+        assert_ne!(en.etag, es.etag);
+        assert_eq!(en.etag, Validators::for_body(b"[]", Some("en")).etag);
+    }
+
+    #[test]
+    fn if_none_match_returns_not_modified() {
+        let validators = Validators::for_body(b"{}", None);
+        let mut request = HeaderMap::new();
+        request.insert(header::IF_NONE_MATCH, HeaderValue::from_str(&validators.etag).unwrap());
+
+        let response = respond(&request, Bytes::from_static(b"{}"), validators);
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert!(response.headers().contains_key(header::ETAG));
+    }
+
+    #[test]
+    fn if_modified_since_compares_seconds() {
+        let updated = Utc.with_ymd_and_hms(2025, 3, 2, 10, 0, 0).unwrap();
+        let mut request = HeaderMap::new();
+        request.insert(
+            header::IF_MODIFIED_SINCE,
+            HeaderValue::from_static("Sun, 02 Mar 2025 10:00:00 GMT"),
+        );
+
+        let fresh = respond(
+            &request,
+            Bytes::from_static(b"{}"),
+            Validators::for_body(b"{}", None).last_modified(Some(updated)),
+        );
+        assert_eq!(fresh.status(), StatusCode::NOT_MODIFIED);
+
+        let stale = respond(
+            &request,
+            Bytes::from_static(b"{}"),
+            Validators::for_body(b"{}", None)
+                .last_modified(Some(updated + chrono::Duration::seconds(5))),
+        );
+        assert_eq!(stale.status(), StatusCode::OK);
+        assert_eq!(
+            stale.headers().get(header::LAST_MODIFIED).and_then(|v| v.to_str().ok()),
+            Some("Sun, 02 Mar 2025 10:00:05 GMT")
+        );
+    }
+
+    #[test]
+    fn personalized_bodies_are_private() {
+        let request = HeaderMap::new();
+        let mine = respond(
+            &request,
+            Bytes::from_static(b"{}"),
+            Validators::for_body(b"{}", None).per_user(true),
+        );
+        assert_eq!(
+            mine.headers().get(header::CACHE_CONTROL).and_then(|v| v.to_str().ok()),
+            Some("private")
+        );
+        assert_eq!(mine.headers().get(header::VARY).and_then(|v| v.to_str().ok()), Some("x-user-id"));
+
+        let anonymous = respond(
+            &request,
+            Bytes::from_static(b"{}"),
+            Validators::for_body(b"{}", None).per_user(false),
+        );
+        assert!(!anonymous.headers().contains_key(header::CACHE_CONTROL));
+        assert_eq!(
+            anonymous.headers().get(header::VARY).and_then(|v| v.to_str().ok()),
+            Some("x-user-id")
+        );
+    }
+
+    #[test]
+    fn if_match_rejects_stale_and_weak_tags() {
+        let current = "\"abc\"";
+        let mut request = HeaderMap::new();
+        assert!(if_match_allows(&request, current));
+
+        request.insert(header::IF_MATCH, HeaderValue::from_static("\"old\", \"abc\""));
+        assert!(if_match_allows(&request, current));
+
+        request.insert(header::IF_MATCH, HeaderValue::from_static("W/\"abc\""));
+        assert!(!if_match_allows(&request, current));
+
+        request.insert(header::IF_MATCH, HeaderValue::from_static("*"));
+        assert!(if_match_allows(&request, current));
+    }
+}
@@ -1,10 +1,31 @@
 use std::env;
 
+use chrono_tz::Tz;
+
+use crate::resources::play::scoring::ScoringConfig;
+
+#[derive(Clone)]
 pub struct AppConfig {
     host: String,
     port: u16,
     pub mongo_uri: String,
     pub mongo_db: String,
+    pub cache_ttl_secs: u64,
+    pub cache_max_entries: usize,
+    pub cache_max_bytes: usize,
+    pub trash_retention_days: i64,
+    pub trash_purge_interval_secs: u64,
+    pub tombstone_retention_days: i64,
+    pub report_flag_threshold: u64,
+    pub challenge_timezone: Tz,
+    pub challenge_size: usize,
+    pub challenge_repeat_window_days: i64,
+    pub calibration_interval_secs: u64,
+    pub calibration_min_answers: i64,
+    pub quiz_scoring: ScoringConfig,
+    pub streak_daily_goal: i64,
+    pub streak_max_freezes: i64,
+    pub streak_freeze_every_days: i64,
 }
 
 impl AppConfig {
@@ -16,11 +37,64 @@ impl AppConfig {
             env::var("MONGO_URI").unwrap_or_else(|_| "mongodb://localhost:27017".to_string());
         let mongo_db = env::var("MONGO_DB").unwrap_or_else(|_| "verbumdei".to_string());
 
+        let cache_ttl_secs = env::var("CACHE_TTL_SECS").ok().and_then(|s| s.parse().ok()).unwrap_or(300);
+        let cache_max_entries =
+            env::var("CACHE_MAX_ENTRIES").ok().and_then(|s| s.parse().ok()).unwrap_or(512);
+        let cache_max_bytes =
+            env::var("CACHE_MAX_BYTES").ok().and_then(|s| s.parse().ok()).unwrap_or(64 * 1024 * 1024);
+
+        let trash_retention_days =
+            env::var("TRASH_RETENTION_DAYS").ok().and_then(|s| s.parse().ok()).unwrap_or(30);
+        let trash_purge_interval_secs =
+            env::var("TRASH_PURGE_INTERVAL_SECS").ok().and_then(|s| s.parse().ok()).unwrap_or(3600);
+
+        let tombstone_retention_days =
+            env::var("TOMBSTONE_RETENTION_DAYS").ok().and_then(|s| s.parse().ok()).unwrap_or(90);
+
+        let report_flag_threshold =
+            env::var("REPORT_FLAG_THRESHOLD").ok().and_then(|s| s.parse().ok()).unwrap_or(5);
+
+        let challenge_timezone =
+            env::var("CHALLENGE_TIMEZONE").ok().and_then(|s| s.parse().ok()).unwrap_or(Tz::UTC);
+        let challenge_size = env::var("CHALLENGE_SIZE").ok().and_then(|s| s.parse().ok()).unwrap_or(5);
+        let challenge_repeat_window_days =
+            env::var("CHALLENGE_REPEAT_WINDOW_DAYS").ok().and_then(|s| s.parse().ok()).unwrap_or(60);
+
+        let calibration_interval_secs =
+            env::var("CALIBRATION_INTERVAL_SECS").ok().and_then(|s| s.parse().ok()).unwrap_or(3600);
+        let calibration_min_answers =
+            env::var("CALIBRATION_MIN_ANSWERS").ok().and_then(|s| s.parse().ok()).unwrap_or(30);
+
+        let quiz_scoring = ScoringConfig::from_env();
+
+        let streak_daily_goal =
+            env::var("STREAK_DAILY_GOAL").ok().and_then(|s| s.parse().ok()).unwrap_or(5);
+        let streak_max_freezes =
+            env::var("STREAK_MAX_FREEZES").ok().and_then(|s| s.parse().ok()).unwrap_or(2);
+        let streak_freeze_every_days =
+            env::var("STREAK_FREEZE_EVERY_DAYS").ok().and_then(|s| s.parse().ok()).unwrap_or(7);
+
         Self {
             host,
             port,
             mongo_uri,
             mongo_db,
+            cache_ttl_secs,
+            cache_max_entries,
+            cache_max_bytes,
+            trash_retention_days,
+            trash_purge_interval_secs,
+            tombstone_retention_days,
+            report_flag_threshold,
+            challenge_timezone,
+            challenge_size,
+            challenge_repeat_window_days,
+            calibration_interval_secs,
+            calibration_min_answers,
+            quiz_scoring,
+            streak_daily_goal,
+            streak_max_freezes,
+            streak_freeze_every_days,
         }
     }
 
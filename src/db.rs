@@ -1,6 +1,21 @@
-use mongodb::{Client, Database, bson::doc, options::ClientOptions};
+use mongodb::{
+    Client, Database,
+    bson::{DateTime, Document, doc},
+    error::{ErrorKind, WriteFailure},
+    options::ClientOptions,
+};
 
-use crate::config::AppConfig;
+use crate::{
+    config::AppConfig,
+    resources::{
+        bookmarks::queries as bookmark_queries, challenges::queries as challenge_queries,
+        groups::queries as group_queries, leaderboards::queries as leaderboard_queries,
+        play::queries as play_queries, repetition::queries as repetition_queries,
+        revisions::queries as revision_queries, rooms::queries as room_queries,
+        stats::queries as stats_queries, streaks::queries as streak_queries,
+        sync::queries as sync_queries,
+    },
+};
 
 pub async fn init_mongo(cfg: &AppConfig) -> mongodb::error::Result<Database> {
     let mut client_options = ClientOptions::parse(&cfg.mongo_uri).await?;
@@ -12,5 +27,31 @@ pub async fn init_mongo(cfg: &AppConfig) -> mongodb::error::Result<Database> {
     db.run_command(doc! { "ping": 1 }, None).await?;
     tracing::info!("Connected to MongoDB at {} (db: {})", cfg.mongo_uri, cfg.mongo_db);
 
+    revision_queries::ensure_indexes(&db).await?;
+    leaderboard_queries::ensure_indexes(&db).await?;
+    stats_queries::ensure_indexes(&db).await?;
+    room_queries::ensure_indexes(&db).await?;
+    group_queries::ensure_indexes(&db).await?;
+    streak_queries::ensure_indexes(&db).await?;
+    bookmark_queries::ensure_indexes(&db).await?;
+    repetition_queries::ensure_indexes(&db).await?;
+    challenge_queries::ensure_indexes(&db).await?;
+    play_queries::ensure_indexes(&db).await?;
+    sync_queries::ensure_indexes(&db).await?;
+
     Ok(db)
 }
+
+/// Whether a write failed on a unique index.
+pub fn is_duplicate_key(err: &mongodb::error::Error) -> bool {
+    matches!(err.kind.as_ref(), ErrorKind::Write(WriteFailure::WriteError(write)) if write.code == 11000)
+}
+
+/// `updated_at` for a write replacing `previous`: now, but always past the stored value, so a
+/// write guarded on the old timestamp can never match the new document.
+pub fn next_updated_at(previous: Option<&Document>) -> DateTime {
+    let now = DateTime::now().timestamp_millis();
+    let after =
+        previous.and_then(|doc| doc.get_datetime("updated_at").ok()).map(|at| at.timestamp_millis() + 1);
+    DateTime::from_millis(after.map_or(now, |after| now.max(after)))
+}
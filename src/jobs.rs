@@ -0,0 +1,198 @@
+use std::{collections::HashMap, time::Duration};
+
+use mongodb::{
+    Database,
+    bson::{DateTime, oid::ObjectId},
+};
+use tracing::{error, info};
+
+use crate::{
+    config::AppConfig,
+    resources::{
+        questions::{model::QuestionDto, queries as question_queries},
+        stats::{
+            calibration::{Rating, update},
+            model::PlayerRating,
+            queries as stats_queries,
+        },
+        sync::queries as sync_queries,
+    },
+};
+
+/// Key of the calibration job's progress in `job_state`.
+const CALIBRATION_JOB: &str = "difficulty_calibration";
+/// Answers replayed per round trip.
+const CALIBRATION_BATCH: i64 = 5000;
+
+/// Starts the background jobs that keep stored content tidy and difficulty calibrated.
+pub fn spawn_all(db: Database, cfg: &AppConfig) {
+    let retention_days = cfg.trash_retention_days;
+    let tombstone_retention_days = cfg.tombstone_retention_days;
+    let interval = Duration::from_secs(cfg.trash_purge_interval_secs.max(60));
+    let trash_db = db.clone();
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            match purge_trash(&trash_db, retention_days).await {
+                Ok(0) => {}
+                Ok(purged) => info!(purged, "purged expired questions from trash"),
+                Err(err) => error!(error = ?err, "trash purge failed"),
+            }
+            match purge_tombstones(&trash_db, tombstone_retention_days).await {
+                Ok(0) => {}
+                Ok(purged) => info!(purged, "purged expired sync tombstones"),
+                Err(err) => error!(error = ?err, "tombstone purge failed"),
+            }
+        }
+    });
+
+    let interval = Duration::from_secs(cfg.calibration_interval_secs.max(60));
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            match calibrate_difficulty(&db).await {
+                Ok(0) => {}
+                Ok(answers) => info!(answers, "calibrated question difficulty"),
+                Err(err) => error!(error = ?err, "difficulty calibration failed"),
+            }
+        }
+    });
+}
+
+/// Permanently deletes questions that have been in the trash longer than `retention_days`.
+pub async fn purge_trash(db: &Database, retention_days: i64) -> mongodb::error::Result<u64> {
+    let cutoff = chrono::Utc::now() - chrono::Duration::days(retention_days.max(0));
+    question_queries::purge_deleted_before(db, DateTime::from_chrono(cutoff)).await
+}
+
+/// Deletes sync tombstones older than `retention_days`; clients syncing from before that get a
+/// full snapshot instead.
+pub async fn purge_tombstones(db: &Database, retention_days: i64) -> mongodb::error::Result<u64> {
+    let cutoff = chrono::Utc::now() - chrono::Duration::days(retention_days.max(0));
+    sync_queries::purge_tombstones_before(db, DateTime::from_chrono(cutoff)).await
+}
+
+/// Replays answers by signed-in players logged since the last run through the Elo model, updating
+/// player ratings and question difficulty, and returns how many were replayed. Progress is saved
+/// after every batch, and each rating records the last answer it counts, so a batch replayed after
+/// a crash mid-save counts each answer once. Assumes a single running instance.
+pub async fn calibrate_difficulty(db: &Database) -> mongodb::error::Result<usize> {
+    let mut after = stats_queries::find_job_state(db, CALIBRATION_JOB).await?.map(|state| state.last_id);
+    let mut replayed = 0;
+    loop {
+        let events = stats_queries::events_after(db, after, CALIBRATION_BATCH).await?;
+        let Some(last_id) = events.last().map(|event| event.id) else {
+            break;
+        };
+
+        let mut user_ids: Vec<String> = events.iter().filter_map(|e| e.user_id.clone()).collect();
+        user_ids.sort_unstable();
+        user_ids.dedup();
+        let mut question_ids: Vec<String> = events.iter().map(|e| e.question_id.clone()).collect();
+        question_ids.sort_unstable();
+        question_ids.dedup();
+
+        let mut players: HashMap<String, Calibrated> = stats_queries::find_player_ratings(db, &user_ids)
+            .await?
+            .into_iter()
+            .map(|p| {
+                let rating = Rating {
+                    value: p.rating,
+                    answers: p.answers,
+                };
+                (p.user_id, Calibrated::new(rating, p.calibrated_through))
+            })
+            .collect();
+        // Questions deleted since they were answered drop out here.
+        let mut questions: HashMap<String, Calibrated> =
+            question_queries::find_questions_by_ids(db, &question_ids)
+                .await?
+                .into_iter()
+                .map(|q| {
+                    let through = q.difficulty_through;
+                    let q = QuestionDto::from(q);
+                    let mut rating = Rating::default();
+                    if let (Some(value), Some(answers)) = (q.difficulty, q.difficulty_answers) {
+                        rating = Rating {
+                            value,
+                            answers,
+                        };
+                    }
+                    (q.id, Calibrated::new(rating, through))
+                })
+                .collect();
+
+        for event in &events {
+            let (Some(user_id), Some(question)) =
+                (&event.user_id, questions.get_mut(&event.question_id))
+            else {
+                continue;
+            };
+            let player = players
+                .entry(user_id.clone())
+                .or_insert_with(|| Calibrated::new(Rating::default(), None));
+            // A side stored after this answer already counts it; only the other side moves.
+            let (mut player_rating, mut question_rating) = (player.rating, question.rating);
+            update(&mut player_rating, &mut question_rating, event.correct);
+            player.apply(event.id, player_rating);
+            question.apply(event.id, question_rating);
+        }
+
+        let now = DateTime::now();
+        for (user_id, player) in players {
+            if !player.changed {
+                continue;
+            }
+            let player = PlayerRating {
+                user_id,
+                rating: player.rating.value,
+                answers: player.rating.answers,
+                calibrated_through: Some(last_id),
+                updated_at: now,
+            };
+            stats_queries::save_player_rating(db, &player).await?;
+        }
+        for (id, question) in &questions {
+            if question.changed {
+                let rating = question.rating;
+                question_queries::set_difficulty(db, id, rating.value, rating.answers, last_id).await?;
+            }
+        }
+        stats_queries::save_job_state(db, CALIBRATION_JOB, last_id).await?;
+
+        replayed += events.len();
+        if (events.len() as i64) < CALIBRATION_BATCH {
+            break;
+        }
+        after = Some(last_id);
+    }
+    Ok(replayed)
+}
+
+/// A rating being replayed, with the last answer it already counted when loaded.
+struct Calibrated {
+    rating: Rating,
+    through: Option<ObjectId>,
+    changed: bool,
+}
+
+impl Calibrated {
+    fn new(rating: Rating, through: Option<ObjectId>) -> Self {
+        Self {
+            rating,
+            through,
+            changed: false,
+        }
+    }
+
+    /// Takes `rating` as the result of answer `id`, unless the stored rating already counts it.
+    fn apply(&mut self, id: ObjectId, rating: Rating) {
+        if self.through.is_some_and(|through| through >= id) {
+            return;
+        }
+        self.rating = rating;
+        self.changed = true;
+    }
+}
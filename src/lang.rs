@@ -0,0 +1,56 @@
+pub fn resolve_lang(
+    query_lang: Option<&str>,
+    accept_language: Option<&axum::http::HeaderValue>,
+) -> String {
+    if let Some(lang) = query_lang.and_then(normalize_lang) {
+        return lang.to_string();
+    }
+
+    if let Some(lang) =
+        accept_language.and_then(|header| header.to_str().ok()).and_then(resolve_from_accept_language)
+    {
+        return lang.to_string();
+    }
+
+    "en".to_string()
+}
+
+/// Responses vary by `Accept-Language` unless an explicit, supported `lang` query wins.
+pub fn varies_by_header(query_lang: Option<&str>) -> bool {
+    query_lang.and_then(normalize_lang).is_none()
+}
+
+fn resolve_from_accept_language(value: &str) -> Option<&str> {
+    value
+        .split(',')
+        .filter_map(|part| {
+            let base = part.trim().split(';').next()?.trim();
+            normalize_lang(base)
+        })
+        .find(|lang| is_supported_lang(lang))
+}
+
+pub fn normalize_lang(value: &str) -> Option<&str> {
+    let normalized = value.trim().split('-').next()?.to_ascii_lowercase();
+    match normalized.as_str() {
+        "en" => Some("en"),
+        "es" => Some("es"),
+        "pt" => Some("pt"),
+        "sv" => Some("sv"),
+        _ => None,
+    }
+}
+
+pub fn is_supported_lang(value: &str) -> bool {
+    matches!(value, "en" | "es" | "pt" | "sv")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::resolve_from_accept_language;
+
+    #[test]
+    fn picks_first_supported_accept_language() {
+        assert_eq!(resolve_from_accept_language("fr-FR, sv-SE;q=0.9, en;q=0.8"), Some("sv"));
+    }
+}
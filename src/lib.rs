@@ -1,4 +1,9 @@
+pub mod auth;
+pub mod cache;
+pub mod conditional;
 pub mod config;
 pub mod db;
+pub mod jobs;
+pub mod lang;
 pub mod resources;
 pub mod routes;
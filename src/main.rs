@@ -1,5 +1,10 @@
+mod auth;
+mod cache;
+mod conditional;
 mod config;
 mod db;
+mod jobs;
+mod lang;
 mod resources;
 mod routes;
 use tracing_subscriber::EnvFilter;
@@ -14,9 +19,9 @@ async fn main() {
 
     tracing::info!("Starting server on {}", cfg.address());
 
-    let state = routes::api::ApiState {
-        db,
-    };
+    jobs::spawn_all(db.clone(), &cfg);
+
+    let state = routes::api::ApiState::new(db, &cfg);
     let app = routes::api::router(state);
 
     let listener = tokio::net::TcpListener::bind(cfg.address()).await.expect("failed to bind address");
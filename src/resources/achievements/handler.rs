@@ -0,0 +1,267 @@
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BTreeSet},
+};
+
+use axum::routing::{MethodRouter, get as axum_get, post as axum_post};
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+use mongodb::bson::DateTime;
+use serde_json::json;
+use tracing::error;
+
+use crate::{
+    auth::Caller,
+    lang::resolve_lang,
+    resources::{
+        achievements::{
+            model::{
+                AchievementsDto, AchievementsQuery, Badge, BadgeDto, EarnedBadge, EpisodeReadDto,
+                PlayerAchievements, Rule, badges, episode_key, is_met, perfect_eras, rule_progress,
+            },
+            queries,
+        },
+        eras::queries::{self as era_queries, EpisodeLookup, episode_ids},
+        streaks::{handler as streak_handler, model::EPISODE_READ},
+        workflow::model::{ContentStatus, Visibility},
+    },
+    routes::api::ApiState,
+};
+
+pub fn mine() -> MethodRouter<ApiState> {
+    axum_get(my_achievements)
+}
+
+pub fn episode() -> MethodRouter<ApiState> {
+    axum_post(read_episode)
+}
+
+/// The caller's badges in `lang`: earned ones with when they were earned, and locked ones with
+/// progress towards them. Badges added to the catalog since the caller's last activity are
+/// awarded here if already deserved.
+pub async fn my_achievements(
+    State(state): State<ApiState>,
+    Query(params): Query<AchievementsQuery>,
+    caller: Caller,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let user_id = match caller.require_user() {
+        Ok(user_id) => user_id,
+        Err(forbidden) => return forbidden.into_response(),
+    };
+    let mut player = match queries::find_player(&state.db, user_id).await {
+        Ok(player) => player.unwrap_or_else(|| PlayerAchievements {
+            user_id: user_id.to_string(),
+            ..Default::default()
+        }),
+        Err(err) => {
+            error!(error = ?err, "failed to load achievements");
+            return error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to load achievements");
+        }
+    };
+    let catalog = badges();
+    let era_episodes = match era_episodes(&state, &catalog, &[]).await {
+        Ok(era_episodes) => era_episodes,
+        Err(err) => {
+            error!(error = ?err, "failed to load era episodes");
+            return error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to load achievements");
+        }
+    };
+    match award_new(&state, &catalog, &player, &era_episodes).await {
+        Ok(awarded) => player.earned.extend(awarded.into_iter().map(|(_, earned)| earned)),
+        Err(err) => {
+            error!(error = ?err, "failed to award badges");
+            return error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to load achievements");
+        }
+    }
+
+    let lang = resolve_lang(params.lang.as_deref(), headers.get("accept-language"));
+    let (mut earned, mut locked) = (Vec::new(), Vec::new());
+    for badge in &catalog {
+        let (current, target) = rule_progress(&badge.rule, &player, &era_episodes);
+        match player.earned.iter().find(|e| e.id == badge.id) {
+            // Earned badges stay complete even if the target later grows (say, a new episode).
+            Some(at) => earned.push((
+                at.earned_at,
+                BadgeDto::new(badge, Some((target, target)), Some(at.earned_at), &lang),
+            )),
+            None => locked.push(BadgeDto::new(badge, Some((current, target)), None, &lang)),
+        }
+    }
+    earned.sort_by_key(|(earned_at, _)| Reverse(*earned_at));
+    let dto = AchievementsDto {
+        earned: earned.into_iter().map(|(_, badge)| badge).collect(),
+        locked,
+    };
+    (StatusCode::OK, Json(dto)).into_response()
+}
+
+/// Records that the caller read an episode, returning any badges it earned.
+pub async fn read_episode(
+    State(state): State<ApiState>,
+    Path((era_id, episode_id)): Path<(String, String)>,
+    Query(params): Query<AchievementsQuery>,
+    caller: Caller,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let user_id = match caller.require_user() {
+        Ok(user_id) => user_id,
+        Err(forbidden) => return forbidden.into_response(),
+    };
+    let lang = resolve_lang(params.lang.as_deref(), headers.get("accept-language"));
+    match era_queries::find_episode_for_era(
+        &state.db,
+        &era_id,
+        &episode_id,
+        &lang,
+        Visibility::Published,
+    )
+    .await
+    {
+        Ok(EpisodeLookup::Found(_)) => {}
+        Ok(EpisodeLookup::EraNotFound) => return error_response(StatusCode::NOT_FOUND, "era not found"),
+        Ok(EpisodeLookup::EpisodeNotFound) => {
+            return error_response(StatusCode::NOT_FOUND, "episode not found");
+        }
+        Err(err) => {
+            error!(error = ?err, "failed to fetch episode");
+            return error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to record episode");
+        }
+    }
+
+    streak_handler::record_activity(&state, user_id, EPISODE_READ).await;
+    let earned = record_episode(&state, user_id, &era_id, &episode_id)
+        .await
+        .into_iter()
+        .map(|(badge, earned)| BadgeDto::new(&badge, None, Some(earned.earned_at), &lang))
+        .collect();
+    let dto = EpisodeReadDto {
+        era_id,
+        episode_id,
+        earned,
+    };
+    (StatusCode::OK, Json(dto)).into_response()
+}
+
+/// Counts graded answers towards the player's badges. Failures are logged; the answers are
+/// already graded.
+pub async fn record_answers(state: &ApiState, user_id: &str, lang: &str, answered: i64, correct: i64) {
+    let player = queries::record_answers(&state.db, user_id, lang, answered, correct).await;
+    after_event(state, user_id, player).await;
+}
+
+/// Credits perfect eras in a graded quiz or challenge result. `answers` pairs each question's era
+/// with whether it was answered correctly.
+pub async fn record_result(state: &ApiState, user_id: &str, answers: &[(Option<String>, bool)]) {
+    let eras = perfect_eras(answers);
+    if eras.is_empty() {
+        return;
+    }
+    let player = queries::record_perfect_eras(&state.db, user_id, &eras).await;
+    after_event(state, user_id, player).await;
+}
+
+/// Counts an episode as read, returning the badges it earned. Failures are logged.
+pub async fn record_episode(
+    state: &ApiState,
+    user_id: &str,
+    era_id: &str,
+    episode_id: &str,
+) -> Vec<(Badge, EarnedBadge)> {
+    let key = episode_key(era_id, episode_id);
+    let player = queries::record_episode(&state.db, user_id, &key).await;
+    after_event(state, user_id, player).await
+}
+
+/// Credits the player's longest streak so far. Failures are logged.
+pub async fn record_streak(state: &ApiState, user_id: &str, longest: i64) {
+    let player = queries::record_streak(&state.db, user_id, longest).await;
+    after_event(state, user_id, player).await;
+}
+
+/// Awards whatever the updated player now deserves.
+async fn after_event(
+    state: &ApiState,
+    user_id: &str,
+    player: mongodb::error::Result<Option<PlayerAchievements>>,
+) -> Vec<(Badge, EarnedBadge)> {
+    let player = match player {
+        Ok(Some(player)) => player,
+        Ok(None) => return Vec::new(),
+        Err(err) => {
+            error!(error = ?err, user_id, "failed to record achievement progress");
+            return Vec::new();
+        }
+    };
+    let catalog = badges();
+    let awarded = match era_episodes(state, &catalog, &player.earned).await {
+        Ok(era_episodes) => award_new(state, &catalog, &player, &era_episodes).await,
+        Err(err) => Err(err),
+    };
+    awarded.unwrap_or_else(|err| {
+        error!(error = ?err, user_id, "failed to award badges");
+        Vec::new()
+    })
+}
+
+/// Awards every badge the player meets but does not hold yet.
+async fn award_new(
+    state: &ApiState,
+    catalog: &[Badge],
+    player: &PlayerAchievements,
+    era_episodes: &BTreeMap<String, BTreeSet<String>>,
+) -> mongodb::error::Result<Vec<(Badge, EarnedBadge)>> {
+    let mut awarded = Vec::new();
+    for badge in catalog {
+        if player.earned.iter().any(|e| e.id == badge.id)
+            || !is_met(rule_progress(&badge.rule, player, era_episodes))
+        {
+            continue;
+        }
+        let earned = EarnedBadge {
+            id: badge.id.clone(),
+            earned_at: DateTime::now(),
+        };
+        // Another event may have awarded it concurrently.
+        if queries::award(&state.db, &player.user_id, &earned).await? {
+            awarded.push((badge.clone(), earned));
+        }
+    }
+    Ok(awarded)
+}
+
+/// Episode ids of the published eras named by `era_complete` rules, skipping badges in `earned`.
+async fn era_episodes(
+    state: &ApiState,
+    catalog: &[Badge],
+    earned: &[EarnedBadge],
+) -> mongodb::error::Result<BTreeMap<String, BTreeSet<String>>> {
+    let mut eras = BTreeMap::new();
+    for badge in catalog {
+        let Rule::EraComplete {
+            era,
+        } = &badge.rule
+        else {
+            continue;
+        };
+        if eras.contains_key(era) || earned.iter().any(|e| e.id == badge.id) {
+            continue;
+        }
+        let episodes = match era_queries::find_era_document(&state.db, era).await? {
+            Some(doc) if ContentStatus::of_document(&doc) == ContentStatus::Published => {
+                episode_ids(&doc)
+            }
+            _ => BTreeSet::new(),
+        };
+        eras.insert(era.clone(), episodes);
+    }
+    Ok(eras)
+}
+
+fn error_response(status: StatusCode, msg: &str) -> Response {
+    (status, Json(json!({ "error": msg }))).into_response()
+}
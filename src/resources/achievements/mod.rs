@@ -0,0 +1,3 @@
+pub mod handler;
+pub mod model;
+pub mod queries;
@@ -0,0 +1,304 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use chrono::Utc;
+use mongodb::bson::DateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::resources::ui::badges::badges_config;
+
+/// Questions from one era a result must include, all correct, to count as perfect.
+pub const PERFECT_MIN_QUESTIONS: usize = 3;
+
+/// What a badge asks of a player.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Rule {
+    /// Answer `count` questions.
+    Answers {
+        count: u64,
+    },
+    /// Answer `count` questions correctly.
+    CorrectAnswers {
+        count: u64,
+    },
+    /// Reach a study streak of `days` days (see `streaks`).
+    StreakDays {
+        days: u64,
+    },
+    /// Answer questions in `count` locales.
+    Languages {
+        count: u64,
+    },
+    /// Read `count` episodes.
+    EpisodesRead {
+        count: u64,
+    },
+    /// Read every episode of `era`.
+    EraComplete {
+        era: String,
+    },
+    /// Finish a quiz or challenge with at least [`PERFECT_MIN_QUESTIONS`] questions from `era`,
+    /// all correct.
+    PerfectEra {
+        era: String,
+    },
+}
+
+/// A badge from the catalog in `ui::badges`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Badge {
+    pub id: String,
+    pub rule: Rule,
+    pub label: BTreeMap<String, String>,
+    pub description: BTreeMap<String, String>,
+}
+
+/// The badge catalog. Malformed entries are caught by the tests rather than at runtime.
+pub fn badges() -> Vec<Badge> {
+    serde_json::from_value(badges_config()["badges"].clone()).unwrap_or_default()
+}
+
+/// Everything the rules look at for one player (collection `player_achievements`, keyed by user
+/// id), folded in as events arrive so reading achievements never replays history.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct PlayerAchievements {
+    #[serde(rename = "_id")]
+    pub user_id: String,
+    #[serde(default)]
+    pub answered: i64,
+    #[serde(default)]
+    pub correct: i64,
+    /// Locales the player answered in.
+    #[serde(default)]
+    pub langs: Vec<String>,
+    /// Longest study streak, pushed by `streaks` as it grows.
+    #[serde(default)]
+    pub longest_streak: i64,
+    /// Episodes read, as `<era_id>/<episode_id>`.
+    #[serde(default)]
+    pub episodes: Vec<String>,
+    /// Eras with a perfect result.
+    #[serde(default)]
+    pub perfect_eras: Vec<String>,
+    #[serde(default)]
+    pub earned: Vec<EarnedBadge>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EarnedBadge {
+    pub id: String,
+    pub earned_at: DateTime,
+}
+
+pub fn episode_key(era_id: &str, episode_id: &str) -> String {
+    format!("{era_id}/{episode_id}")
+}
+
+/// How far a player is towards one rule, as `(current, target)`. `current` is capped at `target`.
+/// `era_episodes` holds the episode ids of every era named by an `era_complete` rule; an era
+/// without episodes cannot be completed.
+pub fn rule_progress(
+    rule: &Rule,
+    player: &PlayerAchievements,
+    era_episodes: &BTreeMap<String, BTreeSet<String>>,
+) -> (u64, u64) {
+    let (current, target) = match rule {
+        Rule::Answers {
+            count,
+        } => (player.answered.max(0) as u64, *count),
+        Rule::CorrectAnswers {
+            count,
+        } => (player.correct.max(0) as u64, *count),
+        Rule::StreakDays {
+            days,
+        } => (player.longest_streak.max(0) as u64, *days),
+        Rule::Languages {
+            count,
+        } => (player.langs.len() as u64, *count),
+        Rule::EpisodesRead {
+            count,
+        } => (player.episodes.len() as u64, *count),
+        Rule::EraComplete {
+            era,
+        } => {
+            let episodes = era_episodes.get(era).cloned().unwrap_or_default();
+            let read =
+                episodes.iter().filter(|id| player.episodes.contains(&episode_key(era, id))).count();
+            (read as u64, episodes.len() as u64)
+        }
+        Rule::PerfectEra {
+            era,
+        } => (u64::from(player.perfect_eras.contains(era)), 1),
+    };
+    (current.min(target), target)
+}
+
+pub fn is_met((current, target): (u64, u64)) -> bool {
+    target > 0 && current >= target
+}
+
+/// Eras a graded result was perfect on. `answers` pairs each question's era with whether it was
+/// answered correctly, as for the leaderboards.
+pub fn perfect_eras(answers: &[(Option<String>, bool)]) -> Vec<String> {
+    let mut per_era: BTreeMap<&str, (usize, bool)> = BTreeMap::new();
+    for (era, correct) in answers {
+        if let Some(era) = era {
+            let entry = per_era.entry(era).or_insert((0, true));
+            entry.0 += 1;
+            entry.1 &= *correct;
+        }
+    }
+    per_era
+        .into_iter()
+        .filter(|(_, (count, all_correct))| *count >= PERFECT_MIN_QUESTIONS && *all_correct)
+        .map(|(era, _)| era.to_string())
+        .collect()
+}
+
+#[derive(Deserialize)]
+pub struct AchievementsQuery {
+    pub lang: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BadgeDto {
+    pub id: String,
+    pub label: String,
+    pub description: String,
+    /// Left out for badges reported as just earned.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub progress: Option<ProgressDto>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub earned_at: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProgressDto {
+    pub current: u64,
+    pub target: u64,
+}
+
+impl BadgeDto {
+    pub fn new(
+        badge: &Badge,
+        progress: Option<(u64, u64)>,
+        earned_at: Option<DateTime>,
+        lang: &str,
+    ) -> Self {
+        Self {
+            id: badge.id.clone(),
+            label: localized(&badge.label, lang),
+            description: localized(&badge.description, lang),
+            progress: progress.map(|(current, target)| ProgressDto {
+                current,
+                target,
+            }),
+            earned_at: earned_at.map(|at| at.to_chrono().with_timezone(&Utc).to_rfc3339()),
+        }
+    }
+}
+
+/// Earned badges, most recent first, and the rest in catalog order.
+#[derive(Debug, Serialize)]
+pub struct AchievementsDto {
+    pub earned: Vec<BadgeDto>,
+    pub locked: Vec<BadgeDto>,
+}
+
+/// Response to reading an episode, with any badges it earned.
+#[derive(Debug, Serialize)]
+pub struct EpisodeReadDto {
+    pub era_id: String,
+    pub episode_id: String,
+    pub earned: Vec<BadgeDto>,
+}
+
+fn localized(text: &BTreeMap<String, String>, lang: &str) -> String {
+    text.get(lang).or_else(|| text.get("en")).cloned().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, BTreeSet};
+
+    use super::{PlayerAchievements, Rule, badges, episode_key, is_met, perfect_eras, rule_progress};
+    use crate::resources::ui::badges::badges_config;
+
+    #[test]
+    fn every_catalog_badge_parses_with_english_text() {
+        let catalog = badges();
+        assert_eq!(catalog.len(), badges_config()["badges"].as_array().map_or(0, Vec::len));
+        assert!(catalog.iter().all(|b| b.label.contains_key("en") && b.description.contains_key("en")));
+        let ids: BTreeSet<&str> = catalog.iter().map(|b| b.id.as_str()).collect();
+        assert_eq!(ids.len(), catalog.len());
+    }
+
+    #[test]
+    fn rules_measure_progress() {
+        let player = PlayerAchievements {
+            user_id: "ana".into(),
+            answered: 12,
+            correct: 9,
+            langs: vec!["en".into(), "es".into()],
+            longest_streak: 3,
+            episodes: vec![episode_key("creation", "light"), episode_key("creation", "eden")],
+            perfect_eras: vec!["psalms".into()],
+            earned: Vec::new(),
+        };
+        let mut eras = BTreeMap::new();
+        eras.insert("creation".to_string(), BTreeSet::from(["light".to_string(), "eden".to_string()]));
+        eras.insert("messianics".to_string(), BTreeSet::new());
+
+        let progress = |rule: Rule| rule_progress(&rule, &player, &eras);
+        assert_eq!(
+            progress(Rule::CorrectAnswers {
+                count: 100
+            }),
+            (9, 100)
+        );
+        assert_eq!(
+            progress(Rule::Answers {
+                count: 1
+            }),
+            (1, 1)
+        );
+        assert_eq!(
+            progress(Rule::StreakDays {
+                days: 7
+            }),
+            (3, 7)
+        );
+        assert_eq!(
+            progress(Rule::Languages {
+                count: 4
+            }),
+            (2, 4)
+        );
+        assert!(is_met(progress(Rule::EraComplete {
+            era: "creation".into()
+        })));
+        assert!(!is_met(progress(Rule::EraComplete {
+            era: "messianics".into()
+        })));
+        assert!(is_met(progress(Rule::PerfectEra {
+            era: "psalms".into()
+        })));
+    }
+
+    #[test]
+    fn perfect_needs_enough_questions_all_correct() {
+        let answer = |era: &str, correct| (Some(era.to_string()), correct);
+        let answers = vec![
+            answer("psalms", true),
+            answer("psalms", true),
+            answer("psalms", true),
+            answer("gospel", true),
+            answer("gospel", true),
+            answer("gospel", true),
+            answer("gospel", false),
+            answer("kings", true),
+            (None, true),
+        ];
+        assert_eq!(perfect_eras(&answers), vec!["psalms".to_string()]);
+    }
+}
@@ -0,0 +1,76 @@
+use mongodb::{
+    Collection, Database,
+    bson::{self, Document, doc},
+    options::{FindOneAndUpdateOptions, ReturnDocument},
+};
+
+use crate::resources::achievements::model::{EarnedBadge, PlayerAchievements};
+
+fn players_collection(db: &Database) -> Collection<PlayerAchievements> {
+    db.collection("player_achievements")
+}
+
+pub async fn find_player(
+    db: &Database,
+    user_id: &str,
+) -> mongodb::error::Result<Option<PlayerAchievements>> {
+    players_collection(db).find_one(doc! { "_id": user_id }, None).await
+}
+
+/// Counts a batch of answers given in `lang`.
+pub async fn record_answers(
+    db: &Database,
+    user_id: &str,
+    lang: &str,
+    answered: i64,
+    correct: i64,
+) -> mongodb::error::Result<Option<PlayerAchievements>> {
+    let update = doc! {
+        "$inc": { "answered": answered, "correct": correct },
+        "$addToSet": { "langs": lang },
+    };
+    apply(db, user_id, update).await
+}
+
+pub async fn record_perfect_eras(
+    db: &Database,
+    user_id: &str,
+    eras: &[String],
+) -> mongodb::error::Result<Option<PlayerAchievements>> {
+    apply(db, user_id, doc! { "$addToSet": { "perfect_eras": { "$each": eras } } }).await
+}
+
+/// Records an episode read; `key` is from `episode_key`.
+pub async fn record_episode(
+    db: &Database,
+    user_id: &str,
+    key: &str,
+) -> mongodb::error::Result<Option<PlayerAchievements>> {
+    apply(db, user_id, doc! { "$addToSet": { "episodes": key } }).await
+}
+
+pub async fn record_streak(
+    db: &Database,
+    user_id: &str,
+    longest: i64,
+) -> mongodb::error::Result<Option<PlayerAchievements>> {
+    apply(db, user_id, doc! { "$max": { "longest_streak": longest } }).await
+}
+
+/// Awards a badge. Returns `false` when the player already had it.
+pub async fn award(db: &Database, user_id: &str, badge: &EarnedBadge) -> mongodb::error::Result<bool> {
+    let filter = doc! { "_id": user_id, "earned.id": { "$ne": &badge.id } };
+    let update = doc! { "$push": { "earned": bson::to_bson(badge)? } };
+    let result = players_collection(db).update_one(filter, update, None).await?;
+    Ok(result.modified_count > 0)
+}
+
+async fn apply(
+    db: &Database,
+    user_id: &str,
+    update: Document,
+) -> mongodb::error::Result<Option<PlayerAchievements>> {
+    let options =
+        FindOneAndUpdateOptions::builder().upsert(true).return_document(ReturnDocument::After).build();
+    players_collection(db).find_one_and_update(doc! { "_id": user_id }, update, options).await
+}
@@ -0,0 +1,49 @@
+use axum::routing::{MethodRouter, get as axum_get};
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{auth::Caller, routes::api::ApiState};
+
+pub fn cache() -> MethodRouter<ApiState> {
+    axum_get(cache_stats).delete(invalidate_cache)
+}
+
+pub async fn cache_stats(State(state): State<ApiState>, caller: Caller) -> impl IntoResponse {
+    if let Err(forbidden) = caller.require_admin() {
+        return forbidden.into_response();
+    }
+    (StatusCode::OK, Json(state.cache.stats())).into_response()
+}
+
+#[derive(Deserialize)]
+pub struct InvalidateQuery {
+    pub prefix: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct InvalidateResponse {
+    pub removed: usize,
+}
+
+pub async fn invalidate_cache(
+    State(state): State<ApiState>,
+    Query(params): Query<InvalidateQuery>,
+    caller: Caller,
+) -> impl IntoResponse {
+    if let Err(forbidden) = caller.require_admin() {
+        return forbidden.into_response();
+    }
+    let removed = state.cache.invalidate_prefix(params.prefix.as_deref().unwrap_or_default());
+    (
+        StatusCode::OK,
+        Json(InvalidateResponse {
+            removed,
+        }),
+    )
+        .into_response()
+}
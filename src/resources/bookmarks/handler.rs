@@ -0,0 +1,361 @@
+use axum::routing::{MethodRouter, delete as axum_delete, get as axum_get, put as axum_put};
+use axum::{
+    Json,
+    body::Bytes,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use mongodb::bson::{DateTime, oid::ObjectId};
+use serde_json::{Value, json};
+use tracing::error;
+
+use crate::{
+    auth::Caller,
+    resources::{
+        bookmarks::{
+            model::{
+                Bookmark, BookmarkDto, BookmarkListDto, BookmarkQuery, CreateNote, DEFAULT_LIMIT,
+                MAX_LIMIT, Note, NoteDto, NoteListDto, NoteQuery, Target, UpdateNote, clean_note,
+                search_patterns, validate_kind, visit_content,
+            },
+            queries,
+        },
+        eras::queries::{self as era_queries, episode_ids},
+        questions::queries as question_queries,
+        workflow::model::ContentStatus,
+    },
+    routes::api::ApiState,
+};
+
+pub fn bookmarks() -> MethodRouter<ApiState> {
+    axum_get(list_bookmarks).post(create_bookmark)
+}
+
+pub fn bookmark() -> MethodRouter<ApiState> {
+    axum_delete(delete_bookmark)
+}
+
+pub fn notes() -> MethodRouter<ApiState> {
+    axum_get(list_notes).post(create_note)
+}
+
+pub fn note() -> MethodRouter<ApiState> {
+    axum_put(update_note).delete(delete_note)
+}
+
+/// The caller's bookmarks, newest first.
+pub async fn list_bookmarks(
+    State(state): State<ApiState>,
+    Query(params): Query<BookmarkQuery>,
+    caller: Caller,
+) -> impl IntoResponse {
+    let user_id = match caller.require_user() {
+        Ok(user_id) => user_id,
+        Err(forbidden) => return forbidden.into_response(),
+    };
+    if let Err(msg) = validate_kind(params.kind.as_deref()) {
+        return error_response(StatusCode::BAD_REQUEST, msg);
+    }
+    let (limit, offset) = page(params.limit, params.offset);
+    match queries::list_bookmarks(
+        &state.db,
+        user_id,
+        params.kind.as_deref(),
+        params.era.as_deref(),
+        limit,
+        offset,
+    )
+    .await
+    {
+        Ok((bookmarks, total)) => {
+            let items = bookmarks.into_iter().map(BookmarkDto::from).collect();
+            (
+                StatusCode::OK,
+                Json(BookmarkListDto {
+                    items,
+                    total: total as usize,
+                }),
+            )
+                .into_response()
+        }
+        Err(err) => {
+            error!(error = ?err, "failed to list bookmarks");
+            error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to list bookmarks")
+        }
+    }
+}
+
+/// Bookmarks a published era, episode or question. Bookmarking it again returns the existing
+/// bookmark.
+pub async fn create_bookmark(
+    State(state): State<ApiState>,
+    caller: Caller,
+    Json(target): Json<Target>,
+) -> impl IntoResponse {
+    let user_id = match caller.require_user() {
+        Ok(user_id) => user_id,
+        Err(forbidden) => return forbidden.into_response(),
+    };
+    let era_id = match resolve_target(&state, &target).await {
+        Ok(era_id) => era_id,
+        Err(response) => return response,
+    };
+
+    let key = target.key();
+    let bookmark = Bookmark {
+        id: ObjectId::new().to_hex(),
+        user_id: user_id.to_string(),
+        target,
+        key,
+        era_id,
+        created_at: DateTime::now(),
+    };
+    match queries::insert_bookmark(&state.db, &bookmark).await {
+        Ok(true) => (StatusCode::CREATED, Json(BookmarkDto::from(bookmark))).into_response(),
+        Ok(false) => match queries::find_bookmark(&state.db, user_id, &bookmark.key).await {
+            Ok(Some(existing)) => (StatusCode::OK, Json(BookmarkDto::from(existing))).into_response(),
+            Ok(None) => error_response(StatusCode::CONFLICT, "bookmark changed, try again"),
+            Err(err) => {
+                error!(error = ?err, "failed to fetch bookmark");
+                error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to save bookmark")
+            }
+        },
+        Err(err) => {
+            error!(error = ?err, "failed to save bookmark");
+            error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to save bookmark")
+        }
+    }
+}
+
+pub async fn delete_bookmark(
+    State(state): State<ApiState>,
+    Path(bookmark_id): Path<String>,
+    caller: Caller,
+) -> impl IntoResponse {
+    let user_id = match caller.require_user() {
+        Ok(user_id) => user_id,
+        Err(forbidden) => return forbidden.into_response(),
+    };
+    match queries::delete_bookmark(&state.db, user_id, &bookmark_id).await {
+        Ok(true) => StatusCode::NO_CONTENT.into_response(),
+        Ok(false) => error_response(StatusCode::NOT_FOUND, "bookmark not found"),
+        Err(err) => {
+            error!(error = ?err, "failed to delete bookmark");
+            error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to delete bookmark")
+        }
+    }
+}
+
+/// The caller's notes, most recently edited first; `q` keeps notes containing every word.
+pub async fn list_notes(
+    State(state): State<ApiState>,
+    Query(params): Query<NoteQuery>,
+    caller: Caller,
+) -> impl IntoResponse {
+    let user_id = match caller.require_user() {
+        Ok(user_id) => user_id,
+        Err(forbidden) => return forbidden.into_response(),
+    };
+    if let Err(msg) = validate_kind(params.kind.as_deref()) {
+        return error_response(StatusCode::BAD_REQUEST, msg);
+    }
+    let patterns = search_patterns(params.q.as_deref().unwrap_or_default());
+    let (limit, offset) = page(params.limit, params.offset);
+    match queries::list_notes(
+        &state.db,
+        user_id,
+        params.kind.as_deref(),
+        params.era.as_deref(),
+        &patterns,
+        limit,
+        offset,
+    )
+    .await
+    {
+        Ok((notes, total)) => {
+            let items = notes.into_iter().map(NoteDto::from).collect();
+            (
+                StatusCode::OK,
+                Json(NoteListDto {
+                    items,
+                    total: total as usize,
+                }),
+            )
+                .into_response()
+        }
+        Err(err) => {
+            error!(error = ?err, "failed to list notes");
+            error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to list notes")
+        }
+    }
+}
+
+/// Adds a private note to a published era, episode or question.
+pub async fn create_note(
+    State(state): State<ApiState>,
+    caller: Caller,
+    Json(payload): Json<CreateNote>,
+) -> impl IntoResponse {
+    let user_id = match caller.require_user() {
+        Ok(user_id) => user_id,
+        Err(forbidden) => return forbidden.into_response(),
+    };
+    let text = match clean_note(&payload.text) {
+        Ok(text) => text,
+        Err(msg) => return error_response(StatusCode::BAD_REQUEST, msg),
+    };
+    let era_id = match resolve_target(&state, &payload.target).await {
+        Ok(era_id) => era_id,
+        Err(response) => return response,
+    };
+
+    let now = DateTime::now();
+    let note = Note {
+        id: ObjectId::new().to_hex(),
+        user_id: user_id.to_string(),
+        key: payload.target.key(),
+        target: payload.target,
+        era_id,
+        text,
+        created_at: now,
+        updated_at: now,
+    };
+    match queries::insert_note(&state.db, &note).await {
+        Ok(()) => (StatusCode::CREATED, Json(NoteDto::from(note))).into_response(),
+        Err(err) => {
+            error!(error = ?err, "failed to save note");
+            error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to save note")
+        }
+    }
+}
+
+pub async fn update_note(
+    State(state): State<ApiState>,
+    Path(note_id): Path<String>,
+    caller: Caller,
+    Json(payload): Json<UpdateNote>,
+) -> impl IntoResponse {
+    let user_id = match caller.require_user() {
+        Ok(user_id) => user_id,
+        Err(forbidden) => return forbidden.into_response(),
+    };
+    let text = match clean_note(&payload.text) {
+        Ok(text) => text,
+        Err(msg) => return error_response(StatusCode::BAD_REQUEST, msg),
+    };
+    match queries::update_note(&state.db, user_id, &note_id, &text).await {
+        Ok(Some(note)) => (StatusCode::OK, Json(NoteDto::from(note))).into_response(),
+        Ok(None) => error_response(StatusCode::NOT_FOUND, "note not found"),
+        Err(err) => {
+            error!(error = ?err, "failed to update note");
+            error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to update note")
+        }
+    }
+}
+
+pub async fn delete_note(
+    State(state): State<ApiState>,
+    Path(note_id): Path<String>,
+    caller: Caller,
+) -> impl IntoResponse {
+    let user_id = match caller.require_user() {
+        Ok(user_id) => user_id,
+        Err(forbidden) => return forbidden.into_response(),
+    };
+    match queries::delete_note(&state.db, user_id, &note_id).await {
+        Ok(true) => StatusCode::NO_CONTENT.into_response(),
+        Ok(false) => error_response(StatusCode::NOT_FOUND, "note not found"),
+        Err(err) => {
+            error!(error = ?err, "failed to delete note");
+            error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to delete note")
+        }
+    }
+}
+
+/// Adds a `bookmarked` flag to every era and episode in an eras API body for a signed-in caller.
+/// `era_id` is set when the body holds that era's episodes. Failures are logged and leave the
+/// body unmarked.
+pub async fn mark_bookmarked(
+    state: &ApiState,
+    user_id: &str,
+    body: Bytes,
+    era_id: Option<&str>,
+) -> Bytes {
+    let Ok(mut value) = serde_json::from_slice::<Value>(&body) else {
+        return body;
+    };
+    let mut keys = Vec::new();
+    visit_content(&mut value, era_id, &mut |_, key| keys.push(key));
+    if keys.is_empty() {
+        return body;
+    }
+    let bookmarked = match queries::bookmarked_keys(&state.db, user_id, &keys).await {
+        Ok(bookmarked) => bookmarked,
+        Err(err) => {
+            error!(error = ?err, user_id, "failed to load bookmarks");
+            return body;
+        }
+    };
+    visit_content(&mut value, era_id, &mut |object, key| {
+        object.insert("bookmarked".to_string(), Value::Bool(bookmarked.contains(&key)));
+    });
+    serde_json::to_vec(&value).map(Bytes::from).unwrap_or(body)
+}
+
+/// Checks the target exists and is published, returning its era.
+async fn resolve_target(state: &ApiState, target: &Target) -> Result<Option<String>, Response> {
+    if let Err(msg) = target.validate() {
+        return Err(error_response(StatusCode::BAD_REQUEST, msg));
+    }
+    match target {
+        Target::Era {
+            era_id,
+        }
+        | Target::Episode {
+            era_id,
+            ..
+        } => {
+            let era = match era_queries::find_era_document(&state.db, era_id).await {
+                Ok(Some(doc)) if ContentStatus::of_document(&doc) == ContentStatus::Published => doc,
+                Ok(_) => return Err(error_response(StatusCode::NOT_FOUND, "era not found")),
+                Err(err) => {
+                    error!(error = ?err, "failed to fetch era");
+                    return Err(error_response(
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        "failed to fetch era",
+                    ));
+                }
+            };
+            if let Target::Episode {
+                episode_id,
+                ..
+            } = target
+                && !episode_ids(&era).contains(episode_id)
+            {
+                return Err(error_response(StatusCode::NOT_FOUND, "episode not found"));
+            }
+            Ok(Some(era_id.clone()))
+        }
+        Target::Question {
+            question_id,
+        } => match question_queries::find_question_by_id(&state.db, question_id).await {
+            Ok(Some(question)) if question.status == ContentStatus::Published => Ok(question.era),
+            Ok(_) => Err(error_response(StatusCode::NOT_FOUND, "question not found")),
+            Err(err) => {
+                error!(error = ?err, "failed to fetch question");
+                Err(error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to fetch question"))
+            }
+        },
+    }
+}
+
+/// `limit` and `offset` as the driver takes them, with the default and cap applied.
+fn page(limit: Option<usize>, offset: Option<usize>) -> (i64, u64) {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    (limit as i64, offset.unwrap_or(0) as u64)
+}
+
+fn error_response(status: StatusCode, msg: &str) -> Response {
+    (status, Json(json!({ "error": msg }))).into_response()
+}
@@ -0,0 +1,307 @@
+use chrono::Utc;
+use mongodb::bson::DateTime;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+pub const MAX_NOTE_LEN: usize = 2000;
+pub const DEFAULT_LIMIT: usize = 50;
+pub const MAX_LIMIT: usize = 200;
+
+/// What a bookmark or note is attached to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Target {
+    Era {
+        era_id: String,
+    },
+    Episode {
+        era_id: String,
+        episode_id: String,
+    },
+    Question {
+        question_id: String,
+    },
+}
+
+impl Target {
+    /// Identifies the target among a player's bookmarks, e.g. `episode:creation/world`.
+    pub fn key(&self) -> String {
+        match self {
+            Target::Era {
+                era_id,
+            } => era_key(era_id),
+            Target::Episode {
+                era_id,
+                episode_id,
+            } => episode_key(era_id, episode_id),
+            Target::Question {
+                question_id,
+            } => format!("question:{question_id}"),
+        }
+    }
+
+    /// Checks the ids are present; whether the content exists is up to the handler.
+    pub fn validate(&self) -> Result<(), &'static str> {
+        let ids: Vec<&str> = match self {
+            Target::Era {
+                era_id,
+            } => vec![era_id],
+            Target::Episode {
+                era_id,
+                episode_id,
+            } => vec![era_id, episode_id],
+            Target::Question {
+                question_id,
+            } => vec![question_id],
+        };
+        if ids.iter().any(|id| id.trim().is_empty()) {
+            return Err("target ids must not be empty");
+        }
+        Ok(())
+    }
+}
+
+pub fn era_key(era_id: &str) -> String {
+    format!("era:{era_id}")
+}
+
+pub fn episode_key(era_id: &str, episode_id: &str) -> String {
+    format!("episode:{era_id}/{episode_id}")
+}
+
+/// A saved era, episode or question (collection `bookmarks`, unique per player and `key`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bookmark {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub user_id: String,
+    pub target: Target,
+    pub key: String,
+    /// The target's era, for filtering; a question's era when it was bookmarked.
+    pub era_id: Option<String>,
+    pub created_at: DateTime,
+}
+
+/// A private note on an era, episode or question (collection `notes`). A target may have several.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Note {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub user_id: String,
+    pub target: Target,
+    pub key: String,
+    pub era_id: Option<String>,
+    pub text: String,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+/// Body of `POST /v1/me/notes`: the target's fields plus `text`.
+#[derive(Deserialize)]
+pub struct CreateNote {
+    #[serde(flatten)]
+    pub target: Target,
+    pub text: String,
+}
+
+#[derive(Deserialize)]
+pub struct UpdateNote {
+    pub text: String,
+}
+
+/// `kind` (`era`, `episode` or `question`) and `era` narrow the list.
+#[derive(Deserialize)]
+pub struct BookmarkQuery {
+    pub kind: Option<String>,
+    pub era: Option<String>,
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+}
+
+/// As [`BookmarkQuery`], plus `q` to search note text.
+#[derive(Deserialize)]
+pub struct NoteQuery {
+    pub kind: Option<String>,
+    pub era: Option<String>,
+    pub q: Option<String>,
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+}
+
+pub fn validate_kind(kind: Option<&str>) -> Result<(), &'static str> {
+    match kind {
+        None | Some("era" | "episode" | "question") => Ok(()),
+        Some(_) => Err("kind must be era, episode or question"),
+    }
+}
+
+/// The note text trimmed, if it is 1 to [`MAX_NOTE_LEN`] characters long.
+pub fn clean_note(text: &str) -> Result<String, &'static str> {
+    let text = text.trim();
+    if text.is_empty() || text.chars().count() > MAX_NOTE_LEN {
+        return Err("text must be 1 to 2000 characters");
+    }
+    Ok(text.to_string())
+}
+
+/// One regex per word of `query`, with metacharacters escaped, so a note matches when its text
+/// contains every word.
+pub fn search_patterns(query: &str) -> Vec<String> {
+    query
+        .split_whitespace()
+        .map(|word| {
+            let mut pattern = String::with_capacity(word.len());
+            for c in word.chars() {
+                if "\\^$.|?*+()[]{}".contains(c) {
+                    pattern.push('\\');
+                }
+                pattern.push(c);
+            }
+            pattern
+        })
+        .collect()
+}
+
+/// Calls `f` with each era and episode object in an eras API body, along with its bookmark key.
+/// An object is an episode when it has its own `era_id`, sits in an era's `episodes`, or `era_id`
+/// is given for a body of one era's episodes.
+pub fn visit_content(
+    value: &mut Value,
+    era_id: Option<&str>,
+    f: &mut dyn FnMut(&mut Map<String, Value>, String),
+) {
+    match value {
+        Value::Array(items) => {
+            for item in items {
+                visit_content(item, era_id, f);
+            }
+        }
+        Value::Object(object) => {
+            let Some(id) = object.get("id").and_then(Value::as_str).map(str::to_string) else {
+                return;
+            };
+            let own_era = object.get("era_id").and_then(Value::as_str).map(str::to_string);
+            match own_era.as_deref().or(era_id) {
+                Some(era_id) => f(object, episode_key(era_id, &id)),
+                None => {
+                    if let Some(episodes) = object.get_mut("episodes") {
+                        visit_content(episodes, Some(&id), f);
+                    }
+                    f(object, era_key(&id));
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct BookmarkDto {
+    pub id: String,
+    #[serde(flatten)]
+    pub target: Target,
+    pub created_at: String,
+}
+
+impl From<Bookmark> for BookmarkDto {
+    fn from(bookmark: Bookmark) -> Self {
+        Self {
+            id: bookmark.id,
+            target: bookmark.target,
+            created_at: rfc3339(bookmark.created_at),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct NoteDto {
+    pub id: String,
+    #[serde(flatten)]
+    pub target: Target,
+    pub text: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl From<Note> for NoteDto {
+    fn from(note: Note) -> Self {
+        Self {
+            id: note.id,
+            target: note.target,
+            text: note.text,
+            created_at: rfc3339(note.created_at),
+            updated_at: rfc3339(note.updated_at),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct BookmarkListDto {
+    pub items: Vec<BookmarkDto>,
+    pub total: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NoteListDto {
+    pub items: Vec<NoteDto>,
+    pub total: usize,
+}
+
+fn rfc3339(at: DateTime) -> String {
+    at.to_chrono().with_timezone(&Utc).to_rfc3339()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{Value, json};
+
+    use super::{CreateNote, Target, clean_note, search_patterns, visit_content};
+
+    #[test]
+    fn targets_parse_flat_and_key_by_kind() {
+        let note: CreateNote = serde_json::from_value(json!({
+            "kind": "episode",
+            "era_id": "creation",
+            "episode_id": "world",
+            "text": "Light first",
+        }))
+        .unwrap();
+        assert_eq!(note.target.key(), "episode:creation/world");
+        let question = Target::Question {
+            question_id: " ".into(),
+        };
+        assert!(question.validate().is_err());
+        assert!(serde_json::from_value::<Target>(json!({ "kind": "book" })).is_err());
+    }
+
+    #[test]
+    fn content_keys_cover_eras_and_their_episodes() {
+        let mut keys = Vec::new();
+        let mut collect = |_: &mut serde_json::Map<String, Value>, key: String| keys.push(key);
+        let mut era = json!({ "id": "creation", "episodes": [{ "id": "world" }] });
+        visit_content(&mut era, None, &mut collect);
+        let mut episodes = json!([{ "id": "eden" }]);
+        visit_content(&mut episodes, Some("creation"), &mut collect);
+        let mut search = json!([{ "era_id": "kings", "id": "saul" }]);
+        visit_content(&mut search, None, &mut collect);
+        assert_eq!(
+            keys,
+            vec![
+                "episode:creation/world",
+                "era:creation",
+                "episode:creation/eden",
+                "episode:kings/saul"
+            ]
+        );
+    }
+
+    #[test]
+    fn notes_are_trimmed_and_searched_by_word() {
+        assert_eq!(clean_note("  Abraham's call "), Ok("Abraham's call".to_string()));
+        assert!(clean_note("   ").is_err());
+        assert!(clean_note(&"x".repeat(2001)).is_err());
+        assert_eq!(search_patterns(" ur  ABRAHAM "), vec!["ur", "ABRAHAM"]);
+        assert_eq!(search_patterns("3:16 (a+b)"), vec!["3:16", "\\(a\\+b\\)"]);
+        assert!(search_patterns("   ").is_empty());
+    }
+}
@@ -0,0 +1,163 @@
+use std::collections::BTreeSet;
+
+use futures_util::TryStreamExt;
+use mongodb::{
+    Collection, Database, IndexModel,
+    bson::{DateTime, Document, doc},
+    options::{FindOneAndUpdateOptions, FindOptions, IndexOptions, ReturnDocument},
+};
+
+use crate::{
+    db::is_duplicate_key,
+    resources::bookmarks::model::{Bookmark, Note},
+};
+
+fn bookmarks_collection(db: &Database) -> Collection<Bookmark> {
+    db.collection("bookmarks")
+}
+
+fn notes_collection(db: &Database) -> Collection<Note> {
+    db.collection("notes")
+}
+
+/// One bookmark per player and target; notes are looked up per player.
+pub async fn ensure_indexes(db: &Database) -> mongodb::error::Result<()> {
+    let unique = IndexOptions::builder().unique(true).build();
+    let index = IndexModel::builder().keys(doc! { "user_id": 1, "key": 1 }).options(unique).build();
+    bookmarks_collection(db).create_index(index, None).await?;
+    let index = IndexModel::builder().keys(doc! { "user_id": 1, "created_at": -1 }).build();
+    bookmarks_collection(db).create_index(index, None).await?;
+    let index = IndexModel::builder().keys(doc! { "user_id": 1, "updated_at": -1 }).build();
+    notes_collection(db).create_index(index, None).await?;
+    Ok(())
+}
+
+/// Saves a bookmark. Returns `false` when the player already bookmarked the target.
+pub async fn insert_bookmark(db: &Database, bookmark: &Bookmark) -> mongodb::error::Result<bool> {
+    match bookmarks_collection(db).insert_one(bookmark, None).await {
+        Ok(_) => Ok(true),
+        Err(err) if is_duplicate_key(&err) => Ok(false),
+        Err(err) => Err(err),
+    }
+}
+
+pub async fn find_bookmark(
+    db: &Database,
+    user_id: &str,
+    key: &str,
+) -> mongodb::error::Result<Option<Bookmark>> {
+    bookmarks_collection(db).find_one(doc! { "user_id": user_id, "key": key }, None).await
+}
+
+/// A page of the player's bookmarks, newest first, with the total that match.
+pub async fn list_bookmarks(
+    db: &Database,
+    user_id: &str,
+    kind: Option<&str>,
+    era_id: Option<&str>,
+    limit: i64,
+    offset: u64,
+) -> mongodb::error::Result<(Vec<Bookmark>, u64)> {
+    let filter = saved_filter(user_id, kind, era_id);
+    let total = bookmarks_collection(db).count_documents(filter.clone(), None).await?;
+    let options = FindOptions::builder()
+        .sort(doc! { "created_at": -1, "_id": -1 })
+        .skip(Some(offset))
+        .limit(Some(limit))
+        .build();
+    let mut cursor = bookmarks_collection(db).find(filter, options).await?;
+    let mut bookmarks = Vec::new();
+    while let Some(bookmark) = cursor.try_next().await? {
+        bookmarks.push(bookmark);
+    }
+    Ok((bookmarks, total))
+}
+
+/// Which of `keys` the player bookmarked.
+pub async fn bookmarked_keys(
+    db: &Database,
+    user_id: &str,
+    keys: &[String],
+) -> mongodb::error::Result<BTreeSet<String>> {
+    let filter = doc! { "user_id": user_id, "key": { "$in": keys } };
+    let mut cursor = bookmarks_collection(db).find(filter, None).await?;
+    let mut found = BTreeSet::new();
+    while let Some(bookmark) = cursor.try_next().await? {
+        found.insert(bookmark.key);
+    }
+    Ok(found)
+}
+
+pub async fn delete_bookmark(db: &Database, user_id: &str, id: &str) -> mongodb::error::Result<bool> {
+    let result =
+        bookmarks_collection(db).delete_one(doc! { "_id": id, "user_id": user_id }, None).await?;
+    Ok(result.deleted_count > 0)
+}
+
+pub async fn insert_note(db: &Database, note: &Note) -> mongodb::error::Result<()> {
+    notes_collection(db).insert_one(note, None).await?;
+    Ok(())
+}
+
+/// A page of the player's notes, most recently edited first, with the total that match. Each of
+/// `patterns` must match the text, ignoring case.
+pub async fn list_notes(
+    db: &Database,
+    user_id: &str,
+    kind: Option<&str>,
+    era_id: Option<&str>,
+    patterns: &[String],
+    limit: i64,
+    offset: u64,
+) -> mongodb::error::Result<(Vec<Note>, u64)> {
+    let mut filter = saved_filter(user_id, kind, era_id);
+    if !patterns.is_empty() {
+        let words: Vec<Document> = patterns
+            .iter()
+            .map(|pattern| doc! { "text": { "$regex": pattern, "$options": "i" } })
+            .collect();
+        filter.insert("$and", words);
+    }
+    let total = notes_collection(db).count_documents(filter.clone(), None).await?;
+    let options = FindOptions::builder()
+        .sort(doc! { "updated_at": -1, "_id": -1 })
+        .skip(Some(offset))
+        .limit(Some(limit))
+        .build();
+    let mut cursor = notes_collection(db).find(filter, options).await?;
+    let mut notes = Vec::new();
+    while let Some(note) = cursor.try_next().await? {
+        notes.push(note);
+    }
+    Ok((notes, total))
+}
+
+/// Replaces a note's text. Returns `None` when the player has no such note.
+pub async fn update_note(
+    db: &Database,
+    user_id: &str,
+    id: &str,
+    text: &str,
+) -> mongodb::error::Result<Option<Note>> {
+    let update = doc! { "$set": { "text": text, "updated_at": DateTime::now() } };
+    let options = FindOneAndUpdateOptions::builder().return_document(ReturnDocument::After).build();
+    notes_collection(db)
+        .find_one_and_update(doc! { "_id": id, "user_id": user_id }, update, options)
+        .await
+}
+
+pub async fn delete_note(db: &Database, user_id: &str, id: &str) -> mongodb::error::Result<bool> {
+    let result = notes_collection(db).delete_one(doc! { "_id": id, "user_id": user_id }, None).await?;
+    Ok(result.deleted_count > 0)
+}
+
+fn saved_filter(user_id: &str, kind: Option<&str>, era_id: Option<&str>) -> Document {
+    let mut filter = doc! { "user_id": user_id };
+    if let Some(kind) = kind {
+        filter.insert("target.kind", kind);
+    }
+    if let Some(era_id) = era_id {
+        filter.insert("era_id", era_id);
+    }
+    filter
+}
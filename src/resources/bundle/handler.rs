@@ -0,0 +1,194 @@
+use std::io::Write;
+
+use axum::routing::{MethodRouter, get as axum_get};
+use axum::{
+    Json,
+    body::Bytes,
+    extract::{Query, State},
+    http::{HeaderMap, HeaderValue, StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use flate2::{Compression, write::GzEncoder};
+use serde::Deserialize;
+use serde_json::json;
+use tracing::error;
+
+use crate::{
+    conditional::{self, Validators},
+    lang::{resolve_lang, varies_by_header},
+    resources::bundle::{model::BundleVersion, queries},
+    routes::api::ApiState,
+};
+
+pub fn get() -> MethodRouter<ApiState> {
+    axum_get(get_bundle)
+}
+
+pub fn version() -> MethodRouter<ApiState> {
+    axum_get(get_bundle_version)
+}
+
+#[derive(Deserialize)]
+pub struct BundleQuery {
+    pub lang: Option<String>,
+    /// `gzip` forces compression, `none` disables it; otherwise `Accept-Encoding` decides.
+    pub compress: Option<String>,
+}
+
+pub async fn get_bundle(
+    State(state): State<ApiState>,
+    Query(params): Query<BundleQuery>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let lang = resolve_lang(params.lang.as_deref(), headers.get("accept-language"));
+    let (body, version) = match snapshot(&state, &lang).await {
+        Ok(snapshot) => snapshot,
+        Err(response) => return response,
+    };
+
+    let gzip = match params.compress.as_deref() {
+        Some("gzip") => true,
+        Some("none") => false,
+        _ => accepts_gzip(&headers),
+    };
+    // The gzip and identity bodies differ byte for byte, so each gets its own strong ETag.
+    let etag = if gzip {
+        format!("\"{}-gzip\"", version.version)
+    } else {
+        format!("\"{}\"", version.version)
+    };
+    let validators =
+        Validators::with_etag(etag).vary_accept_language(varies_by_header(params.lang.as_deref()));
+    if conditional::is_not_modified(&headers, &validators) {
+        let mut response = StatusCode::NOT_MODIFIED.into_response();
+        let response_headers = response.headers_mut();
+        conditional::apply_validators(response_headers, &validators);
+        response_headers.append(header::VARY, HeaderValue::from_static("Accept-Encoding"));
+        return response;
+    }
+
+    let body = if gzip {
+        let key = format!("bundle:{lang}:gzip:{}", version.version);
+        match state.cache.get(&key) {
+            Some(compressed) => compressed,
+            None => match gzip_bytes(&body) {
+                Ok(compressed) => {
+                    state.cache.insert(key, compressed.clone());
+                    compressed
+                }
+                Err(err) => {
+                    error!(error = ?err, "failed to compress bundle");
+                    return internal_error("failed to build bundle");
+                }
+            },
+        }
+    } else {
+        body
+    };
+
+    let mut response =
+        (StatusCode::OK, [(header::CONTENT_TYPE, "application/json")], body).into_response();
+    let response_headers = response.headers_mut();
+    conditional::apply_validators(response_headers, &validators);
+    response_headers.append(header::VARY, HeaderValue::from_static("Accept-Encoding"));
+    if gzip {
+        response_headers.insert(header::CONTENT_ENCODING, HeaderValue::from_static("gzip"));
+    }
+    if let Ok(value) = HeaderValue::from_str(&version.version) {
+        response_headers.insert("x-bundle-version", value);
+    }
+    response
+}
+
+pub async fn get_bundle_version(
+    State(state): State<ApiState>,
+    Query(params): Query<BundleQuery>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let lang = resolve_lang(params.lang.as_deref(), headers.get("accept-language"));
+    let version = match snapshot(&state, &lang).await {
+        Ok((_, version)) => version,
+        Err(response) => return response,
+    };
+
+    let validators = Validators::with_etag(format!("\"{}\"", version.version))
+        .vary_accept_language(varies_by_header(params.lang.as_deref()));
+    match serde_json::to_vec(&version) {
+        Ok(body) => conditional::respond(&headers, Bytes::from(body), validators),
+        Err(err) => {
+            error!(error = ?err, "failed to encode bundle version");
+            internal_error("failed to build bundle")
+        }
+    }
+}
+
+/// Returns the encoded bundle and its version summary, rebuilding both when either is missing
+/// from the response cache. Question writes invalidate the `bundle:` prefix.
+async fn snapshot(state: &ApiState, lang: &str) -> Result<(Bytes, BundleVersion), Response> {
+    let json_key = format!("bundle:{lang}:json");
+    let version_key = format!("bundle:{lang}:version");
+
+    if let (Some(body), Some(version)) = (state.cache.get(&json_key), state.cache.get(&version_key))
+        && let Ok(version) = serde_json::from_slice::<BundleVersion>(&version)
+    {
+        return Ok((body, version));
+    }
+
+    let content = match queries::load_content(&state.db, lang).await {
+        Ok(content) => content,
+        Err(err) => {
+            error!(error = ?err, "failed to load bundle content");
+            return Err(internal_error("failed to build bundle"));
+        }
+    };
+
+    let encoded = queries::build_bundle(content, lang).and_then(|(bundle, version)| {
+        let body = state.cache.store(json_key, &bundle)?;
+        state.cache.store(version_key, &version)?;
+        Ok((body, version))
+    });
+    encoded.map_err(|err| {
+        error!(error = ?err, "failed to encode bundle");
+        internal_error("failed to build bundle")
+    })
+}
+
+fn accepts_gzip(headers: &HeaderMap) -> bool {
+    headers.get(header::ACCEPT_ENCODING).and_then(|value| value.to_str().ok()).is_some_and(|value| {
+        value.split(',').any(|part| {
+            let mut pieces = part.trim().split(';');
+            let coding = pieces.next().unwrap_or_default().trim();
+            let rejected = pieces.any(|param| matches!(param.trim(), "q=0" | "q=0.0" | "q=0.00"));
+            coding.eq_ignore_ascii_case("gzip") && !rejected
+        })
+    })
+}
+
+fn gzip_bytes(body: &[u8]) -> std::io::Result<Bytes> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(body)?;
+    encoder.finish().map(Bytes::from)
+}
+
+fn internal_error(message: &'static str) -> Response {
+    (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": message }))).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::{HeaderMap, HeaderValue, header};
+
+    use super::accepts_gzip;
+
+    #[test]
+    fn negotiates_gzip_from_accept_encoding() {
+        let mut headers = HeaderMap::new();
+        assert!(!accepts_gzip(&headers));
+
+        headers.insert(header::ACCEPT_ENCODING, HeaderValue::from_static("br, gzip;q=0.8"));
+        assert!(accepts_gzip(&headers));
+
+        headers.insert(header::ACCEPT_ENCODING, HeaderValue::from_static("gzip;q=0, identity"));
+        assert!(!accepts_gzip(&headers));
+    }
+}
@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+
+use crate::resources::{eras::model::EraDto, questions::model::LocalizedQuestionDto};
+
+/// Bumped whenever the bundle layout changes in a way older clients cannot read.
+pub const BUNDLE_SCHEMA_VERSION: u32 = 1;
+
+/// Everything the app needs to play offline in one language.
+#[derive(Debug, Serialize)]
+pub struct BundleContent {
+    pub eras: Vec<EraDto>,
+    pub questions: Vec<LocalizedQuestionDto>,
+    pub levels: serde_json::Value,
+    pub locales: serde_json::Value,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Bundle {
+    pub schema_version: u32,
+    pub version: String,
+    pub lang: String,
+    pub generated_at: String,
+    #[serde(flatten)]
+    pub content: BundleContent,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BundleVersion {
+    pub schema_version: u32,
+    pub version: String,
+    pub lang: String,
+    pub generated_at: String,
+    pub era_count: usize,
+    pub episode_count: usize,
+    pub question_count: usize,
+}
@@ -0,0 +1,52 @@
+use chrono::Utc;
+use mongodb::Database;
+
+use crate::{
+    conditional,
+    resources::{
+        bundle::model::{BUNDLE_SCHEMA_VERSION, Bundle, BundleContent, BundleVersion},
+        eras::queries as era_queries,
+        questions::queries as question_queries,
+        ui::{levels::levels_config, locales::locales_config},
+    },
+};
+
+pub async fn load_content(db: &Database, lang: &str) -> mongodb::error::Result<BundleContent> {
+    let eras = era_queries::list_eras_full(db, lang).await?;
+    let questions =
+        question_queries::list_all_questions(db).await?.into_iter().map(|q| q.localize(lang)).collect();
+
+    Ok(BundleContent {
+        eras,
+        questions,
+        levels: levels_config(),
+        locales: locales_config(),
+    })
+}
+
+/// Wraps content in a bundle whose `version` is a hash of the content alone, so rebuilding an
+/// unchanged snapshot yields the same version regardless of `generated_at`.
+pub fn build_bundle(content: BundleContent, lang: &str) -> serde_json::Result<(Bundle, BundleVersion)> {
+    let encoded = serde_json::to_vec(&content)?;
+    let version =
+        conditional::content_hash(&[&BUNDLE_SCHEMA_VERSION.to_be_bytes(), lang.as_bytes(), &encoded]);
+    let generated_at = Utc::now().to_rfc3339();
+
+    let summary = BundleVersion {
+        schema_version: BUNDLE_SCHEMA_VERSION,
+        version: version.clone(),
+        lang: lang.to_string(),
+        generated_at: generated_at.clone(),
+        era_count: content.eras.len(),
+        episode_count: content.eras.iter().map(|era| era.episodes.len()).sum(),
+        question_count: content.questions.len(),
+    };
+    let bundle = Bundle {
+        schema_version: BUNDLE_SCHEMA_VERSION,
+        version,
+        lang: lang.to_string(),
+        generated_at,
+        content,
+    };
+    Ok((bundle, summary))
+}
@@ -0,0 +1,346 @@
+use axum::routing::{MethodRouter, get as axum_get, post as axum_post};
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+use chrono::{Duration, NaiveDate, Utc};
+use mongodb::bson::DateTime;
+use serde_json::json;
+use tracing::error;
+
+use crate::{
+    auth::Caller,
+    lang::resolve_lang,
+    resources::{
+        achievements::handler as achievement_handler,
+        challenges::{
+            model::{
+                Challenge, ChallengeDto, ChallengeResult, ChallengeResultDto, DATE_FORMAT,
+                LeaderboardEntryDto, LeaderboardQuery, SUBMIT_GRACE_MINUTES, SubmitChallenge,
+                accepts_results, challenge_date, pick_questions, player_key, session_for,
+            },
+            queries,
+        },
+        leaderboards::handler as leaderboard_handler,
+        play::model::{PlayQuery, PlayQuestionDto, shuffle_for, to_stored_answer},
+        questions::{
+            model::QuestionDto,
+            queries as question_queries,
+            types::{TimedAnswer, check_answer},
+        },
+        repetition::handler as repetition_handler,
+        stats::{handler as stats_handler, model::AnswerOutcome},
+        workflow::model::ContentStatus,
+    },
+    routes::api::ApiState,
+};
+
+pub fn today() -> MethodRouter<ApiState> {
+    axum_get(get_today)
+}
+
+pub fn by_date() -> MethodRouter<ApiState> {
+    axum_get(get_challenge)
+}
+
+pub fn results() -> MethodRouter<ApiState> {
+    axum_post(submit_result)
+}
+
+pub fn leaderboard() -> MethodRouter<ApiState> {
+    axum_get(get_leaderboard)
+}
+
+pub async fn get_today(
+    State(state): State<ApiState>,
+    Query(params): Query<PlayQuery>,
+    caller: Caller,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let date = challenge_date(Utc::now(), state.config.challenge_timezone);
+    challenge_response(&state, date, &params, &caller, &headers).await
+}
+
+pub async fn get_challenge(
+    State(state): State<ApiState>,
+    Path(date): Path<String>,
+    Query(params): Query<PlayQuery>,
+    caller: Caller,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    match parse_date(&state, &date) {
+        Ok(date) => challenge_response(&state, date, &params, &caller, &headers).await,
+        Err((status, msg)) => error_response(status, msg),
+    }
+}
+
+/// Grades a player's answers to a day's challenge. Each player gets one recorded attempt per day,
+/// timed by the server from their first signed-in fetch of that day's challenge. Only the current
+/// day (plus a short grace period) is open, so past days cannot be replayed for leaderboard points.
+pub async fn submit_result(
+    State(state): State<ApiState>,
+    Path(date): Path<String>,
+    Query(params): Query<PlayQuery>,
+    caller: Caller,
+    headers: HeaderMap,
+    Json(payload): Json<SubmitChallenge>,
+) -> impl IntoResponse {
+    let user_id = match caller.require_user() {
+        Ok(user_id) => user_id.to_string(),
+        Err(forbidden) => return forbidden.into_response(),
+    };
+    let date = match parse_date(&state, &date) {
+        Ok(date) => date,
+        Err((status, msg)) => return error_response(status, msg),
+    };
+    if !accepts_results(date, Utc::now(), state.config.challenge_timezone) {
+        return error_response(StatusCode::FORBIDDEN, "challenge is closed");
+    }
+    let (challenge, questions) = match challenge_questions(&state, date).await {
+        Ok(loaded) => loaded,
+        Err(response) => return response,
+    };
+    if payload.answers.len() != questions.len() {
+        return error_response(StatusCode::BAD_REQUEST, "one answer per challenge question is required");
+    }
+
+    let lang = resolve_lang(params.lang.as_deref(), headers.get("accept-language"));
+    let date = date.format(DATE_FORMAT).to_string();
+    let key = player_key(&date, &user_id);
+    // Players who never fetched the challenge signed in are timed from its creation, so skipping
+    // the fetch never shortens the clock.
+    let started_at = match queries::find_start(&state.db, &key).await {
+        Ok(start) => start.map_or(challenge.created_at, |start| start.started_at),
+        Err(err) => {
+            error!(error = ?err, "failed to load challenge start");
+            return error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to record result");
+        }
+    };
+    let session = challenge.shuffle_session();
+    let mut correct = Vec::with_capacity(questions.len());
+    let mut outcomes = Vec::with_capacity(questions.len());
+    for (
+        question,
+        TimedAnswer {
+            answer,
+            time_ms,
+        },
+    ) in questions.iter().zip(payload.answers)
+    {
+        let shuffle = shuffle_for(question, Some(&user_id), &session);
+        let check = to_stored_answer(answer, &shuffle)
+            .and_then(|answer| Ok((check_answer(question, &answer, &lang)?, answer)));
+        match check {
+            Ok((check, answer)) => {
+                outcomes.push(AnswerOutcome::new(&question.id, &answer, check.correct, time_ms));
+                correct.push(check.correct);
+            }
+            Err(msg) => return error_response(StatusCode::BAD_REQUEST, msg),
+        }
+    }
+
+    let completed_at = DateTime::now();
+    let result = ChallengeResult {
+        id: key,
+        date: date.clone(),
+        user_id,
+        score: correct.iter().filter(|&&c| c).count() as i32,
+        total: correct.len() as i32,
+        duration_ms: (completed_at.timestamp_millis() - started_at.timestamp_millis()).max(0),
+        completed_at,
+    };
+    match queries::insert_result(&state.db, &result).await {
+        Ok(true) => {
+            let eras: Vec<(Option<String>, bool)> =
+                questions.iter().map(|q| q.era.clone()).zip(correct.iter().copied()).collect();
+            leaderboard_handler::record_result(&state, &result.user_id, &eras).await;
+            achievement_handler::record_result(&state, &result.user_id, &eras).await;
+            let reviews: Vec<(String, bool)> =
+                questions.iter().map(|q| q.id.clone()).zip(correct.iter().copied()).collect();
+            repetition_handler::record_outcomes(&state, &result.user_id, &reviews).await;
+            stats_handler::record_answers(&state, Some(&result.user_id), &lang, &outcomes).await;
+            (
+                StatusCode::CREATED,
+                Json(ChallengeResultDto {
+                    date,
+                    score: result.score,
+                    total: result.total,
+                    duration_ms: result.duration_ms,
+                    correct,
+                }),
+            )
+                .into_response()
+        }
+        Ok(false) => error_response(StatusCode::CONFLICT, "challenge already completed"),
+        Err(err) => {
+            error!(error = ?err, "failed to record challenge result");
+            error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to record result")
+        }
+    }
+}
+
+pub async fn get_leaderboard(
+    State(state): State<ApiState>,
+    Path(date): Path<String>,
+    Query(params): Query<LeaderboardQuery>,
+) -> impl IntoResponse {
+    let date = match parse_date(&state, &date) {
+        Ok(date) => date.format(DATE_FORMAT).to_string(),
+        Err((status, msg)) => return error_response(status, msg),
+    };
+    let limit = params.limit.unwrap_or(50).min(100) as i64;
+    let offset = params.offset.unwrap_or(0);
+
+    match queries::list_results(&state.db, &date, limit, offset).await {
+        Ok(results) => {
+            let items: Vec<LeaderboardEntryDto> = results
+                .into_iter()
+                .enumerate()
+                .map(|(idx, r)| LeaderboardEntryDto {
+                    rank: offset + idx as u64 + 1,
+                    user_id: r.user_id,
+                    score: r.score,
+                    total: r.total,
+                    duration_ms: r.duration_ms,
+                })
+                .collect();
+            (StatusCode::OK, Json(json!({ "date": date, "items": items }))).into_response()
+        }
+        Err(err) => {
+            error!(error = ?err, "failed to load challenge leaderboard");
+            error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to load leaderboard")
+        }
+    }
+}
+
+async fn challenge_response(
+    state: &ApiState,
+    date: NaiveDate,
+    params: &PlayQuery,
+    caller: &Caller,
+    headers: &HeaderMap,
+) -> Response {
+    let (challenge, questions) = match challenge_questions(state, date).await {
+        Ok(loaded) => loaded,
+        Err(response) => return response,
+    };
+    let lang = resolve_lang(params.lang.as_deref(), headers.get("accept-language"));
+    let date = date.format(DATE_FORMAT).to_string();
+    if let Some(user_id) = caller.user_id.as_deref() {
+        let key = player_key(&date, user_id);
+        if let Err(err) = queries::record_start(&state.db, &key, &date, user_id).await {
+            error!(error = ?err, "failed to record challenge start");
+            return error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to load challenge");
+        }
+    }
+    let session = session_for(&date);
+    let shuffle_session = challenge.shuffle_session();
+    let items = questions
+        .into_iter()
+        .map(|question| {
+            let shuffle = shuffle_for(&question, caller.user_id.as_deref(), &shuffle_session);
+            PlayQuestionDto::new(question, &lang, session.clone(), &shuffle)
+        })
+        .collect();
+
+    let challenge = ChallengeDto {
+        date,
+        timezone: state.config.challenge_timezone.name().to_string(),
+        session,
+        items,
+    };
+    (StatusCode::OK, Json(challenge)).into_response()
+}
+
+/// Only today and earlier have challenges; future dates would leak upcoming questions.
+fn parse_date(state: &ApiState, raw: &str) -> Result<NaiveDate, (StatusCode, &'static str)> {
+    let date = NaiveDate::parse_from_str(raw, DATE_FORMAT)
+        .map_err(|_| (StatusCode::BAD_REQUEST, "date must be YYYY-MM-DD"))?;
+    if date > challenge_date(Utc::now(), state.config.challenge_timezone) {
+        return Err((StatusCode::NOT_FOUND, "no challenge for that date yet"));
+    }
+    Ok(date)
+}
+
+/// The day's challenge and its questions in challenge order, creating today's challenge on first
+/// request. Questions unpublished since then are left out.
+async fn challenge_questions(
+    state: &ApiState,
+    date: NaiveDate,
+) -> Result<(Challenge, Vec<QuestionDto>), Response> {
+    let challenge = match ensure_challenge(state, date).await {
+        Ok(Some(challenge)) => challenge,
+        Ok(None) => return Err(error_response(StatusCode::NOT_FOUND, "no challenge for that date")),
+        Err(err) => {
+            error!(error = ?err, "failed to load challenge");
+            return Err(error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to load challenge"));
+        }
+    };
+
+    let questions = match question_queries::find_questions_by_ids(&state.db, &challenge.question_ids)
+        .await
+    {
+        Ok(questions) => questions,
+        Err(err) => {
+            error!(error = ?err, "failed to load challenge questions");
+            return Err(error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to load challenge"));
+        }
+    };
+    let mut questions: Vec<QuestionDto> = questions
+        .into_iter()
+        .map(QuestionDto::from)
+        .filter(|q| q.status == ContentStatus::Published)
+        .collect();
+    questions.sort_by_key(|q| challenge.question_ids.iter().position(|id| *id == q.id));
+    Ok((challenge, questions))
+}
+
+/// Past days only serve the challenge they already have; a new one is only drawn for today.
+async fn ensure_challenge(
+    state: &ApiState,
+    date: NaiveDate,
+) -> mongodb::error::Result<Option<Challenge>> {
+    let key = date.format(DATE_FORMAT).to_string();
+    if let Some(challenge) = queries::find_challenge(&state.db, &key).await? {
+        return Ok(Some(challenge));
+    }
+    if date != challenge_date(Utc::now(), state.config.challenge_timezone) {
+        return Ok(None);
+    }
+
+    let window_start = (date - Duration::days(state.config.challenge_repeat_window_days.max(0)))
+        .format(DATE_FORMAT)
+        .to_string();
+    let recent = queries::questions_used_between(&state.db, &window_start, &key).await?;
+    let candidates = question_queries::list_published_ids(&state.db).await?;
+    let picked = pick_questions(&candidates, &recent, &key, state.config.challenge_size);
+    if picked.is_empty() {
+        return Ok(None);
+    }
+    queries::create_challenge(&state.db, &key, &picked).await
+}
+
+/// Whether `question_id` is in a challenge that still accepts results. Free play must not grade
+/// those questions, or it would hand out the challenge's solutions.
+pub async fn in_open_challenge(state: &ApiState, question_id: &str) -> mongodb::error::Result<bool> {
+    let now = Utc::now();
+    let tz = state.config.challenge_timezone;
+    let mut dates =
+        vec![challenge_date(now, tz), challenge_date(now - Duration::minutes(SUBMIT_GRACE_MINUTES), tz)];
+    dates.dedup();
+    for date in dates {
+        let key = date.format(DATE_FORMAT).to_string();
+        if let Some(challenge) = queries::find_challenge(&state.db, &key).await?
+            && challenge.question_ids.iter().any(|id| id == question_id)
+        {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+fn error_response(status: StatusCode, msg: &str) -> Response {
+    (status, Json(json!({ "error": msg }))).into_response()
+}
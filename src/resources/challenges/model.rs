@@ -0,0 +1,208 @@
+use std::{
+    collections::HashSet,
+    hash::{BuildHasher, Hasher, RandomState},
+};
+
+use chrono::{NaiveDate, Utc};
+use chrono_tz::Tz;
+use mongodb::bson::{DateTime, oid::ObjectId};
+use serde::{Deserialize, Serialize};
+
+use crate::resources::{
+    play::{model::PlayQuestionDto, shuffle::Shuffle},
+    questions::types::TimedAnswer,
+};
+
+pub const DATE_FORMAT: &str = "%Y-%m-%d";
+/// How long after midnight the previous day's challenge still accepts results, so a player who
+/// started just before the day ended can finish.
+pub const SUBMIT_GRACE_MINUTES: i64 = 15;
+const SESSION_PREFIX: &str = "challenge-";
+
+/// The questions of one calendar day's challenge (collection `challenges`, keyed by date).
+/// Stored on first request so later edits to the question pool never change a past day.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Challenge {
+    #[serde(rename = "_id")]
+    pub date: String,
+    pub question_ids: Vec<String>,
+    /// Never sent to clients. Mixed into the shuffle seed so the public session id cannot be used
+    /// to reproduce a player's option order elsewhere. Challenges stored before it existed have none.
+    #[serde(default)]
+    pub secret: String,
+    pub created_at: DateTime,
+}
+
+impl Challenge {
+    /// The session the day's options are shuffled under.
+    pub fn shuffle_session(&self) -> String {
+        format!("{}:{}", session_for(&self.date), self.secret)
+    }
+}
+
+/// When a player first fetched a day's challenge (collection `challenge_starts`, keyed by
+/// `<date>:<user_id>`). Results are timed from here.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChallengeStart {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub date: String,
+    pub user_id: String,
+    pub started_at: DateTime,
+}
+
+/// A player's single graded attempt at a day's challenge (collection `challenge_results`, keyed
+/// by `<date>:<user_id>`).
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChallengeResult {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub date: String,
+    pub user_id: String,
+    pub score: i32,
+    pub total: i32,
+    pub duration_ms: i64,
+    pub completed_at: DateTime,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ChallengeDto {
+    pub date: String,
+    pub timezone: String,
+    /// Play session to pass to the answer endpoints; every player shares it for the day.
+    pub session: String,
+    pub items: Vec<PlayQuestionDto>,
+}
+
+/// Answers in the order of the challenge's items, with positions as displayed to the player.
+#[derive(Debug, Deserialize)]
+pub struct SubmitChallenge {
+    pub answers: Vec<TimedAnswer>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ChallengeResultDto {
+    pub date: String,
+    pub score: i32,
+    pub total: i32,
+    pub duration_ms: i64,
+    pub correct: Vec<bool>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LeaderboardEntryDto {
+    pub rank: u64,
+    pub user_id: String,
+    pub score: i32,
+    pub total: i32,
+    pub duration_ms: i64,
+}
+
+#[derive(Deserialize)]
+pub struct LeaderboardQuery {
+    pub limit: Option<u64>,
+    pub offset: Option<u64>,
+}
+
+/// The calendar day `now` falls on in the challenge timezone.
+pub fn challenge_date(now: chrono::DateTime<Utc>, tz: Tz) -> NaiveDate {
+    now.with_timezone(&tz).date_naive()
+}
+
+/// Whether results for `date` are accepted at `now`: the current day, and the previous one for
+/// [`SUBMIT_GRACE_MINUTES`] after it ends.
+pub fn accepts_results(date: NaiveDate, now: chrono::DateTime<Utc>, tz: Tz) -> bool {
+    date == challenge_date(now, tz)
+        || date == challenge_date(now - chrono::Duration::minutes(SUBMIT_GRACE_MINUTES), tz)
+}
+
+pub fn session_for(date: &str) -> String {
+    format!("{SESSION_PREFIX}{date}")
+}
+
+pub fn is_challenge_session(session: &str) -> bool {
+    session.starts_with(SESSION_PREFIX)
+}
+
+/// `<date>:<user_id>`, the key of a player's start and result for a day.
+pub fn player_key(date: &str, user_id: &str) -> String {
+    format!("{date}:{user_id}")
+}
+
+/// A fresh challenge secret. `RandomState` keys come from the OS RNG, so its hashes cannot be
+/// predicted from anything a client sees.
+pub fn new_secret() -> String {
+    let bytes: Vec<u8> = (0..2)
+        .flat_map(|_| {
+            let mut hasher = RandomState::new().build_hasher();
+            hasher.write(&ObjectId::new().bytes());
+            hasher.finish().to_le_bytes()
+        })
+        .collect();
+    hex::encode(bytes)
+}
+
+/// Picks `size` of `candidates` for `date`, deterministically. Questions used within the repeat
+/// window (`recent`) are only drawn on once the fresh ones run out.
+pub fn pick_questions(
+    candidates: &[String],
+    recent: &HashSet<String>,
+    date: &str,
+    size: usize,
+) -> Vec<String> {
+    let (fresh, repeats): (Vec<String>, Vec<String>) =
+        candidates.iter().cloned().partition(|id| !recent.contains(id));
+    let seed = Shuffle::seed(None, "daily-challenge", date);
+
+    let mut picked = Shuffle::new(seed, fresh.len()).apply(&fresh);
+    picked.extend(Shuffle::new(seed, repeats.len()).apply(&repeats));
+    picked.truncate(size);
+    picked
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use chrono::{NaiveDate, TimeZone, Utc};
+    use chrono_tz::Tz;
+
+    use super::{accepts_results, challenge_date, pick_questions};
+
+    #[test]
+    fn picks_are_stable_and_avoid_recent_questions() {
+        let candidates: Vec<String> = (0..10).map(|n| format!("q{n}")).collect();
+        let recent: HashSet<String> = ["q1", "q2", "q3"].iter().map(|id| id.to_string()).collect();
+
+        let picked = pick_questions(&candidates, &recent, "2026-10-18", 5);
+        assert_eq!(picked, pick_questions(&candidates, &recent, "2026-10-18", 5));
+        assert_eq!(picked.len(), 5);
+        assert!(picked.iter().all(|id| !recent.contains(id)));
+
+        let short = pick_questions(&candidates, &recent, "2026-10-18", 9);
+        assert_eq!(short.iter().filter(|id| recent.contains(*id)).count(), 2);
+    }
+
+    #[test]
+    fn dates_follow_the_configured_timezone() {
+        let now = Utc.with_ymd_and_hms(2026, 10, 18, 23, 30, 0).unwrap();
+        assert_eq!(challenge_date(now, Tz::UTC), NaiveDate::from_ymd_opt(2026, 10, 18).unwrap());
+        assert_eq!(
+            challenge_date(now, Tz::Europe__Stockholm),
+            NaiveDate::from_ymd_opt(2026, 10, 19).unwrap()
+        );
+    }
+
+    #[test]
+    fn results_are_accepted_for_today_and_briefly_for_yesterday() {
+        let day = |d| NaiveDate::from_ymd_opt(2026, 10, d).unwrap();
+        let just_after_midnight = Utc.with_ymd_and_hms(2026, 10, 18, 0, 10, 0).unwrap();
+        assert!(accepts_results(day(18), just_after_midnight, Tz::UTC));
+        assert!(accepts_results(day(17), just_after_midnight, Tz::UTC));
+        assert!(!accepts_results(day(16), just_after_midnight, Tz::UTC));
+
+        let later = Utc.with_ymd_and_hms(2026, 10, 18, 0, 20, 0).unwrap();
+        assert!(!accepts_results(day(17), later, Tz::UTC));
+        assert!(!accepts_results(NaiveDate::from_ymd_opt(1900, 1, 1).unwrap(), later, Tz::UTC));
+    }
+}
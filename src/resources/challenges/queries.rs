@@ -0,0 +1,126 @@
+use std::collections::HashSet;
+
+use futures_util::TryStreamExt;
+use mongodb::{
+    Collection, Database, IndexModel,
+    bson::{DateTime, doc},
+    options::{FindOptions, UpdateOptions},
+};
+
+use crate::resources::challenges::model::{Challenge, ChallengeResult, ChallengeStart, new_secret};
+
+fn challenges_collection(db: &Database) -> Collection<Challenge> {
+    db.collection("challenges")
+}
+
+fn starts_collection(db: &Database) -> Collection<ChallengeStart> {
+    db.collection("challenge_starts")
+}
+
+fn results_collection(db: &Database) -> Collection<ChallengeResult> {
+    db.collection("challenge_results")
+}
+
+/// Index backing a day's result board in its sort order.
+pub async fn ensure_indexes(db: &Database) -> mongodb::error::Result<()> {
+    let index = IndexModel::builder()
+        .keys(doc! { "date": 1, "score": -1, "duration_ms": 1, "completed_at": 1 })
+        .build();
+    results_collection(db).create_index(index, None).await?;
+    Ok(())
+}
+
+pub async fn find_challenge(db: &Database, date: &str) -> mongodb::error::Result<Option<Challenge>> {
+    challenges_collection(db).find_one(doc! { "_id": date }, None).await
+}
+
+/// Stores a day's questions unless another request got there first; either way returns the
+/// challenge that is now stored.
+pub async fn create_challenge(
+    db: &Database,
+    date: &str,
+    question_ids: &[String],
+) -> mongodb::error::Result<Option<Challenge>> {
+    let update = doc! {
+        "$setOnInsert": {
+            "question_ids": question_ids,
+            "secret": new_secret(),
+            "created_at": DateTime::now(),
+        },
+    };
+    let options = UpdateOptions::builder().upsert(true).build();
+    challenges_collection(db).update_one(doc! { "_id": date }, update, options).await?;
+    find_challenge(db, date).await
+}
+
+/// Question ids used by challenges dated from `from` (inclusive) to `to` (exclusive).
+pub async fn questions_used_between(
+    db: &Database,
+    from: &str,
+    to: &str,
+) -> mongodb::error::Result<HashSet<String>> {
+    // `YYYY-MM-DD` keys sort chronologically.
+    let filter = doc! { "_id": { "$gte": from, "$lt": to } };
+    let mut cursor = challenges_collection(db).find(filter, None).await?;
+    let mut used = HashSet::new();
+    while let Some(challenge) = cursor.try_next().await? {
+        used.extend(challenge.question_ids);
+    }
+    Ok(used)
+}
+
+/// Records when a player first fetched a day's challenge; later fetches keep the original start.
+pub async fn record_start(
+    db: &Database,
+    id: &str,
+    date: &str,
+    user_id: &str,
+) -> mongodb::error::Result<()> {
+    let update = doc! {
+        "$setOnInsert": { "date": date, "user_id": user_id, "started_at": DateTime::now() },
+    };
+    let options = UpdateOptions::builder().upsert(true).build();
+    starts_collection(db).update_one(doc! { "_id": id }, update, options).await?;
+    Ok(())
+}
+
+pub async fn find_start(db: &Database, id: &str) -> mongodb::error::Result<Option<ChallengeStart>> {
+    starts_collection(db).find_one(doc! { "_id": id }, None).await
+}
+
+/// Records a result unless the player already has one for that day. Returns `false` if they did.
+pub async fn insert_result(db: &Database, result: &ChallengeResult) -> mongodb::error::Result<bool> {
+    let update = doc! {
+        "$setOnInsert": {
+            "date": &result.date,
+            "user_id": &result.user_id,
+            "score": result.score,
+            "total": result.total,
+            "duration_ms": result.duration_ms,
+            "completed_at": result.completed_at,
+        }
+    };
+    let options = UpdateOptions::builder().upsert(true).build();
+    let outcome = results_collection(db).update_one(doc! { "_id": &result.id }, update, options).await?;
+    Ok(outcome.upserted_id.is_some())
+}
+
+/// A day's results, best first: higher score, then faster, then earlier.
+pub async fn list_results(
+    db: &Database,
+    date: &str,
+    limit: i64,
+    offset: u64,
+) -> mongodb::error::Result<Vec<ChallengeResult>> {
+    let options = FindOptions::builder()
+        .sort(doc! { "score": -1, "duration_ms": 1, "completed_at": 1 })
+        .skip(Some(offset))
+        .limit(Some(limit))
+        .build();
+    let mut cursor = results_collection(db).find(doc! { "date": date }, options).await?;
+    let mut results = Vec::new();
+    while let Some(result) = cursor.try_next().await? {
+        results.push(result);
+    }
+    Ok(results)
+}
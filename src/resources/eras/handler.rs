@@ -1,15 +1,35 @@
-use axum::routing::{MethodRouter, get as axum_get};
+use axum::routing::{MethodRouter, get as axum_get, post as axum_post};
 use axum::{
     Json,
+    body::Bytes,
     extract::{Path, Query, State},
-    http::{HeaderMap, StatusCode},
-    response::IntoResponse,
+    http::{HeaderMap, StatusCode, header},
+    response::{IntoResponse, Response},
 };
+use mongodb::bson::{self, Bson, Document};
 use serde::{Deserialize, Serialize};
 use tracing::error;
 
 use crate::{
-    resources::eras::queries::{self, EpisodeLookup},
+    auth::Caller,
+    conditional::{self, Validators},
+    lang::is_supported_lang,
+    lang::{resolve_lang, varies_by_header},
+    resources::{
+        bookmarks::handler as bookmark_handler,
+        eras::queries::{self, EpisodeLookup},
+        revisions::{
+            model::{
+                ACTION_CREATE, ACTION_DELETE, ACTION_REVERT, ACTION_UPDATE, RevertRequest, change_reason,
+            },
+            queries::{self as revision_queries, NewRevision},
+        },
+        sync::{
+            model::{ENTITY_EPISODE, ENTITY_ERA},
+            queries as sync_queries,
+        },
+        workflow::model::{Visibility, visibility_for, working_copy},
+    },
     routes::api::ApiState,
 };
 
@@ -23,11 +43,16 @@ struct ErrorEnvelope {
 pub struct EpisodesSearchQuery {
     pub book: Option<String>,
     pub lang: Option<String>,
+    #[serde(default)]
+    pub preview: bool,
 }
 
+/// `preview=true` lets staff see unpublished eras; preview responses bypass the cache.
 #[derive(Deserialize)]
 pub struct LangQuery {
     pub lang: Option<String>,
+    #[serde(default)]
+    pub preview: bool,
 }
 
 pub fn collection() -> MethodRouter<ApiState> {
@@ -35,7 +60,11 @@ pub fn collection() -> MethodRouter<ApiState> {
 }
 
 pub fn era() -> MethodRouter<ApiState> {
-    axum_get(get_era)
+    axum_get(get_era).put(put_era).delete(delete_era)
+}
+
+pub fn revert() -> MethodRouter<ApiState> {
+    axum_post(revert_era)
 }
 
 pub fn episodes_collection() -> MethodRouter<ApiState> {
@@ -53,11 +82,27 @@ pub fn episodes_search() -> MethodRouter<ApiState> {
 pub async fn list_eras(
     State(state): State<ApiState>,
     Query(params): Query<LangQuery>,
+    caller: Caller,
     headers: HeaderMap,
 ) -> impl IntoResponse {
+    let visibility = match visibility_for(&caller, params.preview, None) {
+        Ok(visibility) => visibility,
+        Err(forbidden) => return forbidden.into_response(),
+    };
     let lang = resolve_lang(params.lang.as_deref(), headers.get("accept-language"));
-    match queries::list_eras(&state.db, &lang).await {
-        Ok(eras) => (StatusCode::OK, Json(eras)).into_response(),
+    let viewer = Viewer {
+        user_id: caller.user_id.as_deref(),
+        era_id: None,
+    };
+    let key = cache_key(visibility, format!("eras:list:{lang}"));
+    if let Some(body) = cached_body(&state, key.as_deref()) {
+        return localized_response(&state, &headers, viewer, body, &lang, params.lang.as_deref()).await;
+    }
+
+    match queries::list_eras(&state.db, &lang, visibility).await {
+        Ok(eras) => {
+            cached_response(&state, &headers, viewer, key, &eras, &lang, params.lang.as_deref()).await
+        }
         Err(err) => {
             error!(error = ?err, "failed to list eras");
             internal_error_response("failed to list eras")
@@ -69,15 +114,30 @@ pub async fn get_era(
     State(state): State<ApiState>,
     Path(era_id): Path<String>,
     Query(params): Query<LangQuery>,
+    caller: Caller,
     headers: HeaderMap,
 ) -> impl IntoResponse {
     if era_id.trim().is_empty() {
         return bad_request_response("eraId must not be empty");
     }
+    let visibility = match visibility_for(&caller, params.preview, None) {
+        Ok(visibility) => visibility,
+        Err(forbidden) => return forbidden.into_response(),
+    };
     let lang = resolve_lang(params.lang.as_deref(), headers.get("accept-language"));
+    let viewer = Viewer {
+        user_id: caller.user_id.as_deref(),
+        era_id: None,
+    };
+    let key = cache_key(visibility, format!("eras:era:{era_id}:{lang}"));
+    if let Some(body) = cached_body(&state, key.as_deref()) {
+        return localized_response(&state, &headers, viewer, body, &lang, params.lang.as_deref()).await;
+    }
 
-    match queries::find_era_by_id(&state.db, &era_id, &lang).await {
-        Ok(Some(era)) => (StatusCode::OK, Json(era)).into_response(),
+    match queries::find_era_by_id(&state.db, &era_id, &lang, visibility).await {
+        Ok(Some(era)) => {
+            cached_response(&state, &headers, viewer, key, &era, &lang, params.lang.as_deref()).await
+        }
         Ok(None) => not_found_response("Era not found"),
         Err(err) => {
             error!(error = ?err, "failed to fetch era");
@@ -90,15 +150,31 @@ pub async fn list_episodes_for_era(
     State(state): State<ApiState>,
     Path(era_id): Path<String>,
     Query(params): Query<LangQuery>,
+    caller: Caller,
     headers: HeaderMap,
 ) -> impl IntoResponse {
     if era_id.trim().is_empty() {
         return bad_request_response("eraId must not be empty");
     }
+    let visibility = match visibility_for(&caller, params.preview, None) {
+        Ok(visibility) => visibility,
+        Err(forbidden) => return forbidden.into_response(),
+    };
     let lang = resolve_lang(params.lang.as_deref(), headers.get("accept-language"));
+    let viewer = Viewer {
+        user_id: caller.user_id.as_deref(),
+        era_id: Some(&era_id),
+    };
+    let key = cache_key(visibility, format!("eras:episodes:{era_id}:{lang}"));
+    if let Some(body) = cached_body(&state, key.as_deref()) {
+        return localized_response(&state, &headers, viewer, body, &lang, params.lang.as_deref()).await;
+    }
 
-    match queries::list_episodes_for_era(&state.db, &era_id, &lang).await {
-        Ok(Some(episodes)) => (StatusCode::OK, Json(episodes)).into_response(),
+    match queries::list_episodes_for_era(&state.db, &era_id, &lang, visibility).await {
+        Ok(Some(episodes)) => {
+            cached_response(&state, &headers, viewer, key, &episodes, &lang, params.lang.as_deref())
+                .await
+        }
         Ok(None) => not_found_response("Era not found"),
         Err(err) => {
             error!(error = ?err, "failed to list episodes for era");
@@ -111,6 +187,7 @@ pub async fn get_episode(
     State(state): State<ApiState>,
     Path((era_id, episode_id)): Path<(String, String)>,
     Query(params): Query<LangQuery>,
+    caller: Caller,
     headers: HeaderMap,
 ) -> impl IntoResponse {
     if era_id.trim().is_empty() {
@@ -119,10 +196,24 @@ pub async fn get_episode(
     if episode_id.trim().is_empty() {
         return bad_request_response("episodeId must not be empty");
     }
+    let visibility = match visibility_for(&caller, params.preview, None) {
+        Ok(visibility) => visibility,
+        Err(forbidden) => return forbidden.into_response(),
+    };
     let lang = resolve_lang(params.lang.as_deref(), headers.get("accept-language"));
+    let viewer = Viewer {
+        user_id: caller.user_id.as_deref(),
+        era_id: Some(&era_id),
+    };
+    let key = cache_key(visibility, format!("eras:episode:{era_id}:{episode_id}:{lang}"));
+    if let Some(body) = cached_body(&state, key.as_deref()) {
+        return localized_response(&state, &headers, viewer, body, &lang, params.lang.as_deref()).await;
+    }
 
-    match queries::find_episode_for_era(&state.db, &era_id, &episode_id, &lang).await {
-        Ok(EpisodeLookup::Found(episode)) => (StatusCode::OK, Json(episode)).into_response(),
+    match queries::find_episode_for_era(&state.db, &era_id, &episode_id, &lang, visibility).await {
+        Ok(EpisodeLookup::Found(episode)) => {
+            cached_response(&state, &headers, viewer, key, &episode, &lang, params.lang.as_deref()).await
+        }
         Ok(EpisodeLookup::EraNotFound) => not_found_response("Era not found"),
         Ok(EpisodeLookup::EpisodeNotFound) => not_found_response("Episode not found under era"),
         Err(err) => {
@@ -134,6 +225,7 @@ pub async fn get_episode(
 
 pub async fn search_episodes(
     State(state): State<ApiState>,
+    caller: Caller,
     headers: HeaderMap,
     Query(params): Query<EpisodesSearchQuery>,
 ) -> impl IntoResponse {
@@ -147,8 +239,24 @@ pub async fn search_episodes(
         return bad_request_response("book query parameter must not be empty");
     }
 
-    match queries::search_episodes_by_book(&state.db, book, &lang).await {
-        Ok(episodes) => (StatusCode::OK, Json(episodes)).into_response(),
+    let visibility = match visibility_for(&caller, params.preview, None) {
+        Ok(visibility) => visibility,
+        Err(forbidden) => return forbidden.into_response(),
+    };
+    let viewer = Viewer {
+        user_id: caller.user_id.as_deref(),
+        era_id: None,
+    };
+    let key = cache_key(visibility, format!("eras:search:{book}:{lang}"));
+    if let Some(body) = cached_body(&state, key.as_deref()) {
+        return localized_response(&state, &headers, viewer, body, &lang, params.lang.as_deref()).await;
+    }
+
+    match queries::search_episodes_by_book(&state.db, book, &lang, visibility).await {
+        Ok(episodes) => {
+            cached_response(&state, &headers, viewer, key, &episodes, &lang, params.lang.as_deref())
+                .await
+        }
         Err(err) => {
             error!(error = ?err, "failed to search episodes by book");
             internal_error_response("failed to search episodes")
@@ -156,6 +264,398 @@ pub async fn search_episodes(
     }
 }
 
+/// Creates or replaces an era from its full multi-locale document
+/// (`{"type": ..., "en": {...}, "es": {...}, ...}`). The era is stored as a draft; if it was
+/// published, it and its episodes are reported as deleted to delta sync until it is published again.
+pub async fn put_era(
+    State(state): State<ApiState>,
+    Path(era_id): Path<String>,
+    caller: Caller,
+    headers: HeaderMap,
+    Json(payload): Json<serde_json::Value>,
+) -> impl IntoResponse {
+    if let Err(forbidden) = caller.require_editor() {
+        return forbidden.into_response();
+    }
+    if era_id.trim().is_empty() {
+        return bad_request_response("eraId must not be empty");
+    }
+    let era = match bson::to_document(&payload) {
+        Ok(era) => era,
+        Err(_) => return bad_request_response("era must be a JSON object"),
+    };
+    if let Err(msg) = validate_era_document(&era) {
+        return bad_request_response(msg);
+    }
+
+    store_era(&state, &era_id, era, None, change_reason(&headers), &caller, &headers).await
+}
+
+/// Restores an era to the content editors had at revision `number` (a pending edit, if there was
+/// one), as a new draft. The revert itself is appended as a new revision, so history is never
+/// rewritten.
+pub async fn revert_era(
+    State(state): State<ApiState>,
+    Path((era_id, number)): Path<(String, i64)>,
+    caller: Caller,
+    headers: HeaderMap,
+    body: Option<Json<RevertRequest>>,
+) -> impl IntoResponse {
+    if let Err(forbidden) = caller.require_editor() {
+        return forbidden.into_response();
+    }
+
+    let mut era = match revision_queries::find_revision(&state.db, ENTITY_ERA, &era_id, number).await {
+        Ok(Some(revision)) => working_copy(&revision.snapshot),
+        Ok(None) => return not_found_response("revision not found"),
+        Err(err) => {
+            error!(error = ?err, "failed to fetch revision");
+            return internal_error_response("failed to revert era");
+        }
+    };
+    for managed in queries::MANAGED_FIELDS {
+        era.remove(managed);
+    }
+    if validate_era_document(&era).is_err() {
+        error!(era_id, number, "revision snapshot is not an era");
+        return (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(ErrorEnvelope {
+                error: "UnprocessableEntity",
+                message: "revision cannot be reverted to".to_string(),
+            }),
+        )
+            .into_response();
+    }
+
+    let reason = body
+        .and_then(|Json(body)| body.reason)
+        .or_else(|| change_reason(&headers))
+        .unwrap_or_else(|| format!("reverted to revision {number}"));
+    store_era(&state, &era_id, era, Some(ACTION_REVERT), Some(reason), &caller, &headers).await
+}
+
+/// Stores `era` as a draft and records the revision, honouring `If-Match`. A published era stays
+/// live and gets `era` as its pending edit until that is published. `action` defaults to create or
+/// update.
+async fn store_era(
+    state: &ApiState,
+    era_id: &str,
+    era: Document,
+    action: Option<&str>,
+    reason: Option<String>,
+    caller: &Caller,
+    headers: &HeaderMap,
+) -> Response {
+    let lang = resolve_lang(None, headers.get("accept-language"));
+    let current = match check_if_match(state, era_id, caller, headers, &lang).await {
+        Ok(current) => current,
+        Err(response) => return response,
+    };
+    let era = queries::edited_era(era_id, era, current.as_ref());
+    let created = current.is_none();
+    let write = WriteEra {
+        era_id,
+        action: action.unwrap_or(if created {
+            ACTION_CREATE
+        } else {
+            ACTION_UPDATE
+        }),
+        caller,
+        reason,
+        failure: "failed to store era",
+    };
+    let stored = queries::write_era(&state.db, era_id, current.as_ref(), &era);
+    match write_era(state, era.clone(), write, stored).await {
+        Ok(true) => {}
+        Ok(false) => return conditional::precondition_failed(),
+        Err(response) => return response,
+    }
+    invalidate_era_caches(state);
+
+    let status = if created {
+        StatusCode::CREATED
+    } else {
+        StatusCode::OK
+    };
+    match era_representation(state, era_id, caller, &lang).await {
+        Ok(Some((body, etag))) => (
+            status,
+            [(header::CONTENT_TYPE, "application/json".to_string()), (header::ETAG, etag)],
+            body,
+        )
+            .into_response(),
+        Ok(None) => not_found_response("Era not found"),
+        Err(err) => {
+            error!(error = ?err, "failed to fetch era");
+            internal_error_response("failed to fetch era")
+        }
+    }
+}
+
+pub async fn delete_era(
+    State(state): State<ApiState>,
+    Path(era_id): Path<String>,
+    caller: Caller,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    if let Err(forbidden) = caller.require_editor() {
+        return forbidden.into_response();
+    }
+    let lang = resolve_lang(None, headers.get("accept-language"));
+    let current = match check_if_match(&state, &era_id, &caller, &headers, &lang).await {
+        Ok(Some(current)) => current,
+        Ok(None) => return not_found_response("Era not found"),
+        Err(response) => return response,
+    };
+    let write = WriteEra {
+        era_id: &era_id,
+        action: ACTION_DELETE,
+        caller: &caller,
+        reason: change_reason(&headers),
+        failure: "failed to delete era",
+    };
+    let deleted = queries::delete_era(&state.db, &era_id, &current);
+    match write_era(&state, current.clone(), write, deleted).await {
+        Ok(true) => {
+            invalidate_era_caches(&state);
+            let episode_ids: Vec<String> = queries::episode_ids(&current).into_iter().collect();
+            let tombstones = async {
+                sync_queries::record_tombstones(
+                    &state.db,
+                    ENTITY_ERA,
+                    std::slice::from_ref(&era_id),
+                    None,
+                )
+                .await?;
+                sync_queries::record_tombstones(&state.db, ENTITY_EPISODE, &episode_ids, Some(&era_id))
+                    .await
+            };
+            if let Err(err) = tombstones.await {
+                error!(error = ?err, era_id, "failed to record era tombstones");
+            }
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Ok(false) => conditional::precondition_failed(),
+        Err(response) => response,
+    }
+}
+
+/// Checks `If-Match` against the era as the caller reads it (`GET /v1/eras/:eraId?preview=true`
+/// in the request's `Accept-Language`) and returns the stored document for the write's
+/// `updated_at` guard, or `None` if the era does not exist yet. An `If-Match` on a missing era
+/// always fails.
+async fn check_if_match(
+    state: &ApiState,
+    era_id: &str,
+    caller: &Caller,
+    headers: &HeaderMap,
+    lang: &str,
+) -> Result<Option<Document>, Response> {
+    let current = match queries::find_era_document(&state.db, era_id).await {
+        Ok(current) => current,
+        Err(err) => {
+            error!(error = ?err, "failed to fetch era");
+            return Err(internal_error_response("failed to fetch era"));
+        }
+    };
+    if !headers.contains_key(header::IF_MATCH) {
+        return Ok(current);
+    }
+    if current.is_none() {
+        return Err(conditional::precondition_failed());
+    }
+    match era_representation(state, era_id, caller, lang).await {
+        Ok(Some((_, etag))) if conditional::if_match_allows(headers, &etag) => Ok(current),
+        Ok(_) => Err(conditional::precondition_failed()),
+        Err(err) => {
+            error!(error = ?err, "failed to fetch era");
+            Err(internal_error_response("failed to fetch era"))
+        }
+    }
+}
+
+/// The era body `caller` gets from a preview read in `lang`, with its ETag.
+async fn era_representation(
+    state: &ApiState,
+    era_id: &str,
+    caller: &Caller,
+    lang: &str,
+) -> mongodb::error::Result<Option<(Bytes, String)>> {
+    let Some(era) = queries::find_era_by_id(&state.db, era_id, lang, Visibility::Any).await? else {
+        return Ok(None);
+    };
+    let body = Bytes::from(serde_json::to_vec(&era).unwrap_or_default());
+    let body = match caller.user_id.as_deref() {
+        Some(user_id) => bookmark_handler::mark_bookmarked(state, user_id, body, None).await,
+        None => body,
+    };
+    let etag = Validators::for_body(&body, Some(lang)).etag;
+    Ok(Some((body, etag)))
+}
+
+/// How an era write is recorded in its history.
+pub struct WriteEra<'a> {
+    pub era_id: &'a str,
+    pub action: &'a str,
+    pub caller: &'a Caller,
+    pub reason: Option<String>,
+    /// Error message for a failed write.
+    pub failure: &'a str,
+}
+
+/// Appends `snapshot` to the era's history, then runs `store`, which writes that same document
+/// (or deletes it). The revision goes first so no change is stored without one; if the write does
+/// not apply, the revision is withdrawn again. `Ok(false)` means the era changed since it was read;
+/// any failure comes back as a `500` for the caller to return.
+pub async fn write_era(
+    state: &ApiState,
+    snapshot: Document,
+    write: WriteEra<'_>,
+    store: impl Future<Output = mongodb::error::Result<bool>>,
+) -> Result<bool, Response> {
+    let WriteEra {
+        era_id,
+        action,
+        caller,
+        reason,
+        failure,
+    } = write;
+    let revision = NewRevision {
+        entity: ENTITY_ERA,
+        entity_id: era_id,
+        action,
+        snapshot,
+        author: caller.user_id.as_deref(),
+        reason: reason.as_deref(),
+    };
+    let revision = match revision_queries::record_revision(&state.db, revision).await {
+        Ok(revision) => revision,
+        Err(err) => {
+            error!(error = ?err, era_id, action, "failed to record era revision");
+            return Err(internal_error_response("failed to record revision"));
+        }
+    };
+
+    let stored = store.await;
+    if !matches!(stored, Ok(true))
+        && let Err(err) = revision_queries::withdraw_revision(&state.db, revision.id).await
+    {
+        error!(error = ?err, era_id, action, "failed to withdraw era revision");
+    }
+    stored.map_err(|err| {
+        error!(error = ?err, era_id, action, "failed to write era");
+        internal_error_response(failure)
+    })
+}
+
+fn invalidate_era_caches(state: &ApiState) {
+    state.cache.invalidate_prefix("eras:");
+    state.cache.invalidate_prefix("bundle:");
+}
+
+fn validate_era_document(era: &Document) -> Result<(), &'static str> {
+    let mut has_en = false;
+    for (key, value) in era {
+        match key.as_str() {
+            "_id" | "updated_at" => return Err("_id and updated_at are managed by the server"),
+            "type" => {
+                if !matches!(value, Bson::String(_) | Bson::Null) {
+                    return Err("type must be a string");
+                }
+            }
+            lang if is_supported_lang(lang) => {
+                let Bson::Document(locale) = value else {
+                    return Err("each locale must be an object");
+                };
+                if !matches!(locale.get("name"), Some(Bson::String(name)) if !name.trim().is_empty()) {
+                    return Err("each locale requires a name");
+                }
+                if let Some(episodes) = locale.get("episodes") {
+                    let Bson::Array(episodes) = episodes else {
+                        return Err("episodes must be an array");
+                    };
+                    let all_have_ids = episodes.iter().all(|episode| {
+                        matches!(
+                            episode.as_document().and_then(|e| e.get("id")),
+                            Some(Bson::String(id)) if !id.trim().is_empty()
+                        )
+                    });
+                    if !all_have_ids {
+                        return Err("every episode requires an id");
+                    }
+                }
+                has_en |= lang == "en";
+            }
+            _ => return Err("unsupported era field; expected type, en, es, pt or sv"),
+        }
+    }
+
+    if has_en {
+        Ok(())
+    } else {
+        Err("era requires an en locale")
+    }
+}
+
+/// Only the public (published) view is cached; previews are always read fresh.
+fn cache_key(visibility: Visibility, key: String) -> Option<String> {
+    (visibility == Visibility::Published).then_some(key)
+}
+
+fn cached_body(state: &ApiState, key: Option<&str>) -> Option<Bytes> {
+    key.and_then(|key| state.cache.get(key))
+}
+
+/// Who an eras response is for: signed-in callers get `bookmarked` flags. `era_id` is set when the
+/// body holds that era's episodes.
+#[derive(Clone, Copy)]
+struct Viewer<'a> {
+    user_id: Option<&'a str>,
+    era_id: Option<&'a str>,
+}
+
+async fn cached_response<T: Serialize>(
+    state: &ApiState,
+    headers: &HeaderMap,
+    viewer: Viewer<'_>,
+    key: Option<String>,
+    value: &T,
+    lang: &str,
+    query_lang: Option<&str>,
+) -> axum::response::Response {
+    let body = match key {
+        Some(key) => state.cache.store(key, value),
+        None => serde_json::to_vec(value).map(Bytes::from),
+    };
+    match body {
+        Ok(body) => localized_response(state, headers, viewer, body, lang, query_lang).await,
+        Err(err) => {
+            error!(error = ?err, "failed to encode eras response");
+            internal_error_response("failed to encode response")
+        }
+    }
+}
+
+/// The cached body stays the same for everyone; bookmarks are marked per response.
+async fn localized_response(
+    state: &ApiState,
+    headers: &HeaderMap,
+    viewer: Viewer<'_>,
+    body: Bytes,
+    lang: &str,
+    query_lang: Option<&str>,
+) -> axum::response::Response {
+    let body = match viewer.user_id {
+        Some(user_id) => bookmark_handler::mark_bookmarked(state, user_id, body, viewer.era_id).await,
+        None => body,
+    };
+    let validators = Validators::for_body(&body, Some(lang))
+        .vary_accept_language(varies_by_header(query_lang))
+        .per_user(viewer.user_id.is_some());
+    conditional::respond(headers, body, validators)
+}
+
 fn not_found_response(message: impl Into<String>) -> axum::response::Response {
     (
         StatusCode::NOT_FOUND,
@@ -189,51 +689,27 @@ fn internal_error_response(message: impl Into<String>) -> axum::response::Respon
         .into_response()
 }
 
-fn resolve_lang(query_lang: Option<&str>, accept_language: Option<&axum::http::HeaderValue>) -> String {
-    if let Some(lang) = query_lang.and_then(normalize_lang) {
-        return lang.to_string();
-    }
-
-    if let Some(lang) =
-        accept_language.and_then(|header| header.to_str().ok()).and_then(resolve_from_accept_language)
-    {
-        return lang.to_string();
-    }
+#[cfg(test)]
+mod tests {
+    use mongodb::bson::doc;
 
-    "en".to_string()
-}
+    use super::validate_era_document;
 
-fn resolve_from_accept_language(value: &str) -> Option<&str> {
-    value
-        .split(',')
-        .filter_map(|part| {
-            let base = part.trim().split(';').next()?.trim();
-            normalize_lang(base)
-        })
-        .find(|lang| is_supported_lang(lang))
-}
+    #[test]
+    fn era_document_requires_english_locale() {
+        let era = doc! {"es": {"name": "Creación"}};
+        assert!(validate_era_document(&era).is_err());
 
-fn normalize_lang(value: &str) -> Option<&str> {
-    let normalized = value.trim().split('-').next()?.to_ascii_lowercase();
-    match normalized.as_str() {
-        "en" => Some("en"),
-        "es" => Some("es"),
-        "pt" => Some("pt"),
-        "sv" => Some("sv"),
-        _ => None,
+        let era = doc! {"type": "meta", "en": {"name": "Creation", "episodes": [{"id": "world"}]}};
+        assert!(validate_era_document(&era).is_ok());
     }
-}
-
-fn is_supported_lang(value: &str) -> bool {
-    matches!(value, "en" | "es" | "pt" | "sv")
-}
-
-#[cfg(test)]
-mod tests {
-    use super::resolve_from_accept_language;
 
     #[test]
-    fn picks_first_supported_accept_language() {
-        assert_eq!(resolve_from_accept_language("fr-FR, sv-SE;q=0.9, en;q=0.8"), Some("sv"));
+    fn era_document_rejects_episodes_without_ids() {
+        let era = doc! {"en": {"name": "Creation", "episodes": [{"name": "World"}]}};
+        assert!(validate_era_document(&era).is_err());
+
+        let era = doc! {"en": {"name": "Creation"}, "de": {"name": "Schöpfung"}};
+        assert!(validate_era_document(&era).is_err());
     }
 }
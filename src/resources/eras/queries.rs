@@ -1,12 +1,21 @@
+use std::collections::BTreeSet;
+
 use futures_util::TryStreamExt;
 use mongodb::{
     Collection, Database,
-    bson::{Bson, Document, doc},
+    bson::{Bson, DateTime, Document, doc},
     options::FindOneOptions,
 };
 
-use crate::resources::eras::model::{
-    EpisodeDto, EpisodeListItem, EpisodeSearchItem, EraDto, EraListItem, Reference,
+use crate::{
+    db::{is_duplicate_key, next_updated_at},
+    resources::{
+        eras::model::{EpisodeDto, EpisodeListItem, EpisodeSearchItem, EraDto, EraListItem, Reference},
+        workflow::model::{
+            ContentStatus, PENDING_FIELD, Visibility, edited, guard_pending, working_copy,
+            working_status_filter,
+        },
+    },
 };
 
 pub enum EpisodeLookup {
@@ -15,11 +24,18 @@ pub enum EpisodeLookup {
     Found(EpisodeDto),
 }
 
+/// Era document fields the server maintains; everything else is content.
+pub const MANAGED_FIELDS: [&str; 4] = ["_id", "status", "updated_at", PENDING_FIELD];
+
 fn eras_collection(db: &Database) -> Collection<Document> {
     db.collection("eras")
 }
 
-pub async fn list_eras(db: &Database, lang: &str) -> mongodb::error::Result<Vec<EraListItem>> {
+pub async fn list_eras(
+    db: &Database,
+    lang: &str,
+    visibility: Visibility,
+) -> mongodb::error::Result<Vec<EraListItem>> {
     let mut projection = doc! {"_id": 1, "type": 1};
     projection.insert(format!("{lang}.name"), 1);
     projection.insert(format!("{lang}.label"), 1);
@@ -32,14 +48,48 @@ pub async fn list_eras(db: &Database, lang: &str) -> mongodb::error::Result<Vec<
         .projection(projection)
         .sort(doc! {format!("{lang}.order"): 1, "_id": 1})
         .build();
-    let mut cursor = eras_collection(db).find(doc! {}, options).await?;
+    let mut cursor = eras_collection(db).find(visible(doc! {}, visibility), options).await?;
 
     let mut eras = Vec::new();
     while let Some(era) = cursor.try_next().await? {
         eras.push(parse_era_list_item(era, lang));
     }
 
-    sort_eras(&mut eras);
+    sort_eras(&mut eras, |era| (era.era_type.as_deref(), era.order, &era.id));
+    Ok(eras)
+}
+
+/// Full published era documents (with episodes and references) for one language, in display order.
+pub async fn list_eras_full(db: &Database, lang: &str) -> mongodb::error::Result<Vec<EraDto>> {
+    find_eras_full(db, visible(doc! {}, Visibility::Published), lang).await
+}
+
+/// Eras written through the API at or after `since`. Eras imported without `updated_at` are only
+/// part of full snapshots.
+pub async fn list_eras_updated_since(
+    db: &Database,
+    since: DateTime,
+    lang: &str,
+) -> mongodb::error::Result<Vec<EraDto>> {
+    find_eras_full(db, visible(doc! {"updated_at": {"$gte": since}}, Visibility::Published), lang).await
+}
+
+async fn find_eras_full(
+    db: &Database,
+    filter: Document,
+    lang: &str,
+) -> mongodb::error::Result<Vec<EraDto>> {
+    let mut projection = doc! {"_id": 1, "type": 1};
+    projection.insert(lang, 1);
+    let options = mongodb::options::FindOptions::builder().projection(projection).build();
+    let mut cursor = eras_collection(db).find(filter, options).await?;
+
+    let mut eras = Vec::new();
+    while let Some(era) = cursor.try_next().await? {
+        eras.push(parse_era(era, lang));
+    }
+
+    sort_eras(&mut eras, |era| (era.era_type.as_deref(), era.order, &era.id));
     Ok(eras)
 }
 
@@ -47,11 +97,9 @@ pub async fn find_era_by_id(
     db: &Database,
     era_id: &str,
     lang: &str,
+    visibility: Visibility,
 ) -> mongodb::error::Result<Option<EraDto>> {
-    let mut projection = doc! {"_id": 1, "type": 1};
-    projection.insert(lang, 1);
-    let options = FindOneOptions::builder().projection(projection).build();
-    let era = eras_collection(db).find_one(doc! {"_id": era_id}, options).await?;
+    let era = find_visible_era(db, era_id, &["_id", "type", lang], visibility).await?;
     Ok(era.map(|doc| parse_era(doc, lang)))
 }
 
@@ -59,11 +107,9 @@ pub async fn list_episodes_for_era(
     db: &Database,
     era_id: &str,
     lang: &str,
+    visibility: Visibility,
 ) -> mongodb::error::Result<Option<Vec<EpisodeListItem>>> {
-    let mut projection = Document::new();
-    projection.insert(format!("{lang}.episodes"), 1);
-    let options = FindOneOptions::builder().projection(projection).build();
-    let era = eras_collection(db).find_one(doc! {"_id": era_id}, options).await?;
+    let era = find_visible_era(db, era_id, &[lang], visibility).await?;
     Ok(era.map(|doc| parse_episode_list(doc, lang)))
 }
 
@@ -72,11 +118,9 @@ pub async fn find_episode_for_era(
     era_id: &str,
     episode_id: &str,
     lang: &str,
+    visibility: Visibility,
 ) -> mongodb::error::Result<EpisodeLookup> {
-    let mut projection = Document::new();
-    projection.insert(format!("{lang}.episodes"), 1);
-    let options = FindOneOptions::builder().projection(projection).build();
-    let era = eras_collection(db).find_one(doc! {"_id": era_id}, options).await?;
+    let era = find_visible_era(db, era_id, &[lang], visibility).await?;
     let Some(era) = era else {
         return Ok(EpisodeLookup::EraNotFound);
     };
@@ -90,6 +134,131 @@ pub async fn find_episode_for_era(
     Ok(EpisodeLookup::EpisodeNotFound)
 }
 
+/// One era as `visibility` reads it, limited to the top-level `fields`. Preview reads (anything
+/// but the published view) get the working copy, with a pending edit in place of the published
+/// content.
+async fn find_visible_era(
+    db: &Database,
+    era_id: &str,
+    fields: &[&str],
+    visibility: Visibility,
+) -> mongodb::error::Result<Option<Document>> {
+    let preview = visibility != Visibility::Published;
+    let mut projection = Document::new();
+    for field in fields {
+        projection.insert(*field, 1);
+        if preview {
+            projection.insert(format!("{PENDING_FIELD}.{field}"), 1);
+        }
+    }
+    let options = FindOneOptions::builder().projection(projection).build();
+    let era = eras_collection(db).find_one(visible(doc! {"_id": era_id}, visibility), options).await?;
+    Ok(era.map(|era| {
+        if preview {
+            working_copy(&era)
+        } else {
+            era
+        }
+    }))
+}
+
+/// The era stored under `era_id` once the full multi-locale document `era` replaces `previous`,
+/// if any. A published era keeps serving its current content and stages the replacement as a
+/// pending draft; anything else is replaced as a draft.
+pub fn edited_era(era_id: &str, mut era: Document, previous: Option<&Document>) -> Document {
+    let Some(previous) = previous else {
+        era.insert("_id", era_id);
+        era.insert("status", ContentStatus::Draft.as_str());
+        era.insert("updated_at", next_updated_at(None));
+        return era;
+    };
+    // Content the replacement leaves out (a dropped locale or `type`) is removed, not kept.
+    for key in previous.keys() {
+        if !MANAGED_FIELDS.contains(&key.as_str()) && !era.contains_key(key) {
+            era.insert(key.clone(), Bson::Null);
+        }
+    }
+    edited(previous, era)
+}
+
+/// Stores `era` in place of `previous`, or inserts it when `previous` is `None`. Returns `false`
+/// without writing if the era was modified (or, when `previous` is `None`, created) since.
+pub async fn write_era(
+    db: &Database,
+    era_id: &str,
+    previous: Option<&Document>,
+    era: &Document,
+) -> mongodb::error::Result<bool> {
+    match previous {
+        Some(previous) => {
+            let filter = unmodified_filter(era_id, previous);
+            Ok(eras_collection(db).replace_one(filter, era, None).await?.matched_count > 0)
+        }
+        None => match eras_collection(db).insert_one(era, None).await {
+            Ok(_) => Ok(true),
+            Err(err) if is_duplicate_key(&err) => Ok(false),
+            Err(err) => Err(err),
+        },
+    }
+}
+
+/// Raw stored era document, whatever its status.
+pub async fn find_era_document(db: &Database, era_id: &str) -> mongodb::error::Result<Option<Document>> {
+    eras_collection(db).find_one(doc! {"_id": era_id}, None).await
+}
+
+/// Eras, or pending edits of published ones, waiting for review, as reviewers see them.
+pub async fn list_eras_in_review(db: &Database) -> mongodb::error::Result<Vec<Document>> {
+    let options =
+        mongodb::options::FindOptions::builder().sort(doc! {"updated_at": 1, "_id": 1}).build();
+    let mut cursor =
+        eras_collection(db).find(working_status_filter(ContentStatus::InReview), options).await?;
+
+    let mut eras = Vec::new();
+    while let Some(era) = cursor.try_next().await? {
+        eras.push(working_copy(&era));
+    }
+    Ok(eras)
+}
+
+fn visible(mut filter: Document, visibility: Visibility) -> Document {
+    visibility.apply(&mut filter);
+    filter
+}
+
+/// Deletes an era unless it was modified since `previous` was read. Returns `false` if nothing
+/// matched.
+pub async fn delete_era(
+    db: &Database,
+    era_id: &str,
+    previous: &Document,
+) -> mongodb::error::Result<bool> {
+    let result = eras_collection(db).delete_one(unmodified_filter(era_id, previous), None).await?;
+    Ok(result.deleted_count > 0)
+}
+
+/// Matches the era only while its `updated_at` and pending edit are still the ones in `previous`.
+/// Eras imported without timestamps match until their first write.
+fn unmodified_filter(era_id: &str, previous: &Document) -> Document {
+    let mut filter = match previous.get_datetime("updated_at") {
+        Ok(updated_at) => doc! {"_id": era_id, "updated_at": *updated_at},
+        Err(_) => doc! {"_id": era_id, "updated_at": {"$exists": false}},
+    };
+    guard_pending(&mut filter, previous);
+    filter
+}
+
+/// Episode ids across every locale of a raw era document.
+pub fn episode_ids(era: &Document) -> BTreeSet<String> {
+    era.iter()
+        .filter_map(|(_, value)| value.as_document())
+        .filter_map(|locale| get_array(locale, "episodes"))
+        .flatten()
+        .map(|episode| get_string(&episode, "id"))
+        .filter(|id| !id.is_empty())
+        .collect()
+}
+
 fn parse_era_list_item(doc: Document, lang: &str) -> EraListItem {
     let locale = get_document_for_lang(&doc, lang);
     let episodes = locale.and_then(|d| get_array(d, "episodes")).unwrap_or_default();
@@ -112,6 +281,7 @@ pub async fn search_episodes_by_book(
     db: &Database,
     book: &str,
     lang: &str,
+    visibility: Visibility,
 ) -> mongodb::error::Result<Vec<EpisodeSearchItem>> {
     let episodes_path = format!("{lang}.episodes");
     let references_path = format!("{episodes_path}.references");
@@ -127,6 +297,7 @@ pub async fn search_episodes_by_book(
     match_book_label.insert(book_label_path, book);
 
     let pipeline = vec![
+        doc! {"$match": visible(doc! {}, visibility)},
         doc! {"$unwind": format!("${episodes_path}")},
         doc! {"$unwind": format!("${references_path}")},
         doc! {"$match": {"$or": [match_book_id, match_book_label]}},
@@ -317,10 +488,15 @@ fn get_i32_opt(doc: &Document, key: &str) -> Option<i32> {
     }
 }
 
-fn sort_eras(eras: &mut [EraListItem]) {
+/// Orders eras by `order`, then id, with meta eras last. `key` picks out the era's type, order
+/// and id so list items and full eras share the ordering.
+fn sort_eras<T>(eras: &mut [T], key: impl Fn(&T) -> (Option<&str>, i32, &str)) {
     eras.sort_by(|a, b| {
-        let a_is_meta = a.era_type.as_deref() == Some("meta");
-        let b_is_meta = b.era_type.as_deref() == Some("meta");
-        a_is_meta.cmp(&b_is_meta).then_with(|| a.order.cmp(&b.order)).then_with(|| a.id.cmp(&b.id))
+        let (a_type, a_order, a_id) = key(a);
+        let (b_type, b_order, b_id) = key(b);
+        (a_type == Some("meta"))
+            .cmp(&(b_type == Some("meta")))
+            .then_with(|| a_order.cmp(&b_order))
+            .then_with(|| a_id.cmp(b_id))
     });
 }
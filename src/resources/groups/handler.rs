@@ -0,0 +1,735 @@
+use axum::routing::{MethodRouter, delete as axum_delete, get as axum_get, post as axum_post};
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+use mongodb::bson::{DateTime, oid::ObjectId};
+use serde_json::json;
+use tracing::error;
+
+use crate::{
+    auth::Caller,
+    lang::resolve_lang,
+    resources::{
+        achievements::handler as achievement_handler,
+        eras::queries as era_queries,
+        groups::{
+            model::{
+                Assignment, AssignmentAnswerDto, AssignmentDetailDto, AssignmentDto, AssignmentListDto,
+                AssignmentProgress, AssignmentQuery, AssignmentReportDto, AssignmentTarget,
+                CreateAssignment, CreateGroup, Group, GroupDto, GroupListDto, GroupMember, JoinGroup,
+                MAX_ASSIGNMENT_QUESTIONS, MAX_NAME_LEN, MAX_TITLE_LEN, MemberProgressDto,
+                ProgressAnswer, ProgressDto, clean_text, dedup_ids, parse_due_at, sort_report,
+            },
+            queries,
+        },
+        play::model::{PlayAnswerDto, PlayQuestionDto, shuffle_for, to_stored_answer},
+        questions::{
+            model::QuestionDto,
+            queries::{self as question_queries, DifficultyRange, SampleFilter},
+            types::{TimedAnswer, check_answer},
+        },
+        repetition::handler as repetition_handler,
+        rooms::model::{join_code, normalize_code},
+        stats::{handler as stats_handler, model::AnswerOutcome},
+        streaks::{handler as streak_handler, model::EPISODE_READ},
+        workflow::model::{ContentStatus, Visibility},
+    },
+    routes::api::ApiState,
+};
+
+/// Attempts at drawing an invite code not already in use.
+const CODE_ATTEMPTS: usize = 5;
+
+pub fn collection() -> MethodRouter<ApiState> {
+    axum_get(list_groups).post(create_group)
+}
+
+pub fn join() -> MethodRouter<ApiState> {
+    axum_post(join_group)
+}
+
+pub fn group() -> MethodRouter<ApiState> {
+    axum_get(get_group)
+}
+
+pub fn member() -> MethodRouter<ApiState> {
+    axum_delete(remove_member)
+}
+
+pub fn assignments() -> MethodRouter<ApiState> {
+    axum_get(list_assignments).post(create_assignment)
+}
+
+pub fn assignment() -> MethodRouter<ApiState> {
+    axum_get(get_assignment)
+}
+
+pub fn answer() -> MethodRouter<ApiState> {
+    axum_post(answer_question)
+}
+
+pub fn episode() -> MethodRouter<ApiState> {
+    axum_post(complete_episode)
+}
+
+pub fn report() -> MethodRouter<ApiState> {
+    axum_get(get_report)
+}
+
+/// Creates a group owned by the caller, with a fresh invite code for members to join with.
+pub async fn create_group(
+    State(state): State<ApiState>,
+    caller: Caller,
+    Json(payload): Json<CreateGroup>,
+) -> impl IntoResponse {
+    let owner_id = match caller.require_user() {
+        Ok(user_id) => user_id.to_string(),
+        Err(forbidden) => return forbidden.into_response(),
+    };
+    let Some(name) = clean_text(&payload.name, MAX_NAME_LEN) else {
+        return error_response(StatusCode::BAD_REQUEST, "name must be 1 to 80 characters");
+    };
+
+    let now = DateTime::now();
+    let mut group = Group {
+        id: ObjectId::new().to_hex(),
+        name,
+        owner_id,
+        invite_code: String::new(),
+        members: Vec::new(),
+        created_at: now,
+        updated_at: now,
+    };
+    let mut created = false;
+    for _ in 0..CODE_ATTEMPTS {
+        group.invite_code = join_code(&ObjectId::new().bytes());
+        match queries::insert_group(&state.db, &group).await {
+            Ok(true) => {
+                created = true;
+                break;
+            }
+            Ok(false) => continue,
+            Err(err) => {
+                error!(error = ?err, "failed to store group");
+                return error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to create group");
+            }
+        }
+    }
+    if !created {
+        error!("no free invite code after {CODE_ATTEMPTS} attempts");
+        return error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to create group");
+    }
+
+    let dto = GroupDto::new(group, &caller.user_id.unwrap_or_default());
+    (StatusCode::CREATED, Json(dto)).into_response()
+}
+
+/// Groups the caller owns or belongs to.
+pub async fn list_groups(State(state): State<ApiState>, caller: Caller) -> impl IntoResponse {
+    let user_id = match caller.require_user() {
+        Ok(user_id) => user_id,
+        Err(forbidden) => return forbidden.into_response(),
+    };
+    match queries::list_groups_for(&state.db, user_id).await {
+        Ok(groups) => {
+            let items = groups.into_iter().map(|group| GroupDto::new(group, user_id)).collect();
+            (
+                StatusCode::OK,
+                Json(GroupListDto {
+                    items,
+                }),
+            )
+                .into_response()
+        }
+        Err(err) => {
+            error!(error = ?err, "failed to list groups");
+            error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to list groups")
+        }
+    }
+}
+
+pub async fn get_group(
+    State(state): State<ApiState>,
+    Path(group_id): Path<String>,
+    caller: Caller,
+) -> impl IntoResponse {
+    let user_id = match caller.require_user() {
+        Ok(user_id) => user_id,
+        Err(forbidden) => return forbidden.into_response(),
+    };
+    match load_group(&state, &group_id, user_id).await {
+        Ok(group) => (StatusCode::OK, Json(GroupDto::new(group, user_id))).into_response(),
+        Err(response) => response,
+    }
+}
+
+/// Joins the group with the given invite code. Joining again, or joining one's own group, is a
+/// no-op that returns the group.
+pub async fn join_group(
+    State(state): State<ApiState>,
+    caller: Caller,
+    Json(payload): Json<JoinGroup>,
+) -> impl IntoResponse {
+    let user_id = match caller.require_user() {
+        Ok(user_id) => user_id.to_string(),
+        Err(forbidden) => return forbidden.into_response(),
+    };
+    let group = match queries::find_group_by_code(&state.db, &normalize_code(&payload.code)).await {
+        Ok(Some(group)) => group,
+        Ok(None) => return error_response(StatusCode::NOT_FOUND, "invalid invite code"),
+        Err(err) => {
+            error!(error = ?err, "failed to look up invite code");
+            return error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to join group");
+        }
+    };
+    let member = GroupMember {
+        user_id: user_id.clone(),
+        joined_at: DateTime::now(),
+    };
+    match queries::add_member(&state.db, &group.id, &member).await {
+        Ok(Some(group)) => (StatusCode::OK, Json(GroupDto::new(group, &user_id))).into_response(),
+        Ok(None) => error_response(StatusCode::NOT_FOUND, "invalid invite code"),
+        Err(err) => {
+            error!(error = ?err, "failed to join group");
+            error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to join group")
+        }
+    }
+}
+
+/// The owner removes a member, or a member leaves. Their progress on assignments is kept.
+pub async fn remove_member(
+    State(state): State<ApiState>,
+    Path((group_id, member_id)): Path<(String, String)>,
+    caller: Caller,
+) -> impl IntoResponse {
+    let user_id = match caller.require_user() {
+        Ok(user_id) => user_id,
+        Err(forbidden) => return forbidden.into_response(),
+    };
+    let group = match load_group(&state, &group_id, user_id).await {
+        Ok(group) => group,
+        Err(response) => return response,
+    };
+    if group.owner_id != user_id && member_id != user_id {
+        return error_response(StatusCode::FORBIDDEN, "only the owner can remove other members");
+    }
+    match queries::remove_member(&state.db, &group.id, &member_id).await {
+        Ok(true) => StatusCode::NO_CONTENT.into_response(),
+        Ok(false) => error_response(StatusCode::NOT_FOUND, "member not found"),
+        Err(err) => {
+            error!(error = ?err, "failed to remove group member");
+            error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to remove member")
+        }
+    }
+}
+
+/// Sets an assignment for the group (owner only). Era targets cover every episode of the era plus
+/// a snapshot of up to 50 of its published questions; episode targets must name episodes of the
+/// era; question targets must name published questions.
+pub async fn create_assignment(
+    State(state): State<ApiState>,
+    Path(group_id): Path<String>,
+    Query(params): Query<AssignmentQuery>,
+    caller: Caller,
+    headers: HeaderMap,
+    Json(payload): Json<CreateAssignment>,
+) -> impl IntoResponse {
+    let user_id = match caller.require_user() {
+        Ok(user_id) => user_id,
+        Err(forbidden) => return forbidden.into_response(),
+    };
+    let group = match load_group(&state, &group_id, user_id).await {
+        Ok(group) => group,
+        Err(response) => return response,
+    };
+    if group.owner_id != user_id {
+        return error_response(StatusCode::FORBIDDEN, "only the owner can set assignments");
+    }
+    let Some(title) = clean_text(&payload.title, MAX_TITLE_LEN) else {
+        return error_response(StatusCode::BAD_REQUEST, "title must be 1 to 120 characters");
+    };
+    let now = DateTime::now();
+    let due_at = match parse_due_at(&payload.due_at) {
+        Ok(due_at) if due_at > now => due_at,
+        Ok(_) => return error_response(StatusCode::BAD_REQUEST, "due_at must be in the future"),
+        Err(msg) => return error_response(StatusCode::BAD_REQUEST, msg),
+    };
+
+    let lang = resolve_lang(params.lang.as_deref(), headers.get("accept-language"));
+    let (era_id, episode_ids, question_ids) = match resolve_target(&state, &payload.target, &lang).await
+    {
+        Ok(resolved) => resolved,
+        Err(response) => return response,
+    };
+    if episode_ids.is_empty() && question_ids.is_empty() {
+        return error_response(StatusCode::CONFLICT, "the target has no episodes or questions");
+    }
+
+    let assignment = Assignment {
+        id: ObjectId::new().to_hex(),
+        group_id: group.id,
+        title,
+        target: payload.target,
+        era_id,
+        episode_ids,
+        question_ids,
+        due_at,
+        created_by: user_id.to_string(),
+        created_at: now,
+    };
+    if let Err(err) = queries::insert_assignment(&state.db, &assignment).await {
+        error!(error = ?err, "failed to store assignment");
+        return error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to create assignment");
+    }
+    (StatusCode::CREATED, Json(AssignmentDto::new(assignment, None, now))).into_response()
+}
+
+/// The group's assignments, soonest due first. Members see their own progress on each.
+pub async fn list_assignments(
+    State(state): State<ApiState>,
+    Path(group_id): Path<String>,
+    caller: Caller,
+) -> impl IntoResponse {
+    let user_id = match caller.require_user() {
+        Ok(user_id) => user_id,
+        Err(forbidden) => return forbidden.into_response(),
+    };
+    let group = match load_group(&state, &group_id, user_id).await {
+        Ok(group) => group,
+        Err(response) => return response,
+    };
+    let assignments = match queries::list_assignments(&state.db, &group.id).await {
+        Ok(assignments) => assignments,
+        Err(err) => {
+            error!(error = ?err, "failed to list assignments");
+            return error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to list assignments");
+        }
+    };
+
+    let now = DateTime::now();
+    let mut items = Vec::with_capacity(assignments.len());
+    for assignment in assignments {
+        let progress = if group.is_member(user_id) {
+            match queries::find_progress(&state.db, &assignment.id, user_id).await {
+                Ok(progress) => Some(ProgressDto::new(&assignment, progress.as_ref(), now)),
+                Err(err) => {
+                    error!(error = ?err, "failed to load assignment progress");
+                    return error_response(
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        "failed to list assignments",
+                    );
+                }
+            }
+        } else {
+            None
+        };
+        items.push(AssignmentDto::new(assignment, progress, now));
+    }
+    (
+        StatusCode::OK,
+        Json(AssignmentListDto {
+            items,
+        }),
+    )
+        .into_response()
+}
+
+/// One assignment with its episodes and questions ready to play, in the assignment's own shuffled
+/// order. Members also see their progress.
+pub async fn get_assignment(
+    State(state): State<ApiState>,
+    Path((group_id, assignment_id)): Path<(String, String)>,
+    Query(params): Query<AssignmentQuery>,
+    caller: Caller,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let user_id = match caller.require_user() {
+        Ok(user_id) => user_id,
+        Err(forbidden) => return forbidden.into_response(),
+    };
+    let (group, assignment) = match load_assignment(&state, &group_id, &assignment_id, user_id).await {
+        Ok(loaded) => loaded,
+        Err(response) => return response,
+    };
+    let lang = resolve_lang(params.lang.as_deref(), headers.get("accept-language"));
+
+    let episodes = match assignment.era_id.as_deref() {
+        Some(era_id) => {
+            match era_queries::list_episodes_for_era(&state.db, era_id, &lang, Visibility::Published)
+                .await
+            {
+                Ok(listed) => {
+                    let mut listed = listed.unwrap_or_default();
+                    listed.retain(|episode| assignment.episode_ids.contains(&episode.id));
+                    listed.sort_by_key(|episode| {
+                        assignment.episode_ids.iter().position(|id| *id == episode.id)
+                    });
+                    listed
+                }
+                Err(err) => {
+                    error!(error = ?err, "failed to load assignment episodes");
+                    return error_response(
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        "failed to load assignment",
+                    );
+                }
+            }
+        }
+        None => Vec::new(),
+    };
+    let questions = match published_questions(&state, &assignment.question_ids).await {
+        Ok(questions) => questions
+            .into_iter()
+            .map(|question| {
+                let shuffle = shuffle_for(&question, Some(user_id), &assignment.id);
+                PlayQuestionDto::new(question, &lang, assignment.id.clone(), &shuffle)
+            })
+            .collect(),
+        Err(err) => {
+            error!(error = ?err, "failed to load assignment questions");
+            return error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to load assignment");
+        }
+    };
+
+    let now = DateTime::now();
+    let progress = if group.is_member(user_id) {
+        match queries::find_progress(&state.db, &assignment.id, user_id).await {
+            Ok(progress) => Some(ProgressDto::new(&assignment, progress.as_ref(), now)),
+            Err(err) => {
+                error!(error = ?err, "failed to load assignment progress");
+                return error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to load assignment");
+            }
+        }
+    } else {
+        None
+    };
+    let dto = AssignmentDetailDto {
+        assignment: AssignmentDto::new(assignment, progress, now),
+        episodes,
+        questions,
+    };
+    (StatusCode::OK, Json(dto)).into_response()
+}
+
+/// Grades a member's answer to an assignment question, given in the display positions served by
+/// the assignment. Only the first answer to each question counts; it also feeds the question's
+/// statistics and the member's reviews.
+pub async fn answer_question(
+    State(state): State<ApiState>,
+    Path((group_id, assignment_id, question_id)): Path<(String, String, String)>,
+    Query(params): Query<AssignmentQuery>,
+    caller: Caller,
+    headers: HeaderMap,
+    Json(TimedAnswer {
+        answer,
+        time_ms,
+    }): Json<TimedAnswer>,
+) -> impl IntoResponse {
+    let user_id = match caller.require_user() {
+        Ok(user_id) => user_id.to_string(),
+        Err(forbidden) => return forbidden.into_response(),
+    };
+    let assignment = match load_member_assignment(&state, &group_id, &assignment_id, &user_id).await {
+        Ok(assignment) => assignment,
+        Err(response) => return response,
+    };
+    if !assignment.question_ids.contains(&question_id) {
+        return error_response(StatusCode::NOT_FOUND, "question not in this assignment");
+    }
+    let question = match question_queries::find_question_by_id(&state.db, &question_id).await {
+        Ok(Some(question)) if question.status == ContentStatus::Published => question,
+        Ok(_) => return error_response(StatusCode::NOT_FOUND, "question not found"),
+        Err(err) => {
+            error!(error = ?err, "failed to fetch question");
+            return error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to record answer");
+        }
+    };
+
+    let lang = resolve_lang(params.lang.as_deref(), headers.get("accept-language"));
+    let shuffle = shuffle_for(&question, Some(&user_id), &assignment.id);
+    let checked = to_stored_answer(answer, &shuffle)
+        .and_then(|answer| Ok((check_answer(&question, &answer, &lang)?, answer)));
+    let (check, answer) = match checked {
+        Ok(checked) => checked,
+        Err(msg) => return error_response(StatusCode::BAD_REQUEST, msg),
+    };
+
+    let recorded = ProgressAnswer {
+        question_id: question.id.clone(),
+        correct: check.correct,
+        answered_at: DateTime::now(),
+    };
+    let progress = match queries::record_answer(&state.db, &assignment, &user_id, &recorded).await {
+        Ok(Some(progress)) => progress,
+        Ok(None) => {
+            return error_response(
+                StatusCode::CONFLICT,
+                "question already answered for this assignment",
+            );
+        }
+        Err(err) => {
+            error!(error = ?err, "failed to record assignment answer");
+            return error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to record answer");
+        }
+    };
+    let outcome = AnswerOutcome::new(&question.id, &answer, check.correct, time_ms);
+    stats_handler::record_answers(&state, Some(&user_id), &lang, &[outcome]).await;
+    repetition_handler::record_outcomes(&state, &user_id, &[(question.id.clone(), check.correct)]).await;
+
+    let progress = finish(&state, &assignment, progress).await;
+    let dto = AssignmentAnswerDto {
+        result: PlayAnswerDto::new(check, &question, &lang, &shuffle),
+        progress,
+    };
+    (StatusCode::OK, Json(dto)).into_response()
+}
+
+/// Marks one of the assignment's episodes as studied by the calling member. It also counts as read
+/// towards the member's achievements.
+pub async fn complete_episode(
+    State(state): State<ApiState>,
+    Path((group_id, assignment_id, episode_id)): Path<(String, String, String)>,
+    caller: Caller,
+) -> impl IntoResponse {
+    let user_id = match caller.require_user() {
+        Ok(user_id) => user_id.to_string(),
+        Err(forbidden) => return forbidden.into_response(),
+    };
+    let assignment = match load_member_assignment(&state, &group_id, &assignment_id, &user_id).await {
+        Ok(assignment) => assignment,
+        Err(response) => return response,
+    };
+    if !assignment.episode_ids.contains(&episode_id) {
+        return error_response(StatusCode::NOT_FOUND, "episode not in this assignment");
+    }
+    match queries::record_episode(&state.db, &assignment, &user_id, &episode_id).await {
+        Ok(Some(progress)) => {
+            if let Some(era_id) = assignment.era_id.as_deref() {
+                achievement_handler::record_episode(&state, &user_id, era_id, &episode_id).await;
+            }
+            streak_handler::record_activity(&state, &user_id, EPISODE_READ).await;
+            (StatusCode::OK, Json(finish(&state, &assignment, progress).await)).into_response()
+        }
+        Ok(None) => error_response(StatusCode::NOT_FOUND, "assignment not found"),
+        Err(err) => {
+            error!(error = ?err, "failed to record assignment episode");
+            error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to record progress")
+        }
+    }
+}
+
+/// The teacher's report (owner only): each current member's completion and score, least complete
+/// first, and whether they finished (or are still working) past the due date.
+pub async fn get_report(
+    State(state): State<ApiState>,
+    Path((group_id, assignment_id)): Path<(String, String)>,
+    caller: Caller,
+) -> impl IntoResponse {
+    let user_id = match caller.require_user() {
+        Ok(user_id) => user_id,
+        Err(forbidden) => return forbidden.into_response(),
+    };
+    let (group, assignment) = match load_assignment(&state, &group_id, &assignment_id, user_id).await {
+        Ok(loaded) => loaded,
+        Err(response) => return response,
+    };
+    if group.owner_id != user_id {
+        return error_response(StatusCode::FORBIDDEN, "only the owner can see the report");
+    }
+    let stored = match queries::list_progress(&state.db, &assignment.id).await {
+        Ok(stored) => stored,
+        Err(err) => {
+            error!(error = ?err, "failed to load assignment progress");
+            return error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to build report");
+        }
+    };
+
+    let now = DateTime::now();
+    let mut members: Vec<MemberProgressDto> = group
+        .members
+        .iter()
+        .map(|member| {
+            let progress = stored.iter().find(|p| p.user_id == member.user_id);
+            MemberProgressDto {
+                user_id: member.user_id.clone(),
+                progress: ProgressDto::new(&assignment, progress, now),
+            }
+        })
+        .collect();
+    sort_report(&mut members);
+    let completed = members.iter().filter(|m| m.progress.completed_at.is_some()).count();
+    let average_completion = if members.is_empty() {
+        0.0
+    } else {
+        members.iter().map(|m| m.progress.completion).sum::<f64>() / members.len() as f64
+    };
+
+    let dto = AssignmentReportDto {
+        assignment: AssignmentDto::new(assignment, None, now),
+        members,
+        completed,
+        average_completion,
+    };
+    (StatusCode::OK, Json(dto)).into_response()
+}
+
+/// Resolves an assignment target into its era, episodes and questions.
+async fn resolve_target(
+    state: &ApiState,
+    target: &AssignmentTarget,
+    lang: &str,
+) -> Result<(Option<String>, Vec<String>, Vec<String>), Response> {
+    match target {
+        AssignmentTarget::Era(era_id) => {
+            let episodes = era_episode_ids(state, era_id, lang).await?;
+            let eras = [era_id.clone()];
+            let filter = SampleFilter {
+                stage: None,
+                difficulty: DifficultyRange::default(),
+                eras: &eras,
+                levels: &[],
+                tags: &[],
+                exclude: &[],
+            };
+            let questions =
+                question_queries::sample_questions(&state.db, &filter, MAX_ASSIGNMENT_QUESTIONS as i64)
+                    .await
+                    .map_err(|err| {
+                        error!(error = ?err, "failed to sample era questions");
+                        error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to create assignment")
+                    })?;
+            Ok((Some(era_id.clone()), episodes, questions.into_iter().map(|q| q.id).collect()))
+        }
+        AssignmentTarget::Episodes {
+            era_id,
+            episode_ids,
+        } => {
+            let wanted = dedup_ids(episode_ids);
+            if wanted.is_empty() {
+                return Err(error_response(StatusCode::BAD_REQUEST, "episode_ids must not be empty"));
+            }
+            let episodes = era_episode_ids(state, era_id, lang).await?;
+            if let Some(missing) = wanted.iter().find(|id| !episodes.contains(id)) {
+                return Err(error_response(
+                    StatusCode::NOT_FOUND,
+                    &format!("episode {missing} not found in era {era_id}"),
+                ));
+            }
+            Ok((Some(era_id.clone()), wanted, Vec::new()))
+        }
+        AssignmentTarget::Questions(question_ids) => {
+            let wanted = dedup_ids(question_ids);
+            if wanted.is_empty() || wanted.len() > MAX_ASSIGNMENT_QUESTIONS {
+                return Err(error_response(
+                    StatusCode::BAD_REQUEST,
+                    "questions must list 1 to 50 question ids",
+                ));
+            }
+            let found = published_questions(state, &wanted).await.map_err(|err| {
+                error!(error = ?err, "failed to load assignment questions");
+                error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to create assignment")
+            })?;
+            if let Some(missing) = wanted.iter().find(|id| !found.iter().any(|q| q.id == **id)) {
+                return Err(error_response(
+                    StatusCode::NOT_FOUND,
+                    &format!("question {missing} not found"),
+                ));
+            }
+            Ok((None, Vec::new(), wanted))
+        }
+    }
+}
+
+/// Episode ids of a published era, in the era's order.
+async fn era_episode_ids(state: &ApiState, era_id: &str, lang: &str) -> Result<Vec<String>, Response> {
+    match era_queries::list_episodes_for_era(&state.db, era_id, lang, Visibility::Published).await {
+        Ok(Some(episodes)) => Ok(episodes.into_iter().map(|episode| episode.id).collect()),
+        Ok(None) => Err(error_response(StatusCode::NOT_FOUND, "era not found")),
+        Err(err) => {
+            error!(error = ?err, "failed to load era episodes");
+            Err(error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to create assignment"))
+        }
+    }
+}
+
+/// Published questions among `ids`, in the order given. Questions unpublished or trashed since the
+/// assignment was set are left out.
+async fn published_questions(
+    state: &ApiState,
+    ids: &[String],
+) -> mongodb::error::Result<Vec<QuestionDto>> {
+    let found: Vec<QuestionDto> = question_queries::find_questions_by_ids(&state.db, ids)
+        .await?
+        .into_iter()
+        .map(QuestionDto::from)
+        .filter(|question| question.status == ContentStatus::Published)
+        .collect();
+    Ok(ids.iter().filter_map(|id| found.iter().find(|q| q.id == *id).cloned()).collect())
+}
+
+/// Stamps completion once every item is done, and renders the member's progress.
+async fn finish(state: &ApiState, assignment: &Assignment, progress: AssignmentProgress) -> ProgressDto {
+    let now = DateTime::now();
+    let dto = ProgressDto::new(assignment, Some(&progress), now);
+    if progress.completed_at.is_some() || dto.done < dto.total {
+        return dto;
+    }
+    match queries::mark_completed(&state.db, &progress.id, now).await {
+        Ok(Some(completed)) => ProgressDto::new(assignment, Some(&completed), now),
+        Ok(None) => dto,
+        Err(err) => {
+            error!(error = ?err, "failed to mark assignment completed");
+            dto
+        }
+    }
+}
+
+/// The group, if the caller owns or belongs to it. Others get a 404 so groups cannot be probed.
+async fn load_group(state: &ApiState, group_id: &str, user_id: &str) -> Result<Group, Response> {
+    match queries::find_group(&state.db, group_id).await {
+        Ok(Some(group)) if group.can_view(user_id) => Ok(group),
+        Ok(_) => Err(error_response(StatusCode::NOT_FOUND, "group not found")),
+        Err(err) => {
+            error!(error = ?err, "failed to fetch group");
+            Err(error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to fetch group"))
+        }
+    }
+}
+
+async fn load_assignment(
+    state: &ApiState,
+    group_id: &str,
+    assignment_id: &str,
+    user_id: &str,
+) -> Result<(Group, Assignment), Response> {
+    let group = load_group(state, group_id, user_id).await?;
+    match queries::find_assignment(&state.db, &group.id, assignment_id).await {
+        Ok(Some(assignment)) => Ok((group, assignment)),
+        Ok(None) => Err(error_response(StatusCode::NOT_FOUND, "assignment not found")),
+        Err(err) => {
+            error!(error = ?err, "failed to fetch assignment");
+            Err(error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to fetch assignment"))
+        }
+    }
+}
+
+/// An assignment the caller works on as a member; owners set work rather than do it.
+async fn load_member_assignment(
+    state: &ApiState,
+    group_id: &str,
+    assignment_id: &str,
+    user_id: &str,
+) -> Result<Assignment, Response> {
+    let (group, assignment) = load_assignment(state, group_id, assignment_id, user_id).await?;
+    if !group.is_member(user_id) {
+        return Err(error_response(StatusCode::FORBIDDEN, "only members work on assignments"));
+    }
+    Ok(assignment)
+}
+
+fn error_response(status: StatusCode, msg: &str) -> Response {
+    (status, Json(json!({ "error": msg }))).into_response()
+}
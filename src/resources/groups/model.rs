@@ -0,0 +1,435 @@
+use chrono::{DateTime as ChronoDateTime, Utc};
+use mongodb::bson::DateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::resources::{
+    eras::model::EpisodeListItem,
+    play::model::{PlayAnswerDto, PlayQuestionDto},
+};
+
+pub const MAX_NAME_LEN: usize = 80;
+pub const MAX_TITLE_LEN: usize = 120;
+/// Questions snapshotted from an era when an assignment targets the whole era, and the cap on an
+/// explicit question set.
+pub const MAX_ASSIGNMENT_QUESTIONS: usize = 50;
+
+/// A class or study group (collection `groups`). The owner runs it and is not counted as a member.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Group {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub name: String,
+    pub owner_id: String,
+    /// Code members join with; unique across groups.
+    pub invite_code: String,
+    pub members: Vec<GroupMember>,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupMember {
+    pub user_id: String,
+    pub joined_at: DateTime,
+}
+
+impl Group {
+    pub fn is_member(&self, user_id: &str) -> bool {
+        self.members.iter().any(|m| m.user_id == user_id)
+    }
+
+    /// The owner and members may see the group and its assignments.
+    pub fn can_view(&self, user_id: &str) -> bool {
+        self.owner_id == user_id || self.is_member(user_id)
+    }
+
+    pub fn member_ids(&self) -> Vec<String> {
+        self.members.iter().map(|m| m.user_id.clone()).collect()
+    }
+}
+
+/// What an assignment asks of its members.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AssignmentTarget {
+    /// Every episode of the era, plus a snapshot of its published questions.
+    Era(String),
+    /// Selected episodes of one era.
+    Episodes {
+        era_id: String,
+        episode_ids: Vec<String>,
+    },
+    /// A fixed set of published questions.
+    Questions(Vec<String>),
+}
+
+/// Work set for a group, due by `due_at` (collection `assignments`). The target is resolved into
+/// concrete episodes and questions when the assignment is created, so later edits to the era do
+/// not move the goalposts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Assignment {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub group_id: String,
+    pub title: String,
+    pub target: AssignmentTarget,
+    /// Era the episodes belong to, for era and episode targets.
+    pub era_id: Option<String>,
+    pub episode_ids: Vec<String>,
+    pub question_ids: Vec<String>,
+    pub due_at: DateTime,
+    pub created_by: String,
+    pub created_at: DateTime,
+}
+
+impl Assignment {
+    /// Episodes to visit plus questions to answer.
+    pub fn total_items(&self) -> usize {
+        self.episode_ids.len() + self.question_ids.len()
+    }
+
+    pub fn due_at_rfc3339(&self) -> String {
+        rfc3339(self.due_at)
+    }
+}
+
+/// One member's work on an assignment (collection `assignment_progress`, keyed by
+/// `<assignment_id>|<user_id>`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssignmentProgress {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub assignment_id: String,
+    pub group_id: String,
+    pub user_id: String,
+    /// Episodes marked as studied.
+    #[serde(default)]
+    pub episodes: Vec<String>,
+    /// First answer to each question; later attempts do not count.
+    #[serde(default)]
+    pub answers: Vec<ProgressAnswer>,
+    /// When the last item was done.
+    pub completed_at: Option<DateTime>,
+    pub updated_at: DateTime,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProgressAnswer {
+    pub question_id: String,
+    pub correct: bool,
+    pub answered_at: DateTime,
+}
+
+pub fn progress_id(assignment_id: &str, user_id: &str) -> String {
+    format!("{assignment_id}|{user_id}")
+}
+
+#[derive(Deserialize)]
+pub struct CreateGroup {
+    pub name: String,
+}
+
+#[derive(Deserialize)]
+pub struct JoinGroup {
+    pub code: String,
+}
+
+/// Body of `POST /v1/groups/:group_id/assignments`. `due_at` is an RFC 3339 timestamp.
+#[derive(Deserialize)]
+pub struct CreateAssignment {
+    pub title: String,
+    pub target: AssignmentTarget,
+    pub due_at: String,
+}
+
+#[derive(Deserialize)]
+pub struct AssignmentQuery {
+    pub lang: Option<String>,
+}
+
+/// A group as the caller sees it. Only the owner sees the invite code and member list.
+#[derive(Debug, Serialize)]
+pub struct GroupDto {
+    pub id: String,
+    pub name: String,
+    pub owner_id: String,
+    pub owner: bool,
+    pub member_count: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub invite_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub members: Option<Vec<MemberDto>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MemberDto {
+    pub user_id: String,
+    pub joined_at: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GroupListDto {
+    pub items: Vec<GroupDto>,
+}
+
+impl GroupDto {
+    pub fn new(group: Group, viewer: &str) -> Self {
+        let owner = group.owner_id == viewer;
+        Self {
+            member_count: group.members.len(),
+            invite_code: owner.then(|| group.invite_code.clone()),
+            members: owner.then(|| {
+                group
+                    .members
+                    .iter()
+                    .map(|m| MemberDto {
+                        user_id: m.user_id.clone(),
+                        joined_at: rfc3339(m.joined_at),
+                    })
+                    .collect()
+            }),
+            id: group.id,
+            name: group.name,
+            owner_id: group.owner_id,
+            owner,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct AssignmentDto {
+    pub id: String,
+    pub group_id: String,
+    pub title: String,
+    pub target: AssignmentTarget,
+    pub era_id: Option<String>,
+    pub episode_ids: Vec<String>,
+    pub question_ids: Vec<String>,
+    pub due_at: String,
+    pub overdue: bool,
+    pub created_at: String,
+    /// The caller's own progress, for members.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub progress: Option<ProgressDto>,
+}
+
+impl AssignmentDto {
+    pub fn new(assignment: Assignment, progress: Option<ProgressDto>, now: DateTime) -> Self {
+        Self {
+            due_at: assignment.due_at_rfc3339(),
+            overdue: now > assignment.due_at,
+            created_at: rfc3339(assignment.created_at),
+            id: assignment.id,
+            group_id: assignment.group_id,
+            title: assignment.title,
+            target: assignment.target,
+            era_id: assignment.era_id,
+            episode_ids: assignment.episode_ids,
+            question_ids: assignment.question_ids,
+            progress,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct AssignmentListDto {
+    pub items: Vec<AssignmentDto>,
+}
+
+/// How far one member got: `done` of `total` items, and `correct` of the assignment's questions.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProgressDto {
+    pub done: usize,
+    pub total: usize,
+    /// `done / total`, from 0 to 1.
+    pub completion: f64,
+    pub correct: usize,
+    pub answered: usize,
+    pub questions: usize,
+    pub episodes_done: Vec<String>,
+    pub questions_answered: Vec<String>,
+    pub completed_at: Option<String>,
+    /// Finished after the due date, or not finished and past it.
+    pub late: bool,
+}
+
+impl ProgressDto {
+    pub fn new(assignment: &Assignment, progress: Option<&AssignmentProgress>, now: DateTime) -> Self {
+        // Items dropped from the assignment do not count.
+        let episodes_done: Vec<String> = progress
+            .map(|p| {
+                p.episodes.iter().filter(|id| assignment.episode_ids.contains(id)).cloned().collect()
+            })
+            .unwrap_or_default();
+        let answers: Vec<&ProgressAnswer> = progress
+            .map(|p| {
+                p.answers.iter().filter(|a| assignment.question_ids.contains(&a.question_id)).collect()
+            })
+            .unwrap_or_default();
+        let total = assignment.total_items();
+        let done = episodes_done.len() + answers.len();
+        let completed_at = progress.and_then(|p| p.completed_at);
+        Self {
+            done,
+            total,
+            completion: if total == 0 {
+                1.0
+            } else {
+                done as f64 / total as f64
+            },
+            correct: answers.iter().filter(|a| a.correct).count(),
+            answered: answers.len(),
+            questions: assignment.question_ids.len(),
+            episodes_done,
+            questions_answered: answers.iter().map(|a| a.question_id.clone()).collect(),
+            completed_at: completed_at.map(rfc3339),
+            late: completed_at.unwrap_or(now) > assignment.due_at,
+        }
+    }
+}
+
+/// A member's view of an assignment: the work itself, ready to play.
+#[derive(Debug, Serialize)]
+pub struct AssignmentDetailDto {
+    #[serde(flatten)]
+    pub assignment: AssignmentDto,
+    pub episodes: Vec<EpisodeListItem>,
+    pub questions: Vec<PlayQuestionDto>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MemberProgressDto {
+    pub user_id: String,
+    #[serde(flatten)]
+    pub progress: ProgressDto,
+}
+
+/// The teacher's report: every member's progress, least complete first.
+#[derive(Debug, Serialize)]
+pub struct AssignmentReportDto {
+    pub assignment: AssignmentDto,
+    pub members: Vec<MemberProgressDto>,
+    pub completed: usize,
+    pub average_completion: f64,
+}
+
+/// Result of answering an assignment question.
+#[derive(Debug, Serialize)]
+pub struct AssignmentAnswerDto {
+    #[serde(flatten)]
+    pub result: PlayAnswerDto,
+    pub progress: ProgressDto,
+}
+
+/// Validated name or title: trimmed, non-empty and at most `max` characters.
+pub fn clean_text(value: &str, max: usize) -> Option<String> {
+    let value = value.trim();
+    (!value.is_empty() && value.chars().count() <= max).then(|| value.to_string())
+}
+
+pub fn parse_due_at(raw: &str) -> Result<DateTime, &'static str> {
+    ChronoDateTime::parse_from_rfc3339(raw.trim())
+        .map(|at| DateTime::from_chrono(at.with_timezone(&Utc)))
+        .map_err(|_| "due_at must be an RFC 3339 timestamp")
+}
+
+/// Drops blanks and repeats, keeping the first occurrence of each id.
+pub fn dedup_ids(ids: &[String]) -> Vec<String> {
+    let mut unique: Vec<String> = Vec::with_capacity(ids.len());
+    for id in ids.iter().map(|id| id.trim()).filter(|id| !id.is_empty()) {
+        if !unique.iter().any(|seen| seen == id) {
+            unique.push(id.to_string());
+        }
+    }
+    unique
+}
+
+/// Sorts a report so members who most need a nudge come first.
+pub fn sort_report(members: &mut [MemberProgressDto]) {
+    members.sort_by(|a, b| {
+        a.progress
+            .completion
+            .total_cmp(&b.progress.completion)
+            .then(a.progress.correct.cmp(&b.progress.correct))
+            .then(a.user_id.cmp(&b.user_id))
+    });
+}
+
+fn rfc3339(at: DateTime) -> String {
+    at.to_chrono().with_timezone(&Utc).to_rfc3339()
+}
+
+#[cfg(test)]
+mod tests {
+    use mongodb::bson::DateTime;
+
+    use super::{
+        Assignment, AssignmentProgress, AssignmentTarget, ProgressAnswer, ProgressDto, dedup_ids,
+        parse_due_at,
+    };
+
+    fn assignment(due_at: DateTime) -> Assignment {
+        Assignment {
+            id: "a1".into(),
+            group_id: "g1".into(),
+            title: "Prophets".into(),
+            target: AssignmentTarget::Episodes {
+                era_id: "prophets".into(),
+                episode_ids: vec!["elijah".into()],
+            },
+            era_id: Some("prophets".into()),
+            episode_ids: vec!["elijah".into()],
+            question_ids: vec!["q1".into(), "q2".into(), "q3".into()],
+            due_at,
+            created_by: "teacher".into(),
+            created_at: DateTime::from_millis(0),
+        }
+    }
+
+    #[test]
+    fn progress_counts_episodes_and_answers() {
+        let due_at = DateTime::from_millis(10_000);
+        let answer = |id: &str, correct| ProgressAnswer {
+            question_id: id.into(),
+            correct,
+            answered_at: DateTime::from_millis(1_000),
+        };
+        let progress = AssignmentProgress {
+            id: "a1|ana".into(),
+            assignment_id: "a1".into(),
+            group_id: "g1".into(),
+            user_id: "ana".into(),
+            episodes: vec!["elijah".into(), "dropped".into()],
+            answers: vec![answer("q1", true), answer("q2", false)],
+            completed_at: None,
+            updated_at: DateTime::from_millis(1_000),
+        };
+
+        let dto = ProgressDto::new(&assignment(due_at), Some(&progress), DateTime::from_millis(5_000));
+        assert_eq!((dto.done, dto.total, dto.correct, dto.answered), (3, 4, 1, 2));
+        assert_eq!(dto.completion, 0.75);
+        assert_eq!(dto.episodes_done, vec!["elijah".to_string()]);
+        assert!(!dto.late);
+
+        let overdue = ProgressDto::new(&assignment(due_at), None, DateTime::from_millis(20_000));
+        assert_eq!((overdue.done, overdue.completion), (0, 0.0));
+        assert!(overdue.late);
+    }
+
+    #[test]
+    fn targets_and_inputs_parse() {
+        let target: AssignmentTarget = serde_json::from_str(r#"{"era":"prophets"}"#).unwrap();
+        assert_eq!(target, AssignmentTarget::Era("prophets".into()));
+        let target: AssignmentTarget =
+            serde_json::from_str(r#"{"episodes":{"era_id":"prophets","episode_ids":["elijah"]}}"#)
+                .unwrap();
+        assert!(matches!(target, AssignmentTarget::Episodes { .. }));
+
+        assert_eq!(
+            parse_due_at("2026-10-25T18:00:00+02:00").unwrap().timestamp_millis(),
+            1_792_944_000_000
+        );
+        assert!(parse_due_at("next week").is_err());
+        assert_eq!(dedup_ids(&[" q1".into(), "q2".into(), "q1".into(), "".into()]), vec!["q1", "q2"]);
+    }
+}
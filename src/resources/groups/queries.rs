@@ -0,0 +1,220 @@
+use futures_util::TryStreamExt;
+use mongodb::{
+    Collection, Database, IndexModel,
+    bson::{self, DateTime, doc},
+    options::{FindOneAndUpdateOptions, FindOptions, IndexOptions, ReturnDocument, UpdateOptions},
+};
+
+use crate::{
+    db::is_duplicate_key,
+    resources::groups::model::{
+        Assignment, AssignmentProgress, Group, GroupMember, ProgressAnswer, progress_id,
+    },
+};
+
+fn groups_collection(db: &Database) -> Collection<Group> {
+    db.collection("groups")
+}
+
+fn assignments_collection(db: &Database) -> Collection<Assignment> {
+    db.collection("assignments")
+}
+
+fn progress_collection(db: &Database) -> Collection<AssignmentProgress> {
+    db.collection("assignment_progress")
+}
+
+/// Unique invite codes, and the lookups behind "my groups", a group's assignments and reports.
+pub async fn ensure_indexes(db: &Database) -> mongodb::error::Result<()> {
+    let unique = IndexOptions::builder().unique(true).build();
+    let code = IndexModel::builder().keys(doc! { "invite_code": 1 }).options(unique).build();
+    groups_collection(db).create_index(code, None).await?;
+    let members = IndexModel::builder().keys(doc! { "members.user_id": 1 }).build();
+    groups_collection(db).create_index(members, None).await?;
+    let owner = IndexModel::builder().keys(doc! { "owner_id": 1 }).build();
+    groups_collection(db).create_index(owner, None).await?;
+    let by_group = IndexModel::builder().keys(doc! { "group_id": 1, "due_at": 1 }).build();
+    assignments_collection(db).create_index(by_group, None).await?;
+    let by_assignment = IndexModel::builder().keys(doc! { "assignment_id": 1 }).build();
+    progress_collection(db).create_index(by_assignment, None).await?;
+    Ok(())
+}
+
+/// Inserts a new group. Returns `false` when its invite code is already taken.
+pub async fn insert_group(db: &Database, group: &Group) -> mongodb::error::Result<bool> {
+    match groups_collection(db).insert_one(group, None).await {
+        Ok(_) => Ok(true),
+        Err(err) if is_duplicate_key(&err) => Ok(false),
+        Err(err) => Err(err),
+    }
+}
+
+pub async fn find_group(db: &Database, group_id: &str) -> mongodb::error::Result<Option<Group>> {
+    groups_collection(db).find_one(doc! { "_id": group_id }, None).await
+}
+
+pub async fn find_group_by_code(db: &Database, code: &str) -> mongodb::error::Result<Option<Group>> {
+    groups_collection(db).find_one(doc! { "invite_code": code }, None).await
+}
+
+/// Groups the user owns or belongs to, by name.
+pub async fn list_groups_for(db: &Database, user_id: &str) -> mongodb::error::Result<Vec<Group>> {
+    let filter = doc! { "$or": [{ "owner_id": user_id }, { "members.user_id": user_id }] };
+    let options = FindOptions::builder().sort(doc! { "name": 1, "_id": 1 }).build();
+    let mut cursor = groups_collection(db).find(filter, options).await?;
+    let mut groups = Vec::new();
+    while let Some(item) = cursor.try_next().await? {
+        groups.push(item);
+    }
+    Ok(groups)
+}
+
+/// Adds `member` to the group unless they own it or already belong. Returns the group either way.
+pub async fn add_member(
+    db: &Database,
+    group_id: &str,
+    member: &GroupMember,
+) -> mongodb::error::Result<Option<Group>> {
+    let filter = doc! {
+        "_id": group_id,
+        "owner_id": { "$ne": &member.user_id },
+        "members.user_id": { "$ne": &member.user_id },
+    };
+    let update = doc! {
+        "$push": { "members": bson::to_bson(member)? },
+        "$set": { "updated_at": DateTime::now() },
+    };
+    let options = FindOneAndUpdateOptions::builder().return_document(ReturnDocument::After).build();
+    match groups_collection(db).find_one_and_update(filter, update, options).await? {
+        Some(group) => Ok(Some(group)),
+        None => find_group(db, group_id).await,
+    }
+}
+
+/// Removes a member. Returns `false` when they did not belong to the group.
+pub async fn remove_member(
+    db: &Database,
+    group_id: &str,
+    user_id: &str,
+) -> mongodb::error::Result<bool> {
+    let update = doc! {
+        "$pull": { "members": { "user_id": user_id } },
+        "$set": { "updated_at": DateTime::now() },
+    };
+    let result = groups_collection(db)
+        .update_one(doc! { "_id": group_id, "members.user_id": user_id }, update, None)
+        .await?;
+    Ok(result.modified_count > 0)
+}
+
+pub async fn insert_assignment(db: &Database, assignment: &Assignment) -> mongodb::error::Result<()> {
+    assignments_collection(db).insert_one(assignment, None).await?;
+    Ok(())
+}
+
+pub async fn find_assignment(
+    db: &Database,
+    group_id: &str,
+    assignment_id: &str,
+) -> mongodb::error::Result<Option<Assignment>> {
+    assignments_collection(db).find_one(doc! { "_id": assignment_id, "group_id": group_id }, None).await
+}
+
+/// A group's assignments, soonest due first.
+pub async fn list_assignments(db: &Database, group_id: &str) -> mongodb::error::Result<Vec<Assignment>> {
+    let options = FindOptions::builder().sort(doc! { "due_at": 1, "_id": 1 }).build();
+    let mut cursor = assignments_collection(db).find(doc! { "group_id": group_id }, options).await?;
+    let mut assignments = Vec::new();
+    while let Some(item) = cursor.try_next().await? {
+        assignments.push(item);
+    }
+    Ok(assignments)
+}
+
+pub async fn find_progress(
+    db: &Database,
+    assignment_id: &str,
+    user_id: &str,
+) -> mongodb::error::Result<Option<AssignmentProgress>> {
+    progress_collection(db).find_one(doc! { "_id": progress_id(assignment_id, user_id) }, None).await
+}
+
+/// Every member's progress on one assignment.
+pub async fn list_progress(
+    db: &Database,
+    assignment_id: &str,
+) -> mongodb::error::Result<Vec<AssignmentProgress>> {
+    let mut cursor = progress_collection(db).find(doc! { "assignment_id": assignment_id }, None).await?;
+    let mut progress = Vec::new();
+    while let Some(item) = cursor.try_next().await? {
+        progress.push(item);
+    }
+    Ok(progress)
+}
+
+/// Marks an episode as studied. Marking it again changes nothing.
+pub async fn record_episode(
+    db: &Database,
+    assignment: &Assignment,
+    user_id: &str,
+    episode_id: &str,
+) -> mongodb::error::Result<Option<AssignmentProgress>> {
+    let id = ensure_progress(db, assignment, user_id).await?;
+    let update = doc! {
+        "$addToSet": { "episodes": episode_id },
+        "$set": { "updated_at": DateTime::now() },
+    };
+    let options = FindOneAndUpdateOptions::builder().return_document(ReturnDocument::After).build();
+    progress_collection(db).find_one_and_update(doc! { "_id": id }, update, options).await
+}
+
+/// Records the first answer to a question. Returns `None` when the question was already answered.
+pub async fn record_answer(
+    db: &Database,
+    assignment: &Assignment,
+    user_id: &str,
+    answer: &ProgressAnswer,
+) -> mongodb::error::Result<Option<AssignmentProgress>> {
+    let id = ensure_progress(db, assignment, user_id).await?;
+    let filter = doc! { "_id": id, "answers.question_id": { "$ne": &answer.question_id } };
+    let update = doc! {
+        "$push": { "answers": bson::to_bson(answer)? },
+        "$set": { "updated_at": DateTime::now() },
+    };
+    let options = FindOneAndUpdateOptions::builder().return_document(ReturnDocument::After).build();
+    progress_collection(db).find_one_and_update(filter, update, options).await
+}
+
+/// Stamps when the member finished, once.
+pub async fn mark_completed(
+    db: &Database,
+    progress_id: &str,
+    at: DateTime,
+) -> mongodb::error::Result<Option<AssignmentProgress>> {
+    let filter = doc! { "_id": progress_id, "completed_at": null };
+    let update = doc! { "$set": { "completed_at": at } };
+    let options = FindOneAndUpdateOptions::builder().return_document(ReturnDocument::After).build();
+    progress_collection(db).find_one_and_update(filter, update, options).await
+}
+
+async fn ensure_progress(
+    db: &Database,
+    assignment: &Assignment,
+    user_id: &str,
+) -> mongodb::error::Result<String> {
+    let id = progress_id(&assignment.id, user_id);
+    let update = doc! {
+        "$setOnInsert": {
+            "assignment_id": &assignment.id,
+            "group_id": &assignment.group_id,
+            "user_id": user_id,
+            "episodes": [],
+            "answers": [],
+            "completed_at": null,
+            "updated_at": DateTime::now(),
+        }
+    };
+    let options = UpdateOptions::builder().upsert(true).build();
+    progress_collection(db).update_one(doc! { "_id": &id }, update, options).await?;
+    Ok(id)
+}
@@ -0,0 +1,150 @@
+use axum::routing::{MethodRouter, get as axum_get};
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use chrono::{NaiveDate, Utc};
+use mongodb::bson::DateTime;
+use serde_json::json;
+use tracing::error;
+
+use crate::{
+    auth::Caller,
+    resources::{
+        challenges::model::{DATE_FORMAT, challenge_date},
+        groups::queries as group_queries,
+        leaderboards::{
+            model::{
+                LeaderboardDto, LeaderboardEntry, LeaderboardQuery, RankDto, SCOPE_ALL, scope_scores,
+            },
+            queries,
+        },
+    },
+    routes::api::ApiState,
+};
+
+pub fn board() -> MethodRouter<ApiState> {
+    axum_get(get_leaderboard)
+}
+
+/// Ranks players on the global board or an era board (`era`), for all time, a week or a day.
+/// Ties go to whoever reached the score first. `group` ranks only that group's members, and is
+/// open to its owner and members.
+pub async fn get_leaderboard(
+    State(state): State<ApiState>,
+    Query(params): Query<LeaderboardQuery>,
+    caller: Caller,
+) -> impl IntoResponse {
+    let date = match params.date.as_deref() {
+        Some(raw) => match NaiveDate::parse_from_str(raw, DATE_FORMAT) {
+            Ok(date) => date,
+            Err(_) => return error_response(StatusCode::BAD_REQUEST, "date must be YYYY-MM-DD"),
+        },
+        None => challenge_date(Utc::now(), state.config.challenge_timezone),
+    };
+    let members = match params.group.as_deref() {
+        Some(group_id) => {
+            let user_id = match caller.require_user() {
+                Ok(user_id) => user_id,
+                Err(forbidden) => return forbidden.into_response(),
+            };
+            match group_queries::find_group(&state.db, group_id).await {
+                Ok(Some(group)) if group.can_view(user_id) => Some(group.member_ids()),
+                Ok(_) => return error_response(StatusCode::NOT_FOUND, "group not found"),
+                Err(err) => {
+                    error!(error = ?err, "failed to fetch group");
+                    return error_response(
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        "failed to load leaderboard",
+                    );
+                }
+            }
+        }
+        None => None,
+    };
+    let scope = params.era.map(|era| format!("era:{era}")).unwrap_or_else(|| SCOPE_ALL.to_string());
+    let period = params.period.key(date);
+    let limit = params.limit.unwrap_or(50).min(100) as i64;
+    let offset = params.offset.unwrap_or(0);
+
+    let entries =
+        match queries::list_entries(&state.db, &scope, &period, members.as_deref(), limit, offset).await
+        {
+            Ok(entries) => entries,
+            Err(err) => {
+                error!(error = ?err, "failed to load leaderboard");
+                return error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to load leaderboard");
+            }
+        };
+    let items = entries
+        .into_iter()
+        .enumerate()
+        .map(|(idx, entry)| rank_dto(entry, offset + idx as u64 + 1))
+        .collect();
+
+    let me = match caller.user_id.as_deref() {
+        Some(user_id) => match own_rank(&state, &scope, &period, members.as_deref(), user_id).await {
+            Ok(me) => me,
+            Err(err) => {
+                error!(error = ?err, "failed to rank caller");
+                return error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to load leaderboard");
+            }
+        },
+        None => None,
+    };
+
+    (
+        StatusCode::OK,
+        Json(LeaderboardDto {
+            scope,
+            period,
+            group: params.group,
+            items,
+            me,
+        }),
+    )
+        .into_response()
+}
+
+/// Feeds a graded result into the boards. `answers` pairs each question's era with whether it was
+/// answered correctly. Failures are logged; the result itself is already stored.
+pub async fn record_result(state: &ApiState, user_id: &str, answers: &[(Option<String>, bool)]) {
+    let date = challenge_date(Utc::now(), state.config.challenge_timezone);
+    let scores = scope_scores(answers);
+    if let Err(err) = queries::record_scores(&state.db, user_id, &scores, date, DateTime::now()).await {
+        error!(error = ?err, user_id, "failed to update leaderboards");
+    }
+}
+
+async fn own_rank(
+    state: &ApiState,
+    scope: &str,
+    period: &str,
+    members: Option<&[String]>,
+    user_id: &str,
+) -> mongodb::error::Result<Option<RankDto>> {
+    // A group's owner is not on its board.
+    if members.is_some_and(|members| !members.iter().any(|m| m == user_id)) {
+        return Ok(None);
+    }
+    let Some(entry) = queries::find_entry(&state.db, scope, period, user_id).await? else {
+        return Ok(None);
+    };
+    let rank = queries::rank_of(&state.db, &entry, members).await?;
+    Ok(Some(rank_dto(entry, rank)))
+}
+
+fn rank_dto(entry: LeaderboardEntry, rank: u64) -> RankDto {
+    RankDto {
+        rank,
+        user_id: entry.user_id,
+        score: entry.score,
+        answered: entry.answered,
+    }
+}
+
+fn error_response(status: StatusCode, msg: &str) -> Response {
+    (status, Json(json!({ "error": msg }))).into_response()
+}
@@ -0,0 +1,147 @@
+use chrono::NaiveDate;
+use mongodb::bson::DateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::resources::challenges::model::DATE_FORMAT;
+
+/// Scope of the global board; era boards use `era:<id>`.
+pub const SCOPE_ALL: &str = "all";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Period {
+    #[default]
+    AllTime,
+    Week,
+    Day,
+}
+
+impl Period {
+    pub const ALL: [Period; 3] = [Period::AllTime, Period::Week, Period::Day];
+
+    /// Key of the period containing `date`: `all`, `week:2026-W42` (ISO week) or `day:2026-10-18`.
+    pub fn key(self, date: NaiveDate) -> String {
+        match self {
+            Period::AllTime => "all".to_string(),
+            Period::Week => format!("week:{}", date.format("%G-W%V")),
+            Period::Day => format!("day:{}", date.format(DATE_FORMAT)),
+        }
+    }
+}
+
+/// A player's running total on one board (collection `leaderboard_entries`). Totals are
+/// maintained as results are recorded, so reading a board or a rank is a single indexed query.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LeaderboardEntry {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub scope: String,
+    pub period: String,
+    pub user_id: String,
+    pub score: i64,
+    pub answered: i64,
+    /// When the current score was reached; earlier ranks higher on ties.
+    pub reached_at: DateTime,
+}
+
+/// Points earned by one result on one scope.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScopeScore {
+    pub scope: String,
+    pub score: i64,
+    pub answered: i64,
+}
+
+/// Builds the scope scores for a graded result: one for the global board and one per era touched.
+pub fn scope_scores(answers: &[(Option<String>, bool)]) -> Vec<ScopeScore> {
+    let mut scores = vec![ScopeScore {
+        scope: SCOPE_ALL.to_string(),
+        score: 0,
+        answered: 0,
+    }];
+    for (era, correct) in answers {
+        let mut bump = |scope: String| {
+            let idx = match scores.iter().position(|s| s.scope == scope) {
+                Some(idx) => idx,
+                None => {
+                    scores.push(ScopeScore {
+                        scope,
+                        score: 0,
+                        answered: 0,
+                    });
+                    scores.len() - 1
+                }
+            };
+            scores[idx].answered += 1;
+            scores[idx].score += i64::from(*correct);
+        };
+        bump(SCOPE_ALL.to_string());
+        if let Some(era) = era {
+            bump(format!("era:{era}"));
+        }
+    }
+    scores
+}
+
+/// `era` selects an era board; `group` narrows any board to a group's members; `date` picks which
+/// day or week (default: the current one).
+#[derive(Deserialize)]
+pub struct LeaderboardQuery {
+    #[serde(default)]
+    pub period: Period,
+    pub era: Option<String>,
+    pub group: Option<String>,
+    pub date: Option<String>,
+    pub limit: Option<u64>,
+    pub offset: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RankDto {
+    pub rank: u64,
+    pub user_id: String,
+    pub score: i64,
+    pub answered: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LeaderboardDto {
+    pub scope: String,
+    pub period: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    pub items: Vec<RankDto>,
+    /// The caller's own standing, even when outside the requested page.
+    pub me: Option<RankDto>,
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::{Period, ScopeScore, scope_scores};
+
+    #[test]
+    fn period_keys() {
+        let date = NaiveDate::from_ymd_opt(2027, 1, 1).unwrap();
+        assert_eq!(Period::AllTime.key(date), "all");
+        assert_eq!(Period::Week.key(date), "week:2026-W53");
+        assert_eq!(Period::Day.key(date), "day:2027-01-01");
+    }
+
+    #[test]
+    fn scores_split_by_era() {
+        let answers = vec![
+            (Some("prophets".to_string()), true),
+            (Some("prophets".to_string()), false),
+            (None, true),
+        ];
+        let scores = scope_scores(&answers);
+        let score = |scope: &str, score, answered| ScopeScore {
+            scope: scope.to_string(),
+            score,
+            answered,
+        };
+        assert_eq!(scores, vec![score("all", 2, 3), score("era:prophets", 1, 2)]);
+    }
+}
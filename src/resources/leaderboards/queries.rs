@@ -0,0 +1,108 @@
+use chrono::NaiveDate;
+use futures_util::TryStreamExt;
+use mongodb::{
+    Collection, Database, IndexModel,
+    bson::{DateTime, doc},
+    options::{FindOptions, UpdateOptions},
+};
+
+use crate::resources::leaderboards::model::{LeaderboardEntry, Period, ScopeScore};
+
+fn entries_collection(db: &Database) -> Collection<LeaderboardEntry> {
+    db.collection("leaderboard_entries")
+}
+
+/// Index backing board pages and rank counts.
+pub async fn ensure_indexes(db: &Database) -> mongodb::error::Result<()> {
+    let index = IndexModel::builder()
+        .keys(doc! { "scope": 1, "period": 1, "score": -1, "reached_at": 1, "user_id": 1 })
+        .build();
+    entries_collection(db).create_index(index, None).await?;
+    Ok(())
+}
+
+/// Adds a graded result to every board and period it counts towards.
+pub async fn record_scores(
+    db: &Database,
+    user_id: &str,
+    scores: &[ScopeScore],
+    date: NaiveDate,
+    completed_at: DateTime,
+) -> mongodb::error::Result<()> {
+    let options = UpdateOptions::builder().upsert(true).build();
+    for score in scores {
+        for period in Period::ALL {
+            let period = period.key(date);
+            let id = format!("{}|{}|{}", score.scope, period, user_id);
+            let mut update = doc! {
+                "$inc": { "score": score.score, "answered": score.answered },
+                "$setOnInsert": { "scope": &score.scope, "period": &period, "user_id": user_id },
+            };
+            // Only a higher score moves the tie-break timestamp.
+            if score.score > 0 {
+                update.insert("$set", doc! { "reached_at": completed_at });
+            } else if let Ok(on_insert) = update.get_document_mut("$setOnInsert") {
+                on_insert.insert("reached_at", completed_at);
+            }
+            entries_collection(db).update_one(doc! { "_id": id }, update, options.clone()).await?;
+        }
+    }
+    Ok(())
+}
+
+/// One page of a board, best first. `members` restricts the board to those players.
+pub async fn list_entries(
+    db: &Database,
+    scope: &str,
+    period: &str,
+    members: Option<&[String]>,
+    limit: i64,
+    offset: u64,
+) -> mongodb::error::Result<Vec<LeaderboardEntry>> {
+    let options = FindOptions::builder()
+        .sort(doc! { "score": -1, "reached_at": 1, "user_id": 1 })
+        .skip(Some(offset))
+        .limit(Some(limit))
+        .build();
+    let mut filter = doc! { "scope": scope, "period": period };
+    if let Some(members) = members {
+        filter.insert("user_id", doc! { "$in": members });
+    }
+    let mut cursor = entries_collection(db).find(filter, options).await?;
+    let mut entries = Vec::new();
+    while let Some(entry) = cursor.try_next().await? {
+        entries.push(entry);
+    }
+    Ok(entries)
+}
+
+pub async fn find_entry(
+    db: &Database,
+    scope: &str,
+    period: &str,
+    user_id: &str,
+) -> mongodb::error::Result<Option<LeaderboardEntry>> {
+    entries_collection(db).find_one(doc! { "_id": format!("{scope}|{period}|{user_id}") }, None).await
+}
+
+/// 1-based rank of `entry` on its board, among `members` when given: the number of entries ordered
+/// before it, plus one.
+pub async fn rank_of(
+    db: &Database,
+    entry: &LeaderboardEntry,
+    members: Option<&[String]>,
+) -> mongodb::error::Result<u64> {
+    let mut filter = doc! {
+        "scope": &entry.scope,
+        "period": &entry.period,
+        "$or": [
+            { "score": { "$gt": entry.score } },
+            { "score": entry.score, "reached_at": { "$lt": entry.reached_at } },
+            { "score": entry.score, "reached_at": entry.reached_at, "user_id": { "$lt": &entry.user_id } },
+        ],
+    };
+    if let Some(members) = members {
+        filter.insert("user_id", doc! { "$in": members });
+    }
+    Ok(entries_collection(db).count_documents(filter, None).await? + 1)
+}
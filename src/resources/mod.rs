@@ -1,4 +1,23 @@
+pub mod achievements;
+pub mod admin;
+pub mod bookmarks;
+pub mod bundle;
+pub mod challenges;
 pub mod eras;
+pub mod groups;
 pub mod health;
+pub mod leaderboards;
+pub mod placement;
+pub mod play;
 pub mod questions;
+pub mod recommendations;
+pub mod repetition;
+pub mod reports;
+pub mod revisions;
+pub mod rooms;
+pub mod stats;
+pub mod streaks;
+pub mod submissions;
+pub mod sync;
 pub mod ui;
+pub mod workflow;
@@ -0,0 +1,108 @@
+use crate::resources::stats::calibration::{BASE_RATING, expected};
+
+/// Prior belief about a newcomer's ability: centred on an average player, wide enough that a
+/// handful of answers dominates it.
+const PRIOR_SD: f64 = 350.0;
+/// Resolution of the ability grid the posterior is evaluated on.
+const GRID_STEP: f64 = 5.0;
+/// A player has mastered a level when they are expected to answer its average question correctly
+/// this often.
+pub const MASTERY_PROBABILITY: f64 = 0.7;
+
+/// Ability on the calibrated difficulty scale, with its standard error.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Estimate {
+    pub ability: f64,
+    pub standard_error: f64,
+}
+
+/// Expected a-posteriori ability given `(difficulty, correct)` responses, under a normal prior and
+/// the same logistic model the calibration job uses.
+pub fn estimate(responses: &[(f64, bool)]) -> Estimate {
+    let span = 4.0 * PRIOR_SD;
+    let steps = (2.0 * span / GRID_STEP) as usize;
+    let grid: Vec<(f64, f64)> = (0..=steps)
+        .map(|i| {
+            let ability = BASE_RATING - span + i as f64 * GRID_STEP;
+            let prior = -((ability - BASE_RATING) / PRIOR_SD).powi(2) / 2.0;
+            let likelihood: f64 = responses
+                .iter()
+                .map(|&(difficulty, correct)| {
+                    let p = expected(ability, difficulty);
+                    if correct {
+                        p.ln()
+                    } else {
+                        (1.0 - p).ln()
+                    }
+                })
+                .sum();
+            (ability, prior + likelihood)
+        })
+        .collect();
+
+    // Normalise in log space so long runs of answers cannot underflow.
+    let peak = grid.iter().map(|&(_, log_weight)| log_weight).fold(f64::NEG_INFINITY, f64::max);
+    let (mut total, mut mean) = (0.0, 0.0);
+    for &(ability, log_weight) in &grid {
+        let weight = (log_weight - peak).exp();
+        total += weight;
+        mean += weight * ability;
+    }
+    mean /= total;
+    let variance = grid
+        .iter()
+        .map(|&(ability, log_weight)| (log_weight - peak).exp() * (ability - mean).powi(2))
+        .sum::<f64>()
+        / total;
+
+    Estimate {
+        ability: mean,
+        standard_error: variance.sqrt(),
+    }
+}
+
+/// Rank of the highest level mastered without a gap, given `(rank, average difficulty)` for the
+/// levels that have calibrated questions. Levels without any are skipped; nobody places below the
+/// first level.
+pub fn place(ability: f64, levels: &[(usize, f64)]) -> usize {
+    let mut levels = levels.to_vec();
+    levels.sort_by_key(|&(rank, _)| rank);
+    let mut placed = 0;
+    for (rank, difficulty) in levels {
+        if expected(ability, difficulty) < MASTERY_PROBABILITY {
+            break;
+        }
+        placed = rank;
+    }
+    placed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{estimate, place};
+    use crate::resources::stats::calibration::BASE_RATING;
+
+    #[test]
+    fn estimates_narrow_and_follow_answers() {
+        let prior = estimate(&[]);
+        assert!((prior.ability - BASE_RATING).abs() < 1.0);
+        assert!((prior.standard_error - 350.0).abs() < 5.0);
+
+        let strong: Vec<(f64, bool)> =
+            [1500.0, 1600.0, 1700.0, 1800.0, 1900.0].map(|d| (d, true)).to_vec();
+        let strong = estimate(&strong);
+        assert!(strong.ability > 1800.0);
+        assert!(strong.standard_error < prior.standard_error);
+
+        let weak = estimate(&[(1500.0, false), (1400.0, false), (1300.0, false)]);
+        assert!(weak.ability < 1300.0);
+    }
+
+    #[test]
+    fn placement_stops_at_the_first_unmastered_level() {
+        let levels = [(0, 1200.0), (1, 1400.0), (3, 1600.0), (4, 1700.0), (6, 1500.0)];
+        assert_eq!(place(1000.0, &levels), 0);
+        assert_eq!(place(1800.0, &levels), 3);
+        assert_eq!(place(2200.0, &levels), 6);
+    }
+}
@@ -0,0 +1,303 @@
+use axum::routing::{MethodRouter, get as axum_get, post as axum_post};
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+use mongodb::bson::{DateTime, oid::ObjectId};
+use serde_json::json;
+use tracing::error;
+
+use crate::{
+    auth::Caller,
+    lang::resolve_lang,
+    resources::{
+        placement::{
+            ability::{Estimate, estimate, place},
+            model::{
+                CANDIDATES, Placement, PlacementQuery, PlacementResponse, PlacementResultDto,
+                PlacementSession, PlacementStepDto, is_finished,
+            },
+            queries,
+        },
+        play::{
+            model::{PlayQuestionDto, shuffle_for, to_stored_answer},
+            shuffle::Shuffle,
+        },
+        questions::{
+            model::QuestionDto,
+            queries as question_queries,
+            types::{TimedAnswer, check_answer},
+        },
+        stats::{calibration::BASE_RATING, handler as stats_handler, model::AnswerOutcome},
+        ui::levels::{level_id, level_label, level_rank},
+    },
+    routes::api::ApiState,
+};
+
+pub fn start() -> MethodRouter<ApiState> {
+    axum_post(start_placement)
+}
+
+pub fn answer() -> MethodRouter<ApiState> {
+    axum_post(answer_placement)
+}
+
+pub fn mine() -> MethodRouter<ApiState> {
+    axum_get(my_placement)
+}
+
+/// Starts a placement quiz and serves its first question, pitched at an average player.
+pub async fn start_placement(
+    State(state): State<ApiState>,
+    Query(params): Query<PlacementQuery>,
+    caller: Caller,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let user_id = match caller.require_user() {
+        Ok(user_id) => user_id.to_string(),
+        Err(forbidden) => return forbidden.into_response(),
+    };
+    let now = DateTime::now();
+    let mut session = PlacementSession {
+        id: ObjectId::new().to_hex(),
+        user_id,
+        responses: Vec::new(),
+        current: None,
+        created_at: now,
+        updated_at: now,
+    };
+
+    let prior = estimate(&[]);
+    let question = match next_question(&state, &session, prior).await {
+        Ok(Some(question)) => question,
+        Ok(None) => {
+            return error_response(
+                StatusCode::CONFLICT,
+                "not enough calibrated questions for placement",
+            );
+        }
+        Err(err) => {
+            error!(error = ?err, "failed to pick placement question");
+            return error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to start placement");
+        }
+    };
+    session.current = Some(question.id.clone());
+    if let Err(err) = queries::insert_session(&state.db, &session).await {
+        error!(error = ?err, "failed to store placement session");
+        return error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to start placement");
+    }
+
+    let lang = resolve_lang(params.lang.as_deref(), headers.get("accept-language"));
+    let step = step_dto(&session, prior, None, Some((question, lang.as_str())), None);
+    (StatusCode::CREATED, Json(step)).into_response()
+}
+
+/// Grades the answer to the session's current question (in display positions), updates the
+/// ability estimate and serves the question closest to it, or finishes with a level once the
+/// estimate is precise enough.
+pub async fn answer_placement(
+    State(state): State<ApiState>,
+    Path(session_id): Path<String>,
+    Query(params): Query<PlacementQuery>,
+    caller: Caller,
+    headers: HeaderMap,
+    Json(TimedAnswer {
+        answer,
+        time_ms,
+    }): Json<TimedAnswer>,
+) -> impl IntoResponse {
+    let user_id = match caller.require_user() {
+        Ok(user_id) => user_id.to_string(),
+        Err(forbidden) => return forbidden.into_response(),
+    };
+    let mut session = match queries::find_session(&state.db, &session_id).await {
+        Ok(Some(session)) if session.user_id == user_id => session,
+        Ok(_) => return error_response(StatusCode::NOT_FOUND, "placement not found"),
+        Err(err) => {
+            error!(error = ?err, "failed to load placement session");
+            return error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to record answer");
+        }
+    };
+    let Some(current) = session.current.clone() else {
+        return error_response(StatusCode::CONFLICT, "placement already finished");
+    };
+    let lang = resolve_lang(params.lang.as_deref(), headers.get("accept-language"));
+
+    // A question deleted mid-placement is skipped rather than graded.
+    let (mut correct, mut outcome) = (None, None);
+    match question_queries::find_question_by_id(&state.db, &current).await {
+        Ok(Some(question)) => {
+            let shuffle = shuffle_for(&question, Some(&user_id), &session.id);
+            let answer = match to_stored_answer(answer, &shuffle) {
+                Ok(answer) => answer,
+                Err(msg) => return error_response(StatusCode::BAD_REQUEST, msg),
+            };
+            let check = match check_answer(&question, &answer, &lang) {
+                Ok(check) => check,
+                Err(msg) => return error_response(StatusCode::BAD_REQUEST, msg),
+            };
+            outcome = Some(AnswerOutcome::new(&question.id, &answer, check.correct, time_ms));
+            session.responses.push(PlacementResponse {
+                question_id: question.id,
+                difficulty: question.difficulty.unwrap_or(BASE_RATING),
+                correct: check.correct,
+            });
+            correct = Some(check.correct);
+        }
+        Ok(None) => {}
+        Err(err) => {
+            error!(error = ?err, "failed to fetch question");
+            return error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to record answer");
+        }
+    }
+
+    let current_estimate = estimate(&session.responses());
+    session.current = None;
+    let mut next = None;
+    if !is_finished(session.responses.len(), current_estimate) {
+        match next_question(&state, &session, current_estimate).await {
+            Ok(question) => next = question,
+            Err(err) => {
+                error!(error = ?err, "failed to pick placement question");
+                return error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to record answer");
+            }
+        }
+    }
+    session.current = next.as_ref().map(|q| q.id.clone());
+
+    match queries::advance_session(&state.db, &session, &current).await {
+        Ok(true) => {
+            if let Some(outcome) = outcome {
+                stats_handler::record_answers(&state, Some(&user_id), &lang, &[outcome]).await;
+            }
+        }
+        Ok(false) => return error_response(StatusCode::CONFLICT, "question already answered"),
+        Err(err) => {
+            error!(error = ?err, "failed to store placement answer");
+            return error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to record answer");
+        }
+    }
+
+    if let Some(question) = next {
+        let step = step_dto(&session, current_estimate, correct, Some((question, lang.as_str())), None);
+        return (StatusCode::OK, Json(step)).into_response();
+    }
+
+    let placement = match finish(&state, &session, current_estimate).await {
+        Ok(placement) => placement,
+        Err(err) => {
+            error!(error = ?err, "failed to store placement");
+            return error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to record answer");
+        }
+    };
+    let label = level_label(&placement.level, &lang);
+    let result = PlacementResultDto::new(placement, label);
+    (StatusCode::OK, Json(step_dto(&session, current_estimate, correct, None, Some(result))))
+        .into_response()
+}
+
+/// The caller's latest placement result.
+pub async fn my_placement(
+    State(state): State<ApiState>,
+    Query(params): Query<PlacementQuery>,
+    caller: Caller,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let user_id = match caller.require_user() {
+        Ok(user_id) => user_id,
+        Err(forbidden) => return forbidden.into_response(),
+    };
+    match queries::find_placement(&state.db, user_id).await {
+        Ok(Some(placement)) => {
+            let lang = resolve_lang(params.lang.as_deref(), headers.get("accept-language"));
+            let label = level_label(&placement.level, &lang);
+            (StatusCode::OK, Json(PlacementResultDto::new(placement, label))).into_response()
+        }
+        Ok(None) => error_response(StatusCode::NOT_FOUND, "no placement yet"),
+        Err(err) => {
+            error!(error = ?err, "failed to load placement");
+            error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to load placement")
+        }
+    }
+}
+
+/// The calibrated question nearest the current estimate, where a response is most informative.
+/// One of the few nearest is picked per session and step.
+async fn next_question(
+    state: &ApiState,
+    session: &PlacementSession,
+    estimate: Estimate,
+) -> mongodb::error::Result<Option<QuestionDto>> {
+    let mut candidates = question_queries::nearest_calibrated(
+        &state.db,
+        estimate.ability,
+        state.config.calibration_min_answers,
+        &session.asked(),
+        CANDIDATES,
+    )
+    .await?;
+    candidates.truncate(CANDIDATES as usize);
+    if candidates.is_empty() {
+        return Ok(None);
+    }
+
+    let ids: Vec<String> = candidates.iter().map(|q| q.id.clone()).collect();
+    let step = session.responses.len().to_string();
+    let seed = Shuffle::seed(Some(&session.user_id), &session.id, &step);
+    let picked = Shuffle::new(seed, ids.len()).apply(&ids).swap_remove(0);
+    Ok(candidates.into_iter().find(|q| q.id == picked))
+}
+
+/// Maps the final estimate onto the levels catalog and stores it as the player's placement.
+async fn finish(
+    state: &ApiState,
+    session: &PlacementSession,
+    estimate: Estimate,
+) -> mongodb::error::Result<Placement> {
+    let levels: Vec<(usize, f64)> =
+        question_queries::level_difficulty(&state.db, state.config.calibration_min_answers)
+            .await?
+            .into_iter()
+            .filter_map(|(level, difficulty)| Some((level_rank(&level)?, difficulty)))
+            .collect();
+    let placement = Placement {
+        user_id: session.user_id.clone(),
+        session: session.id.clone(),
+        level: level_id(place(estimate.ability, &levels)).unwrap_or_default(),
+        ability: estimate.ability,
+        standard_error: estimate.standard_error,
+        answered: session.responses.len() as i32,
+        correct: session.responses.iter().filter(|r| r.correct).count() as i32,
+        completed_at: DateTime::now(),
+    };
+    queries::save_placement(&state.db, &placement).await?;
+    Ok(placement)
+}
+
+fn step_dto(
+    session: &PlacementSession,
+    estimate: Estimate,
+    correct: Option<bool>,
+    question: Option<(QuestionDto, &str)>,
+    result: Option<PlacementResultDto>,
+) -> PlacementStepDto {
+    let question = question.map(|(question, lang)| {
+        let shuffle = shuffle_for(&question, Some(&session.user_id), &session.id);
+        PlayQuestionDto::new(question, lang, session.id.clone(), &shuffle)
+    });
+    PlacementStepDto {
+        session: session.id.clone(),
+        answered: session.responses.len(),
+        ability: estimate.ability,
+        standard_error: estimate.standard_error,
+        correct,
+        question,
+        result,
+    }
+}
+
+fn error_response(status: StatusCode, msg: &str) -> Response {
+    (status, Json(json!({ "error": msg }))).into_response()
+}
@@ -0,0 +1,4 @@
+pub mod ability;
+pub mod handler;
+pub mod model;
+pub mod queries;
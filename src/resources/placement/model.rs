@@ -0,0 +1,134 @@
+use chrono::Utc;
+use mongodb::bson::DateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::resources::{placement::ability::Estimate, play::model::PlayQuestionDto};
+
+/// Questions every placement asks before it may stop early.
+pub const MIN_QUESTIONS: usize = 5;
+/// Hard cap on a placement's length.
+pub const MAX_QUESTIONS: usize = 20;
+/// A placement stops once the ability estimate is this precise.
+pub const TARGET_STANDARD_ERROR: f64 = 110.0;
+/// The next question is drawn from this many calibrated questions nearest the current estimate,
+/// so players starting out alike do not all see the same sequence.
+pub const CANDIDATES: i64 = 3;
+
+/// An adaptive placement in progress or finished (collection `placement_sessions`).
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PlacementSession {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub user_id: String,
+    pub responses: Vec<PlacementResponse>,
+    /// The question awaiting an answer; `None` once the placement is finished.
+    pub current: Option<String>,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlacementResponse {
+    pub question_id: String,
+    /// The question's calibrated difficulty when it was answered.
+    pub difficulty: f64,
+    pub correct: bool,
+}
+
+/// A player's latest placement outcome (collection `placements`, keyed by user id).
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Placement {
+    #[serde(rename = "_id")]
+    pub user_id: String,
+    pub session: String,
+    pub level: String,
+    pub ability: f64,
+    pub standard_error: f64,
+    pub answered: i32,
+    pub correct: i32,
+    pub completed_at: DateTime,
+}
+
+#[derive(Deserialize)]
+pub struct PlacementQuery {
+    pub lang: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PlacementResultDto {
+    pub level: String,
+    pub label: Option<String>,
+    pub ability: f64,
+    pub standard_error: f64,
+    pub answered: i32,
+    pub correct: i32,
+    pub completed_at: String,
+}
+
+impl PlacementResultDto {
+    pub fn new(placement: Placement, label: Option<String>) -> Self {
+        Self {
+            level: placement.level,
+            label,
+            ability: placement.ability,
+            standard_error: placement.standard_error,
+            answered: placement.answered,
+            correct: placement.correct,
+            completed_at: placement.completed_at.to_chrono().with_timezone(&Utc).to_rfc3339(),
+        }
+    }
+}
+
+/// One step of a placement: the next question to answer, or the result once it is finished.
+#[derive(Debug, Serialize)]
+pub struct PlacementStepDto {
+    pub session: String,
+    pub answered: usize,
+    pub ability: f64,
+    pub standard_error: f64,
+    /// Whether the answer just submitted was correct.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub correct: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub question: Option<PlayQuestionDto>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<PlacementResultDto>,
+}
+
+impl PlacementSession {
+    pub fn responses(&self) -> Vec<(f64, bool)> {
+        self.responses.iter().map(|r| (r.difficulty, r.correct)).collect()
+    }
+
+    pub fn asked(&self) -> Vec<String> {
+        self.responses.iter().map(|r| r.question_id.clone()).chain(self.current.clone()).collect()
+    }
+}
+
+/// Whether a placement with `answered` responses and `estimate` has seen enough.
+pub fn is_finished(answered: usize, estimate: Estimate) -> bool {
+    answered >= MAX_QUESTIONS
+        || (answered >= MIN_QUESTIONS && estimate.standard_error <= TARGET_STANDARD_ERROR)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MAX_QUESTIONS, MIN_QUESTIONS, is_finished};
+    use crate::resources::placement::ability::{Estimate, estimate};
+
+    #[test]
+    fn placements_stop_when_precise_or_capped() {
+        let precise = Estimate {
+            ability: 1500.0,
+            standard_error: 90.0,
+        };
+        assert!(!is_finished(MIN_QUESTIONS - 1, precise));
+        assert!(is_finished(MIN_QUESTIONS, precise));
+        assert!(is_finished(MAX_QUESTIONS, estimate(&[])));
+
+        // Answers at the player's level converge within the cap.
+        let responses: Vec<(f64, bool)> = (0..MAX_QUESTIONS).map(|i| (1500.0, i % 2 == 0)).collect();
+        let finished_at = (1..=MAX_QUESTIONS).find(|&n| is_finished(n, estimate(&responses[..n])));
+        assert!(finished_at.is_some_and(|n| n < MAX_QUESTIONS));
+    }
+}
@@ -0,0 +1,57 @@
+use mongodb::{
+    Collection, Database, bson,
+    bson::{DateTime, doc},
+    options::ReplaceOptions,
+};
+
+use crate::resources::placement::model::{Placement, PlacementSession};
+
+fn sessions_collection(db: &Database) -> Collection<PlacementSession> {
+    db.collection("placement_sessions")
+}
+
+fn placements_collection(db: &Database) -> Collection<Placement> {
+    db.collection("placements")
+}
+
+pub async fn insert_session(db: &Database, session: &PlacementSession) -> mongodb::error::Result<()> {
+    sessions_collection(db).insert_one(session, None).await?;
+    Ok(())
+}
+
+pub async fn find_session(db: &Database, id: &str) -> mongodb::error::Result<Option<PlacementSession>> {
+    sessions_collection(db).find_one(doc! { "_id": id }, None).await
+}
+
+/// Stores the session's new responses and next question, unless the question it was waiting on
+/// has been answered concurrently. Returns `false` in that case.
+pub async fn advance_session(
+    db: &Database,
+    session: &PlacementSession,
+    answered: &str,
+) -> mongodb::error::Result<bool> {
+    let update = doc! {
+        "$set": {
+            "responses": bson::to_bson(&session.responses)?,
+            "current": &session.current,
+            "updated_at": DateTime::now(),
+        }
+    };
+    let result = sessions_collection(db)
+        .update_one(doc! { "_id": &session.id, "current": answered }, update, None)
+        .await?;
+    Ok(result.modified_count > 0)
+}
+
+/// Keeps only the latest placement per player.
+pub async fn save_placement(db: &Database, placement: &Placement) -> mongodb::error::Result<()> {
+    let options = ReplaceOptions::builder().upsert(true).build();
+    placements_collection(db)
+        .replace_one(doc! { "_id": &placement.user_id }, placement, options)
+        .await?;
+    Ok(())
+}
+
+pub async fn find_placement(db: &Database, user_id: &str) -> mongodb::error::Result<Option<Placement>> {
+    placements_collection(db).find_one(doc! { "_id": user_id }, None).await
+}
@@ -0,0 +1,496 @@
+use axum::routing::{MethodRouter, get as axum_get, post as axum_post};
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+use mongodb::bson::{DateTime, oid::ObjectId};
+use serde_json::json;
+use tracing::error;
+
+use crate::{
+    auth::Caller,
+    lang::resolve_lang,
+    resources::{
+        achievements::handler as achievement_handler,
+        challenges::{handler as challenge_handler, model::is_challenge_session},
+        leaderboards::handler as leaderboard_handler,
+        play::{
+            model::{
+                DEFAULT_SET_SIZE, GradedAnswer, MAX_SET_SIZE, PlayAnswerDto, PlayQuery, PlayQuestionDto,
+                PlaySetDto, QUIZ_POOL_FACTOR, QuestionStartDto, QuizAnswer, QuizRequest, QuizResult,
+                QuizResultDto, RandomQuery, SubmitQuiz, balance, check_submission, shuffle_for,
+                split_list, to_stored_answer,
+            },
+            queries,
+            scoring::{QuizMode, is_late},
+        },
+        questions::{
+            model::QuestionDto,
+            queries::{self as question_queries, DifficultyRange, SampleFilter},
+            types::{TimedAnswer, check_answer},
+        },
+        repetition::handler as repetition_handler,
+        stats::{handler as stats_handler, model::AnswerOutcome},
+        workflow::model::{ContentStatus, visibility_for},
+    },
+    routes::api::ApiState,
+};
+
+pub fn question() -> MethodRouter<ApiState> {
+    axum_get(play_question)
+}
+
+pub fn answer() -> MethodRouter<ApiState> {
+    axum_post(answer_question)
+}
+
+pub fn random() -> MethodRouter<ApiState> {
+    axum_get(random_questions)
+}
+
+pub fn quizzes() -> MethodRouter<ApiState> {
+    axum_post(create_quiz)
+}
+
+pub fn quiz_results() -> MethodRouter<ApiState> {
+    axum_post(submit_quiz)
+}
+
+pub fn start() -> MethodRouter<ApiState> {
+    axum_post(start_question)
+}
+
+/// Serves a question for play in the session's shuffled order. Clients keep the returned
+/// `session` and send it back to see the same order again and to answer.
+pub async fn play_question(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+    Query(params): Query<PlayQuery>,
+    caller: Caller,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let question = match load_question(&state, &id, &caller, params.preview).await {
+        Ok(question) => question,
+        Err(response) => return response,
+    };
+    let lang = resolve_lang(params.lang.as_deref(), headers.get("accept-language"));
+    let session = params.session.unwrap_or_else(|| ObjectId::new().to_hex());
+    let shuffle = shuffle_for(&question, caller.user_id.as_deref(), &session);
+
+    (StatusCode::OK, Json(PlayQuestionDto::new(question, &lang, session, &shuffle))).into_response()
+}
+
+/// Grades an answer given in display positions for `session`, mapping them back to the stored
+/// options before checking. Answers to published questions feed the question's statistics.
+/// Daily challenge and quiz sessions are refused: their answers are only graded on submission, so
+/// the solution is never revealed ahead of a ranked result.
+pub async fn answer_question(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+    Query(params): Query<PlayQuery>,
+    caller: Caller,
+    headers: HeaderMap,
+    Json(TimedAnswer {
+        answer,
+        time_ms,
+    }): Json<TimedAnswer>,
+) -> impl IntoResponse {
+    let Some(session) = params.session.as_deref() else {
+        return error_response(StatusCode::BAD_REQUEST, "session is required");
+    };
+    if is_challenge_session(session) {
+        return error_response(StatusCode::FORBIDDEN, "challenge answers are graded on submission");
+    }
+    match queries::find_session(&state.db, session).await {
+        Ok(None) => {}
+        Ok(Some(_)) => {
+            return error_response(StatusCode::FORBIDDEN, "quiz answers are graded on submission");
+        }
+        Err(err) => {
+            error!(error = ?err, "failed to load quiz session");
+            return error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to check answer");
+        }
+    }
+    match challenge_handler::in_open_challenge(&state, &id).await {
+        Ok(false) => {}
+        Ok(true) => {
+            return error_response(StatusCode::FORBIDDEN, "question is in an open daily challenge");
+        }
+        Err(err) => {
+            error!(error = ?err, "failed to load open challenges");
+            return error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to check answer");
+        }
+    }
+    let question = match load_question(&state, &id, &caller, params.preview).await {
+        Ok(question) => question,
+        Err(response) => return response,
+    };
+    let lang = resolve_lang(params.lang.as_deref(), headers.get("accept-language"));
+    let shuffle = shuffle_for(&question, caller.user_id.as_deref(), session);
+
+    let answer = match to_stored_answer(answer, &shuffle) {
+        Ok(answer) => answer,
+        Err(msg) => return error_response(StatusCode::BAD_REQUEST, msg),
+    };
+    match check_answer(&question, &answer, &lang) {
+        Ok(check) => {
+            if question.status == ContentStatus::Published {
+                let outcome = AnswerOutcome::new(&question.id, &answer, check.correct, time_ms);
+                stats_handler::record_answers(&state, caller.user_id.as_deref(), &lang, &[outcome])
+                    .await;
+            }
+            (StatusCode::OK, Json(PlayAnswerDto::new(check, &question, &lang, &shuffle))).into_response()
+        }
+        Err(msg) => error_response(StatusCode::BAD_REQUEST, msg),
+    }
+}
+
+/// Random published questions, ready for play. Nothing is remembered between calls; pass
+/// `exclude` to skip questions already seen.
+pub async fn random_questions(
+    State(state): State<ApiState>,
+    Query(params): Query<RandomQuery>,
+    caller: Caller,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let count = params.count.unwrap_or(DEFAULT_SET_SIZE).clamp(1, MAX_SET_SIZE);
+    let (eras, levels) = (split_list(params.era.as_deref()), split_list(params.level.as_deref()));
+    let (tags, exclude) = (split_list(params.tags.as_deref()), split_list(params.exclude.as_deref()));
+    let filter = SampleFilter {
+        stage: params.stage,
+        difficulty: DifficultyRange {
+            min: params.min_difficulty,
+            max: params.max_difficulty,
+        },
+        eras: &eras,
+        levels: &levels,
+        tags: &tags,
+        exclude: &exclude,
+    };
+
+    match question_queries::sample_questions(&state.db, &filter, count as i64).await {
+        Ok(questions) => {
+            let lang = resolve_lang(params.lang.as_deref(), headers.get("accept-language"));
+            let session = params.session.unwrap_or_else(|| ObjectId::new().to_hex());
+            play_set(questions, &lang, session, None, caller.user_id.as_deref()).into_response()
+        }
+        Err(err) => {
+            error!(error = ?err, "failed to sample questions");
+            error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to sample questions")
+        }
+    }
+}
+
+/// Builds a quiz balanced across eras and levels. Questions served earlier in the same `session`
+/// are never repeated; once the pool is exhausted the quiz comes back short (or empty). Items carry
+/// the time limit that applies under the session's mode. Sessions started signed in can only be
+/// continued by the same player.
+pub async fn create_quiz(
+    State(state): State<ApiState>,
+    Query(params): Query<PlayQuery>,
+    caller: Caller,
+    headers: HeaderMap,
+    body: Option<Json<QuizRequest>>,
+) -> impl IntoResponse {
+    let request = body.map(|Json(request)| request).unwrap_or_default();
+    let count = request.count.unwrap_or(DEFAULT_SET_SIZE).clamp(1, MAX_SET_SIZE);
+    let session = request.session.unwrap_or_else(|| ObjectId::new().to_hex());
+
+    let (served, mode, owner) = match queries::find_session(&state.db, &session).await {
+        Ok(Some(existing))
+            if existing
+                .user_id
+                .as_deref()
+                .is_none_or(|owner| caller.user_id.as_deref() == Some(owner)) =>
+        {
+            (existing.served, existing.mode, existing.user_id)
+        }
+        Ok(Some(_)) => return error_response(StatusCode::NOT_FOUND, "quiz session not found"),
+        Ok(None) => (Vec::new(), request.mode, caller.user_id.clone()),
+        Err(err) => {
+            error!(error = ?err, "failed to load quiz session");
+            return error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to build quiz");
+        }
+    };
+    let filter = SampleFilter {
+        stage: request.stage,
+        difficulty: DifficultyRange {
+            min: request.min_difficulty,
+            max: request.max_difficulty,
+        },
+        eras: &request.eras,
+        levels: &request.levels,
+        tags: &request.tags,
+        exclude: &served,
+    };
+    let candidates =
+        match question_queries::sample_questions(&state.db, &filter, (count * QUIZ_POOL_FACTOR) as i64)
+            .await
+        {
+            Ok(candidates) => candidates,
+            Err(err) => {
+                error!(error = ?err, "failed to sample questions");
+                return error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to build quiz");
+            }
+        };
+
+    let mut questions = balance(candidates, count);
+    let rule = state.config.quiz_scoring.rule(mode);
+    for question in &mut questions {
+        question.time_limit_secs = rule.time_limit_secs(question.time_limit_secs);
+    }
+    let ids: Vec<String> = questions.iter().map(|q| q.id.clone()).collect();
+    if let Err(err) =
+        queries::record_served(&state.db, &session, caller.user_id.as_deref(), mode, &ids).await
+    {
+        error!(error = ?err, session, "failed to record served questions");
+    }
+
+    let lang = resolve_lang(params.lang.as_deref(), headers.get("accept-language"));
+    // Shuffle for the session's owner, the player `submit_quiz` grades against.
+    play_set(questions, &lang, session, Some(mode), owner.as_deref()).into_response()
+}
+
+/// Starts the clock on a question served in a quiz session. The start time is the server's, and
+/// starting again returns the original start rather than extending the deadline.
+pub async fn start_question(
+    State(state): State<ApiState>,
+    Path((session_id, question_id)): Path<(String, String)>,
+    caller: Caller,
+) -> impl IntoResponse {
+    let session = match queries::find_session(&state.db, &session_id).await {
+        Ok(Some(session))
+            if session.served.contains(&question_id)
+                && session
+                    .user_id
+                    .as_deref()
+                    .is_none_or(|owner| caller.user_id.as_deref() == Some(owner)) =>
+        {
+            session
+        }
+        Ok(_) => return error_response(StatusCode::NOT_FOUND, "question not served in this session"),
+        Err(err) => {
+            error!(error = ?err, "failed to load quiz session");
+            return error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to start question");
+        }
+    };
+    let question_limit = match question_queries::find_question_by_id(&state.db, &question_id).await {
+        Ok(Some(question)) => question.time_limit_secs,
+        Ok(None) => return error_response(StatusCode::NOT_FOUND, "question not found"),
+        Err(err) => {
+            error!(error = ?err, "failed to fetch question");
+            return error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to start question");
+        }
+    };
+
+    let now = DateTime::now();
+    let started_at = match queries::start_question(&state.db, &session.id, &question_id, now).await {
+        Ok(true) => now,
+        Ok(false) => match session.started.get(&question_id) {
+            Some(started_at) => *started_at,
+            // Started concurrently since the session was loaded.
+            None => match queries::find_session(&state.db, &session.id).await {
+                Ok(Some(reloaded)) => reloaded.started.get(&question_id).copied().unwrap_or(now),
+                Ok(None) => return error_response(StatusCode::NOT_FOUND, "quiz session not found"),
+                Err(err) => {
+                    error!(error = ?err, "failed to load quiz session");
+                    return error_response(
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        "failed to start question",
+                    );
+                }
+            },
+        },
+        Err(err) => {
+            error!(error = ?err, "failed to start question");
+            return error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to start question");
+        }
+    };
+
+    let time_limit_secs = state.config.quiz_scoring.rule(session.mode).time_limit_secs(question_limit);
+    let dto = QuestionStartDto::new(session.id, question_id, started_at, time_limit_secs);
+    (StatusCode::OK, Json(dto)).into_response()
+}
+
+/// Grades answers to questions served in a quiz session and records the result, feeding the
+/// leaderboards. Each question counts once per session. Sessions started signed in can only be
+/// submitted by the same player. Answered questions are scheduled for review.
+///
+/// Timed questions are graded against their server-issued start: answers arriving after the
+/// deadline, or to questions never started, count as incorrect. Points follow the session mode's
+/// scoring rule, so timed quizzes should submit each answer as it is given.
+pub async fn submit_quiz(
+    State(state): State<ApiState>,
+    Query(params): Query<PlayQuery>,
+    caller: Caller,
+    headers: HeaderMap,
+    Json(payload): Json<SubmitQuiz>,
+) -> impl IntoResponse {
+    let user_id = match caller.require_user() {
+        Ok(user_id) => user_id.to_string(),
+        Err(forbidden) => return forbidden.into_response(),
+    };
+    // Only the player who started the quiz may submit it; a quiz started signed out has no owner
+    // to credit, so anyone could otherwise claim it for the leaderboards.
+    let session = match queries::find_session(&state.db, &payload.session).await {
+        Ok(Some(session)) if session.user_id.as_deref() == Some(user_id.as_str()) => session,
+        Ok(Some(session)) if session.user_id.is_none() => {
+            return error_response(StatusCode::FORBIDDEN, "quiz was not started signed in");
+        }
+        Ok(_) => return error_response(StatusCode::NOT_FOUND, "quiz session not found"),
+        Err(err) => {
+            error!(error = ?err, "failed to load quiz session");
+            return error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to record quiz result");
+        }
+    };
+    if let Err(msg) = check_submission(&session, &payload.answers) {
+        return error_response(StatusCode::BAD_REQUEST, msg);
+    }
+
+    let ids: Vec<String> = payload.answers.iter().map(|a| a.question_id.clone()).collect();
+    let questions: Vec<QuestionDto> = match question_queries::find_questions_by_ids(&state.db, &ids)
+        .await
+    {
+        Ok(questions) => questions.into_iter().map(QuestionDto::from).collect(),
+        Err(err) => {
+            error!(error = ?err, "failed to load quiz questions");
+            return error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to record quiz result");
+        }
+    };
+
+    let lang = resolve_lang(params.lang.as_deref(), headers.get("accept-language"));
+    let rule = state.config.quiz_scoring.rule(session.mode);
+    let received_at = DateTime::now();
+    let mut graded = Vec::with_capacity(payload.answers.len());
+    let mut outcomes = Vec::with_capacity(payload.answers.len());
+    for QuizAnswer {
+        question_id,
+        answer,
+        time_ms,
+    } in payload.answers
+    {
+        // Questions trashed since they were served are skipped rather than failing the batch.
+        let Some(question) = questions.iter().find(|q| q.id == question_id) else {
+            continue;
+        };
+        // Grade against the order the questions were served in.
+        let shuffle = shuffle_for(question, session.user_id.as_deref(), &session.id);
+        let check = to_stored_answer(answer, &shuffle)
+            .and_then(|answer| Ok((check_answer(question, &answer, &lang)?, answer)));
+        let elapsed_ms = session
+            .started
+            .get(&question_id)
+            .map(|started_at| received_at.timestamp_millis() - started_at.timestamp_millis());
+        let limit_ms = rule.time_limit_secs(question.time_limit_secs).map(|secs| i64::from(secs) * 1000);
+        let late = limit_ms.is_some_and(|limit_ms| is_late(elapsed_ms, limit_ms));
+        match check {
+            Ok((check, answer)) => {
+                let correct = check.correct && !late;
+                let time_ms = elapsed_ms.or(time_ms);
+                outcomes.push(AnswerOutcome::new(&question_id, &answer, correct, time_ms));
+                graded.push(GradedAnswer {
+                    question_id,
+                    era: question.era.clone(),
+                    correct,
+                    points: rule.score(correct, elapsed_ms, limit_ms),
+                    elapsed_ms,
+                    late,
+                });
+            }
+            Err(msg) => return error_response(StatusCode::BAD_REQUEST, msg),
+        }
+    }
+
+    match queries::mark_scored(&state.db, &session.id, &ids).await {
+        Ok(true) => {}
+        Ok(false) => {
+            return error_response(StatusCode::CONFLICT, "question already answered in this session");
+        }
+        Err(err) => {
+            error!(error = ?err, "failed to mark quiz answers");
+            return error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to record quiz result");
+        }
+    }
+
+    let result = QuizResult {
+        id: ObjectId::new(),
+        user_id: user_id.clone(),
+        session: session.id,
+        mode: session.mode,
+        score: graded.iter().filter(|a| a.correct).count() as i32,
+        total: graded.len() as i32,
+        points: graded.iter().map(|a| a.points).sum(),
+        duration_ms: (received_at.timestamp_millis() - session.created_at.timestamp_millis()).max(0),
+        answers: graded,
+        completed_at: DateTime::now(),
+    };
+    if let Err(err) = queries::insert_result(&state.db, &result).await {
+        error!(error = ?err, "failed to record quiz result");
+        return error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to record quiz result");
+    }
+    let eras: Vec<(Option<String>, bool)> =
+        result.answers.iter().map(|a| (a.era.clone(), a.correct)).collect();
+    leaderboard_handler::record_result(&state, &user_id, &eras).await;
+    achievement_handler::record_result(&state, &user_id, &eras).await;
+    let reviews: Vec<(String, bool)> =
+        result.answers.iter().map(|a| (a.question_id.clone(), a.correct)).collect();
+    repetition_handler::record_outcomes(&state, &user_id, &reviews).await;
+    stats_handler::record_answers(&state, Some(&user_id), &lang, &outcomes).await;
+
+    let dto = QuizResultDto {
+        session: result.session,
+        mode: result.mode,
+        score: result.score,
+        total: result.total,
+        points: result.points,
+        answers: result.answers,
+    };
+    (StatusCode::CREATED, Json(dto)).into_response()
+}
+
+fn play_set(
+    questions: Vec<QuestionDto>,
+    lang: &str,
+    session: String,
+    mode: Option<QuizMode>,
+    user_id: Option<&str>,
+) -> impl IntoResponse {
+    let items = questions
+        .into_iter()
+        .map(|question| {
+            let shuffle = shuffle_for(&question, user_id, &session);
+            PlayQuestionDto::new(question, lang, session.clone(), &shuffle)
+        })
+        .collect();
+    (
+        StatusCode::OK,
+        Json(PlaySetDto {
+            session,
+            mode,
+            items,
+        }),
+    )
+}
+
+async fn load_question(
+    state: &ApiState,
+    id: &str,
+    caller: &Caller,
+    preview: bool,
+) -> Result<QuestionDto, Response> {
+    let visibility = visibility_for(caller, preview, None).map_err(IntoResponse::into_response)?;
+    match question_queries::find_question_by_id(&state.db, id).await {
+        Ok(Some(question)) if visibility.allows(question.status) => Ok(question),
+        Ok(_) => Err(error_response(StatusCode::NOT_FOUND, "question not found")),
+        Err(err) => {
+            error!(error = ?err, "failed to fetch question");
+            Err(error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to fetch question"))
+        }
+    }
+}
+
+fn error_response(status: StatusCode, msg: &str) -> Response {
+    (status, Json(json!({ "error": msg }))).into_response()
+}
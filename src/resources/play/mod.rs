@@ -0,0 +1,5 @@
+pub mod handler;
+pub mod model;
+pub mod queries;
+pub mod scoring;
+pub mod shuffle;
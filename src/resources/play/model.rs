@@ -0,0 +1,489 @@
+use std::collections::BTreeMap;
+
+use chrono::Utc;
+use mongodb::bson::{DateTime, oid::ObjectId};
+use serde::{Deserialize, Serialize};
+
+use crate::resources::{
+    play::{scoring::QuizMode, shuffle::Shuffle},
+    questions::{
+        model::{QuestionDto, localized},
+        types::{Answer, AnswerCheck, QuestionType, Solution},
+    },
+};
+
+/// `session` is any opaque string the client keeps for the length of a play session; the server
+/// issues one when it is missing.
+#[derive(Deserialize)]
+pub struct PlayQuery {
+    pub session: Option<String>,
+    pub lang: Option<String>,
+    #[serde(default)]
+    pub preview: bool,
+}
+
+pub const DEFAULT_SET_SIZE: usize = 10;
+pub const MAX_SET_SIZE: usize = 50;
+/// Candidates drawn per requested question, so quizzes have room to balance eras and levels.
+pub const QUIZ_POOL_FACTOR: usize = 5;
+
+/// Filters for `GET /v1/questions/random`. `era`, `level`, `tags` and `exclude` take
+/// comma-separated lists.
+#[derive(Deserialize)]
+pub struct RandomQuery {
+    pub count: Option<usize>,
+    pub stage: Option<i32>,
+    pub era: Option<String>,
+    pub level: Option<String>,
+    pub tags: Option<String>,
+    pub exclude: Option<String>,
+    pub min_difficulty: Option<f64>,
+    pub max_difficulty: Option<f64>,
+    pub session: Option<String>,
+    pub lang: Option<String>,
+}
+
+/// Body of `POST /v1/quizzes`. Passing the same `session` again continues it without repeats.
+#[derive(Deserialize, Default)]
+pub struct QuizRequest {
+    pub count: Option<usize>,
+    pub stage: Option<i32>,
+    #[serde(default)]
+    pub eras: Vec<String>,
+    #[serde(default)]
+    pub levels: Vec<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub min_difficulty: Option<f64>,
+    pub max_difficulty: Option<f64>,
+    /// Scoring mode for a new session; a continued session keeps its own.
+    #[serde(default)]
+    pub mode: QuizMode,
+    pub session: Option<String>,
+}
+
+/// Question ids served, and already scored, in a quiz session (collection `quiz_sessions`).
+#[derive(Debug, Serialize, Deserialize)]
+pub struct QuizSession {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub user_id: Option<String>,
+    pub served: Vec<String>,
+    #[serde(default)]
+    pub scored: Vec<String>,
+    #[serde(default)]
+    pub mode: QuizMode,
+    /// Server time each question was started at, by question id. Timed questions are graded
+    /// against it.
+    #[serde(default)]
+    pub started: BTreeMap<String, DateTime>,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+/// Answers to questions served in a quiz session, in display positions.
+#[derive(Debug, Deserialize)]
+pub struct SubmitQuiz {
+    pub session: String,
+    pub answers: Vec<QuizAnswer>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct QuizAnswer {
+    pub question_id: String,
+    pub answer: Answer,
+    pub time_ms: Option<i64>,
+}
+
+/// A graded batch of quiz answers (collection `quiz_results`).
+#[derive(Debug, Serialize, Deserialize)]
+pub struct QuizResult {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    pub user_id: String,
+    pub session: String,
+    #[serde(default)]
+    pub mode: QuizMode,
+    pub score: i32,
+    pub total: i32,
+    /// Score under the mode's scoring rule; `score` counts correct answers.
+    #[serde(default)]
+    pub points: i64,
+    pub duration_ms: i64,
+    pub answers: Vec<GradedAnswer>,
+    pub completed_at: DateTime,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GradedAnswer {
+    pub question_id: String,
+    pub era: Option<String>,
+    pub correct: bool,
+    #[serde(default)]
+    pub points: i64,
+    /// Server-measured time from the question's start, for started questions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub elapsed_ms: Option<i64>,
+    /// Answered after the deadline (or never started) and graded incorrect for it.
+    #[serde(default)]
+    pub late: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct QuizResultDto {
+    pub session: String,
+    pub mode: QuizMode,
+    pub score: i32,
+    pub total: i32,
+    pub points: i64,
+    pub answers: Vec<GradedAnswer>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PlaySetDto {
+    pub session: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<QuizMode>,
+    pub items: Vec<PlayQuestionDto>,
+}
+
+/// A question's server-issued start, with the deadline when it is timed.
+#[derive(Debug, Serialize)]
+pub struct QuestionStartDto {
+    pub session: String,
+    pub question_id: String,
+    pub started_at: String,
+    pub time_limit_secs: Option<i32>,
+    pub deadline: Option<String>,
+}
+
+impl QuestionStartDto {
+    pub fn new(
+        session: String,
+        question_id: String,
+        started_at: DateTime,
+        time_limit_secs: Option<i32>,
+    ) -> Self {
+        let started_at = started_at.to_chrono().with_timezone(&Utc);
+        Self {
+            session,
+            question_id,
+            started_at: started_at.to_rfc3339(),
+            time_limit_secs,
+            deadline: time_limit_secs
+                .map(|secs| (started_at + chrono::Duration::seconds(i64::from(secs))).to_rfc3339()),
+        }
+    }
+}
+
+/// A question as shown to a player: one language, items in session order, no solution.
+#[derive(Debug, Serialize)]
+pub struct PlayQuestionDto {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub question_type: QuestionType,
+    pub session: String,
+    pub stage: i32,
+    pub stage_label: Option<String>,
+    pub prompt: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub options: Vec<String>,
+    /// Matching questions only: left items in authored order, right items shuffled.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub left: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub right: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub era: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub level: Option<String>,
+    pub tags: Vec<String>,
+    pub image_url: Option<String>,
+    /// Seconds allowed once the question is started; quizzes apply their mode's default.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_limit_secs: Option<i32>,
+}
+
+/// The graded answer, with the solution and explanations in the player's display order.
+#[derive(Debug, Serialize)]
+pub struct PlayAnswerDto {
+    pub correct: bool,
+    pub solution: Solution,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub explanations: Vec<Option<String>>,
+}
+
+/// Builds the session shuffle for a question. True/false keeps its authored order and
+/// fill-in-the-blank has nothing to shuffle; matching shuffles the right-hand column.
+pub fn shuffle_for(question: &QuestionDto, user_id: Option<&str>, session: &str) -> Shuffle {
+    let len = match question.question_type {
+        QuestionType::SingleChoice | QuestionType::MultipleCorrect | QuestionType::Ordering => {
+            question.options.len()
+        }
+        QuestionType::Matching => question.pairs.len(),
+        QuestionType::TrueFalse => return Shuffle::identity(question.options.len()),
+        QuestionType::FillBlank => return Shuffle::identity(0),
+    };
+    Shuffle::new(Shuffle::seed(user_id, session, &question.id), len)
+}
+
+impl PlayQuestionDto {
+    pub fn new(question: QuestionDto, lang: &str, session: String, shuffle: &Shuffle) -> Self {
+        let (options, left, right) = if question.question_type == QuestionType::Matching {
+            let right: Vec<String> = question.pairs.iter().map(|p| localized(&p.right, lang)).collect();
+            (
+                Vec::new(),
+                question.pairs.iter().map(|p| localized(&p.left, lang)).collect(),
+                shuffle.apply(&right),
+            )
+        } else {
+            let options: Vec<String> =
+                question.options.iter().map(|o| localized(&o.text, lang)).collect();
+            (shuffle.apply(&options), Vec::new(), Vec::new())
+        };
+
+        Self {
+            id: question.id,
+            question_type: question.question_type,
+            session,
+            stage: question.stage,
+            stage_label: question.stage_label.as_ref().map(|label| localized(label, lang)),
+            prompt: localized(&question.prompt, lang),
+            options,
+            left,
+            right,
+            era: question.era,
+            level: question.level,
+            tags: question.tags,
+            image_url: question.image_url,
+            time_limit_secs: question.time_limit_secs,
+        }
+    }
+}
+
+/// Every answered question must have been served in the session, not scored yet and answered once.
+pub fn check_submission(session: &QuizSession, answers: &[QuizAnswer]) -> Result<(), &'static str> {
+    if answers.is_empty() {
+        return Err("at least one answer is required");
+    }
+    for (idx, answer) in answers.iter().enumerate() {
+        if !session.served.contains(&answer.question_id) {
+            return Err("question was not served in this session");
+        }
+        if session.scored.contains(&answer.question_id)
+            || answers[..idx].iter().any(|a| a.question_id == answer.question_id)
+        {
+            return Err("question already answered in this session");
+        }
+    }
+    Ok(())
+}
+
+pub fn split_list(value: Option<&str>) -> Vec<String> {
+    value
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::to_owned)
+        .collect()
+}
+
+/// Picks `count` questions spread evenly over eras and, within each era, over levels. Candidates
+/// arrive in random order; buckets are visited in the order they first appear, so no era is
+/// favoured when there are more eras than questions.
+pub fn balance(candidates: Vec<QuestionDto>, count: usize) -> Vec<QuestionDto> {
+    type Bucket = (Option<String>, Vec<QuestionDto>);
+    let mut eras: Vec<(Option<String>, Vec<Bucket>)> = Vec::new();
+    for question in candidates {
+        let era = match eras.iter().position(|(era, _)| *era == question.era) {
+            Some(idx) => idx,
+            None => {
+                eras.push((question.era.clone(), Vec::new()));
+                eras.len() - 1
+            }
+        };
+        let levels = &mut eras[era].1;
+        match levels.iter_mut().find(|(level, _)| *level == question.level) {
+            Some((_, bucket)) => bucket.push(question),
+            None => levels.push((question.level.clone(), vec![question])),
+        }
+    }
+
+    let per_era: Vec<Vec<QuestionDto>> = eras
+        .into_iter()
+        .map(|(_, levels)| interleave(levels.into_iter().map(|(_, bucket)| bucket).collect()))
+        .collect();
+    let mut picked = interleave(per_era);
+    picked.truncate(count);
+    picked
+}
+
+/// Round-robin over `lists`: first item of each, then the second of each, and so on.
+fn interleave<T>(lists: Vec<Vec<T>>) -> Vec<T> {
+    let mut iters: Vec<_> = lists.into_iter().map(Vec::into_iter).collect();
+    let mut out = Vec::new();
+    loop {
+        let before = out.len();
+        for iter in &mut iters {
+            out.extend(iter.next());
+        }
+        if out.len() == before {
+            return out;
+        }
+    }
+}
+
+/// Translates an answer given in display positions into stored indices.
+pub fn to_stored_answer(answer: Answer, shuffle: &Shuffle) -> Result<Answer, &'static str> {
+    Ok(match answer {
+        Answer::Selected(positions) => Answer::Selected(shuffle.to_stored(&positions)?),
+        Answer::Order(positions) => Answer::Order(shuffle.to_stored(&positions)?),
+        Answer::Matches(positions) => Answer::Matches(shuffle.to_stored(&positions)?),
+        Answer::Text(text) => Answer::Text(text),
+    })
+}
+
+impl PlayAnswerDto {
+    pub fn new(check: AnswerCheck, question: &QuestionDto, lang: &str, shuffle: &Shuffle) -> Self {
+        let solution = match check.solution {
+            Solution::Selected(stored) => {
+                let mut shown = shuffle.to_display(&stored);
+                shown.sort_unstable();
+                Solution::Selected(shown)
+            }
+            Solution::Order(stored) => Solution::Order(shuffle.to_display(&stored)),
+            Solution::Matches(stored) => Solution::Matches(shuffle.to_display(&stored)),
+            Solution::Text(answers) => Solution::Text(answers),
+        };
+        let explanations = if question.options.iter().any(|o| o.explanation.is_some()) {
+            let stored: Vec<Option<String>> = question
+                .options
+                .iter()
+                .map(|o| o.explanation.as_ref().map(|e| localized(e, lang)))
+                .collect();
+            shuffle.apply(&stored)
+        } else {
+            Vec::new()
+        };
+
+        Self {
+            correct: check.correct,
+            solution,
+            explanations,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mongodb::bson::DateTime;
+
+    use super::{
+        PlayAnswerDto, PlayQuestionDto, QuizAnswer, QuizSession, balance, check_submission, shuffle_for,
+        to_stored_answer,
+    };
+    use crate::resources::{
+        questions::{
+            model::{LocalizedText, OptionDto, QuestionDto},
+            types::{Answer, QuestionType, Solution, check_answer},
+        },
+        workflow::model::ContentStatus,
+    };
+
+    fn ordering_question() -> QuestionDto {
+        QuestionDto {
+            id: "patriarchs".into(),
+            question_type: QuestionType::Ordering,
+            stage: 1,
+            stage_label: None,
+            prompt: LocalizedText::from([("en".to_string(), "Order the patriarchs".to_string())]),
+            options: ["Abraham", "Isaac", "Jacob", "Joseph"]
+                .iter()
+                .map(|name| OptionDto {
+                    text: LocalizedText::from([("en".to_string(), name.to_string())]),
+                    correct: false,
+                    explanation: None,
+                })
+                .collect(),
+            pairs: Vec::new(),
+            answers: Vec::new(),
+            era: None,
+            level: None,
+            time_limit_secs: None,
+            difficulty: None,
+            difficulty_answers: None,
+            tags: Vec::new(),
+            image_url: None,
+            status: ContentStatus::Published,
+            pending_status: None,
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn shuffled_answers_are_graded_against_stored_order() {
+        let question = ordering_question();
+        let shuffle = shuffle_for(&question, Some("ana"), "session");
+        let shown = PlayQuestionDto::new(ordering_question(), "en", "session".into(), &shuffle);
+
+        // Answer by arranging the displayed names into historical order.
+        let order: Vec<usize> = ["Abraham", "Isaac", "Jacob", "Joseph"]
+            .iter()
+            .map(|name| shown.options.iter().position(|o| o == name).unwrap())
+            .collect();
+        let stored = to_stored_answer(Answer::Order(order.clone()), &shuffle).unwrap();
+        let check = check_answer(&question, &stored, "en").unwrap();
+        assert!(check.correct);
+
+        let result = PlayAnswerDto::new(check, &question, "en", &shuffle);
+        assert_eq!(result.solution, Solution::Order(order));
+    }
+
+    #[test]
+    fn balance_spreads_across_eras_and_levels() {
+        let tagged = |id: &str, era: &str, level: &str| {
+            let mut question = ordering_question();
+            question.id = id.into();
+            question.era = Some(era.into());
+            question.level = Some(level.into());
+            question
+        };
+        let candidates = vec![
+            tagged("p1", "prophets", "lay"),
+            tagged("p2", "prophets", "lay"),
+            tagged("p3", "prophets", "saint"),
+            tagged("k1", "kings", "lay"),
+            tagged("g1", "gospel", "monk"),
+        ];
+
+        let ids: Vec<String> = balance(candidates, 4).into_iter().map(|q| q.id).collect();
+        assert_eq!(ids, vec!["p1", "k1", "g1", "p3"]);
+    }
+
+    #[test]
+    fn submissions_must_match_the_session() {
+        let session = QuizSession {
+            id: "s".into(),
+            user_id: Some("ana".into()),
+            served: vec!["q1".into(), "q2".into(), "q3".into()],
+            scored: vec!["q3".into()],
+            mode: Default::default(),
+            started: Default::default(),
+            created_at: DateTime::now(),
+            updated_at: DateTime::now(),
+        };
+        let answer = |id: &str| QuizAnswer {
+            question_id: id.into(),
+            answer: Answer::Selected(vec![0]),
+            time_ms: None,
+        };
+
+        assert!(check_submission(&session, &[answer("q1"), answer("q2")]).is_ok());
+        assert!(check_submission(&session, &[answer("q4")]).is_err());
+        assert!(check_submission(&session, &[answer("q3")]).is_err());
+        assert!(check_submission(&session, &[answer("q1"), answer("q1")]).is_err());
+        assert!(check_submission(&session, &[]).is_err());
+    }
+}
@@ -0,0 +1,81 @@
+use std::time::Duration;
+
+use mongodb::{
+    Collection, Database, IndexModel,
+    bson::{self, DateTime, doc},
+    options::{IndexOptions, UpdateOptions},
+};
+
+use crate::resources::play::{
+    model::{QuizResult, QuizSession},
+    scoring::QuizMode,
+};
+
+/// Quiz sessions are removed after 30 days without activity.
+const SESSION_TTL: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+fn sessions_collection(db: &Database) -> Collection<QuizSession> {
+    db.collection("quiz_sessions")
+}
+
+fn results_collection(db: &Database) -> Collection<QuizResult> {
+    db.collection("quiz_results")
+}
+
+/// Expires idle quiz sessions; every other lookup is by id.
+pub async fn ensure_indexes(db: &Database) -> mongodb::error::Result<()> {
+    let options = IndexOptions::builder().expire_after(SESSION_TTL).build();
+    let index = IndexModel::builder().keys(doc! { "updated_at": 1 }).options(options).build();
+    sessions_collection(db).create_index(index, None).await?;
+    Ok(())
+}
+
+pub async fn find_session(db: &Database, session: &str) -> mongodb::error::Result<Option<QuizSession>> {
+    sessions_collection(db).find_one(doc! { "_id": session }, None).await
+}
+
+/// Records when a served question was started. Returns `false` when it is not served in the
+/// session or was already started, so a restart cannot extend the deadline.
+pub async fn start_question(
+    db: &Database,
+    session: &str,
+    question_id: &str,
+    at: DateTime,
+) -> mongodb::error::Result<bool> {
+    let key = format!("started.{question_id}");
+    let filter = doc! { "_id": session, "served": question_id, &key: { "$exists": false } };
+    let update = doc! { "$set": { &key: at, "updated_at": at } };
+    Ok(sessions_collection(db).update_one(filter, update, None).await?.modified_count > 0)
+}
+
+/// Marks questions as scored in `session`. Returns `false` when any of them already was, so two
+/// concurrent submissions cannot both count.
+pub async fn mark_scored(db: &Database, session: &str, ids: &[String]) -> mongodb::error::Result<bool> {
+    let filter = doc! { "_id": session, "scored": { "$nin": ids } };
+    let update =
+        doc! { "$addToSet": { "scored": { "$each": ids } }, "$set": { "updated_at": DateTime::now() } };
+    Ok(sessions_collection(db).update_one(filter, update, None).await?.modified_count > 0)
+}
+
+pub async fn insert_result(db: &Database, result: &QuizResult) -> mongodb::error::Result<()> {
+    results_collection(db).insert_one(result, None).await?;
+    Ok(())
+}
+
+pub async fn record_served(
+    db: &Database,
+    session: &str,
+    user_id: Option<&str>,
+    mode: QuizMode,
+    ids: &[String],
+) -> mongodb::error::Result<()> {
+    let now = DateTime::now();
+    let update = doc! {
+        "$addToSet": { "served": { "$each": ids } },
+        "$set": { "updated_at": now },
+        "$setOnInsert": { "user_id": user_id, "mode": bson::to_bson(&mode)?, "created_at": now },
+    };
+    let options = UpdateOptions::builder().upsert(true).build();
+    sessions_collection(db).update_one(doc! { "_id": session }, update, options).await?;
+    Ok(())
+}
@@ -0,0 +1,176 @@
+use std::env;
+
+use serde::{Deserialize, Serialize};
+
+/// Time allowed past a deadline for the answer to reach the server.
+pub const LATENCY_GRACE_MS: i64 = 1000;
+
+/// How a quiz session is played and scored, chosen when it is created.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuizMode {
+    #[default]
+    Practice,
+    Competitive,
+    Classroom,
+}
+
+impl QuizMode {
+    fn env_key(self) -> &'static str {
+        match self {
+            QuizMode::Practice => "QUIZ_SCORING_PRACTICE",
+            QuizMode::Competitive => "QUIZ_SCORING_COMPETITIVE",
+            QuizMode::Classroom => "QUIZ_SCORING_CLASSROOM",
+        }
+    }
+}
+
+/// Points awarded per answer in one quiz mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScoringRule {
+    /// Points for a correct answer given in time.
+    pub points: i64,
+    /// Extra points for an instant answer, falling linearly to nothing at the time limit.
+    /// Untimed questions earn no bonus.
+    pub speed_bonus: i64,
+    /// Limit for questions without their own `time_limit_secs`.
+    pub time_limit_secs: Option<i32>,
+}
+
+impl ScoringRule {
+    pub fn default_for(mode: QuizMode) -> Self {
+        match mode {
+            QuizMode::Practice => Self {
+                points: 1,
+                speed_bonus: 0,
+                time_limit_secs: None,
+            },
+            QuizMode::Competitive => Self {
+                points: 500,
+                speed_bonus: 500,
+                time_limit_secs: Some(20),
+            },
+            QuizMode::Classroom => Self {
+                points: 100,
+                speed_bonus: 0,
+                time_limit_secs: Some(60),
+            },
+        }
+    }
+
+    /// Overrides `base` with a spec such as `points=100,speed_bonus=50,time_limit_secs=30`
+    /// (`time_limit_secs=none` drops the default limit). `None` when the spec is malformed.
+    pub fn parse(spec: &str, base: Self) -> Option<Self> {
+        let mut rule = base;
+        for part in spec.split(',').map(str::trim).filter(|part| !part.is_empty()) {
+            let (key, value) = part.split_once('=')?;
+            match key.trim() {
+                "points" => rule.points = value.trim().parse().ok().filter(|v: &i64| *v >= 0)?,
+                "speed_bonus" => {
+                    rule.speed_bonus = value.trim().parse().ok().filter(|v: &i64| *v >= 0)?
+                }
+                "time_limit_secs" => {
+                    rule.time_limit_secs = match value.trim() {
+                        "none" => None,
+                        secs => Some(secs.parse().ok().filter(|v: &i32| *v > 0)?),
+                    }
+                }
+                _ => return None,
+            }
+        }
+        Some(rule)
+    }
+
+    /// The limit that applies to a question: its own, or the mode's default.
+    pub fn time_limit_secs(self, question_limit: Option<i32>) -> Option<i32> {
+        question_limit.or(self.time_limit_secs)
+    }
+
+    /// Points for one answer. `elapsed_ms` is measured by the server from the question's start;
+    /// answers past the deadline should be graded incorrect before scoring.
+    pub fn score(self, correct: bool, elapsed_ms: Option<i64>, limit_ms: Option<i64>) -> i64 {
+        if !correct {
+            return 0;
+        }
+        let bonus = match (elapsed_ms, limit_ms) {
+            (Some(elapsed), Some(limit)) if limit > 0 => {
+                self.speed_bonus * (limit - elapsed.clamp(0, limit)) / limit
+            }
+            _ => 0,
+        };
+        self.points + bonus
+    }
+}
+
+/// Whether an answer `elapsed_ms` after its question started missed a `limit_ms` deadline.
+/// Timed questions that were never started count as late.
+pub fn is_late(elapsed_ms: Option<i64>, limit_ms: i64) -> bool {
+    elapsed_ms.is_none_or(|elapsed| elapsed > limit_ms + LATENCY_GRACE_MS)
+}
+
+/// Scoring rules per quiz mode, each overridable with a `QUIZ_SCORING_<MODE>` spec.
+#[derive(Debug, Clone)]
+pub struct ScoringConfig {
+    practice: ScoringRule,
+    competitive: ScoringRule,
+    classroom: ScoringRule,
+}
+
+impl ScoringConfig {
+    pub fn from_env() -> Self {
+        let rule = |mode: QuizMode| {
+            let base = ScoringRule::default_for(mode);
+            env::var(mode.env_key())
+                .ok()
+                .and_then(|spec| ScoringRule::parse(&spec, base))
+                .unwrap_or(base)
+        };
+        Self {
+            practice: rule(QuizMode::Practice),
+            competitive: rule(QuizMode::Competitive),
+            classroom: rule(QuizMode::Classroom),
+        }
+    }
+
+    pub fn rule(&self, mode: QuizMode) -> ScoringRule {
+        match mode {
+            QuizMode::Practice => self.practice,
+            QuizMode::Competitive => self.competitive,
+            QuizMode::Classroom => self.classroom,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{QuizMode, ScoringRule, is_late};
+
+    #[test]
+    fn faster_answers_score_more() {
+        let rule = ScoringRule::default_for(QuizMode::Competitive);
+        let limit = Some(20_000);
+        assert_eq!(rule.score(true, Some(0), limit), 1000);
+        assert_eq!(rule.score(true, Some(10_000), limit), 750);
+        assert_eq!(rule.score(true, Some(25_000), limit), 500);
+        assert_eq!(rule.score(false, Some(0), limit), 0);
+        assert_eq!(rule.score(true, None, None), 500);
+
+        assert!(!is_late(Some(20_500), 20_000));
+        assert!(is_late(Some(21_500), 20_000));
+        assert!(is_late(None, 20_000));
+    }
+
+    #[test]
+    fn rules_parse_over_defaults() {
+        let base = ScoringRule::default_for(QuizMode::Classroom);
+        let rule = ScoringRule::parse("speed_bonus=50, time_limit_secs=none", base).unwrap();
+        assert_eq!(rule.points, 100);
+        assert_eq!(rule.speed_bonus, 50);
+        assert_eq!(rule.time_limit_secs, None);
+        assert_eq!(rule.time_limit_secs(Some(30)), Some(30));
+
+        assert!(ScoringRule::parse("points=-1", base).is_none());
+        assert!(ScoringRule::parse("bonus=5", base).is_none());
+        assert!(ScoringRule::parse("points", base).is_none());
+    }
+}
@@ -0,0 +1,106 @@
+use sha2::{Digest, Sha256};
+
+/// A reproducible permutation of a question's items for one player session.
+/// `order[display] = stored`: the item shown at position `display` is the stored item `stored`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Shuffle {
+    order: Vec<usize>,
+}
+
+impl Shuffle {
+    pub fn identity(len: usize) -> Self {
+        Self {
+            order: (0..len).collect(),
+        }
+    }
+
+    /// Fisher-Yates driven by SplitMix64, so the same seed always yields the same order.
+    pub fn new(seed: u64, len: usize) -> Self {
+        let mut rng = SplitMix64(seed);
+        let mut order: Vec<usize> = (0..len).collect();
+        for i in (1..len).rev() {
+            let j = (rng.next() % (i as u64 + 1)) as usize;
+            order.swap(i, j);
+        }
+        Self {
+            order,
+        }
+    }
+
+    /// Seeds a shuffle from the player, their session and the question, so a resumed session sees
+    /// the same order while other sessions (and other questions) get different ones.
+    pub fn seed(user_id: Option<&str>, session: &str, question_id: &str) -> u64 {
+        let mut hasher = Sha256::new();
+        for part in [user_id.unwrap_or(""), session, question_id] {
+            hasher.update(part.as_bytes());
+            hasher.update([0u8]);
+        }
+        let digest = hasher.finalize();
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&digest[..8]);
+        u64::from_le_bytes(bytes)
+    }
+
+    pub fn apply<T: Clone>(&self, items: &[T]) -> Vec<T> {
+        self.order.iter().map(|&stored| items[stored].clone()).collect()
+    }
+
+    /// Maps displayed positions back to stored indices.
+    pub fn to_stored(&self, positions: &[usize]) -> Result<Vec<usize>, &'static str> {
+        positions
+            .iter()
+            .map(|&pos| {
+                self.order.get(pos).copied().ok_or("answer refers to an option that does not exist")
+            })
+            .collect()
+    }
+
+    /// Maps stored indices to the positions they are displayed at.
+    pub fn to_display(&self, stored: &[usize]) -> Vec<usize> {
+        stored.iter().filter_map(|&idx| self.order.iter().position(|&s| s == idx)).collect()
+    }
+}
+
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Shuffle;
+
+    #[test]
+    fn same_seed_gives_same_permutation() {
+        let seed = Shuffle::seed(Some("ana"), "session-1", "q1");
+        let first = Shuffle::new(seed, 6);
+        assert_eq!(first, Shuffle::new(seed, 6));
+
+        let mut sorted = first.apply(&[0, 1, 2, 3, 4, 5]);
+        sorted.sort_unstable();
+        assert_eq!(sorted, vec![0, 1, 2, 3, 4, 5]);
+
+        let seeds: Vec<u64> =
+            (0..8).map(|n| Shuffle::seed(Some("ana"), &format!("session-{n}"), "q1")).collect();
+        assert!(seeds.iter().any(|&s| Shuffle::new(s, 6) != first));
+    }
+
+    #[test]
+    fn maps_positions_both_ways() {
+        let shuffle = Shuffle::new(Shuffle::seed(None, "s", "q"), 4);
+        let shown = shuffle.apply(&["a", "b", "c", "d"]);
+        let stored = shuffle.to_stored(&[0, 1, 2, 3]).unwrap();
+        for (pos, idx) in stored.iter().enumerate() {
+            assert_eq!(shown[pos], ["a", "b", "c", "d"][*idx]);
+        }
+        assert_eq!(shuffle.to_display(&stored), vec![0, 1, 2, 3]);
+        assert!(shuffle.to_stored(&[4]).is_err());
+    }
+}
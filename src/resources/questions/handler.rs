@@ -1,16 +1,39 @@
-use axum::routing::{MethodRouter, get as axum_get};
+use axum::routing::{MethodRouter, get as axum_get, post as axum_post};
 use axum::{
     Json,
+    body::Bytes,
     extract::{Path, Query, State},
-    http::StatusCode,
-    response::IntoResponse,
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
 };
+use mongodb::bson::Document;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use tracing::error;
 
 use crate::{
-    resources::questions::{model::CreateQuestion, queries},
+    auth::Caller,
+    conditional::{self, Validators},
+    lang::resolve_lang,
+    resources::{
+        questions::{
+            model::{
+                CreateQuestion, PublicQuestionDto, QuestionDto, TrashedQuestionDto,
+                validate_classification, validate_time_limit,
+            },
+            queries::{self, DifficultyRange},
+            types::{TimedAnswer, check_answer, validate_shape},
+        },
+        revisions::{
+            model::{
+                ACTION_CREATE, ACTION_DELETE, ACTION_RESTORE, ACTION_REVERT, ACTION_UPDATE,
+                RevertRequest, change_reason,
+            },
+            queries::{self as revision_queries, NewRevision},
+        },
+        sync::{model::ENTITY_QUESTION, queries as sync_queries},
+        workflow::model::{ContentStatus, visibility_for, working_copy},
+    },
     routes::api::ApiState,
 };
 
@@ -22,12 +45,50 @@ pub fn collection() -> MethodRouter<ApiState> {
     axum_get(list_questions).post(create_question)
 }
 
-pub async fn get_question(State(state): State<ApiState>, Path(id): Path<String>) -> impl IntoResponse {
-    match queries::find_question_by_id(&state.db, &id).await {
-        Ok(Some(q)) => (StatusCode::OK, Json(q)).into_response(),
-        Ok(None) => {
-            (StatusCode::NOT_FOUND, Json(json!({ "error": "question not found" }))).into_response()
+pub fn restore() -> MethodRouter<ApiState> {
+    axum_post(restore_question)
+}
+
+pub fn trash() -> MethodRouter<ApiState> {
+    axum_get(list_trash).delete(purge_trash)
+}
+
+pub fn revert() -> MethodRouter<ApiState> {
+    axum_post(revert_question)
+}
+
+pub fn answer() -> MethodRouter<ApiState> {
+    axum_post(answer_question)
+}
+
+#[derive(Deserialize)]
+pub struct PreviewQuery {
+    #[serde(default)]
+    pub preview: bool,
+}
+
+pub async fn get_question(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+    Query(params): Query<PreviewQuery>,
+    caller: Caller,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let visibility = match visibility_for(&caller, params.preview, None) {
+        Ok(visibility) => visibility,
+        Err(forbidden) => return forbidden.into_response(),
+    };
+
+    let question = if params.preview {
+        queries::find_working_question(&state.db, &id).await
+    } else {
+        queries::find_question_by_id(&state.db, &id).await
+    };
+    match question {
+        Ok(Some(q)) if visibility.allows(q.status) => {
+            question_response(&headers, q, caller.role.is_staff())
         }
+        Ok(_) => (StatusCode::NOT_FOUND, Json(json!({ "error": "question not found" }))).into_response(),
         Err(err) => {
             error!(error = ?err, "failed to fetch question");
             (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "failed to fetch question" })))
@@ -36,33 +97,72 @@ pub async fn get_question(State(state): State<ApiState>, Path(id): Path<String>)
     }
 }
 
+/// `preview=true` (any status) and `status` are only honoured for staff.
 #[derive(Deserialize)]
 pub struct ListQuery {
     pub stage: Option<i32>,
+    pub min_difficulty: Option<f64>,
+    pub max_difficulty: Option<f64>,
     pub limit: Option<u64>,
     pub offset: Option<u64>,
+    #[serde(default)]
+    pub preview: bool,
+    pub status: Option<ContentStatus>,
 }
 
 #[derive(Serialize)]
-pub struct QuestionsList {
-    pub items: Vec<crate::resources::questions::model::QuestionDto>,
+pub struct QuestionsList<T> {
+    pub items: Vec<T>,
 }
 
 pub async fn list_questions(
     State(state): State<ApiState>,
     Query(params): Query<ListQuery>,
+    caller: Caller,
+    headers: HeaderMap,
 ) -> impl IntoResponse {
+    let visibility = match visibility_for(&caller, params.preview, params.status) {
+        Ok(visibility) => visibility,
+        Err(forbidden) => return forbidden.into_response(),
+    };
     let limit = params.limit.unwrap_or(50).min(100) as i64;
     let offset = params.offset.unwrap_or(0);
 
-    match queries::list_questions(&state.db, params.stage, limit, offset).await {
-        Ok(items) => (
-            StatusCode::OK,
-            Json(QuestionsList {
-                items,
-            }),
-        )
-            .into_response(),
+    let difficulty = DifficultyRange {
+        min: params.min_difficulty,
+        max: params.max_difficulty,
+    };
+    match queries::list_questions(&state.db, params.stage, difficulty, visibility, limit, offset).await {
+        Ok(items) => {
+            let staff = caller.role.is_staff();
+            let body = if staff {
+                serde_json::to_vec(&QuestionsList {
+                    items,
+                })
+            } else {
+                let items: Vec<PublicQuestionDto> =
+                    items.into_iter().map(PublicQuestionDto::from).collect();
+                serde_json::to_vec(&QuestionsList {
+                    items,
+                })
+            };
+            // No `Last-Modified`: deletes, unpublishes and page shifts change a page without
+            // raising any item's `updated_at`, so only the ETag can validate it.
+            match body {
+                Ok(body) => {
+                    let validators = Validators::for_body(&body, None).per_role(staff);
+                    conditional::respond(&headers, Bytes::from(body), validators)
+                }
+                Err(err) => {
+                    error!(error = ?err, "failed to encode questions");
+                    (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        Json(json!({ "error": "failed to list questions" })),
+                    )
+                        .into_response()
+                }
+            }
+        }
         Err(err) => {
             error!(error = ?err, "failed to list questions");
             (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "failed to list questions" })))
@@ -71,18 +171,217 @@ pub async fn list_questions(
     }
 }
 
+pub async fn update_question(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+    caller: Caller,
+    headers: HeaderMap,
+    Json(payload): Json<CreateQuestion>,
+) -> impl IntoResponse {
+    if let Err(forbidden) = caller.require_editor() {
+        return forbidden.into_response();
+    }
+    if let Err(msg) = validate_create_question(&payload) {
+        return (StatusCode::BAD_REQUEST, Json(json!({ "error": msg }))).into_response();
+    }
+
+    let current = match check_if_match(&state, &id, &headers).await {
+        Ok(current) => current,
+        Err(response) => return response,
+    };
+    let next = match queries::edited_question(&current, payload) {
+        Ok(next) => next,
+        Err(err) => {
+            error!(error = ?err, "failed to encode question");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "failed to update question" })),
+            )
+                .into_response();
+        }
+    };
+
+    let write = WriteQuestion {
+        id: &id,
+        action: ACTION_UPDATE,
+        caller: &caller,
+        reason: change_reason(&headers),
+        failure: "failed to update question",
+    };
+    match write_question(&state, Some(&current), &next, write).await {
+        Ok(true) => {
+            invalidate_snapshots(&state);
+            stored_question_response(next)
+        }
+        Ok(false) => conditional::precondition_failed(),
+        Err(response) => response,
+    }
+}
+
 pub async fn delete_question(
     State(state): State<ApiState>,
     Path(id): Path<String>,
+    caller: Caller,
+    headers: HeaderMap,
 ) -> impl IntoResponse {
-    match queries::delete_question_by_id(&state.db, &id).await {
-        Ok(true) => StatusCode::NO_CONTENT.into_response(),
-        Ok(false) => {
-            (StatusCode::NOT_FOUND, Json(json!({ "error": "question not found" }))).into_response()
+    if let Err(forbidden) = caller.require_editor() {
+        return forbidden.into_response();
+    }
+    let current = match check_if_match(&state, &id, &headers).await {
+        Ok(current) => current,
+        Err(response) => return response,
+    };
+    let next = queries::trashed_question(&current, caller.user_id.as_deref());
+
+    let write = WriteQuestion {
+        id: &id,
+        action: ACTION_DELETE,
+        caller: &caller,
+        reason: change_reason(&headers),
+        failure: "failed to delete question",
+    };
+    match write_question(&state, Some(&current), &next, write).await {
+        Ok(true) => {
+            invalidate_snapshots(&state);
+            if let Err(err) = sync_queries::record_tombstones(
+                &state.db,
+                ENTITY_QUESTION,
+                std::slice::from_ref(&id),
+                None,
+            )
+            .await
+            {
+                error!(error = ?err, id, "failed to record question tombstone");
+            }
+            StatusCode::NO_CONTENT.into_response()
         }
+        Ok(false) => conditional::precondition_failed(),
+        Err(response) => response,
+    }
+}
+
+pub async fn restore_question(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+    caller: Caller,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    if let Err(forbidden) = caller.require_editor() {
+        return forbidden.into_response();
+    }
+
+    let current = match queries::find_question_document(&state.db, &id).await {
+        Ok(Some(current)) if current.get_datetime("deleted_at").is_ok() => current,
+        Ok(_) => {
+            return (StatusCode::NOT_FOUND, Json(json!({ "error": "question not found in trash" })))
+                .into_response();
+        }
+        Err(err) => {
+            error!(error = ?err, "failed to fetch question");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "failed to restore question" })),
+            )
+                .into_response();
+        }
+    };
+    let next = queries::restored_question(&current);
+
+    let write = WriteQuestion {
+        id: &id,
+        action: ACTION_RESTORE,
+        caller: &caller,
+        reason: change_reason(&headers),
+        failure: "failed to restore question",
+    };
+    match write_question(&state, Some(&current), &next, write).await {
+        Ok(true) => {
+            invalidate_snapshots(&state);
+            // Only published questions return to players; anything else stays deleted for sync.
+            if ContentStatus::of_document(&next) == ContentStatus::Published
+                && let Err(err) = sync_queries::clear_tombstones(
+                    &state.db,
+                    ENTITY_QUESTION,
+                    std::slice::from_ref(&id),
+                    None,
+                )
+                .await
+            {
+                error!(error = ?err, id, "failed to clear question tombstone");
+            }
+            match queries::question_dto(next) {
+                Ok(q) => (StatusCode::OK, Json(q)).into_response(),
+                Err(err) => {
+                    error!(error = ?err, id, "failed to decode question");
+                    (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        Json(json!({ "error": "failed to restore question" })),
+                    )
+                        .into_response()
+                }
+            }
+        }
+        Ok(false) => (StatusCode::CONFLICT, Json(json!({ "error": "question changed concurrently" })))
+            .into_response(),
+        Err(response) => response,
+    }
+}
+
+#[derive(Deserialize)]
+pub struct TrashQuery {
+    pub limit: Option<u64>,
+    pub offset: Option<u64>,
+}
+
+#[derive(Serialize)]
+pub struct TrashList {
+    pub items: Vec<TrashedQuestionDto>,
+    pub retention_days: i64,
+}
+
+pub async fn list_trash(
+    State(state): State<ApiState>,
+    Query(params): Query<TrashQuery>,
+    caller: Caller,
+) -> impl IntoResponse {
+    if let Err(forbidden) = caller.require_editor() {
+        return forbidden.into_response();
+    }
+    let limit = params.limit.unwrap_or(50).min(100) as i64;
+    let offset = params.offset.unwrap_or(0);
+    let retention_days = state.config.trash_retention_days;
+
+    match queries::list_trash(&state.db, limit, offset).await {
+        Ok(items) => (
+            StatusCode::OK,
+            Json(TrashList {
+                items: items
+                    .into_iter()
+                    .map(|q| TrashedQuestionDto::new(q, chrono::Duration::days(retention_days)))
+                    .collect(),
+                retention_days,
+            }),
+        )
+            .into_response(),
+        Err(err) => {
+            error!(error = ?err, "failed to list trash");
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "failed to list trash" })))
+                .into_response()
+        }
+    }
+}
+
+/// Runs the retention purge immediately instead of waiting for the background job.
+pub async fn purge_trash(State(state): State<ApiState>, caller: Caller) -> impl IntoResponse {
+    if let Err(forbidden) = caller.require_admin() {
+        return forbidden.into_response();
+    }
+
+    match crate::jobs::purge_trash(&state.db, state.config.trash_retention_days).await {
+        Ok(purged) => (StatusCode::OK, Json(json!({ "purged": purged }))).into_response(),
         Err(err) => {
-            error!(error = ?err, "failed to delete question");
-            (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "failed to delete question" })))
+            error!(error = ?err, "failed to purge trash");
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "failed to purge trash" })))
                 .into_response()
         }
     }
@@ -90,45 +389,312 @@ pub async fn delete_question(
 
 pub async fn create_question(
     State(state): State<ApiState>,
+    caller: Caller,
+    headers: HeaderMap,
     Json(payload): Json<CreateQuestion>,
 ) -> impl IntoResponse {
+    if let Err(forbidden) = caller.require_editor() {
+        return forbidden.into_response();
+    }
     if let Err(msg) = validate_create_question(&payload) {
         return (StatusCode::BAD_REQUEST, Json(json!({ "error": msg }))).into_response();
     }
 
-    match queries::insert_question(&state.db, payload).await {
-        Ok(dto) => (StatusCode::CREATED, Json(dto)).into_response(),
+    let question = queries::new_question(payload);
+    let next = match mongodb::bson::to_document(&question) {
+        Ok(next) => next,
+        Err(err) => {
+            error!(error = ?err, "failed to encode question");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "failed to create question" })),
+            )
+                .into_response();
+        }
+    };
+
+    let dto = QuestionDto::from(question);
+    let write = WriteQuestion {
+        id: &dto.id,
+        action: ACTION_CREATE,
+        caller: &caller,
+        reason: change_reason(&headers),
+        failure: "failed to create question",
+    };
+    match write_question(&state, None, &next, write).await {
+        Ok(true) => {
+            invalidate_snapshots(&state);
+            (StatusCode::CREATED, Json(dto)).into_response()
+        }
+        Ok(false) => {
+            (StatusCode::CONFLICT, Json(json!({ "error": "question already exists" }))).into_response()
+        }
+        Err(response) => response,
+    }
+}
+
+/// Restores the editable content of a live question to what editors had at revision `number` (a
+/// pending edit, if there was one), as a new draft. The revert itself is appended as a new
+/// revision, so history is never rewritten.
+pub async fn revert_question(
+    State(state): State<ApiState>,
+    Path((id, number)): Path<(String, i64)>,
+    caller: Caller,
+    headers: HeaderMap,
+    body: Option<Json<RevertRequest>>,
+) -> impl IntoResponse {
+    if let Err(forbidden) = caller.require_editor() {
+        return forbidden.into_response();
+    }
+
+    let revision = match revision_queries::find_revision(&state.db, ENTITY_QUESTION, &id, number).await {
+        Ok(Some(revision)) => revision,
+        Ok(None) => {
+            return (StatusCode::NOT_FOUND, Json(json!({ "error": "revision not found" })))
+                .into_response();
+        }
+        Err(err) => {
+            error!(error = ?err, "failed to fetch revision");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "failed to revert question" })),
+            )
+                .into_response();
+        }
+    };
+    let payload: CreateQuestion = match mongodb::bson::from_document(working_copy(&revision.snapshot)) {
+        Ok(payload) => payload,
+        Err(err) => {
+            error!(error = ?err, id, number, "revision snapshot is not a question");
+            return (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Json(json!({ "error": "revision cannot be reverted to" })),
+            )
+                .into_response();
+        }
+    };
+
+    let current = match check_if_match(&state, &id, &headers).await {
+        Ok(current) => current,
+        Err(response) => return response,
+    };
+    let next = match queries::edited_question(&current, payload) {
+        Ok(next) => next,
+        Err(err) => {
+            error!(error = ?err, "failed to encode question");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "failed to revert question" })),
+            )
+                .into_response();
+        }
+    };
+
+    let reason = body
+        .and_then(|Json(body)| body.reason)
+        .or_else(|| change_reason(&headers))
+        .unwrap_or_else(|| format!("reverted to revision {number}"));
+    let write = WriteQuestion {
+        id: &id,
+        action: ACTION_REVERT,
+        caller: &caller,
+        reason: Some(reason),
+        failure: "failed to revert question",
+    };
+    match write_question(&state, Some(&current), &next, write).await {
+        Ok(true) => {
+            invalidate_snapshots(&state);
+            stored_question_response(next)
+        }
+        Ok(false) => conditional::precondition_failed(),
+        Err(response) => response,
+    }
+}
+
+#[derive(Deserialize)]
+pub struct AnswerQuery {
+    pub lang: Option<String>,
+    #[serde(default)]
+    pub preview: bool,
+}
+
+/// Grades an answer and returns the solution, for staff checking their own content. `lang` only
+/// matters for fill-in-the-blank questions, whose accepted answers are localized. Players answer
+/// through the play endpoints, which also feed the question's statistics; this one would let them
+/// look up the solution to any challenge or quiz question.
+pub async fn answer_question(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+    Query(params): Query<AnswerQuery>,
+    caller: Caller,
+    headers: HeaderMap,
+    Json(TimedAnswer {
+        answer,
+        ..
+    }): Json<TimedAnswer>,
+) -> impl IntoResponse {
+    if let Err(forbidden) = caller.require_staff() {
+        return forbidden.into_response();
+    }
+    let visibility = match visibility_for(&caller, params.preview, None) {
+        Ok(visibility) => visibility,
+        Err(forbidden) => return forbidden.into_response(),
+    };
+    let lang = resolve_lang(params.lang.as_deref(), headers.get("accept-language"));
+
+    match queries::find_question_by_id(&state.db, &id).await {
+        Ok(Some(q)) if visibility.allows(q.status) => match check_answer(&q, &answer, &lang) {
+            Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+            Err(msg) => (StatusCode::BAD_REQUEST, Json(json!({ "error": msg }))).into_response(),
+        },
+        Ok(_) => (StatusCode::NOT_FOUND, Json(json!({ "error": "question not found" }))).into_response(),
         Err(err) => {
-            error!(error = ?err, "failed to create question");
-            (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "failed to create question" })))
+            error!(error = ?err, "failed to fetch question");
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "failed to check answer" })))
                 .into_response()
         }
     }
 }
 
-fn validate_create_question(payload: &CreateQuestion) -> Result<(), &'static str> {
-    validate_localized(&payload.prompt, "prompt")?;
-    if let Some(label) = &payload.stage_label {
-        validate_localized(label, "stage_label")?;
+/// How a question write is recorded in its history.
+pub struct WriteQuestion<'a> {
+    pub id: &'a str,
+    pub action: &'a str,
+    pub caller: &'a Caller,
+    pub reason: Option<String>,
+    /// Error message for a failed write.
+    pub failure: &'a str,
+}
+
+/// Appends `next` to the question's history, then stores it in place of `previous` (or creates
+/// it). The revision goes first so no change is stored without one; if the write does not apply,
+/// the revision is withdrawn again. `Ok(false)` means the question changed since `previous` was
+/// read; any failure comes back as a `500` for the caller to return.
+pub async fn write_question(
+    state: &ApiState,
+    previous: Option<&Document>,
+    next: &Document,
+    write: WriteQuestion<'_>,
+) -> Result<bool, Response> {
+    let WriteQuestion {
+        id,
+        action,
+        caller,
+        reason,
+        failure,
+    } = write;
+    let revision = NewRevision {
+        entity: ENTITY_QUESTION,
+        entity_id: id,
+        action,
+        snapshot: next.clone(),
+        author: caller.user_id.as_deref(),
+        reason: reason.as_deref(),
+    };
+    let revision = match revision_queries::record_revision(&state.db, revision).await {
+        Ok(revision) => revision,
+        Err(err) => {
+            error!(error = ?err, id, action, "failed to record question revision");
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "failed to record revision" })),
+            )
+                .into_response());
+        }
+    };
+
+    let written = queries::write_question(&state.db, previous, next).await;
+    if !matches!(written, Ok(true))
+        && let Err(err) = revision_queries::withdraw_revision(&state.db, revision.id).await
+    {
+        error!(error = ?err, id, action, "failed to withdraw question revision");
     }
+    written.map_err(|err| {
+        error!(error = ?err, id, action, "failed to write question");
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": failure }))).into_response()
+    })
+}
 
-    if payload.options.len() != 4 {
-        return Err("exactly four options are required");
+/// Cached snapshots that embed questions must be rebuilt after any question write.
+fn invalidate_snapshots(state: &ApiState) {
+    state.cache.invalidate_prefix("bundle:");
+}
+
+/// The question as `staff` or, without its solution, as anyone else sees it.
+fn question_response(headers: &HeaderMap, question: QuestionDto, staff: bool) -> Response {
+    let last_modified = question.updated_at_utc();
+    let body = if staff {
+        serde_json::to_vec(&question)
+    } else {
+        serde_json::to_vec(&PublicQuestionDto::from(question))
+    };
+    match body {
+        Ok(body) => {
+            let validators =
+                Validators::for_body(&body, None).last_modified(last_modified).per_role(staff);
+            conditional::respond(headers, Bytes::from(body), validators)
+        }
+        Err(err) => {
+            error!(error = ?err, "failed to encode question");
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "failed to fetch question" })))
+                .into_response()
+        }
     }
+}
 
-    let correct_count = payload.options.iter().filter(|opt| opt.correct).count();
-    if correct_count != 1 {
-        return Err("exactly one option must be marked correct");
+/// The staff preview of a question document that was just written.
+fn stored_question_response(question: Document) -> Response {
+    match queries::question_dto(working_copy(&question)) {
+        Ok(q) => question_response(&HeaderMap::new(), q, true),
+        Err(err) => {
+            error!(error = ?err, "failed to decode question");
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "failed to fetch question" })))
+                .into_response()
+        }
     }
+}
 
-    for opt in &payload.options {
-        validate_localized(&opt.text, "option text")?;
-        if let Some(expl) = &opt.explanation {
-            validate_localized(expl, "option explanation")?;
+/// Loads the current live question and enforces `If-Match` against its staff preview (`GET
+/// /v1/questions/:id?preview=true`, which shows a pending edit). On success returns its stored
+/// document, which writes build on and are guarded against, so an edit landing between this check
+/// and the write is rejected.
+async fn check_if_match(state: &ApiState, id: &str, headers: &HeaderMap) -> Result<Document, Response> {
+    let current = match queries::find_question_document(&state.db, id).await {
+        Ok(Some(current)) if current.get_datetime("deleted_at").is_err() => current,
+        Ok(_) => {
+            return Err(
+                (StatusCode::NOT_FOUND, Json(json!({ "error": "question not found" }))).into_response()
+            );
+        }
+        Err(err) => {
+            error!(error = ?err, "failed to fetch question");
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": "failed to fetch question" })),
+            )
+                .into_response());
         }
+    };
+
+    let body = queries::question_dto(working_copy(&current))
+        .ok()
+        .and_then(|q| serde_json::to_vec(&q).ok())
+        .unwrap_or_default();
+    if !conditional::if_match_allows(headers, &Validators::for_body(&body, None).etag) {
+        return Err(conditional::precondition_failed());
     }
+    Ok(current)
+}
 
-    Ok(())
+pub fn validate_create_question(payload: &CreateQuestion) -> Result<(), &'static str> {
+    validate_localized(&payload.prompt, "prompt")?;
+    if let Some(label) = &payload.stage_label {
+        validate_localized(label, "stage_label")?;
+    }
+    validate_classification(payload)?;
+    validate_time_limit(payload)?;
+
+    validate_shape(payload, validate_localized)
 }
 
 fn validate_localized(
@@ -145,6 +711,8 @@ fn validate_localized(
                     "stage_label" => "stage_label requires en, es, pt, and sv",
                     "option text" => "option text requires en, es, pt, and sv",
                     "option explanation" => "option explanation requires en, es, pt, and sv",
+                    "pair text" => "pair text requires en, es, pt, and sv",
+                    "answer text" => "answer text requires en, es, pt, and sv",
                     _ => "all locales must be provided",
                 });
             }
@@ -156,7 +724,10 @@ fn validate_localized(
 #[cfg(test)]
 mod tests {
     use super::{validate_create_question, validate_localized};
-    use crate::resources::questions::model::{CreateQuestion, LocalizedText, OptionItem};
+    use crate::resources::questions::{
+        model::{CreateQuestion, LocalizedText, OptionItem},
+        types::{MatchPair, QuestionType},
+    };
 
     fn valid_question() -> CreateQuestion {
         let mut stage_label = LocalizedText::new();
@@ -202,6 +773,7 @@ mod tests {
         expl_b.insert("sv".into(), "eftersom".into());
 
         CreateQuestion {
+            question_type: QuestionType::SingleChoice,
             stage: 1,
             stage_label: Some(stage_label),
             prompt,
@@ -227,6 +799,11 @@ mod tests {
                     explanation: None,
                 },
             ],
+            pairs: vec![],
+            answers: vec![],
+            era: None,
+            level: None,
+            time_limit_secs: None,
             tags: vec!["tag".to_string()],
             image_url: None,
         }
@@ -273,6 +850,45 @@ mod tests {
         assert!(validate_create_question(&q).is_err());
     }
 
+    #[test]
+    fn validates_true_false_and_ordering_shapes() {
+        let mut q = valid_question();
+        q.question_type = QuestionType::TrueFalse;
+        assert!(validate_create_question(&q).is_err());
+        q.options.truncate(2);
+        assert!(validate_create_question(&q).is_ok());
+
+        let mut q = valid_question();
+        q.question_type = QuestionType::Ordering;
+        q.options.iter_mut().for_each(|o| o.correct = false);
+        assert!(validate_create_question(&q).is_ok());
+    }
+
+    #[test]
+    fn validates_matching_and_fill_blank_shapes() {
+        let base = valid_question();
+        let mut q = valid_question();
+        q.question_type = QuestionType::Matching;
+        assert!(validate_create_question(&q).is_err());
+        q.pairs = q
+            .options
+            .drain(..)
+            .map(|o| MatchPair {
+                left: o.text,
+                right: base.prompt.clone(),
+            })
+            .collect();
+        assert!(validate_create_question(&q).is_ok());
+
+        let mut q = valid_question();
+        q.question_type = QuestionType::FillBlank;
+        q.answers = vec![q.options.remove(0).text];
+        q.options.clear();
+        assert!(validate_create_question(&q).is_err());
+        q.prompt.values_mut().for_each(|p| p.push_str(" ___"));
+        assert!(validate_create_question(&q).is_ok());
+    }
+
     #[test]
     fn validate_localized_requires_all_locales() {
         let mut lt = LocalizedText::new();
@@ -1,15 +1,39 @@
 use chrono::Utc;
-use mongodb::bson::{Bson, DateTime};
+use mongodb::bson::{Bson, DateTime, Document, oid::ObjectId};
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
+
+use crate::resources::{
+    questions::types::{MatchPair, QuestionType},
+    ui::levels::level_rank,
+    workflow::model::ContentStatus,
+};
+
 pub type LocalizedText = BTreeMap<String, String>;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CreateQuestion {
+    #[serde(rename = "type", default)]
+    pub question_type: QuestionType,
     pub stage: i32,
     pub stage_label: Option<LocalizedText>,
     pub prompt: LocalizedText,
+    #[serde(default)]
     pub options: Vec<OptionItem>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pairs: Vec<MatchPair>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub answers: Vec<LocalizedText>,
+    /// Era id (see `/v1/eras`) the question belongs to, used to filter and balance quizzes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub era: Option<String>,
+    /// Difficulty level id from `/v1/ui/levels`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub level: Option<String>,
+    /// Seconds a player has to answer in quiz play; quiz modes may set a default for questions
+    /// without one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_limit_secs: Option<i32>,
     pub tags: Vec<String>,
     pub image_url: Option<String>,
 }
@@ -25,34 +49,89 @@ pub struct OptionItem {
 pub struct Question {
     #[serde(rename = "_id")]
     pub id: Bson,
+    #[serde(rename = "type", default)]
+    pub question_type: QuestionType,
     pub stage: i32,
     pub stage_label: Option<LocalizedText>,
     pub prompt: LocalizedText,
+    #[serde(default)]
     pub options: Vec<OptionItem>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pairs: Vec<MatchPair>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub answers: Vec<LocalizedText>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub era: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub level: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_limit_secs: Option<i32>,
+    /// Elo-scale difficulty estimated from player answers by the calibration job (an average
+    /// player, rated 1500, gets a 1500 question right half the time), and the answers it rests on.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub difficulty: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub difficulty_answers: Option<i64>,
+    /// Last `answer_log` entry counted in `difficulty`, so a replayed batch is not counted twice.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub difficulty_through: Option<ObjectId>,
     pub tags: Vec<String>,
     pub image_url: Option<String>,
+    /// `None` for questions stored before the review workflow; they count as published.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<ContentStatus>,
     #[serde(default = "old_date_bson")]
     pub created_at: DateTime,
     #[serde(default = "old_date_bson")]
     pub updated_at: DateTime,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<DateTime>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deleted_by: Option<String>,
+    /// Set when open player reports reach `REPORT_FLAG_THRESHOLD`; cleared once they are resolved.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub flagged_at: Option<DateTime>,
+    /// Edit of a published question waiting to be published over it; see `workflow::model::edited`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pending: Option<Document>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OptionDto {
     pub text: LocalizedText,
     pub correct: bool,
     pub explanation: Option<LocalizedText>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QuestionDto {
     pub id: String,
+    #[serde(rename = "type", default)]
+    pub question_type: QuestionType,
     pub stage: i32,
     pub stage_label: Option<LocalizedText>,
     pub prompt: LocalizedText,
     pub options: Vec<OptionDto>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pairs: Vec<MatchPair>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub answers: Vec<LocalizedText>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub era: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub level: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_limit_secs: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub difficulty: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub difficulty_answers: Option<i64>,
     pub tags: Vec<String>,
     pub image_url: Option<String>,
+    pub status: ContentStatus,
+    /// Status of a pending edit; `GET /v1/questions/:id?preview=true` shows its content.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pending_status: Option<ContentStatus>,
     pub created_at: String,
     pub updated_at: String,
 }
@@ -65,12 +144,22 @@ impl From<Question> for QuestionDto {
                 Bson::String(s) => s,
                 other => other.to_string(),
             },
+            question_type: q.question_type,
             stage: q.stage,
             stage_label: q.stage_label,
             prompt: q.prompt,
             options: q.options.into_iter().map(OptionDto::from).collect(),
+            pairs: q.pairs,
+            answers: q.answers,
+            era: q.era,
+            level: q.level,
+            time_limit_secs: q.time_limit_secs,
+            difficulty: q.difficulty,
+            difficulty_answers: q.difficulty_answers,
             tags: q.tags,
             image_url: q.image_url,
+            status: q.status.unwrap_or(ContentStatus::Published),
+            pending_status: q.pending.as_ref().map(ContentStatus::of_document),
             created_at: q.created_at.to_chrono().with_timezone(&Utc).to_rfc3339(),
             updated_at: q.updated_at.to_chrono().with_timezone(&Utc).to_rfc3339(),
         }
@@ -87,6 +176,219 @@ impl From<OptionItem> for OptionDto {
     }
 }
 
+/// A question in the trash, with the date after which the purge job removes it for good.
+#[derive(Debug, Serialize)]
+pub struct TrashedQuestionDto {
+    #[serde(flatten)]
+    pub question: QuestionDto,
+    pub deleted_at: String,
+    pub deleted_by: Option<String>,
+    pub purge_after: String,
+}
+
+impl TrashedQuestionDto {
+    pub fn new(question: Question, retention: chrono::Duration) -> Self {
+        let deleted_at = question.deleted_at.unwrap_or(question.updated_at).to_chrono();
+        let deleted_by = question.deleted_by.clone();
+        Self {
+            question: QuestionDto::from(question),
+            deleted_at: deleted_at.to_rfc3339(),
+            deleted_by,
+            purge_after: (deleted_at + retention).to_rfc3339(),
+        }
+    }
+}
+
+/// A question as non-staff callers read it from `/v1/questions`: option text without `correct`
+/// flags or explanations, and no accepted `answers`. Ordering options and the right-hand matching
+/// column are sorted by text, since their authored order is the solution.
+#[derive(Debug, Serialize)]
+pub struct PublicQuestionDto {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub question_type: QuestionType,
+    pub stage: i32,
+    pub stage_label: Option<LocalizedText>,
+    pub prompt: LocalizedText,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub options: Vec<PublicOptionDto>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub left: Vec<LocalizedText>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub right: Vec<LocalizedText>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub era: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub level: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_limit_secs: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub difficulty: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub difficulty_answers: Option<i64>,
+    pub tags: Vec<String>,
+    pub image_url: Option<String>,
+    pub status: ContentStatus,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PublicOptionDto {
+    pub text: LocalizedText,
+}
+
+impl From<QuestionDto> for PublicQuestionDto {
+    fn from(q: QuestionDto) -> Self {
+        let mut options: Vec<LocalizedText> = q.options.into_iter().map(|o| o.text).collect();
+        if q.question_type == QuestionType::Ordering {
+            options.sort();
+        }
+        let (left, mut right): (Vec<_>, Vec<_>) = q.pairs.into_iter().map(|p| (p.left, p.right)).unzip();
+        right.sort();
+        Self {
+            id: q.id,
+            question_type: q.question_type,
+            stage: q.stage,
+            stage_label: q.stage_label,
+            prompt: q.prompt,
+            options: options
+                .into_iter()
+                .map(|text| PublicOptionDto {
+                    text,
+                })
+                .collect(),
+            left,
+            right,
+            era: q.era,
+            level: q.level,
+            time_limit_secs: q.time_limit_secs,
+            difficulty: q.difficulty,
+            difficulty_answers: q.difficulty_answers,
+            tags: q.tags,
+            image_url: q.image_url,
+            status: q.status,
+            created_at: q.created_at,
+            updated_at: q.updated_at,
+        }
+    }
+}
+
+/// A question flattened to a single language, as consumed by players.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LocalizedQuestionDto {
+    pub id: String,
+    #[serde(rename = "type", default)]
+    pub question_type: QuestionType,
+    pub stage: i32,
+    pub stage_label: Option<String>,
+    pub prompt: String,
+    pub options: Vec<LocalizedOptionDto>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pairs: Vec<LocalizedPairDto>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub answers: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub era: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub level: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_limit_secs: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub difficulty: Option<f64>,
+    pub tags: Vec<String>,
+    pub image_url: Option<String>,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LocalizedOptionDto {
+    pub text: String,
+    pub correct: bool,
+    pub explanation: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LocalizedPairDto {
+    pub left: String,
+    pub right: String,
+}
+
+impl QuestionDto {
+    /// Picks `lang` from every localized field, falling back to English when it is missing.
+    pub fn localize(self, lang: &str) -> LocalizedQuestionDto {
+        LocalizedQuestionDto {
+            id: self.id,
+            question_type: self.question_type,
+            stage: self.stage,
+            stage_label: self.stage_label.as_ref().map(|label| localized(label, lang)),
+            prompt: localized(&self.prompt, lang),
+            options: self
+                .options
+                .into_iter()
+                .map(|o| LocalizedOptionDto {
+                    text: localized(&o.text, lang),
+                    correct: o.correct,
+                    explanation: o.explanation.as_ref().map(|e| localized(e, lang)),
+                })
+                .collect(),
+            pairs: self
+                .pairs
+                .iter()
+                .map(|p| LocalizedPairDto {
+                    left: localized(&p.left, lang),
+                    right: localized(&p.right, lang),
+                })
+                .collect(),
+            answers: self.answers.iter().map(|a| localized(a, lang)).collect(),
+            era: self.era,
+            level: self.level,
+            time_limit_secs: self.time_limit_secs,
+            difficulty: self.difficulty,
+            tags: self.tags,
+            image_url: self.image_url,
+            updated_at: self.updated_at,
+        }
+    }
+
+    pub fn updated_at_utc(&self) -> Option<chrono::DateTime<Utc>> {
+        chrono::DateTime::parse_from_rfc3339(&self.updated_at).ok().map(|dt| dt.with_timezone(&Utc))
+    }
+}
+
+/// Checks the optional `era` and `level` a question is classified under.
+pub fn validate_classification(question: &CreateQuestion) -> Result<(), &'static str> {
+    if let Some(era) = &question.era
+        && (era.is_empty()
+            || !era.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-'))
+    {
+        return Err("era must be an era id such as \"prophets\"");
+    }
+    if let Some(level) = &question.level
+        && level_rank(level).is_none()
+    {
+        return Err("level must be one of the ids from /v1/ui/levels");
+    }
+    Ok(())
+}
+
+/// Shortest and longest per-question time limit an author may set.
+pub const MIN_TIME_LIMIT_SECS: i32 = 5;
+pub const MAX_TIME_LIMIT_SECS: i32 = 600;
+
+pub fn validate_time_limit(question: &CreateQuestion) -> Result<(), &'static str> {
+    match question.time_limit_secs {
+        Some(secs) if !(MIN_TIME_LIMIT_SECS..=MAX_TIME_LIMIT_SECS).contains(&secs) => {
+            Err("time_limit_secs must be between 5 and 600")
+        }
+        _ => Ok(()),
+    }
+}
+
+pub fn localized(text: &LocalizedText, lang: &str) -> String {
+    text.get(lang).or_else(|| text.get("en")).cloned().unwrap_or_default()
+}
+
 fn old_date_bson() -> DateTime {
     DateTime::from_chrono(
         chrono::NaiveDate::from_ymd_opt(1920, 1, 1)
@@ -97,3 +399,77 @@ fn old_date_bson() -> DateTime {
             .unwrap(),
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{LocalizedText, OptionDto, PublicQuestionDto, QuestionDto};
+    use crate::resources::{
+        questions::types::{MatchPair, QuestionType},
+        workflow::model::ContentStatus,
+    };
+
+    fn text(en: &str) -> LocalizedText {
+        [("en".to_string(), en.to_string())].into_iter().collect()
+    }
+
+    fn question(question_type: QuestionType) -> QuestionDto {
+        QuestionDto {
+            id: "q1".into(),
+            question_type,
+            stage: 1,
+            stage_label: None,
+            prompt: text("Order them"),
+            options: ["Genesis", "Exodus", "Leviticus"]
+                .into_iter()
+                .map(|t| OptionDto {
+                    text: text(t),
+                    correct: true,
+                    explanation: Some(text("Because")),
+                })
+                .collect(),
+            pairs: vec![
+                MatchPair {
+                    left: text("Moses"),
+                    right: text("Sinai"),
+                },
+                MatchPair {
+                    left: text("Noah"),
+                    right: text("Ararat"),
+                },
+            ],
+            answers: vec![text("wept")],
+            era: None,
+            level: None,
+            time_limit_secs: None,
+            difficulty: None,
+            difficulty_answers: None,
+            tags: Vec::new(),
+            image_url: None,
+            status: ContentStatus::Published,
+            pending_status: None,
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn public_questions_hide_the_solution() {
+        let public =
+            serde_json::to_value(PublicQuestionDto::from(question(QuestionType::Ordering))).unwrap();
+        assert!(public.get("answers").is_none());
+        assert!(public.pointer("/options/0/correct").is_none());
+        assert!(public.pointer("/options/0/explanation").is_none());
+        let order: Vec<_> = public["options"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|o| o.pointer("/text/en").and_then(|v| v.as_str()).unwrap())
+            .collect();
+        assert_eq!(order, ["Exodus", "Genesis", "Leviticus"]);
+        assert_eq!(public.pointer("/left/0/en").and_then(|v| v.as_str()), Some("Moses"));
+        assert_eq!(public.pointer("/right/0/en").and_then(|v| v.as_str()), Some("Ararat"));
+
+        let choice = PublicQuestionDto::from(question(QuestionType::SingleChoice));
+        assert_eq!(choice.options[0].text, text("Genesis"));
+    }
+}
@@ -1,71 +1,237 @@
 use futures_util::TryStreamExt;
 use mongodb::{
     Collection, Database,
-    bson::{Bson, DateTime, doc, oid::ObjectId},
+    bson::{self, Bson, DateTime, Document, doc, oid::ObjectId},
     options::FindOptions,
 };
 
-use crate::resources::questions::model::{CreateQuestion, Question, QuestionDto};
+use crate::{
+    db::is_duplicate_key,
+    resources::{
+        questions::model::{CreateQuestion, Question, QuestionDto},
+        workflow::model::{
+            ContentStatus, Visibility, edited, guard_pending, next_write_at, working_copy,
+            working_status_filter,
+        },
+    },
+};
 
 pub async fn find_question_by_id(
     db: &Database,
     id: &str,
 ) -> mongodb::error::Result<Option<QuestionDto>> {
     let collection: Collection<Question> = db.collection("questions");
-    let filter = match ObjectId::parse_str(id) {
-        Ok(oid) => doc! { "_id": oid },
-        Err(_) => doc! { "_id": id }, // allow string ids too
-    };
-    let res = collection.find_one(filter, None).await?;
+    let res = collection.find_one(live(id_filter(id)), None).await?;
     Ok(res.map(QuestionDto::from))
 }
 
-pub async fn insert_question(
+/// A live question as editors work on it, with a pending edit in place of the published wording.
+pub async fn find_working_question(
     db: &Database,
-    payload: CreateQuestion,
-) -> mongodb::error::Result<QuestionDto> {
-    let collection: Collection<Question> = db.collection("questions");
+    id: &str,
+) -> mongodb::error::Result<Option<QuestionDto>> {
+    let collection: Collection<Document> = db.collection("questions");
+    match collection.find_one(live(id_filter(id)), None).await? {
+        Some(doc) => Ok(Some(question_dto(working_copy(&doc))?)),
+        None => Ok(None),
+    }
+}
 
+/// Raw stored document regardless of trash state, the base for writes and their revisions.
+pub async fn find_question_document(
+    db: &Database,
+    id: &str,
+) -> mongodb::error::Result<Option<Document>> {
+    let collection: Collection<Document> = db.collection("questions");
+    collection.find_one(id_filter(id), None).await
+}
+
+/// A new draft question built from `payload`, ready to be stored with `write_question`.
+pub fn new_question(payload: CreateQuestion) -> Question {
     let now = DateTime::now();
-    let doc = Question {
+    Question {
         id: Bson::ObjectId(ObjectId::new()),
+        question_type: payload.question_type,
         stage: payload.stage,
         stage_label: payload.stage_label,
         prompt: payload.prompt,
         options: payload.options,
+        pairs: payload.pairs,
+        answers: payload.answers,
+        era: payload.era,
+        level: payload.level,
+        time_limit_secs: payload.time_limit_secs,
+        difficulty: None,
+        difficulty_answers: None,
+        difficulty_through: None,
         tags: payload.tags,
         image_url: payload.image_url,
+        status: Some(ContentStatus::Draft),
         created_at: now,
         updated_at: now,
+        deleted_at: None,
+        deleted_by: None,
+        flagged_at: None,
+        pending: None,
+    }
+}
+
+/// `previous` with its editable fields replaced by `payload`, so changed wording is reviewed again
+/// before it goes live. A published question keeps serving its current wording meanwhile.
+pub fn edited_question(
+    previous: &Document,
+    payload: CreateQuestion,
+) -> mongodb::error::Result<Document> {
+    let fields = doc! {
+        "type": bson::to_bson(&payload.question_type)?,
+        "stage": payload.stage,
+        "stage_label": bson::to_bson(&payload.stage_label)?,
+        "prompt": bson::to_bson(&payload.prompt)?,
+        "options": bson::to_bson(&payload.options)?,
+        "pairs": bson::to_bson(&payload.pairs)?,
+        "answers": bson::to_bson(&payload.answers)?,
+        "era": payload.era,
+        "level": payload.level,
+        "time_limit_secs": payload.time_limit_secs,
+        "tags": payload.tags,
+        "image_url": payload.image_url,
     };
+    Ok(edited(previous, fields))
+}
 
-    collection.insert_one(&doc, None).await?;
-    Ok(QuestionDto::from(doc))
+/// `previous` moved to the trash. It stays in the collection, hidden from every read endpoint,
+/// until it is restored or purged after the retention period.
+pub fn trashed_question(previous: &Document, deleted_by: Option<&str>) -> Document {
+    let mut next = previous.clone();
+    next.insert("deleted_at", DateTime::now());
+    next.insert("deleted_by", deleted_by);
+    next
 }
 
-pub async fn delete_question_by_id(db: &Database, id: &str) -> mongodb::error::Result<bool> {
+/// `previous` taken out of the trash. `updated_at` is bumped so delta sync re-delivers it.
+pub fn restored_question(previous: &Document) -> Document {
+    let mut next = previous.clone();
+    next.remove("deleted_at");
+    next.remove("deleted_by");
+    next.insert("updated_at", next_write_at(previous));
+    next
+}
+
+/// Stores `next` in place of `previous`, or inserts it when `previous` is `None`. Returns `false`
+/// without writing if the stored question is no longer `previous` (its `updated_at` or trash
+/// state moved on), or already exists for an insert.
+pub async fn write_question(
+    db: &Database,
+    previous: Option<&Document>,
+    next: &Document,
+) -> mongodb::error::Result<bool> {
+    let collection: Collection<Document> = db.collection("questions");
+    match previous {
+        Some(previous) => {
+            let result = collection.replace_one(unmodified_filter(previous), next, None).await?;
+            Ok(result.matched_count > 0)
+        }
+        None => match collection.insert_one(next, None).await {
+            Ok(_) => Ok(true),
+            Err(err) if is_duplicate_key(&err) => Ok(false),
+            Err(err) => Err(err),
+        },
+    }
+}
+
+/// The API representation of a raw stored question.
+pub fn question_dto(document: Document) -> mongodb::error::Result<QuestionDto> {
+    Ok(QuestionDto::from(bson::from_document::<Question>(document)?))
+}
+
+/// Flags or unflags a question for editor attention. Flagging keeps the original `flagged_at`.
+pub async fn set_question_flag(db: &Database, id: &str, flagged: bool) -> mongodb::error::Result<()> {
     let collection: Collection<Question> = db.collection("questions");
-    let filter = match ObjectId::parse_str(id) {
-        Ok(oid) => doc! { "_id": oid },
-        Err(_) => doc! { "_id": id },
+    let mut filter = id_filter(id);
+    let update = if flagged {
+        filter.insert("flagged_at", Bson::Null);
+        doc! { "$set": { "flagged_at": DateTime::now() } }
+    } else {
+        doc! { "$unset": { "flagged_at": "" } }
     };
+    collection.update_one(filter, update, None).await?;
+    Ok(())
+}
+
+/// Live questions with the given ids, in no particular order.
+pub async fn find_questions_by_ids(
+    db: &Database,
+    ids: &[String],
+) -> mongodb::error::Result<Vec<Question>> {
+    let collection: Collection<Question> = db.collection("questions");
+    let ids: Vec<Bson> = ids.iter().map(|id| id_bson(id)).collect();
+
+    let mut cursor = collection.find(live(doc! { "_id": { "$in": ids } }), None).await?;
+    let mut results = Vec::new();
+    while let Some(doc) = cursor.try_next().await? {
+        results.push(doc);
+    }
+    Ok(results)
+}
 
-    let result = collection.delete_one(filter, None).await?;
-    Ok(result.deleted_count > 0)
+/// Live questions, or pending edits of published ones, waiting for review, oldest first, as
+/// reviewers see them.
+pub async fn list_in_review(db: &Database) -> mongodb::error::Result<Vec<QuestionDto>> {
+    let collection: Collection<Document> = db.collection("questions");
+    let options = FindOptions::builder().sort(doc! { "updated_at": 1, "_id": 1 }).build();
+
+    let filter = live(working_status_filter(ContentStatus::InReview));
+    let mut cursor = collection.find(filter, options).await?;
+    let mut results = Vec::new();
+    while let Some(doc) = cursor.try_next().await? {
+        results.push(question_dto(working_copy(&doc))?);
+    }
+    Ok(results)
+}
+
+pub async fn list_trash(
+    db: &Database,
+    limit: i64,
+    offset: u64,
+) -> mongodb::error::Result<Vec<Question>> {
+    let collection: Collection<Question> = db.collection("questions");
+    let options = FindOptions::builder()
+        .sort(doc! { "deleted_at": -1, "_id": 1 })
+        .skip(Some(offset))
+        .limit(Some(limit))
+        .build();
+
+    let mut cursor = collection.find(doc! { "deleted_at": { "$ne": Bson::Null } }, options).await?;
+    let mut results = Vec::new();
+    while let Some(doc) = cursor.try_next().await? {
+        results.push(doc);
+    }
+    Ok(results)
+}
+
+/// Permanently removes questions that have been in the trash since before `cutoff`.
+pub async fn purge_deleted_before(db: &Database, cutoff: DateTime) -> mongodb::error::Result<u64> {
+    let collection: Collection<Question> = db.collection("questions");
+    let result = collection.delete_many(doc! { "deleted_at": { "$lt": cutoff } }, None).await?;
+    Ok(result.deleted_count)
 }
 
 pub async fn list_questions(
     db: &Database,
     stage: Option<i32>,
+    difficulty: DifficultyRange,
+    visibility: Visibility,
     limit: i64,
     offset: u64,
 ) -> mongodb::error::Result<Vec<QuestionDto>> {
     let collection: Collection<Question> = db.collection("questions");
 
-    let mut filter = doc! {};
+    let mut filter = live(doc! {});
+    visibility.apply(&mut filter);
     if let Some(stage) = stage {
         filter.insert("stage", stage);
     }
+    difficulty.apply(&mut filter);
 
     let options = FindOptions::builder().skip(Some(offset)).limit(Some(limit)).build();
 
@@ -77,3 +243,260 @@ pub async fn list_questions(
     }
     Ok(results)
 }
+
+/// Every published question in a stable order (stage, then id), for snapshot exports.
+pub async fn list_all_questions(db: &Database) -> mongodb::error::Result<Vec<QuestionDto>> {
+    let collection: Collection<Question> = db.collection("questions");
+    let options = FindOptions::builder().sort(doc! { "stage": 1, "_id": 1 }).build();
+
+    let mut cursor = collection.find(published(doc! {}), options).await?;
+    let mut results = Vec::new();
+    while let Some(doc) = cursor.try_next().await? {
+        results.push(QuestionDto::from(doc));
+    }
+    Ok(results)
+}
+
+/// Ids of every published question, sorted, for deterministic selection such as daily challenges.
+pub async fn list_published_ids(db: &Database) -> mongodb::error::Result<Vec<String>> {
+    let collection: Collection<Document> = db.collection("questions");
+    let options = FindOptions::builder().projection(doc! { "_id": 1 }).sort(doc! { "_id": 1 }).build();
+
+    let mut cursor = collection.find(published(doc! {}), options).await?;
+    let mut ids = Vec::new();
+    while let Some(doc) = cursor.try_next().await? {
+        match doc.get("_id") {
+            Some(Bson::ObjectId(oid)) => ids.push(oid.to_hex()),
+            Some(Bson::String(id)) => ids.push(id.clone()),
+            _ => {}
+        }
+    }
+    Ok(ids)
+}
+
+pub async fn list_questions_updated_since(
+    db: &Database,
+    since: DateTime,
+) -> mongodb::error::Result<Vec<QuestionDto>> {
+    let collection: Collection<Question> = db.collection("questions");
+    let options = FindOptions::builder().sort(doc! { "updated_at": 1, "_id": 1 }).build();
+
+    let mut cursor =
+        collection.find(published(doc! { "updated_at": { "$gte": since } }), options).await?;
+    let mut results = Vec::new();
+    while let Some(doc) = cursor.try_next().await? {
+        results.push(QuestionDto::from(doc));
+    }
+    Ok(results)
+}
+
+/// Bounds on calibrated difficulty. Either bound excludes questions not yet calibrated.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DifficultyRange {
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+}
+
+impl DifficultyRange {
+    fn apply(self, filter: &mut Document) {
+        let mut range = doc! {};
+        if let Some(min) = self.min {
+            range.insert("$gte", min);
+        }
+        if let Some(max) = self.max {
+            range.insert("$lte", max);
+        }
+        if !range.is_empty() {
+            filter.insert("difficulty", range);
+        }
+    }
+}
+
+/// Stores the calibration job's difficulty estimate, counting answers up to `through`. Skips
+/// questions whose estimate already covers `through`, so a batch replayed after a crash is not
+/// counted twice. Bumps `updated_at` like any other write so ETags and delta sync see the change.
+pub async fn set_difficulty(
+    db: &Database,
+    id: &str,
+    difficulty: f64,
+    answers: i64,
+    through: ObjectId,
+) -> mongodb::error::Result<()> {
+    let collection: Collection<Question> = db.collection("questions");
+    let mut filter = id_filter(id);
+    filter.insert(
+        "$or",
+        vec![
+            doc! { "difficulty_through": { "$exists": false } },
+            doc! { "difficulty_through": { "$lt": through } },
+        ],
+    );
+    // A pipeline update so `updated_at` can move past its stored value even if the clock lags.
+    let update = vec![doc! { "$set": {
+        "difficulty": difficulty,
+        "difficulty_answers": answers,
+        "difficulty_through": through,
+        "updated_at": { "$max": ["$$NOW", { "$add": ["$updated_at", 1_i64] }] },
+    } }];
+    collection.update_one(filter, update, None).await?;
+    Ok(())
+}
+
+/// Published questions whose difficulty rests on at least `min_answers` answers.
+pub async fn list_calibrated(
+    db: &Database,
+    min_answers: i64,
+) -> mongodb::error::Result<Vec<QuestionDto>> {
+    let collection: Collection<Question> = db.collection("questions");
+    let filter = published(doc! { "difficulty_answers": { "$gte": min_answers } });
+    let mut cursor = collection.find(filter, None).await?;
+    let mut results = Vec::new();
+    while let Some(doc) = cursor.try_next().await? {
+        results.push(QuestionDto::from(doc));
+    }
+    Ok(results)
+}
+
+/// Up to `take` calibrated published questions on each side of `target` difficulty, nearest
+/// first, skipping `exclude`.
+pub async fn nearest_calibrated(
+    db: &Database,
+    target: f64,
+    min_answers: i64,
+    exclude: &[String],
+    take: i64,
+) -> mongodb::error::Result<Vec<QuestionDto>> {
+    let collection: Collection<Question> = db.collection("questions");
+    let ids: Vec<Bson> = exclude.iter().map(|id| id_bson(id)).collect();
+
+    let mut results = Vec::new();
+    for (range, direction) in [("$gte", 1), ("$lt", -1)] {
+        let filter = published(doc! {
+            "_id": { "$nin": &ids },
+            "difficulty_answers": { "$gte": min_answers },
+            "difficulty": { range: target },
+        });
+        let options =
+            FindOptions::builder().sort(doc! { "difficulty": direction }).limit(Some(take)).build();
+        let mut cursor = collection.find(filter, options).await?;
+        while let Some(doc) = cursor.try_next().await? {
+            results.push(QuestionDto::from(doc));
+        }
+    }
+    results.sort_by(|a, b| {
+        let distance = |q: &QuestionDto| (q.difficulty.unwrap_or(target) - target).abs();
+        distance(a).total_cmp(&distance(b))
+    });
+    Ok(results)
+}
+
+/// Average calibrated difficulty per `level`, over published questions with at least
+/// `min_answers` answers.
+pub async fn level_difficulty(
+    db: &Database,
+    min_answers: i64,
+) -> mongodb::error::Result<Vec<(String, f64)>> {
+    let collection: Collection<Question> = db.collection("questions");
+    let pipeline = vec![
+        doc! { "$match": published(doc! {
+            "level": { "$type": "string" },
+            "difficulty_answers": { "$gte": min_answers },
+        }) },
+        doc! { "$group": { "_id": "$level", "difficulty": { "$avg": "$difficulty" } } },
+    ];
+    let mut cursor = collection.aggregate(pipeline, None).await?;
+    let mut levels = Vec::new();
+    while let Some(doc) = cursor.try_next().await? {
+        if let (Ok(level), Ok(difficulty)) = (doc.get_str("_id"), doc.get_f64("difficulty")) {
+            levels.push((level.to_string(), difficulty));
+        }
+    }
+    Ok(levels)
+}
+
+/// Narrows random sampling; empty slices leave that dimension open.
+#[derive(Default)]
+pub struct SampleFilter<'a> {
+    pub stage: Option<i32>,
+    pub difficulty: DifficultyRange,
+    pub eras: &'a [String],
+    pub levels: &'a [String],
+    pub tags: &'a [String],
+    pub exclude: &'a [String],
+}
+
+/// Up to `size` published questions matching `filter`, picked at random by MongoDB's `$sample`.
+pub async fn sample_questions(
+    db: &Database,
+    filter: &SampleFilter<'_>,
+    size: i64,
+) -> mongodb::error::Result<Vec<QuestionDto>> {
+    let collection: Collection<Question> = db.collection("questions");
+
+    let mut matcher = doc! {};
+    if let Some(stage) = filter.stage {
+        matcher.insert("stage", stage);
+    }
+    if !filter.eras.is_empty() {
+        matcher.insert("era", doc! { "$in": filter.eras });
+    }
+    if !filter.levels.is_empty() {
+        matcher.insert("level", doc! { "$in": filter.levels });
+    }
+    if !filter.tags.is_empty() {
+        matcher.insert("tags", doc! { "$in": filter.tags });
+    }
+    if !filter.exclude.is_empty() {
+        let ids: Vec<Bson> = filter.exclude.iter().map(|id| id_bson(id)).collect();
+        matcher.insert("_id", doc! { "$nin": ids });
+    }
+    filter.difficulty.apply(&mut matcher);
+
+    let pipeline = vec![doc! { "$match": published(matcher) }, doc! { "$sample": { "size": size } }];
+    let mut cursor = collection.aggregate(pipeline, None).await?;
+    let mut results = Vec::new();
+    while let Some(doc) = cursor.try_next().await? {
+        results.push(QuestionDto::from(bson::from_document::<Question>(doc)?));
+    }
+    Ok(results)
+}
+
+fn id_bson(id: &str) -> Bson {
+    match ObjectId::parse_str(id) {
+        Ok(oid) => Bson::ObjectId(oid),
+        Err(_) => Bson::String(id.to_string()),
+    }
+}
+
+fn id_filter(id: &str) -> Document {
+    match ObjectId::parse_str(id) {
+        Ok(oid) => doc! { "_id": oid },
+        Err(_) => doc! { "_id": id }, // allow string ids too
+    }
+}
+
+/// Restricts a filter to questions that are not in the trash.
+fn live(mut filter: Document) -> Document {
+    filter.insert("deleted_at", Bson::Null);
+    filter
+}
+
+fn published(filter: Document) -> Document {
+    let mut filter = live(filter);
+    Visibility::Published.apply(&mut filter);
+    filter
+}
+
+/// Matches the question only while it is still `previous`: same `updated_at`, trash state and
+/// pending edit.
+/// Documents imported without timestamps match until their first write.
+fn unmodified_filter(previous: &Document) -> Document {
+    let mut filter = doc! { "_id": previous.get("_id").cloned().unwrap_or(Bson::Null) };
+    match previous.get_datetime("updated_at") {
+        Ok(updated_at) => filter.insert("updated_at", *updated_at),
+        Err(_) => filter.insert("updated_at", doc! { "$exists": false }),
+    };
+    filter.insert("deleted_at", previous.get("deleted_at").cloned().unwrap_or(Bson::Null));
+    guard_pending(&mut filter, previous);
+    filter
+}
@@ -0,0 +1,384 @@
+use serde::{Deserialize, Serialize};
+
+use crate::resources::questions::model::{
+    CreateQuestion, LocalizedText, OptionDto, QuestionDto, localized,
+};
+
+/// Marker a fill-in-the-blank prompt must contain, in every locale, where the missing words go.
+pub const BLANK_MARKER: &str = "___";
+
+const MIN_ITEMS: usize = 3;
+const MAX_ITEMS: usize = 8;
+
+/// How a question is answered. Documents stored before question types existed have no `type`
+/// and deserialize as `single_choice`.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum QuestionType {
+    /// Four options, exactly one correct.
+    #[default]
+    SingleChoice,
+    /// Two options (true and false, localized by the author), exactly one correct.
+    TrueFalse,
+    /// Three to eight options; every correct one must be picked and nothing else.
+    MultipleCorrect,
+    /// Three to eight options, authored in their correct order; `correct` flags are ignored.
+    Ordering,
+    /// Three to eight `pairs`; players match each left item to its right item.
+    Matching,
+    /// A prompt containing `___` and one or more accepted `answers`.
+    FillBlank,
+}
+
+impl QuestionType {
+    /// Types answered by picking options, where per-option pick counts mean something.
+    pub fn is_choice(self) -> bool {
+        matches!(
+            self,
+            QuestionType::SingleChoice | QuestionType::TrueFalse | QuestionType::MultipleCorrect
+        )
+    }
+
+    fn uses_options(self) -> bool {
+        !matches!(self, QuestionType::Matching | QuestionType::FillBlank)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MatchPair {
+    pub left: LocalizedText,
+    pub right: LocalizedText,
+}
+
+/// A player's answer. Its shape must match the question type:
+/// `{"selected": [..]}` for the choice types, `{"order": [..]}` for ordering,
+/// `{"matches": [..]}` (right index per left item) for matching and `{"text": ".."}` for
+/// fill-in-the-blank. Indices refer to the authored order.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum Answer {
+    Selected(Vec<usize>),
+    Order(Vec<usize>),
+    Matches(Vec<usize>),
+    Text(String),
+}
+
+/// An [`Answer`] with the optional time the player took, as in
+/// `{"selected": [1], "time_ms": 5400}`.
+#[derive(Debug, Deserialize)]
+pub struct TimedAnswer {
+    #[serde(flatten)]
+    pub answer: Answer,
+    pub time_ms: Option<i64>,
+}
+
+/// The expected answer, in the same shape as [`Answer`].
+#[derive(Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Solution {
+    Selected(Vec<usize>),
+    Order(Vec<usize>),
+    Matches(Vec<usize>),
+    Text(Vec<String>),
+}
+
+#[derive(Debug, Serialize)]
+pub struct AnswerCheck {
+    pub correct: bool,
+    pub solution: Solution,
+}
+
+/// Checks the type-specific shape of a question. `text` validates each localized field, so the
+/// same rules serve full authoring (all locales) and community submissions (partial locales).
+pub fn validate_shape<F>(question: &CreateQuestion, text: F) -> Result<(), &'static str>
+where
+    F: Fn(&LocalizedText, &'static str) -> Result<(), &'static str>,
+{
+    let kind = question.question_type;
+    if !kind.uses_options() && !question.options.is_empty() {
+        return Err("options are not used by this question type");
+    }
+    if kind != QuestionType::Matching && !question.pairs.is_empty() {
+        return Err("pairs are only used by matching questions");
+    }
+    if kind != QuestionType::FillBlank && !question.answers.is_empty() {
+        return Err("answers are only used by fill_blank questions");
+    }
+
+    let correct_count = question.options.iter().filter(|opt| opt.correct).count();
+    match kind {
+        QuestionType::SingleChoice => {
+            if question.options.len() != 4 {
+                return Err("exactly four options are required");
+            }
+            if correct_count != 1 {
+                return Err("exactly one option must be marked correct");
+            }
+        }
+        QuestionType::TrueFalse => {
+            if question.options.len() != 2 {
+                return Err("true_false questions require exactly two options");
+            }
+            if correct_count != 1 {
+                return Err("exactly one option must be marked correct");
+            }
+        }
+        QuestionType::MultipleCorrect => {
+            if !(MIN_ITEMS..=MAX_ITEMS).contains(&question.options.len()) {
+                return Err("multiple_correct questions require three to eight options");
+            }
+            if correct_count == 0 {
+                return Err("at least one option must be marked correct");
+            }
+        }
+        QuestionType::Ordering => {
+            if !(MIN_ITEMS..=MAX_ITEMS).contains(&question.options.len()) {
+                return Err("ordering questions require three to eight options");
+            }
+        }
+        QuestionType::Matching => {
+            if !(MIN_ITEMS..=MAX_ITEMS).contains(&question.pairs.len()) {
+                return Err("matching questions require three to eight pairs");
+            }
+            for pair in &question.pairs {
+                text(&pair.left, "pair text")?;
+                text(&pair.right, "pair text")?;
+            }
+        }
+        QuestionType::FillBlank => {
+            if question.prompt.values().any(|prompt| !prompt.contains(BLANK_MARKER)) {
+                return Err("fill_blank prompts must contain ___ in every locale");
+            }
+            if question.answers.is_empty() {
+                return Err("fill_blank questions require at least one accepted answer");
+            }
+            for answer in &question.answers {
+                text(answer, "answer text")?;
+            }
+        }
+    }
+
+    for opt in &question.options {
+        text(&opt.text, "option text")?;
+        if let Some(expl) = &opt.explanation {
+            text(expl, "option explanation")?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Grades `answer` against a stored question. Errors when the answer's shape does not fit the
+/// question type or refers to items that do not exist.
+pub fn check_answer(
+    question: &QuestionDto,
+    answer: &Answer,
+    lang: &str,
+) -> Result<AnswerCheck, &'static str> {
+    let options = &question.options;
+    match (question.question_type, answer) {
+        (QuestionType::SingleChoice | QuestionType::TrueFalse, Answer::Selected(selected)) => {
+            let expected = correct_indices(options);
+            if selected.len() != 1 {
+                return Err("select exactly one option");
+            }
+            in_range(selected, options.len())?;
+            Ok(AnswerCheck {
+                correct: expected == *selected,
+                solution: Solution::Selected(expected),
+            })
+        }
+        (QuestionType::MultipleCorrect, Answer::Selected(selected)) => {
+            in_range(selected, options.len())?;
+            let mut picked = selected.clone();
+            picked.sort_unstable();
+            picked.dedup();
+            let expected = correct_indices(options);
+            Ok(AnswerCheck {
+                correct: picked == expected,
+                solution: Solution::Selected(expected),
+            })
+        }
+        (QuestionType::Ordering, Answer::Order(order)) => {
+            let expected: Vec<usize> = (0..options.len()).collect();
+            is_permutation(order, options.len())?;
+            Ok(AnswerCheck {
+                correct: *order == expected,
+                solution: Solution::Order(expected),
+            })
+        }
+        (QuestionType::Matching, Answer::Matches(matches)) => {
+            let expected: Vec<usize> = (0..question.pairs.len()).collect();
+            is_permutation(matches, question.pairs.len())?;
+            Ok(AnswerCheck {
+                correct: *matches == expected,
+                solution: Solution::Matches(expected),
+            })
+        }
+        (QuestionType::FillBlank, Answer::Text(text)) => {
+            let accepted: Vec<String> = question.answers.iter().map(|a| localized(a, lang)).collect();
+            let given = normalize_text(text);
+            Ok(AnswerCheck {
+                correct: !given.is_empty() && accepted.iter().any(|a| normalize_text(a) == given),
+                solution: Solution::Text(accepted),
+            })
+        }
+        _ => Err("answer does not match the question type"),
+    }
+}
+
+/// The expected answer to a question, as [`check_answer`] reports it, without grading anything.
+pub fn solution(question: &QuestionDto, lang: &str) -> Solution {
+    match question.question_type {
+        QuestionType::SingleChoice | QuestionType::TrueFalse | QuestionType::MultipleCorrect => {
+            Solution::Selected(correct_indices(&question.options))
+        }
+        QuestionType::Ordering => Solution::Order((0..question.options.len()).collect()),
+        QuestionType::Matching => Solution::Matches((0..question.pairs.len()).collect()),
+        QuestionType::FillBlank => {
+            Solution::Text(question.answers.iter().map(|a| localized(a, lang)).collect())
+        }
+    }
+}
+
+fn correct_indices(options: &[OptionDto]) -> Vec<usize> {
+    options.iter().enumerate().filter(|(_, opt)| opt.correct).map(|(idx, _)| idx).collect()
+}
+
+fn in_range(indices: &[usize], len: usize) -> Result<(), &'static str> {
+    if indices.iter().any(|&idx| idx >= len) {
+        return Err("answer refers to an option that does not exist");
+    }
+    Ok(())
+}
+
+fn is_permutation(indices: &[usize], len: usize) -> Result<(), &'static str> {
+    let mut sorted = indices.to_vec();
+    sorted.sort_unstable();
+    if sorted != (0..len).collect::<Vec<_>>() {
+        return Err("answer must list every item exactly once");
+    }
+    Ok(())
+}
+
+/// Case-folds, drops punctuation and collapses whitespace, so "Jesus wept." matches "jesus  wept".
+pub fn normalize_text(text: &str) -> String {
+    let cleaned: String = text
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() {
+                c
+            } else {
+                ' '
+            }
+        })
+        .flat_map(char::to_lowercase)
+        .collect();
+    cleaned.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Answer, MatchPair, QuestionType, Solution, TimedAnswer, check_answer, normalize_text};
+    use crate::resources::{
+        questions::model::{LocalizedText, OptionDto, QuestionDto},
+        workflow::model::ContentStatus,
+    };
+
+    fn text(value: &str) -> LocalizedText {
+        let mut lt = LocalizedText::new();
+        lt.insert("en".into(), value.into());
+        lt
+    }
+
+    fn question(question_type: QuestionType, correct: &[bool]) -> QuestionDto {
+        QuestionDto {
+            id: "q".into(),
+            question_type,
+            stage: 1,
+            stage_label: None,
+            prompt: text("prompt"),
+            options: correct
+                .iter()
+                .enumerate()
+                .map(|(idx, &correct)| OptionDto {
+                    text: text(&idx.to_string()),
+                    correct,
+                    explanation: None,
+                })
+                .collect(),
+            pairs: Vec::new(),
+            answers: Vec::new(),
+            era: None,
+            level: None,
+            time_limit_secs: None,
+            difficulty: None,
+            difficulty_answers: None,
+            tags: Vec::new(),
+            image_url: None,
+            status: ContentStatus::Published,
+            pending_status: None,
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn multiple_correct_needs_the_exact_set() {
+        let q = question(QuestionType::MultipleCorrect, &[true, false, true, false]);
+        let check =
+            |selected: Vec<usize>| check_answer(&q, &Answer::Selected(selected), "en").unwrap().correct;
+        assert!(check(vec![2, 0]));
+        assert!(!check(vec![0]));
+        assert!(!check(vec![0, 1, 2]));
+    }
+
+    #[test]
+    fn ordering_and_matching_expect_authored_order() {
+        let q = question(QuestionType::Ordering, &[false, false, false]);
+        assert!(check_answer(&q, &Answer::Order(vec![0, 1, 2]), "en").unwrap().correct);
+        assert!(!check_answer(&q, &Answer::Order(vec![1, 0, 2]), "en").unwrap().correct);
+        assert!(check_answer(&q, &Answer::Order(vec![0, 0, 2]), "en").is_err());
+
+        let mut q = question(QuestionType::Matching, &[]);
+        q.pairs = (0..3)
+            .map(|idx| MatchPair {
+                left: text("l"),
+                right: text(&idx.to_string()),
+            })
+            .collect();
+        let result = check_answer(&q, &Answer::Matches(vec![0, 2, 1]), "en").unwrap();
+        assert!(!result.correct);
+        assert_eq!(result.solution, Solution::Matches(vec![0, 1, 2]));
+    }
+
+    #[test]
+    fn fill_blank_ignores_case_and_punctuation() {
+        let mut q = question(QuestionType::FillBlank, &[]);
+        q.answers = vec![text("Jesus wept")];
+        let check = |given: &str| check_answer(&q, &Answer::Text(given.into()), "sv").unwrap().correct;
+        assert!(check("  jesus WEPT. "));
+        assert!(!check("Jesus slept"));
+        assert!(!check(""));
+        assert_eq!(normalize_text("In the  beginning,"), "in the beginning");
+    }
+
+    #[test]
+    fn rejects_answers_of_the_wrong_shape() {
+        let q = question(QuestionType::TrueFalse, &[true, false]);
+        assert!(check_answer(&q, &Answer::Order(vec![0, 1]), "en").is_err());
+        assert!(check_answer(&q, &Answer::Selected(vec![0, 1]), "en").is_err());
+        assert!(check_answer(&q, &Answer::Selected(vec![5]), "en").is_err());
+    }
+
+    #[test]
+    fn timed_answers_keep_the_plain_shape() {
+        let timed: TimedAnswer = serde_json::from_str(r#"{"selected": [2], "time_ms": 5400}"#).unwrap();
+        assert!(matches!(timed.answer, Answer::Selected(ref picks) if picks == &[2]));
+        assert_eq!(timed.time_ms, Some(5400));
+
+        let plain: TimedAnswer = serde_json::from_str(r#"{"text": "Jesus wept"}"#).unwrap();
+        assert!(matches!(plain.answer, Answer::Text(_)));
+        assert_eq!(plain.time_ms, None);
+    }
+}
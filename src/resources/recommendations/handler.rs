@@ -0,0 +1,115 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use axum::routing::{MethodRouter, get as axum_get};
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+use mongodb::bson::DateTime;
+use serde_json::json;
+use tracing::error;
+
+use crate::{
+    auth::Caller,
+    lang::resolve_lang,
+    resources::{
+        achievements::queries as achievement_queries,
+        eras::queries as era_queries,
+        questions::{model::QuestionDto, queries as question_queries},
+        recommendations::model::{
+            DEFAULT_LIMIT, DUE_QUESTIONS, EraTotals, MAX_LIMIT, RecommendationsDto,
+            RecommendationsQuery, recommend,
+        },
+        repetition::queries as repetition_queries,
+        stats::queries as stats_queries,
+    },
+    routes::api::ApiState,
+};
+
+pub fn mine() -> MethodRouter<ApiState> {
+    axum_get(my_recommendations)
+}
+
+/// What the caller could study next, with a reason for each suggestion in `lang`.
+pub async fn my_recommendations(
+    State(state): State<ApiState>,
+    Query(params): Query<RecommendationsQuery>,
+    caller: Caller,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let user_id = match caller.require_user() {
+        Ok(user_id) => user_id,
+        Err(forbidden) => return forbidden.into_response(),
+    };
+    let lang = resolve_lang(params.lang.as_deref(), headers.get("accept-language"));
+    let limit = params.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+    let due = match repetition_queries::list_due(&state.db, user_id, DateTime::now(), DUE_QUESTIONS)
+        .await
+    {
+        Ok(cards) => cards.into_iter().map(|card| card.question_id).collect(),
+        Err(err) => {
+            error!(error = ?err, "failed to load due reviews");
+            return error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to load recommendations");
+        }
+    };
+    let eras = match era_queries::list_eras_full(&state.db, &lang).await {
+        Ok(eras) => eras,
+        Err(err) => {
+            error!(error = ?err, "failed to load eras");
+            return error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to load recommendations");
+        }
+    };
+    let totals = match era_totals(&state, user_id).await {
+        Ok(totals) => totals,
+        Err(err) => {
+            error!(error = ?err, "failed to load answer totals");
+            return error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to load recommendations");
+        }
+    };
+    let visited: BTreeSet<String> = match achievement_queries::find_player(&state.db, user_id).await {
+        Ok(player) => player.map(|player| player.episodes.into_iter().collect()).unwrap_or_default(),
+        Err(err) => {
+            error!(error = ?err, "failed to load episodes read");
+            return error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to load recommendations");
+        }
+    };
+
+    let dto = RecommendationsDto {
+        recommendations: recommend(due, &eras, &totals, &visited, &lang, limit),
+        lang,
+    };
+    (StatusCode::OK, Json(dto)).into_response()
+}
+
+/// The caller's answers per era, from the answer log. Questions without an era, or since deleted,
+/// are left out.
+async fn era_totals(
+    state: &ApiState,
+    user_id: &str,
+) -> mongodb::error::Result<BTreeMap<String, EraTotals>> {
+    let per_question = stats_queries::player_question_totals(&state.db, user_id).await?;
+    let ids: Vec<String> = per_question.iter().map(|row| row.question_id.clone()).collect();
+    let eras: BTreeMap<String, String> = question_queries::find_questions_by_ids(&state.db, &ids)
+        .await?
+        .into_iter()
+        .map(QuestionDto::from)
+        .filter_map(|question| Some((question.id, question.era?)))
+        .collect();
+
+    let mut totals: BTreeMap<String, EraTotals> = BTreeMap::new();
+    for row in per_question {
+        if let Some(era) = eras.get(&row.question_id) {
+            let entry = totals.entry(era.clone()).or_default();
+            entry.answered += row.answered;
+            entry.correct += row.correct;
+        }
+    }
+    Ok(totals)
+}
+
+fn error_response(status: StatusCode, msg: &str) -> Response {
+    (status, Json(json!({ "error": msg }))).into_response()
+}
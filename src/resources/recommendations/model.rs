@@ -0,0 +1,297 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::resources::{
+    achievements::model::episode_key,
+    eras::model::{EpisodeDto, EraDto},
+};
+
+pub const DEFAULT_LIMIT: usize = 10;
+pub const MAX_LIMIT: usize = 50;
+/// Answers in an era before its accuracy counts.
+pub const MIN_ERA_ANSWERS: i64 = 3;
+/// Eras answered correctly less often than this are suggested for study.
+pub const WEAK_ACCURACY: f64 = 0.7;
+/// Due questions listed in the review suggestion.
+pub const DUE_QUESTIONS: i64 = 20;
+
+#[derive(Deserialize)]
+pub struct RecommendationsQuery {
+    pub lang: Option<String>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecommendationKind {
+    Review,
+    Era,
+    Episode,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReasonCode {
+    /// Spaced-repetition cards are due.
+    DueReviews,
+    /// The player's accuracy in the era is below [`WEAK_ACCURACY`].
+    WeakEra,
+    /// An unread episode of a weak era.
+    WeakEraEpisode,
+    /// The next unread episode of an era.
+    UnvisitedEpisode,
+}
+
+/// A player's answers in one era.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct EraTotals {
+    pub answered: i64,
+    pub correct: i64,
+}
+
+impl EraTotals {
+    pub fn accuracy(self) -> f64 {
+        if self.answered == 0 {
+            0.0
+        } else {
+            self.correct as f64 / self.answered as f64
+        }
+    }
+}
+
+/// What the player could study next.
+#[derive(Debug, Serialize)]
+pub struct RecommendationDto {
+    pub kind: RecommendationKind,
+    pub reason_code: ReasonCode,
+    /// Short explanation in the requested language.
+    pub reason: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub era_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub era_label: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub episode_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub episode_label: Option<String>,
+    /// Due questions, most overdue first.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub question_ids: Vec<String>,
+    /// Share of the era's answers that were correct.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub accuracy: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub answered: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RecommendationsDto {
+    pub lang: String,
+    pub recommendations: Vec<RecommendationDto>,
+}
+
+/// Suggestions, most pressing first: due reviews, then each weak era (weakest first) followed by
+/// its next unread episode, then the next unread episode of every other era in display order.
+/// `eras` are the published eras in display order; `visited` holds `episode_key`s.
+pub fn recommend(
+    due: Vec<String>,
+    eras: &[EraDto],
+    totals: &BTreeMap<String, EraTotals>,
+    visited: &BTreeSet<String>,
+    lang: &str,
+    limit: usize,
+) -> Vec<RecommendationDto> {
+    let mut out = Vec::new();
+    if !due.is_empty() {
+        let count = due.len().to_string();
+        out.push(RecommendationDto {
+            kind: RecommendationKind::Review,
+            reason_code: ReasonCode::DueReviews,
+            reason: reason_text(ReasonCode::DueReviews, lang, &[("count", &count)]),
+            era_id: None,
+            era_label: None,
+            episode_id: None,
+            episode_label: None,
+            question_ids: due,
+            accuracy: None,
+            answered: None,
+        });
+    }
+
+    let mut weak: Vec<(&EraDto, EraTotals)> = eras
+        .iter()
+        .filter_map(|era| totals.get(&era.id).map(|t| (era, *t)))
+        .filter(|(_, t)| t.answered >= MIN_ERA_ANSWERS && t.accuracy() < WEAK_ACCURACY)
+        .collect();
+    weak.sort_by(|(_, a), (_, b)| {
+        a.accuracy().total_cmp(&b.accuracy()).then_with(|| b.answered.cmp(&a.answered))
+    });
+
+    let mut suggested = BTreeSet::new();
+    for (era, era_totals) in &weak {
+        let percent = ((era_totals.accuracy() * 100.0).round() as i64).to_string();
+        let params = [("era", era.label.as_str()), ("percent", percent.as_str())];
+        out.push(RecommendationDto {
+            kind: RecommendationKind::Era,
+            reason_code: ReasonCode::WeakEra,
+            reason: reason_text(ReasonCode::WeakEra, lang, &params),
+            era_id: Some(era.id.clone()),
+            era_label: Some(era.label.clone()),
+            episode_id: None,
+            episode_label: None,
+            question_ids: Vec::new(),
+            accuracy: Some(era_totals.accuracy()),
+            answered: Some(era_totals.answered),
+        });
+        if let Some(episode) = next_unread(era, visited) {
+            out.push(episode_suggestion(era, episode, ReasonCode::WeakEraEpisode, lang));
+        }
+        suggested.insert(era.id.as_str());
+    }
+    for era in eras.iter().filter(|era| !suggested.contains(era.id.as_str())) {
+        if let Some(episode) = next_unread(era, visited) {
+            out.push(episode_suggestion(era, episode, ReasonCode::UnvisitedEpisode, lang));
+        }
+    }
+    out.truncate(limit);
+    out
+}
+
+fn next_unread<'a>(era: &'a EraDto, visited: &BTreeSet<String>) -> Option<&'a EpisodeDto> {
+    era.episodes.iter().find(|episode| !visited.contains(&episode_key(&era.id, &episode.id)))
+}
+
+fn episode_suggestion(
+    era: &EraDto,
+    episode: &EpisodeDto,
+    reason_code: ReasonCode,
+    lang: &str,
+) -> RecommendationDto {
+    RecommendationDto {
+        kind: RecommendationKind::Episode,
+        reason_code,
+        reason: reason_text(reason_code, lang, &[("era", &era.label)]),
+        era_id: Some(era.id.clone()),
+        era_label: Some(era.label.clone()),
+        episode_id: Some(episode.id.clone()),
+        episode_label: Some(episode.label.clone()),
+        question_ids: Vec::new(),
+        accuracy: None,
+        answered: None,
+    }
+}
+
+/// The reason in `lang` (English when missing), with `{name}` placeholders filled from `params`.
+pub fn reason_text(code: ReasonCode, lang: &str, params: &[(&str, &str)]) -> String {
+    let templates: [(&str, &str); 4] = match code {
+        ReasonCode::DueReviews => [
+            ("en", "Time to review: {count} due."),
+            ("es", "Hora de repasar: {count} pendientes."),
+            ("pt", "Hora de revisar: {count} pendentes."),
+            ("sv", "Dags att repetera: {count} väntar."),
+        ],
+        ReasonCode::WeakEra => [
+            ("en", "You answered {percent}% correctly in {era}."),
+            ("es", "Acertaste el {percent}% en {era}."),
+            ("pt", "Você acertou {percent}% em {era}."),
+            ("sv", "Du svarade rätt på {percent}% i {era}."),
+        ],
+        ReasonCode::WeakEraEpisode => [
+            ("en", "Read this to strengthen {era}."),
+            ("es", "Léelo para reforzar {era}."),
+            ("pt", "Leia para reforçar {era}."),
+            ("sv", "Läs den för att stärka {era}."),
+        ],
+        ReasonCode::UnvisitedEpisode => [
+            ("en", "Next in {era}; you haven't read it yet."),
+            ("es", "Lo siguiente en {era}; aún no lo has leído."),
+            ("pt", "O próximo em {era}; você ainda não o leu."),
+            ("sv", "Nästa i {era}; du har inte läst den än."),
+        ],
+    };
+    let template = templates.iter().find(|(code, _)| *code == lang).unwrap_or(&templates[0]).1;
+    params
+        .iter()
+        .fold(template.to_string(), |text, (name, value)| text.replace(&format!("{{{name}}}"), value))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, BTreeSet};
+
+    use super::{EraTotals, ReasonCode, recommend};
+    use crate::resources::{
+        achievements::model::episode_key,
+        eras::model::{EpisodeDto, EraDto},
+    };
+
+    fn era(id: &str, episodes: &[&str]) -> EraDto {
+        EraDto {
+            id: id.into(),
+            name: id.into(),
+            label: id.to_uppercase(),
+            image_path: None,
+            order: 0,
+            era_type: None,
+            books: Vec::new(),
+            episodes: episodes
+                .iter()
+                .map(|episode| EpisodeDto {
+                    id: episode.to_string(),
+                    name: episode.to_string(),
+                    label: episode.to_string(),
+                    order: 0,
+                    references: Vec::new(),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn weakest_eras_come_first_with_their_unread_episodes() {
+        let eras =
+            vec![era("creation", &["world", "eden"]), era("kings", &["saul"]), era("psalms", &[])];
+        let mut totals = BTreeMap::new();
+        totals.insert(
+            "creation".to_string(),
+            EraTotals {
+                answered: 10,
+                correct: 6,
+            },
+        );
+        totals.insert(
+            "kings".to_string(),
+            EraTotals {
+                answered: 4,
+                correct: 1,
+            },
+        );
+        totals.insert(
+            "psalms".to_string(),
+            EraTotals {
+                answered: 2,
+                correct: 0,
+            },
+        );
+        let visited = BTreeSet::from([episode_key("creation", "world")]);
+
+        let out = recommend(vec!["q1".into()], &eras, &totals, &visited, "es", 10);
+        let codes: Vec<(ReasonCode, Option<&str>, Option<&str>)> =
+            out.iter().map(|r| (r.reason_code, r.era_id.as_deref(), r.episode_id.as_deref())).collect();
+        assert_eq!(
+            codes,
+            vec![
+                (ReasonCode::DueReviews, None, None),
+                (ReasonCode::WeakEra, Some("kings"), None),
+                (ReasonCode::WeakEraEpisode, Some("kings"), Some("saul")),
+                (ReasonCode::WeakEra, Some("creation"), None),
+                (ReasonCode::WeakEraEpisode, Some("creation"), Some("eden")),
+            ]
+        );
+        assert_eq!(out[1].reason, "Acertaste el 25% en KINGS.");
+        assert_eq!(out[0].reason, "Hora de repasar: 1 pendientes.");
+
+        assert_eq!(recommend(Vec::new(), &eras, &BTreeMap::new(), &visited, "xx", 1).len(), 1);
+    }
+}
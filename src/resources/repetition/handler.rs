@@ -0,0 +1,202 @@
+use axum::routing::{MethodRouter, get as axum_get, post as axum_post};
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+use chrono::{Duration, Utc};
+use mongodb::{Database, bson::DateTime};
+use serde_json::json;
+use tracing::error;
+
+use crate::{
+    auth::Caller,
+    lang::resolve_lang,
+    resources::{
+        questions::{model::QuestionDto, queries as question_queries},
+        repetition::{
+            model::{
+                DueItemDto, DueQuery, RecordReview, ReviewCard, ReviewLog, ReviewStatsDto, ScheduleDto,
+                StatsQuery, quality_for, retention,
+            },
+            queries,
+        },
+        workflow::model::ContentStatus,
+    },
+    routes::api::ApiState,
+};
+
+pub fn record() -> MethodRouter<ApiState> {
+    axum_post(record_review)
+}
+
+pub fn due() -> MethodRouter<ApiState> {
+    axum_get(due_reviews)
+}
+
+pub fn stats() -> MethodRouter<ApiState> {
+    axum_get(review_stats)
+}
+
+/// Records a study outcome for the caller and returns the question's next due date.
+pub async fn record_review(
+    State(state): State<ApiState>,
+    Path(question_id): Path<String>,
+    caller: Caller,
+    Json(payload): Json<RecordReview>,
+) -> impl IntoResponse {
+    let user_id = match caller.require_user() {
+        Ok(user_id) => user_id,
+        Err(forbidden) => return forbidden.into_response(),
+    };
+    let quality = match quality_for(payload.correct, payload.quality) {
+        Ok(quality) => quality,
+        Err(msg) => return error_response(StatusCode::BAD_REQUEST, msg),
+    };
+
+    match question_queries::find_question_by_id(&state.db, &question_id).await {
+        Ok(Some(q)) if q.status == ContentStatus::Published => {}
+        Ok(_) => return error_response(StatusCode::NOT_FOUND, "question not found"),
+        Err(err) => {
+            error!(error = ?err, "failed to fetch question");
+            return error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to record review");
+        }
+    }
+
+    match apply_review(&state.db, user_id, &question_id, quality, payload.correct).await {
+        Ok(card) => (StatusCode::OK, Json(ScheduleDto::from(&card))).into_response(),
+        Err(err) => {
+            error!(error = ?err, "failed to record review");
+            error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to record review")
+        }
+    }
+}
+
+/// Questions due for the caller, most overdue first, in the flattened single-language view.
+/// Questions no longer published are skipped.
+pub async fn due_reviews(
+    State(state): State<ApiState>,
+    Query(params): Query<DueQuery>,
+    caller: Caller,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let user_id = match caller.require_user() {
+        Ok(user_id) => user_id,
+        Err(forbidden) => return forbidden.into_response(),
+    };
+    let lang = resolve_lang(params.lang.as_deref(), headers.get("accept-language"));
+    let limit = params.limit.unwrap_or(20).clamp(1, 100);
+
+    let cards = match queries::list_due(&state.db, user_id, DateTime::now(), limit).await {
+        Ok(cards) => cards,
+        Err(err) => {
+            error!(error = ?err, "failed to list due reviews");
+            return error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to list due reviews");
+        }
+    };
+    let ids: Vec<String> = cards.iter().map(|c| c.question_id.clone()).collect();
+    let questions: Vec<QuestionDto> =
+        match question_queries::find_questions_by_ids(&state.db, &ids).await {
+            Ok(questions) => questions.into_iter().map(QuestionDto::from).collect(),
+            Err(err) => {
+                error!(error = ?err, "failed to load due questions");
+                return error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to list due reviews");
+            }
+        };
+
+    let mut questions = questions;
+    let items: Vec<DueItemDto> = cards
+        .iter()
+        .filter_map(|card| {
+            let idx = questions
+                .iter()
+                .position(|q| q.id == card.question_id && q.status == ContentStatus::Published)?;
+            Some(DueItemDto {
+                question: questions.swap_remove(idx).localize(&lang),
+                review: ScheduleDto::from(card),
+            })
+        })
+        .collect();
+
+    (StatusCode::OK, Json(json!({ "items": items }))).into_response()
+}
+
+/// Card counts and the share of correct reviews per day over the last `days` (default 30).
+pub async fn review_stats(
+    State(state): State<ApiState>,
+    Query(params): Query<StatsQuery>,
+    caller: Caller,
+) -> impl IntoResponse {
+    let user_id = match caller.require_user() {
+        Ok(user_id) => user_id,
+        Err(forbidden) => return forbidden.into_response(),
+    };
+    let window = params.days.unwrap_or(30).clamp(1, 365);
+    let now = DateTime::now();
+    let since = DateTime::from_chrono(Utc::now() - Duration::days(window));
+    let timezone = state.config.challenge_timezone.name();
+
+    let counts = queries::count_cards(&state.db, user_id, now).await;
+    let days = queries::daily_retention(&state.db, user_id, since, timezone).await;
+    match (counts, days) {
+        (Ok((cards, due, learned)), Ok(days)) => {
+            let stats = ReviewStatsDto {
+                cards,
+                due,
+                learned,
+                retention: retention(&days),
+                days,
+            };
+            (StatusCode::OK, Json(stats)).into_response()
+        }
+        (Err(err), _) | (_, Err(err)) => {
+            error!(error = ?err, "failed to compute review stats");
+            error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to compute review stats")
+        }
+    }
+}
+
+/// Schedules questions answered elsewhere (quizzes, daily challenges) for review. Failures are
+/// logged; the caller's result is already stored.
+pub async fn record_outcomes(state: &ApiState, user_id: &str, outcomes: &[(String, bool)]) {
+    for (question_id, correct) in outcomes {
+        let quality = if *correct {
+            4
+        } else {
+            1
+        };
+        if let Err(err) = apply_review(&state.db, user_id, question_id, quality, *correct).await {
+            error!(error = ?err, user_id, question_id, "failed to schedule review");
+        }
+    }
+}
+
+async fn apply_review(
+    db: &Database,
+    user_id: &str,
+    question_id: &str,
+    quality: u8,
+    correct: bool,
+) -> mongodb::error::Result<ReviewCard> {
+    let now = DateTime::now();
+    let mut card = queries::find_card(db, user_id, question_id)
+        .await?
+        .unwrap_or_else(|| ReviewCard::new(user_id, question_id, now));
+    card.review(quality, now);
+    queries::save_card(db, &card).await?;
+
+    let entry = ReviewLog {
+        user_id: user_id.to_string(),
+        question_id: question_id.to_string(),
+        quality,
+        correct,
+        reviewed_at: now,
+    };
+    queries::insert_log(db, &entry).await?;
+    Ok(card)
+}
+
+fn error_response(status: StatusCode, msg: &str) -> Response {
+    (status, Json(json!({ "error": msg }))).into_response()
+}
@@ -0,0 +1,200 @@
+use chrono::{Duration, Utc};
+use mongodb::bson::DateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::resources::questions::model::LocalizedQuestionDto;
+
+pub const INITIAL_EASE: f64 = 2.5;
+const MIN_EASE: f64 = 1.3;
+/// Missed questions come back within the same session rather than the next day.
+const RELEARN_MINUTES: i64 = 10;
+/// Cards whose interval reached this many days count as learned.
+pub const MATURE_INTERVAL_DAYS: i64 = 21;
+
+/// One player's schedule for one question (collection `review_cards`, keyed by
+/// `<user_id>:<question_id>`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewCard {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub user_id: String,
+    pub question_id: String,
+    pub ease: f64,
+    pub interval_days: i64,
+    pub repetitions: i32,
+    pub lapses: i32,
+    pub due_at: DateTime,
+    pub last_reviewed_at: DateTime,
+}
+
+/// Every recorded outcome (collection `review_log`), kept for retention statistics.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReviewLog {
+    pub user_id: String,
+    pub question_id: String,
+    pub quality: u8,
+    pub correct: bool,
+    pub reviewed_at: DateTime,
+}
+
+/// `quality` is the SM-2 grade (0–5); when omitted, `correct` maps to 4 and a miss to 1.
+#[derive(Debug, Deserialize)]
+pub struct RecordReview {
+    pub correct: bool,
+    pub quality: Option<u8>,
+}
+
+#[derive(Deserialize)]
+pub struct DueQuery {
+    pub lang: Option<String>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Deserialize)]
+pub struct StatsQuery {
+    pub days: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ScheduleDto {
+    pub question_id: String,
+    pub due_at: String,
+    pub interval_days: i64,
+    pub ease: f64,
+    pub repetitions: i32,
+    pub lapses: i32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DueItemDto {
+    #[serde(flatten)]
+    pub question: LocalizedQuestionDto,
+    pub review: ScheduleDto,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RetentionDay {
+    pub date: String,
+    pub reviews: i64,
+    pub correct: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReviewStatsDto {
+    pub cards: u64,
+    pub due: u64,
+    pub learned: u64,
+    /// Share of correct reviews over the whole window.
+    pub retention: Option<f64>,
+    pub days: Vec<RetentionDay>,
+}
+
+impl From<&ReviewCard> for ScheduleDto {
+    fn from(card: &ReviewCard) -> Self {
+        Self {
+            question_id: card.question_id.clone(),
+            due_at: card.due_at.to_chrono().to_rfc3339(),
+            interval_days: card.interval_days,
+            ease: card.ease,
+            repetitions: card.repetitions,
+            lapses: card.lapses,
+        }
+    }
+}
+
+pub fn quality_for(correct: bool, quality: Option<u8>) -> Result<u8, &'static str> {
+    match quality {
+        Some(q) if q > 5 => Err("quality must be between 0 and 5"),
+        Some(q) if (q >= 3) != correct => Err("quality must be 3 or more exactly when correct"),
+        Some(q) => Ok(q),
+        None => Ok(if correct {
+            4
+        } else {
+            1
+        }),
+    }
+}
+
+impl ReviewCard {
+    pub fn new(user_id: &str, question_id: &str, now: DateTime) -> Self {
+        Self {
+            id: format!("{user_id}:{question_id}"),
+            user_id: user_id.to_string(),
+            question_id: question_id.to_string(),
+            ease: INITIAL_EASE,
+            interval_days: 0,
+            repetitions: 0,
+            lapses: 0,
+            due_at: now,
+            last_reviewed_at: now,
+        }
+    }
+
+    /// Applies an SM-2 review: passing grades grow the interval (1 day, 6 days, then × ease),
+    /// failing ones reset it and bring the question back in a few minutes. Ease drifts with the
+    /// grade but never drops below 1.3.
+    pub fn review(&mut self, quality: u8, now: DateTime) {
+        let q = f64::from(quality.min(5));
+        self.ease = (self.ease + 0.1 - (5.0 - q) * (0.08 + (5.0 - q) * 0.02)).max(MIN_EASE);
+
+        let now_utc = now.to_chrono().with_timezone(&Utc);
+        if quality >= 3 {
+            self.interval_days = match self.repetitions {
+                0 => 1,
+                1 => 6,
+                _ => ((self.interval_days as f64) * self.ease).round() as i64,
+            };
+            self.repetitions += 1;
+            self.due_at = DateTime::from_chrono(now_utc + Duration::days(self.interval_days));
+        } else {
+            self.interval_days = 0;
+            self.repetitions = 0;
+            self.lapses += 1;
+            self.due_at = DateTime::from_chrono(now_utc + Duration::minutes(RELEARN_MINUTES));
+        }
+        self.last_reviewed_at = now;
+    }
+}
+
+pub fn retention(days: &[RetentionDay]) -> Option<f64> {
+    let reviews: i64 = days.iter().map(|d| d.reviews).sum();
+    let correct: i64 = days.iter().map(|d| d.correct).sum();
+    (reviews > 0).then(|| correct as f64 / reviews as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Utc};
+    use mongodb::bson::DateTime;
+
+    use super::{ReviewCard, quality_for};
+
+    #[test]
+    fn intervals_grow_and_reset_on_a_miss() {
+        let now = DateTime::now();
+        let mut card = ReviewCard::new("ana", "q1", now);
+
+        let intervals: Vec<i64> = (0..4)
+            .map(|_| {
+                card.review(4, now);
+                card.interval_days
+            })
+            .collect();
+        assert_eq!(intervals, vec![1, 6, 15, 38]);
+
+        card.review(1, now);
+        assert_eq!((card.interval_days, card.repetitions, card.lapses), (0, 0, 1));
+        let due_in = card.due_at.to_chrono() - now.to_chrono().with_timezone(&Utc);
+        assert!(due_in <= Duration::minutes(10));
+        assert!(card.ease >= 1.3 && card.ease < 2.5);
+    }
+
+    #[test]
+    fn quality_must_agree_with_correctness() {
+        assert_eq!(quality_for(true, None), Ok(4));
+        assert_eq!(quality_for(false, None), Ok(1));
+        assert_eq!(quality_for(true, Some(5)), Ok(5));
+        assert!(quality_for(true, Some(2)).is_err());
+        assert!(quality_for(false, Some(6)).is_err());
+    }
+}
@@ -0,0 +1,107 @@
+use futures_util::TryStreamExt;
+use mongodb::{
+    Collection, Database, IndexModel,
+    bson::{self, DateTime, doc},
+    options::{FindOptions, ReplaceOptions},
+};
+
+use crate::resources::repetition::model::{MATURE_INTERVAL_DAYS, RetentionDay, ReviewCard, ReviewLog};
+
+fn cards_collection(db: &Database) -> Collection<ReviewCard> {
+    db.collection("review_cards")
+}
+
+fn log_collection(db: &Database) -> Collection<ReviewLog> {
+    db.collection("review_log")
+}
+
+/// Indexes backing the due queue and card counts, and the retention history.
+pub async fn ensure_indexes(db: &Database) -> mongodb::error::Result<()> {
+    let index = IndexModel::builder().keys(doc! { "user_id": 1, "due_at": 1 }).build();
+    cards_collection(db).create_index(index, None).await?;
+    let index = IndexModel::builder().keys(doc! { "user_id": 1, "reviewed_at": 1 }).build();
+    log_collection(db).create_index(index, None).await?;
+    Ok(())
+}
+
+pub async fn find_card(
+    db: &Database,
+    user_id: &str,
+    question_id: &str,
+) -> mongodb::error::Result<Option<ReviewCard>> {
+    cards_collection(db).find_one(doc! { "_id": format!("{user_id}:{question_id}") }, None).await
+}
+
+pub async fn save_card(db: &Database, card: &ReviewCard) -> mongodb::error::Result<()> {
+    let options = ReplaceOptions::builder().upsert(true).build();
+    cards_collection(db).replace_one(doc! { "_id": &card.id }, card, options).await?;
+    Ok(())
+}
+
+pub async fn insert_log(db: &Database, entry: &ReviewLog) -> mongodb::error::Result<()> {
+    log_collection(db).insert_one(entry, None).await?;
+    Ok(())
+}
+
+/// Cards due by `now`, most overdue first.
+pub async fn list_due(
+    db: &Database,
+    user_id: &str,
+    now: DateTime,
+    limit: i64,
+) -> mongodb::error::Result<Vec<ReviewCard>> {
+    let options = FindOptions::builder().sort(doc! { "due_at": 1 }).limit(Some(limit)).build();
+    let filter = doc! { "user_id": user_id, "due_at": { "$lte": now } };
+    let mut cursor = cards_collection(db).find(filter, options).await?;
+    let mut cards = Vec::new();
+    while let Some(card) = cursor.try_next().await? {
+        cards.push(card);
+    }
+    Ok(cards)
+}
+
+/// Total, due and learned (interval of at least three weeks) card counts.
+pub async fn count_cards(
+    db: &Database,
+    user_id: &str,
+    now: DateTime,
+) -> mongodb::error::Result<(u64, u64, u64)> {
+    let collection = cards_collection(db);
+    let total = collection.count_documents(doc! { "user_id": user_id }, None).await?;
+    let due =
+        collection.count_documents(doc! { "user_id": user_id, "due_at": { "$lte": now } }, None).await?;
+    let learned = collection
+        .count_documents(
+            doc! { "user_id": user_id, "interval_days": { "$gte": MATURE_INTERVAL_DAYS } },
+            None,
+        )
+        .await?;
+    Ok((total, due, learned))
+}
+
+/// Reviews and correct reviews per calendar day in `timezone` since `since`, oldest first.
+pub async fn daily_retention(
+    db: &Database,
+    user_id: &str,
+    since: DateTime,
+    timezone: &str,
+) -> mongodb::error::Result<Vec<RetentionDay>> {
+    let pipeline = vec![
+        doc! { "$match": { "user_id": user_id, "reviewed_at": { "$gte": since } } },
+        doc! {
+            "$group": {
+                "_id": { "$dateToString": { "format": "%Y-%m-%d", "date": "$reviewed_at", "timezone": timezone } },
+                "reviews": { "$sum": 1 },
+                "correct": { "$sum": { "$cond": ["$correct", 1, 0] } },
+            }
+        },
+        doc! { "$sort": { "_id": 1 } },
+        doc! { "$project": { "_id": 0, "date": "$_id", "reviews": 1, "correct": 1 } },
+    ];
+    let mut cursor = log_collection(db).aggregate(pipeline, None).await?;
+    let mut days = Vec::new();
+    while let Some(row) = cursor.try_next().await? {
+        days.push(bson::from_document(row)?);
+    }
+    Ok(days)
+}
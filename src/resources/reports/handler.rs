@@ -0,0 +1,245 @@
+use axum::routing::{MethodRouter, get as axum_get, post as axum_post};
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::error;
+
+use crate::{
+    auth::Caller,
+    lang::{normalize_lang, resolve_lang},
+    resources::{
+        questions::{
+            model::{QuestionDto, localized},
+            queries as question_queries,
+        },
+        reports::{
+            model::{CreateReport, ReportDto, ReportStatus, ResolveReports, group_counts},
+            queries::{self, NewReport},
+        },
+        workflow::model::ContentStatus,
+    },
+    routes::api::ApiState,
+};
+
+const MAX_REPORT_LEN: usize = 2000;
+
+pub fn question_reports() -> MethodRouter<ApiState> {
+    axum_get(list_question_reports).post(create_report)
+}
+
+pub fn resolve() -> MethodRouter<ApiState> {
+    axum_post(resolve_reports)
+}
+
+pub fn triage() -> MethodRouter<ApiState> {
+    axum_get(report_triage)
+}
+
+/// Lets players report a wrong answer, bad translation, unclear or offensive question. Once the
+/// open reports on a question reach `REPORT_FLAG_THRESHOLD` it is flagged for editors.
+pub async fn create_report(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+    caller: Caller,
+    headers: HeaderMap,
+    Json(payload): Json<CreateReport>,
+) -> impl IntoResponse {
+    // Reports count towards flagging, so each must come from a player who can only file one.
+    let reporter = match caller.require_user() {
+        Ok(user_id) => user_id,
+        Err(forbidden) => return forbidden.into_response(),
+    };
+    let lang = match payload.lang.as_deref() {
+        Some(lang) => match normalize_lang(lang) {
+            Some(lang) => lang.to_string(),
+            None => return error_response(StatusCode::BAD_REQUEST, "unsupported lang"),
+        },
+        None => resolve_lang(None, headers.get("accept-language")),
+    };
+    let text = payload.text.as_deref().map(str::trim).filter(|t| !t.is_empty());
+    if text.is_some_and(|t| t.chars().count() > MAX_REPORT_LEN) {
+        return error_response(StatusCode::BAD_REQUEST, "report text is too long");
+    }
+
+    match question_queries::find_question_by_id(&state.db, &id).await {
+        Ok(Some(q)) if q.status == ContentStatus::Published => {}
+        Ok(_) => return error_response(StatusCode::NOT_FOUND, "question not found"),
+        Err(err) => {
+            error!(error = ?err, "failed to fetch question");
+            return error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to store report");
+        }
+    }
+
+    match queries::has_open_report(&state.db, &id, reporter).await {
+        Ok(true) => return error_response(StatusCode::CONFLICT, "you already reported this question"),
+        Ok(false) => {}
+        Err(err) => {
+            error!(error = ?err, "failed to check existing reports");
+            return error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to store report");
+        }
+    }
+
+    let report = NewReport {
+        question_id: &id,
+        category: payload.category,
+        lang: &lang,
+        text,
+        reporter,
+    };
+    let report = match queries::insert_report(&state.db, report).await {
+        Ok(report) => report,
+        Err(err) => {
+            error!(error = ?err, "failed to store report");
+            return error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to store report");
+        }
+    };
+
+    if let Err(err) = update_flag(&state, &id).await {
+        error!(error = ?err, id, "failed to update question flag");
+    }
+    (StatusCode::CREATED, Json(ReportDto::from(report))).into_response()
+}
+
+#[derive(Deserialize)]
+pub struct ReportsQuery {
+    pub status: Option<ReportStatus>,
+}
+
+#[derive(Serialize)]
+pub struct ReportsList<T> {
+    pub items: Vec<T>,
+}
+
+pub async fn list_question_reports(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+    Query(params): Query<ReportsQuery>,
+    caller: Caller,
+) -> impl IntoResponse {
+    if let Err(forbidden) = caller.require_editor() {
+        return forbidden.into_response();
+    }
+
+    match queries::list_reports(&state.db, &id, params.status).await {
+        Ok(reports) => (
+            StatusCode::OK,
+            Json(ReportsList {
+                items: reports.into_iter().map(ReportDto::from).collect(),
+            }),
+        )
+            .into_response(),
+        Err(err) => {
+            error!(error = ?err, "failed to list reports");
+            error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to list reports")
+        }
+    }
+}
+
+/// Open reports grouped per question with counts by category and locale, most reported first.
+pub async fn report_triage(State(state): State<ApiState>, caller: Caller) -> impl IntoResponse {
+    if let Err(forbidden) = caller.require_editor() {
+        return forbidden.into_response();
+    }
+
+    let groups = async {
+        let mut groups = group_counts(queries::open_report_counts(&state.db).await?);
+        let ids: Vec<String> = groups.iter().map(|g| g.question_id.clone()).collect();
+        let questions = question_queries::find_questions_by_ids(&state.db, &ids).await?;
+        for question in questions {
+            let flagged = question.flagged_at.is_some();
+            let question = QuestionDto::from(question);
+            if let Some(group) = groups.iter_mut().find(|g| g.question_id == question.id) {
+                group.prompt = Some(localized(&question.prompt, "en"));
+                group.flagged = flagged;
+            }
+        }
+        Ok::<_, mongodb::error::Error>(groups)
+    };
+
+    match groups.await {
+        Ok(items) => (
+            StatusCode::OK,
+            Json(ReportsList {
+                items,
+            }),
+        )
+            .into_response(),
+        Err(err) => {
+            error!(error = ?err, "failed to load report triage");
+            error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to load reports")
+        }
+    }
+}
+
+pub async fn resolve_reports(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+    caller: Caller,
+    Json(payload): Json<ResolveReports>,
+) -> impl IntoResponse {
+    if let Err(forbidden) = caller.require_editor() {
+        return forbidden.into_response();
+    }
+    let resolution = payload.resolution.trim();
+    if resolution.is_empty() {
+        return error_response(StatusCode::BAD_REQUEST, "resolution must not be empty");
+    }
+    let ids = match payload.report_ids.as_deref().map(parse_ids) {
+        Some(Some(ids)) => Some(ids),
+        Some(None) => return error_response(StatusCode::BAD_REQUEST, "invalid report id"),
+        None => None,
+    };
+
+    let resolved = match queries::resolve_reports(
+        &state.db,
+        &id,
+        ids.as_deref(),
+        caller.user_id.as_deref(),
+        resolution,
+    )
+    .await
+    {
+        Ok(resolved) => resolved,
+        Err(err) => {
+            error!(error = ?err, "failed to resolve reports");
+            return error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to resolve reports");
+        }
+    };
+
+    match update_flag(&state, &id).await {
+        Ok((open_count, flagged)) => (
+            StatusCode::OK,
+            Json(json!({ "resolved": resolved, "open_count": open_count, "flagged": flagged })),
+        )
+            .into_response(),
+        Err(err) => {
+            error!(error = ?err, id, "failed to update question flag");
+            error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to update question flag")
+        }
+    }
+}
+
+/// Flags the question while its open reports are at or above the threshold and clears the flag
+/// once they drop below it. A threshold of zero disables flagging. Returns the open count and
+/// whether the question is flagged.
+async fn update_flag(state: &ApiState, id: &str) -> mongodb::error::Result<(u64, bool)> {
+    let threshold = state.config.report_flag_threshold;
+    let open = queries::count_open_reports(&state.db, id).await?;
+    let flagged = threshold > 0 && open >= threshold;
+    question_queries::set_question_flag(&state.db, id, flagged).await?;
+    Ok((open, flagged))
+}
+
+fn parse_ids(ids: &[String]) -> Option<Vec<ObjectId>> {
+    ids.iter().map(|id| ObjectId::parse_str(id).ok()).collect()
+}
+
+fn error_response(status: StatusCode, msg: &str) -> Response {
+    (status, Json(json!({ "error": msg }))).into_response()
+}
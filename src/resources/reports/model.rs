@@ -0,0 +1,190 @@
+use std::collections::BTreeMap;
+
+use chrono::Utc;
+use mongodb::bson::{DateTime, oid::ObjectId};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportCategory {
+    WrongAnswer,
+    TranslationError,
+    Unclear,
+    Offensive,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportStatus {
+    Open,
+    Resolved,
+}
+
+impl ReportStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Open => "open",
+            Self::Resolved => "resolved",
+        }
+    }
+}
+
+/// A player's complaint about a question, in the language they were viewing it in.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Report {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    pub question_id: String,
+    pub category: ReportCategory,
+    pub lang: String,
+    pub text: Option<String>,
+    pub reporter: Option<String>,
+    pub status: ReportStatus,
+    pub created_at: DateTime,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolved_by: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolution: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolved_at: Option<DateTime>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateReport {
+    pub category: ReportCategory,
+    /// Locale the player was viewing; defaults to the request's resolved language.
+    pub lang: Option<String>,
+    pub text: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ResolveReports {
+    pub resolution: String,
+    /// Reports to resolve; all open reports on the question when omitted.
+    pub report_ids: Option<Vec<String>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReportDto {
+    pub id: String,
+    pub question_id: String,
+    pub category: ReportCategory,
+    pub lang: String,
+    pub text: Option<String>,
+    pub reporter: Option<String>,
+    pub status: ReportStatus,
+    pub created_at: String,
+    pub resolved_by: Option<String>,
+    pub resolution: Option<String>,
+    pub resolved_at: Option<String>,
+}
+
+impl From<Report> for ReportDto {
+    fn from(r: Report) -> Self {
+        Self {
+            id: r.id.to_hex(),
+            question_id: r.question_id,
+            category: r.category,
+            lang: r.lang,
+            text: r.text,
+            reporter: r.reporter,
+            status: r.status,
+            created_at: r.created_at.to_chrono().with_timezone(&Utc).to_rfc3339(),
+            resolved_by: r.resolved_by,
+            resolution: r.resolution,
+            resolved_at: r.resolved_at.map(|at| at.to_chrono().with_timezone(&Utc).to_rfc3339()),
+        }
+    }
+}
+
+/// Open reports on one question, aggregated for the triage view.
+#[derive(Debug, Serialize)]
+pub struct ReportGroupDto {
+    pub question_id: String,
+    pub prompt: Option<String>,
+    pub flagged: bool,
+    pub open_count: u64,
+    pub categories: BTreeMap<ReportCategory, u64>,
+    pub locales: BTreeMap<String, u64>,
+    pub latest_at: String,
+}
+
+/// Raw aggregation row: one per (question, category, locale).
+#[derive(Debug, Deserialize)]
+pub struct ReportCountRow {
+    pub question_id: String,
+    pub category: ReportCategory,
+    pub lang: String,
+    pub count: i64,
+    pub latest_at: DateTime,
+}
+
+/// Folds per-category/locale counts into one group per question, most reported first.
+pub fn group_counts(rows: Vec<ReportCountRow>) -> Vec<ReportGroupDto> {
+    let mut groups: BTreeMap<String, (ReportGroupDto, DateTime)> = BTreeMap::new();
+    for row in rows {
+        let count = row.count.max(0) as u64;
+        let (group, latest) = groups.entry(row.question_id.clone()).or_insert_with(|| {
+            (
+                ReportGroupDto {
+                    question_id: row.question_id.clone(),
+                    prompt: None,
+                    flagged: false,
+                    open_count: 0,
+                    categories: BTreeMap::new(),
+                    locales: BTreeMap::new(),
+                    latest_at: String::new(),
+                },
+                row.latest_at,
+            )
+        });
+        group.open_count += count;
+        *group.categories.entry(row.category).or_default() += count;
+        *group.locales.entry(row.lang).or_default() += count;
+        *latest = (*latest).max(row.latest_at);
+    }
+
+    let mut groups: Vec<ReportGroupDto> = groups
+        .into_values()
+        .map(|(mut group, latest)| {
+            group.latest_at = latest.to_chrono().with_timezone(&Utc).to_rfc3339();
+            group
+        })
+        .collect();
+    groups.sort_by(|a, b| b.open_count.cmp(&a.open_count).then_with(|| b.latest_at.cmp(&a.latest_at)));
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use mongodb::bson::DateTime;
+
+    use super::{ReportCategory, ReportCountRow, group_counts};
+
+    fn row(question_id: &str, category: ReportCategory, lang: &str, count: i64) -> ReportCountRow {
+        ReportCountRow {
+            question_id: question_id.into(),
+            category,
+            lang: lang.into(),
+            count,
+            latest_at: DateTime::from_millis(1_760_000_000_000),
+        }
+    }
+
+    #[test]
+    fn groups_reports_per_question_by_volume() {
+        let groups = group_counts(vec![
+            row("q1", ReportCategory::Unclear, "en", 1),
+            row("q2", ReportCategory::TranslationError, "es", 2),
+            row("q2", ReportCategory::TranslationError, "pt", 1),
+            row("q2", ReportCategory::WrongAnswer, "es", 1),
+        ]);
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].question_id, "q2");
+        assert_eq!(groups[0].open_count, 4);
+        assert_eq!(groups[0].categories[&ReportCategory::TranslationError], 3);
+        assert_eq!(groups[0].locales["es"], 3);
+        assert_eq!(groups[1].open_count, 1);
+    }
+}
@@ -0,0 +1,132 @@
+use futures_util::TryStreamExt;
+use mongodb::{
+    Collection, Database,
+    bson::{self, DateTime, Document, doc, oid::ObjectId},
+    options::FindOptions,
+};
+
+use crate::resources::reports::model::{Report, ReportCategory, ReportCountRow, ReportStatus};
+
+fn reports_collection(db: &Database) -> Collection<Report> {
+    db.collection("reports")
+}
+
+pub struct NewReport<'a> {
+    pub question_id: &'a str,
+    pub category: ReportCategory,
+    pub lang: &'a str,
+    pub text: Option<&'a str>,
+    pub reporter: &'a str,
+}
+
+pub async fn insert_report(db: &Database, new: NewReport<'_>) -> mongodb::error::Result<Report> {
+    let report = Report {
+        id: ObjectId::new(),
+        question_id: new.question_id.to_string(),
+        category: new.category,
+        lang: new.lang.to_string(),
+        text: new.text.map(str::to_owned),
+        reporter: Some(new.reporter.to_owned()),
+        status: ReportStatus::Open,
+        created_at: DateTime::now(),
+        resolved_by: None,
+        resolution: None,
+        resolved_at: None,
+    };
+    reports_collection(db).insert_one(&report, None).await?;
+    Ok(report)
+}
+
+/// Whether `reporter` already has an open report on the question.
+pub async fn has_open_report(
+    db: &Database,
+    question_id: &str,
+    reporter: &str,
+) -> mongodb::error::Result<bool> {
+    let mut filter = open_filter(question_id);
+    filter.insert("reporter", reporter);
+    Ok(reports_collection(db).find_one(filter, None).await?.is_some())
+}
+
+pub async fn count_open_reports(db: &Database, question_id: &str) -> mongodb::error::Result<u64> {
+    reports_collection(db).count_documents(open_filter(question_id), None).await
+}
+
+/// Reports on one question, newest first.
+pub async fn list_reports(
+    db: &Database,
+    question_id: &str,
+    status: Option<ReportStatus>,
+) -> mongodb::error::Result<Vec<Report>> {
+    let mut filter = doc! {"question_id": question_id};
+    if let Some(status) = status {
+        filter.insert("status", status.as_str());
+    }
+    let options = FindOptions::builder().sort(doc! {"created_at": -1, "_id": -1}).build();
+
+    let mut cursor = reports_collection(db).find(filter, options).await?;
+    let mut results = Vec::new();
+    while let Some(report) = cursor.try_next().await? {
+        results.push(report);
+    }
+    Ok(results)
+}
+
+/// Open report counts per question, category and locale.
+pub async fn open_report_counts(db: &Database) -> mongodb::error::Result<Vec<ReportCountRow>> {
+    let pipeline = vec![
+        doc! {"$match": {"status": ReportStatus::Open.as_str()}},
+        doc! {
+            "$group": {
+                "_id": {"question_id": "$question_id", "category": "$category", "lang": "$lang"},
+                "count": {"$sum": 1},
+                "latest_at": {"$max": "$created_at"},
+            }
+        },
+        doc! {
+            "$project": {
+                "_id": 0,
+                "question_id": "$_id.question_id",
+                "category": "$_id.category",
+                "lang": "$_id.lang",
+                "count": 1,
+                "latest_at": 1,
+            }
+        },
+    ];
+
+    let mut cursor = reports_collection(db).aggregate(pipeline, None).await?;
+    let mut rows = Vec::new();
+    while let Some(row) = cursor.try_next().await? {
+        rows.push(bson::from_document(row)?);
+    }
+    Ok(rows)
+}
+
+/// Resolves open reports on a question (all of them, or only `ids`) and returns how many changed.
+pub async fn resolve_reports(
+    db: &Database,
+    question_id: &str,
+    ids: Option<&[ObjectId]>,
+    resolved_by: Option<&str>,
+    resolution: &str,
+) -> mongodb::error::Result<u64> {
+    let mut filter = open_filter(question_id);
+    if let Some(ids) = ids {
+        filter.insert("_id", doc! {"$in": ids});
+    }
+    let update = doc! {
+        "$set": {
+            "status": ReportStatus::Resolved.as_str(),
+            "resolved_by": resolved_by,
+            "resolution": resolution,
+            "resolved_at": DateTime::now(),
+        }
+    };
+    let result = reports_collection(db).update_many(filter, update, None).await?;
+    Ok(result.modified_count)
+}
+
+fn open_filter(question_id: &str) -> Document {
+    doc! {"question_id": question_id, "status": ReportStatus::Open.as_str()}
+}
@@ -0,0 +1,193 @@
+use axum::routing::{MethodRouter, get as axum_get};
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use serde::Deserialize;
+use serde_json::json;
+use tracing::error;
+
+use crate::{
+    auth::Caller,
+    resources::{
+        revisions::{
+            model::{RevisionDiffDto, RevisionDto, RevisionSummaryDto, diff_snapshots},
+            queries,
+        },
+        sync::model::{ENTITY_ERA, ENTITY_QUESTION},
+    },
+    routes::api::ApiState,
+};
+
+pub fn question_revisions() -> MethodRouter<ApiState> {
+    axum_get(list_question_revisions)
+}
+
+pub fn question_revision() -> MethodRouter<ApiState> {
+    axum_get(get_question_revision)
+}
+
+pub fn question_diff() -> MethodRouter<ApiState> {
+    axum_get(diff_question_revisions)
+}
+
+pub fn era_revisions() -> MethodRouter<ApiState> {
+    axum_get(list_era_revisions)
+}
+
+pub fn era_revision() -> MethodRouter<ApiState> {
+    axum_get(get_era_revision)
+}
+
+pub fn era_diff() -> MethodRouter<ApiState> {
+    axum_get(diff_era_revisions)
+}
+
+/// `from` defaults to the revision before `to`; `to` defaults to the latest revision.
+#[derive(Deserialize)]
+pub struct DiffQuery {
+    pub from: Option<i64>,
+    pub to: Option<i64>,
+}
+
+pub async fn list_question_revisions(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+    caller: Caller,
+) -> impl IntoResponse {
+    list_response(&state, &caller, ENTITY_QUESTION, &id).await
+}
+
+pub async fn get_question_revision(
+    State(state): State<ApiState>,
+    Path((id, number)): Path<(String, i64)>,
+    caller: Caller,
+) -> impl IntoResponse {
+    revision_response(&state, &caller, ENTITY_QUESTION, &id, number).await
+}
+
+pub async fn diff_question_revisions(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+    Query(params): Query<DiffQuery>,
+    caller: Caller,
+) -> impl IntoResponse {
+    diff_response(&state, &caller, ENTITY_QUESTION, &id, params).await
+}
+
+pub async fn list_era_revisions(
+    State(state): State<ApiState>,
+    Path(era_id): Path<String>,
+    caller: Caller,
+) -> impl IntoResponse {
+    list_response(&state, &caller, ENTITY_ERA, &era_id).await
+}
+
+pub async fn get_era_revision(
+    State(state): State<ApiState>,
+    Path((era_id, number)): Path<(String, i64)>,
+    caller: Caller,
+) -> impl IntoResponse {
+    revision_response(&state, &caller, ENTITY_ERA, &era_id, number).await
+}
+
+pub async fn diff_era_revisions(
+    State(state): State<ApiState>,
+    Path(era_id): Path<String>,
+    Query(params): Query<DiffQuery>,
+    caller: Caller,
+) -> impl IntoResponse {
+    diff_response(&state, &caller, ENTITY_ERA, &era_id, params).await
+}
+
+async fn list_response(state: &ApiState, caller: &Caller, entity: &str, id: &str) -> Response {
+    if let Err(forbidden) = caller.require_staff() {
+        return forbidden.into_response();
+    }
+
+    match queries::list_revisions(&state.db, entity, id).await {
+        Ok(revisions) if revisions.is_empty() => not_found("no revisions recorded"),
+        Ok(revisions) => {
+            let items: Vec<RevisionSummaryDto> =
+                revisions.iter().map(RevisionSummaryDto::from).collect();
+            (StatusCode::OK, Json(json!({ "items": items }))).into_response()
+        }
+        Err(err) => {
+            error!(error = ?err, entity, id, "failed to list revisions");
+            internal_error("failed to list revisions")
+        }
+    }
+}
+
+async fn revision_response(
+    state: &ApiState,
+    caller: &Caller,
+    entity: &str,
+    id: &str,
+    number: i64,
+) -> Response {
+    if let Err(forbidden) = caller.require_staff() {
+        return forbidden.into_response();
+    }
+
+    match queries::find_revision(&state.db, entity, id, number).await {
+        Ok(Some(revision)) => (StatusCode::OK, Json(RevisionDto::from(revision))).into_response(),
+        Ok(None) => not_found("revision not found"),
+        Err(err) => {
+            error!(error = ?err, entity, id, "failed to fetch revision");
+            internal_error("failed to fetch revision")
+        }
+    }
+}
+
+async fn diff_response(
+    state: &ApiState,
+    caller: &Caller,
+    entity: &str,
+    id: &str,
+    params: DiffQuery,
+) -> Response {
+    if let Err(forbidden) = caller.require_staff() {
+        return forbidden.into_response();
+    }
+
+    let to = match params.to {
+        Some(to) => queries::find_revision(&state.db, entity, id, to).await,
+        None => queries::find_latest(&state.db, entity, id).await,
+    };
+    let to = match to {
+        Ok(Some(to)) => to,
+        Ok(None) => return not_found("revision not found"),
+        Err(err) => {
+            error!(error = ?err, entity, id, "failed to fetch revision");
+            return internal_error("failed to diff revisions");
+        }
+    };
+
+    let from_number = params.from.unwrap_or(to.number - 1);
+    let from = match queries::find_revision(&state.db, entity, id, from_number).await {
+        Ok(Some(from)) => from,
+        Ok(None) => return not_found("revision not found"),
+        Err(err) => {
+            error!(error = ?err, entity, id, "failed to fetch revision");
+            return internal_error("failed to diff revisions");
+        }
+    };
+
+    let diff = RevisionDiffDto {
+        from: from.number,
+        to: to.number,
+        changes: diff_snapshots(&from.snapshot, &to.snapshot),
+    };
+    (StatusCode::OK, Json(diff)).into_response()
+}
+
+fn not_found(msg: &str) -> Response {
+    (StatusCode::NOT_FOUND, Json(json!({ "error": msg }))).into_response()
+}
+
+fn internal_error(msg: &str) -> Response {
+    (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": msg }))).into_response()
+}
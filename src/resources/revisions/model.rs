@@ -0,0 +1,182 @@
+use axum::http::HeaderMap;
+use chrono::Utc;
+use mongodb::bson::{Bson, DateTime, Document, oid::ObjectId};
+use serde::{Deserialize, Serialize};
+
+/// Optional header explaining why a write was made; stored on the revision it creates.
+pub const CHANGE_REASON_HEADER: &str = "x-change-reason";
+
+pub const ACTION_CREATE: &str = "create";
+pub const ACTION_UPDATE: &str = "update";
+pub const ACTION_DELETE: &str = "delete";
+pub const ACTION_RESTORE: &str = "restore";
+pub const ACTION_REVERT: &str = "revert";
+pub const ACTION_STATUS: &str = "status";
+
+/// Immutable snapshot of the question or era document a write stores, recorded just before it.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Revision {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    pub entity: String,
+    pub entity_id: String,
+    pub number: i64,
+    pub action: String,
+    pub snapshot: Document,
+    pub author: Option<String>,
+    pub reason: Option<String>,
+    pub created_at: DateTime,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RevisionSummaryDto {
+    pub number: i64,
+    pub action: String,
+    pub author: Option<String>,
+    pub reason: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RevisionDto {
+    #[serde(flatten)]
+    pub summary: RevisionSummaryDto,
+    pub snapshot: serde_json::Value,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RevisionDiffDto {
+    pub from: i64,
+    pub to: i64,
+    pub changes: Vec<FieldChange>,
+}
+
+/// One changed leaf between two snapshots; `before`/`after` are `null` when the path was added
+/// or removed.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct FieldChange {
+    pub path: String,
+    pub before: serde_json::Value,
+    pub after: serde_json::Value,
+}
+
+impl From<&Revision> for RevisionSummaryDto {
+    fn from(r: &Revision) -> Self {
+        Self {
+            number: r.number,
+            action: r.action.clone(),
+            author: r.author.clone(),
+            reason: r.reason.clone(),
+            created_at: r.created_at.to_chrono().with_timezone(&Utc).to_rfc3339(),
+        }
+    }
+}
+
+impl From<Revision> for RevisionDto {
+    fn from(r: Revision) -> Self {
+        Self {
+            summary: RevisionSummaryDto::from(&r),
+            snapshot: Bson::Document(r.snapshot).into_relaxed_extjson(),
+        }
+    }
+}
+
+/// Optional body of the revert endpoints; the reason falls back to `X-Change-Reason`.
+#[derive(Deserialize, Default)]
+pub struct RevertRequest {
+    pub reason: Option<String>,
+}
+
+pub fn change_reason(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(CHANGE_REASON_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_owned)
+}
+
+/// Field-level differences between two snapshots. Server-managed bookkeeping (`_id`,
+/// `updated_at`) is ignored; arrays are compared position by position.
+pub fn diff_snapshots(before: &Document, after: &Document) -> Vec<FieldChange> {
+    let mut changes = Vec::new();
+    diff_documents("", before, after, &mut changes);
+    changes
+}
+
+fn diff_documents(prefix: &str, before: &Document, after: &Document, changes: &mut Vec<FieldChange>) {
+    let mut keys: Vec<&String> = before.keys().chain(after.keys()).collect();
+    keys.sort();
+    keys.dedup();
+
+    for key in keys {
+        if prefix.is_empty() && matches!(key.as_str(), "_id" | "updated_at") {
+            continue;
+        }
+        let path = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{prefix}.{key}")
+        };
+        diff_values(&path, before.get(key), after.get(key), changes);
+    }
+}
+
+fn diff_values(path: &str, before: Option<&Bson>, after: Option<&Bson>, changes: &mut Vec<FieldChange>) {
+    match (before, after) {
+        (Some(Bson::Document(b)), Some(Bson::Document(a))) => diff_documents(path, b, a, changes),
+        (Some(Bson::Array(b)), Some(Bson::Array(a))) => {
+            for idx in 0..b.len().max(a.len()) {
+                diff_values(&format!("{path}.{idx}"), b.get(idx), a.get(idx), changes);
+            }
+        }
+        (b, a) if b == a => {}
+        (b, a) => changes.push(FieldChange {
+            path: path.to_string(),
+            before: b.cloned().map(Bson::into_relaxed_extjson).unwrap_or_default(),
+            after: a.cloned().map(Bson::into_relaxed_extjson).unwrap_or_default(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mongodb::bson::doc;
+    use serde_json::json;
+
+    use super::{FieldChange, diff_snapshots};
+
+    #[test]
+    fn diff_reports_changed_leaves_only() {
+        let before = doc! {
+            "_id": "q1",
+            "prompt": {"en": "Who built the ark?", "es": "¿Quién construyó el arca?"},
+            "options": [{"text": {"en": "Noah"}, "correct": true}],
+            "updated_at": 1,
+        };
+        let after = doc! {
+            "_id": "q1",
+            "prompt": {"en": "Who built the ark?", "es": "¿Quién construyó el arca de Noé?"},
+            "options": [{"text": {"en": "Noah"}, "correct": true}, {"text": {"en": "Moses"}, "correct": false}],
+            "updated_at": 2,
+        };
+
+        let changes = diff_snapshots(&before, &after);
+        assert_eq!(changes.len(), 2);
+        assert_eq!(
+            changes[0],
+            FieldChange {
+                path: "options.1".into(),
+                before: json!(null),
+                after: json!({"text": {"en": "Moses"}, "correct": false}),
+            }
+        );
+        assert_eq!(changes[1].path, "prompt.es");
+    }
+
+    #[test]
+    fn identical_snapshots_have_no_changes() {
+        let snapshot = doc! {"stage": 1, "tags": ["genesis"]};
+        assert!(diff_snapshots(&snapshot, &snapshot).is_empty());
+    }
+}
@@ -0,0 +1,104 @@
+use futures_util::TryStreamExt;
+use mongodb::{
+    Collection, Database, IndexModel,
+    bson::{DateTime, Document, doc, oid::ObjectId},
+    options::{FindOneOptions, FindOptions, IndexOptions},
+};
+
+use crate::{db::is_duplicate_key, resources::revisions::model::Revision};
+
+/// How often a revision is renumbered after losing a race for its number.
+const RECORD_ATTEMPTS: usize = 5;
+
+fn revisions_collection(db: &Database) -> Collection<Revision> {
+    db.collection("revisions")
+}
+
+/// One number per revision of an entity; also serves the history and latest-revision lookups.
+pub async fn ensure_indexes(db: &Database) -> mongodb::error::Result<()> {
+    let unique = IndexOptions::builder().unique(true).build();
+    let index = IndexModel::builder()
+        .keys(doc! { "entity": 1, "entity_id": 1, "number": 1 })
+        .options(unique)
+        .build();
+    revisions_collection(db).create_index(index, None).await?;
+    Ok(())
+}
+
+pub struct NewRevision<'a> {
+    pub entity: &'a str,
+    pub entity_id: &'a str,
+    pub action: &'a str,
+    pub snapshot: Document,
+    pub author: Option<&'a str>,
+    pub reason: Option<&'a str>,
+}
+
+/// Appends a revision numbered one past the entity's latest. Concurrent writers that pick the same
+/// number are rejected by the unique index and retry with the next one.
+pub async fn record_revision(db: &Database, new: NewRevision<'_>) -> mongodb::error::Result<Revision> {
+    let mut revision = Revision {
+        id: ObjectId::new(),
+        entity: new.entity.to_string(),
+        entity_id: new.entity_id.to_string(),
+        number: 0,
+        action: new.action.to_string(),
+        snapshot: new.snapshot,
+        author: new.author.map(str::to_owned),
+        reason: new.reason.map(str::to_owned),
+        created_at: DateTime::now(),
+    };
+    let mut attempt = 1;
+    loop {
+        let latest = find_latest(db, new.entity, new.entity_id).await?;
+        revision.number = latest.map(|r| r.number).unwrap_or(0) + 1;
+        match revisions_collection(db).insert_one(&revision, None).await {
+            Ok(_) => return Ok(revision),
+            Err(err) if is_duplicate_key(&err) && attempt < RECORD_ATTEMPTS => attempt += 1,
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+/// Removes a revision recorded ahead of a content write that then did not apply, so the history
+/// only lists changes that were stored.
+pub async fn withdraw_revision(db: &Database, id: ObjectId) -> mongodb::error::Result<()> {
+    revisions_collection(db).delete_one(doc! {"_id": id}, None).await?;
+    Ok(())
+}
+
+pub async fn list_revisions(
+    db: &Database,
+    entity: &str,
+    entity_id: &str,
+) -> mongodb::error::Result<Vec<Revision>> {
+    let options = FindOptions::builder().sort(doc! {"number": -1}).build();
+    let mut cursor =
+        revisions_collection(db).find(doc! {"entity": entity, "entity_id": entity_id}, options).await?;
+
+    let mut revisions = Vec::new();
+    while let Some(revision) = cursor.try_next().await? {
+        revisions.push(revision);
+    }
+    Ok(revisions)
+}
+
+pub async fn find_revision(
+    db: &Database,
+    entity: &str,
+    entity_id: &str,
+    number: i64,
+) -> mongodb::error::Result<Option<Revision>> {
+    revisions_collection(db)
+        .find_one(doc! {"entity": entity, "entity_id": entity_id, "number": number}, None)
+        .await
+}
+
+pub async fn find_latest(
+    db: &Database,
+    entity: &str,
+    entity_id: &str,
+) -> mongodb::error::Result<Option<Revision>> {
+    let options = FindOneOptions::builder().sort(doc! {"number": -1}).build();
+    revisions_collection(db).find_one(doc! {"entity": entity, "entity_id": entity_id}, options).await
+}
@@ -0,0 +1,442 @@
+use std::sync::Arc;
+
+use axum::routing::{MethodRouter, get as axum_get, post as axum_post};
+use axum::{
+    Json,
+    extract::{
+        Path, Query, State,
+        ws::{Message, WebSocket, WebSocketUpgrade},
+    },
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+use mongodb::bson::{DateTime, oid::ObjectId};
+use serde::Serialize;
+use serde_json::json;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::error;
+
+use crate::{
+    auth::Caller,
+    lang::resolve_lang,
+    resources::{
+        play::{
+            model::{
+                DEFAULT_SET_SIZE, MAX_SET_SIZE, PlayAnswerDto, PlayQuestionDto, QUIZ_POOL_FACTOR,
+                balance, shuffle_for, to_stored_answer,
+            },
+            scoring::{QuizMode, ScoringRule, is_late},
+        },
+        questions::{
+            model::QuestionDto,
+            queries::{self as question_queries, DifficultyRange, SampleFilter},
+            types::{AnswerCheck, check_answer, solution},
+        },
+        rooms::{
+            model::{
+                ClientMessage, CreateRoom, ErrorMessageDto, Room, RoomAnswer, RoomAnswerDto, RoomPhase,
+                RoomPlayer, RoomQuery, RoomStateDto, join_code, next_phase, normalize_code, scores,
+            },
+            queries,
+        },
+        stats::{handler as stats_handler, model::AnswerOutcome},
+    },
+    routes::api::ApiState,
+};
+
+/// Attempts at drawing a join code not already in use.
+const CODE_ATTEMPTS: usize = 5;
+
+pub fn create() -> MethodRouter<ApiState> {
+    axum_post(create_room)
+}
+
+pub fn get() -> MethodRouter<ApiState> {
+    axum_get(get_room)
+}
+
+pub fn socket() -> MethodRouter<ApiState> {
+    axum_get(connect_room)
+}
+
+/// Opens a room hosted by the caller, with questions drawn and balanced as for quizzes. Players
+/// join with the returned `code`.
+pub async fn create_room(
+    State(state): State<ApiState>,
+    Query(params): Query<RoomQuery>,
+    caller: Caller,
+    headers: HeaderMap,
+    body: Option<Json<CreateRoom>>,
+) -> impl IntoResponse {
+    let host_id = match caller.require_user() {
+        Ok(user_id) => user_id.to_string(),
+        Err(forbidden) => return forbidden.into_response(),
+    };
+    let request = body.map(|Json(request)| request).unwrap_or_default();
+    let count = request.count.unwrap_or(DEFAULT_SET_SIZE).clamp(1, MAX_SET_SIZE);
+    let filter = SampleFilter {
+        stage: request.stage,
+        difficulty: DifficultyRange {
+            min: request.min_difficulty,
+            max: request.max_difficulty,
+        },
+        eras: &request.eras,
+        levels: &request.levels,
+        tags: &request.tags,
+        exclude: &[],
+    };
+    let candidates =
+        match question_queries::sample_questions(&state.db, &filter, (count * QUIZ_POOL_FACTOR) as i64)
+            .await
+        {
+            Ok(candidates) => candidates,
+            Err(err) => {
+                error!(error = ?err, "failed to sample questions");
+                return error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to create room");
+            }
+        };
+    let questions: Vec<Option<QuestionDto>> = balance(candidates, count).into_iter().map(Some).collect();
+    if questions.is_empty() {
+        return error_response(StatusCode::CONFLICT, "no questions match the room filters");
+    }
+
+    let now = DateTime::now();
+    let mut room = Room {
+        code: String::new(),
+        host_id,
+        mode: request.mode.unwrap_or(QuizMode::Competitive),
+        question_ids: questions.iter().flatten().map(|q| q.id.clone()).collect(),
+        phase: RoomPhase::Lobby,
+        current: None,
+        started_at: None,
+        players: Vec::new(),
+        answers: Vec::new(),
+        created_at: now,
+        updated_at: now,
+    };
+    let mut created = false;
+    for _ in 0..CODE_ATTEMPTS {
+        room.code = join_code(&ObjectId::new().bytes());
+        match queries::insert_room(&state.db, &room).await {
+            Ok(true) => {
+                created = true;
+                break;
+            }
+            Ok(false) => continue,
+            Err(err) => {
+                error!(error = ?err, "failed to store room");
+                return error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to create room");
+            }
+        }
+    }
+    if !created {
+        error!("no free room code after {CODE_ATTEMPTS} attempts");
+        return error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to create room");
+    }
+
+    let lang = resolve_lang(params.lang.as_deref(), headers.get("accept-language"));
+    let rule = state.config.quiz_scoring.rule(room.mode);
+    let dto = room_state(&room, &questions, &room.host_id, &lang, rule);
+    (StatusCode::CREATED, Json(dto)).into_response()
+}
+
+/// The room as the caller sees it, for clients that poll or show a projector view.
+pub async fn get_room(
+    State(state): State<ApiState>,
+    Path(code): Path<String>,
+    Query(params): Query<RoomQuery>,
+    caller: Caller,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let room = match load_room(&state, &code).await {
+        Ok(room) => room,
+        Err(response) => return response,
+    };
+    let questions = match load_questions(&state, &room).await {
+        Ok(questions) => questions,
+        Err(response) => return response,
+    };
+    let lang = resolve_lang(params.lang.as_deref(), headers.get("accept-language"));
+    let rule = state.config.quiz_scoring.rule(room.mode);
+    let viewer = caller.user_id.as_deref().unwrap_or_default();
+    (StatusCode::OK, Json(room_state(&room, &questions, viewer, &lang, rule))).into_response()
+}
+
+/// Joins the room over a WebSocket. Players are added on first connect (or have their name and
+/// locale updated when reconnecting); the host connects to drive it. Every change to the room is
+/// pushed to all participants as a `state` message rendered for each of them.
+pub async fn connect_room(
+    State(state): State<ApiState>,
+    Path(code): Path<String>,
+    Query(params): Query<RoomQuery>,
+    caller: Caller,
+    headers: HeaderMap,
+    ws: WebSocketUpgrade,
+) -> impl IntoResponse {
+    let user_id = match caller.require_user() {
+        Ok(user_id) => user_id.to_string(),
+        Err(forbidden) => return forbidden.into_response(),
+    };
+    let room = match load_room(&state, &code).await {
+        Ok(room) => room,
+        Err(response) => return response,
+    };
+    let lang = resolve_lang(params.lang.as_deref(), headers.get("accept-language"));
+
+    let room = if room.host_id == user_id {
+        room
+    } else {
+        let player = RoomPlayer {
+            user_id: user_id.clone(),
+            name: params.name.map(|name| name.trim().to_string()).filter(|name| !name.is_empty()),
+            lang: lang.clone(),
+            joined_at: DateTime::now(),
+        };
+        match queries::join_room(&state.db, &room.code, &player).await {
+            Ok(Some(room)) if room.players.iter().any(|p| p.user_id == user_id) => room,
+            Ok(Some(_)) => return error_response(StatusCode::CONFLICT, "room has finished"),
+            Ok(None) => return error_response(StatusCode::NOT_FOUND, "room not found"),
+            Err(err) => {
+                error!(error = ?err, "failed to join room");
+                return error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to join room");
+            }
+        }
+    };
+    let questions = match load_questions(&state, &room).await {
+        Ok(questions) => questions,
+        Err(response) => return response,
+    };
+
+    ws.on_upgrade(move |socket| run_socket(state, socket, room, questions, user_id, lang))
+}
+
+async fn run_socket(
+    state: ApiState,
+    mut socket: WebSocket,
+    room: Room,
+    questions: Vec<Option<QuestionDto>>,
+    user_id: String,
+    lang: String,
+) {
+    let code = room.code.clone();
+    let rule = state.config.quiz_scoring.rule(room.mode);
+    let mut events = state.rooms.subscribe(&code);
+    // Announces the (re)joined player to everyone, this socket included.
+    state.rooms.publish(room);
+
+    loop {
+        tokio::select! {
+            message = socket.recv() => {
+                let text = match message {
+                    Some(Ok(Message::Text(text))) => text,
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    Some(Ok(_)) => continue,
+                };
+                match handle_message(&state, &code, &questions, &user_id, &lang, rule, &text).await {
+                    Ok(room) => state.rooms.publish(room),
+                    Err(msg) => {
+                        let error = ErrorMessageDto { kind: "error", error: msg };
+                        if send_json(&mut socket, &error).await.is_err() {
+                            break;
+                        }
+                    }
+                }
+            }
+            event = events.recv() => {
+                let room = match event {
+                    Ok(room) => room,
+                    // Fell behind: skip straight to the current state.
+                    Err(RecvError::Lagged(_)) => match queries::find_room(&state.db, &code).await {
+                        Ok(Some(room)) => Arc::new(room),
+                        Ok(None) => break,
+                        Err(err) => {
+                            error!(error = ?err, "failed to reload room");
+                            continue;
+                        }
+                    },
+                    Err(RecvError::Closed) => break,
+                };
+                let dto = room_state(&room, &questions, &user_id, &lang, rule);
+                if send_json(&mut socket, &dto).await.is_err() {
+                    break;
+                }
+            }
+        }
+    }
+
+    drop(events);
+    state.rooms.release(&code);
+}
+
+/// Applies one client message to the stored room and returns the room after it.
+async fn handle_message(
+    state: &ApiState,
+    code: &str,
+    questions: &[Option<QuestionDto>],
+    user_id: &str,
+    lang: &str,
+    rule: ScoringRule,
+    text: &str,
+) -> Result<Room, &'static str> {
+    let message: ClientMessage = serde_json::from_str(text).map_err(|_| "unrecognized message")?;
+    let room = match queries::find_room(&state.db, code).await {
+        Ok(Some(room)) => room,
+        Ok(None) => return Err("room not found"),
+        Err(err) => {
+            error!(error = ?err, "failed to load room");
+            return Err("failed to load room");
+        }
+    };
+
+    match message {
+        ClientMessage::Next => {
+            if room.host_id != user_id {
+                return Err("only the host can advance the room");
+            }
+            let to = next_phase(room.phase, room.current, room.question_ids.len())
+                .ok_or("room has finished")?;
+            match queries::advance_room(&state.db, code, (room.phase, room.current), to).await {
+                Ok(Some(room)) => Ok(room),
+                Ok(None) => Err("room already advanced"),
+                Err(err) => {
+                    error!(error = ?err, "failed to advance room");
+                    Err("failed to advance room")
+                }
+            }
+        }
+        ClientMessage::Answer {
+            answer,
+        } => {
+            let index = room
+                .current
+                .filter(|_| room.phase == RoomPhase::Question)
+                .ok_or("no question is open")?;
+            let question = questions
+                .get(index as usize)
+                .and_then(Option::as_ref)
+                .ok_or("question is no longer available")?;
+            let shuffle = shuffle_for(question, Some(user_id), code);
+            let answer = to_stored_answer(answer, &shuffle)?;
+            let check = check_answer(question, &answer, lang)?;
+
+            let elapsed_ms = room.started_at.map_or(0, |started_at| {
+                DateTime::now().timestamp_millis() - started_at.timestamp_millis()
+            });
+            let limit_ms =
+                rule.time_limit_secs(question.time_limit_secs).map(|secs| i64::from(secs) * 1000);
+            let late = limit_ms.is_some_and(|limit_ms| is_late(Some(elapsed_ms), limit_ms));
+            let correct = check.correct && !late;
+            let recorded = RoomAnswer {
+                question_index: index,
+                user_id: user_id.to_string(),
+                correct,
+                points: rule.score(correct, Some(elapsed_ms), limit_ms),
+                elapsed_ms,
+                late,
+            };
+            let room = match queries::record_answer(&state.db, code, index, &recorded).await {
+                Ok(Some(room)) => room,
+                Ok(None) => return Err("answer not accepted"),
+                Err(err) => {
+                    error!(error = ?err, "failed to record room answer");
+                    return Err("failed to record answer");
+                }
+            };
+            let outcome = AnswerOutcome::new(&question.id, &answer, correct, Some(elapsed_ms));
+            stats_handler::record_answers(state, Some(user_id), lang, &[outcome]).await;
+            Ok(room)
+        }
+    }
+}
+
+/// Renders the room for `viewer`: the current question in their locale and option order, their
+/// own answer, the solution once revealed and the scoreboard.
+fn room_state(
+    room: &Room,
+    questions: &[Option<QuestionDto>],
+    viewer: &str,
+    lang: &str,
+    rule: ScoringRule,
+) -> RoomStateDto {
+    let current = room
+        .current
+        .filter(|_| matches!(room.phase, RoomPhase::Question | RoomPhase::Reveal))
+        .and_then(|index| questions.get(index as usize))
+        .and_then(Option::as_ref);
+    let you = room.answer_of(viewer).map(|answer| RoomAnswerDto {
+        correct: answer.correct,
+        points: answer.points,
+        late: answer.late,
+    });
+
+    let (mut question, mut deadline, mut reveal) = (None, None, None);
+    if let Some(current) = current {
+        let shuffle = shuffle_for(current, Some(viewer), &room.code);
+        let time_limit_secs = rule.time_limit_secs(current.time_limit_secs);
+        if room.phase == RoomPhase::Question {
+            deadline = room.started_at.zip(time_limit_secs).map(|(started_at, secs)| {
+                (started_at.to_chrono() + chrono::Duration::seconds(i64::from(secs))).to_rfc3339()
+            });
+        } else {
+            let check = AnswerCheck {
+                correct: you.as_ref().is_some_and(|you| you.correct),
+                solution: solution(current, lang),
+            };
+            reveal = Some(PlayAnswerDto::new(check, current, lang, &shuffle));
+        }
+        let mut shown = current.clone();
+        shown.time_limit_secs = time_limit_secs;
+        question = Some(PlayQuestionDto::new(shown, lang, room.code.clone(), &shuffle));
+    }
+
+    RoomStateDto {
+        kind: "state",
+        code: room.code.clone(),
+        phase: room.phase,
+        mode: room.mode,
+        host: room.host_id == viewer,
+        question_index: room.current.filter(|index| *index >= 0),
+        total: room.question_ids.len(),
+        question,
+        started_at: room.started_at_rfc3339().filter(|_| room.phase == RoomPhase::Question),
+        deadline,
+        answered: room.answered(),
+        you,
+        reveal,
+        scores: scores(room),
+    }
+}
+
+async fn load_room(state: &ApiState, code: &str) -> Result<Room, Response> {
+    match queries::find_room(&state.db, &normalize_code(code)).await {
+        Ok(Some(room)) => Ok(room),
+        Ok(None) => Err(error_response(StatusCode::NOT_FOUND, "room not found")),
+        Err(err) => {
+            error!(error = ?err, "failed to load room");
+            Err(error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to load room"))
+        }
+    }
+}
+
+/// The room's questions by position; questions trashed since the room opened are `None`.
+async fn load_questions(state: &ApiState, room: &Room) -> Result<Vec<Option<QuestionDto>>, Response> {
+    match question_queries::find_questions_by_ids(&state.db, &room.question_ids).await {
+        Ok(found) => {
+            let found: Vec<QuestionDto> = found.into_iter().map(QuestionDto::from).collect();
+            Ok(room.question_ids.iter().map(|id| found.iter().find(|q| q.id == *id).cloned()).collect())
+        }
+        Err(err) => {
+            error!(error = ?err, "failed to load room questions");
+            Err(error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to load room"))
+        }
+    }
+}
+
+async fn send_json<T: Serialize>(socket: &mut WebSocket, message: &T) -> Result<(), axum::Error> {
+    let text = serde_json::to_string(message).map_err(axum::Error::new)?;
+    socket.send(Message::Text(text)).await
+}
+
+fn error_response(status: StatusCode, msg: &str) -> Response {
+    (status, Json(json!({ "error": msg }))).into_response()
+}
@@ -0,0 +1,49 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use tokio::sync::broadcast;
+
+use crate::resources::rooms::model::Room;
+
+/// Changes a slow socket may fall behind by before it skips to the latest state.
+const CHANNEL_CAPACITY: usize = 32;
+
+/// Fans room changes out to the sockets connected to this API instance. Rooms themselves live in
+/// MongoDB; this only carries the latest state to whoever is listening.
+#[derive(Clone, Default)]
+pub struct RoomHub {
+    channels: Arc<Mutex<HashMap<String, broadcast::Sender<Arc<Room>>>>>,
+}
+
+impl RoomHub {
+    pub fn subscribe(&self, code: &str) -> broadcast::Receiver<Arc<Room>> {
+        let mut channels = self.lock();
+        channels
+            .entry(code.to_string())
+            .or_insert_with(|| broadcast::channel(CHANNEL_CAPACITY).0)
+            .subscribe()
+    }
+
+    /// Sends `room` to every socket subscribed to it.
+    pub fn publish(&self, room: Room) {
+        let channels = self.lock();
+        if let Some(sender) = channels.get(&room.code) {
+            // No receivers left is fine; the last one to leave removes the channel.
+            let _ = sender.send(Arc::new(room));
+        }
+    }
+
+    /// Drops the room's channel once nobody is subscribed. Call after dropping a receiver.
+    pub fn release(&self, code: &str) {
+        let mut channels = self.lock();
+        if channels.get(code).is_some_and(|sender| sender.receiver_count() == 0) {
+            channels.remove(code);
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, broadcast::Sender<Arc<Room>>>> {
+        self.channels.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
@@ -0,0 +1,4 @@
+pub mod handler;
+pub mod hub;
+pub mod model;
+pub mod queries;
@@ -0,0 +1,314 @@
+use chrono::Utc;
+use mongodb::bson::DateTime;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::resources::{
+    play::{
+        model::{PlayAnswerDto, PlayQuestionDto},
+        scoring::QuizMode,
+    },
+    questions::types::Answer,
+};
+
+/// Join codes avoid characters that are easy to misread on a projector (`0`/`O`, `1`/`I`).
+const CODE_ALPHABET: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+pub const CODE_LENGTH: usize = 6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoomPhase {
+    /// Players are joining; no question shown yet.
+    Lobby,
+    /// The current question is open for answers.
+    Question,
+    /// Answers to the current question are closed and the solution is shown.
+    Reveal,
+    Finished,
+}
+
+impl RoomPhase {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RoomPhase::Lobby => "lobby",
+            RoomPhase::Question => "question",
+            RoomPhase::Reveal => "reveal",
+            RoomPhase::Finished => "finished",
+        }
+    }
+}
+
+/// A live quiz run by a host (collection `rooms`, keyed by join code). All state lives here, so
+/// players who reconnect pick up where they left off.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Room {
+    #[serde(rename = "_id")]
+    pub code: String,
+    pub host_id: String,
+    pub mode: QuizMode,
+    pub question_ids: Vec<String>,
+    pub phase: RoomPhase,
+    /// Index into `question_ids` of the question being asked or revealed.
+    pub current: Option<i32>,
+    /// When the current question opened.
+    pub started_at: Option<DateTime>,
+    pub players: Vec<RoomPlayer>,
+    pub answers: Vec<RoomAnswer>,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoomPlayer {
+    pub user_id: String,
+    pub name: Option<String>,
+    /// Locale the player sees questions in.
+    pub lang: String,
+    pub joined_at: DateTime,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoomAnswer {
+    pub question_index: i32,
+    pub user_id: String,
+    pub correct: bool,
+    pub points: i64,
+    pub elapsed_ms: i64,
+    pub late: bool,
+}
+
+/// Body of `POST /v1/rooms`; filters work as for quizzes.
+#[derive(Deserialize, Default)]
+pub struct CreateRoom {
+    pub count: Option<usize>,
+    pub stage: Option<i32>,
+    #[serde(default)]
+    pub eras: Vec<String>,
+    #[serde(default)]
+    pub levels: Vec<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub min_difficulty: Option<f64>,
+    pub max_difficulty: Option<f64>,
+    /// Defaults to `competitive`.
+    pub mode: Option<QuizMode>,
+}
+
+/// `name` is shown on the scoreboard; `lang` picks the locale questions are shown in.
+#[derive(Deserialize)]
+pub struct RoomQuery {
+    pub lang: Option<String>,
+    pub name: Option<String>,
+}
+
+/// Messages sent by clients over the room socket.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    /// Host only: open the next question, or close the current one and reveal its solution.
+    Next,
+    /// Answer to the open question, in display positions.
+    Answer {
+        answer: Answer,
+    },
+}
+
+/// The room as one participant sees it, sent on connect and after every change.
+#[derive(Debug, Serialize)]
+pub struct RoomStateDto {
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    pub code: String,
+    pub phase: RoomPhase,
+    pub mode: QuizMode,
+    pub host: bool,
+    pub question_index: Option<i32>,
+    pub total: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub question: Option<PlayQuestionDto>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deadline: Option<String>,
+    /// Players who answered the current question.
+    pub answered: usize,
+    /// The participant's own answer to the current question.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub you: Option<RoomAnswerDto>,
+    /// Solution of the current question, in the participant's display order, once revealed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reveal: Option<PlayAnswerDto>,
+    pub scores: Vec<ScoreDto>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RoomAnswerDto {
+    pub correct: bool,
+    pub points: i64,
+    pub late: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ScoreDto {
+    pub user_id: String,
+    pub name: Option<String>,
+    pub score: i64,
+    pub correct: i32,
+    pub answered: i32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ErrorMessageDto<'a> {
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    pub error: &'a str,
+}
+
+/// A join code derived from `seed` (any unique value, such as a fresh object id).
+pub fn join_code(seed: &[u8]) -> String {
+    Sha256::digest(seed)
+        .iter()
+        .take(CODE_LENGTH)
+        .map(|byte| CODE_ALPHABET[usize::from(*byte) % CODE_ALPHABET.len()] as char)
+        .collect()
+}
+
+/// Codes are matched case-insensitively, since players type them in.
+pub fn normalize_code(code: &str) -> String {
+    code.trim().to_ascii_uppercase()
+}
+
+/// Where the host's `next` moves the room: lobby to the first question, an open question to its
+/// reveal, a reveal to the next question, and the last reveal to the end.
+pub fn next_phase(phase: RoomPhase, current: Option<i32>, total: usize) -> Option<(RoomPhase, i32)> {
+    let current = current.unwrap_or(-1);
+    match phase {
+        RoomPhase::Question => Some((RoomPhase::Reveal, current)),
+        RoomPhase::Lobby | RoomPhase::Reveal if ((current + 1) as usize) < total => {
+            Some((RoomPhase::Question, current + 1))
+        }
+        RoomPhase::Lobby | RoomPhase::Reveal => Some((RoomPhase::Finished, current)),
+        RoomPhase::Finished => None,
+    }
+}
+
+/// The scoreboard: every player with their totals, best first. Ties go by fewer answers, then id.
+pub fn scores(room: &Room) -> Vec<ScoreDto> {
+    let mut scores: Vec<ScoreDto> = room
+        .players
+        .iter()
+        .map(|player| {
+            let answers = room.answers.iter().filter(|a| a.user_id == player.user_id);
+            ScoreDto {
+                user_id: player.user_id.clone(),
+                name: player.name.clone(),
+                score: answers.clone().map(|a| a.points).sum(),
+                correct: answers.clone().filter(|a| a.correct).count() as i32,
+                answered: answers.count() as i32,
+            }
+        })
+        .collect();
+    scores.sort_by(|a, b| {
+        b.score.cmp(&a.score).then(a.answered.cmp(&b.answered)).then(a.user_id.cmp(&b.user_id))
+    });
+    scores
+}
+
+impl Room {
+    pub fn answer_of(&self, user_id: &str) -> Option<&RoomAnswer> {
+        let current = self.current?;
+        self.answers.iter().find(|a| a.question_index == current && a.user_id == user_id)
+    }
+
+    pub fn answered(&self) -> usize {
+        self.current
+            .map_or(0, |current| self.answers.iter().filter(|a| a.question_index == current).count())
+    }
+
+    pub fn started_at_rfc3339(&self) -> Option<String> {
+        self.started_at.map(|at| at.to_chrono().with_timezone(&Utc).to_rfc3339())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mongodb::bson::DateTime;
+
+    use super::{
+        CODE_LENGTH, Room, RoomAnswer, RoomPhase, RoomPlayer, join_code, next_phase, normalize_code,
+        scores,
+    };
+    use crate::resources::play::scoring::QuizMode;
+
+    #[test]
+    fn host_walks_through_every_question_and_reveal() {
+        let mut state = (RoomPhase::Lobby, None);
+        let mut seen = Vec::new();
+        while let Some((phase, current)) = next_phase(state.0, state.1, 2) {
+            seen.push((phase, current));
+            state = (phase, Some(current));
+        }
+        assert_eq!(
+            seen,
+            vec![
+                (RoomPhase::Question, 0),
+                (RoomPhase::Reveal, 0),
+                (RoomPhase::Question, 1),
+                (RoomPhase::Reveal, 1),
+                (RoomPhase::Finished, 1),
+            ]
+        );
+        assert_eq!(next_phase(RoomPhase::Lobby, None, 0), Some((RoomPhase::Finished, -1)));
+    }
+
+    #[test]
+    fn scoreboard_ranks_points_then_fewer_answers() {
+        let player = |id: &str| RoomPlayer {
+            user_id: id.into(),
+            name: None,
+            lang: "en".into(),
+            joined_at: DateTime::now(),
+        };
+        let answer = |id: &str, question_index, points| RoomAnswer {
+            question_index,
+            user_id: id.into(),
+            correct: points > 0,
+            points,
+            elapsed_ms: 0,
+            late: false,
+        };
+        let room = Room {
+            code: "ABCDEF".into(),
+            host_id: "host".into(),
+            mode: QuizMode::Competitive,
+            question_ids: vec!["q1".into(), "q2".into()],
+            phase: RoomPhase::Reveal,
+            current: Some(1),
+            started_at: None,
+            players: vec![player("ana"), player("ben"), player("cy")],
+            answers: vec![
+                answer("ana", 0, 600),
+                answer("ben", 0, 0),
+                answer("ben", 1, 600),
+                answer("cy", 1, 900),
+            ],
+            created_at: DateTime::now(),
+            updated_at: DateTime::now(),
+        };
+
+        let board = scores(&room);
+        let ranked: Vec<(&str, i64)> = board.iter().map(|s| (s.user_id.as_str(), s.score)).collect();
+        assert_eq!(ranked, vec![("cy", 900), ("ana", 600), ("ben", 600)]);
+        assert_eq!(room.answered(), 2);
+        assert!(room.answer_of("ana").is_none());
+    }
+
+    #[test]
+    fn join_codes_are_short_and_readable() {
+        let code = join_code(b"seed");
+        assert_eq!(code.len(), CODE_LENGTH);
+        assert!(code.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit()));
+        assert!(!code.contains(['0', 'O', '1', 'I']));
+        assert_eq!(normalize_code(" abc2ef "), "ABC2EF");
+    }
+}
@@ -0,0 +1,118 @@
+use std::time::Duration;
+
+use mongodb::{
+    Collection, Database, IndexModel,
+    bson::{self, DateTime, doc},
+    options::{FindOneAndUpdateOptions, IndexOptions, ReturnDocument},
+};
+
+use crate::{
+    db::is_duplicate_key,
+    resources::rooms::model::{Room, RoomAnswer, RoomPhase, RoomPlayer},
+};
+
+/// Rooms are removed after a day without activity.
+const ROOM_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+fn rooms_collection(db: &Database) -> Collection<Room> {
+    db.collection("rooms")
+}
+
+/// Expires idle rooms.
+pub async fn ensure_indexes(db: &Database) -> mongodb::error::Result<()> {
+    let options = IndexOptions::builder().expire_after(ROOM_TTL).build();
+    let index = IndexModel::builder().keys(doc! { "updated_at": 1 }).options(options).build();
+    rooms_collection(db).create_index(index, None).await?;
+    Ok(())
+}
+
+/// Inserts a new room. Returns `false` when its join code is already taken.
+pub async fn insert_room(db: &Database, room: &Room) -> mongodb::error::Result<bool> {
+    match rooms_collection(db).insert_one(room, None).await {
+        Ok(_) => Ok(true),
+        Err(err) if is_duplicate_key(&err) => Ok(false),
+        Err(err) => Err(err),
+    }
+}
+
+pub async fn find_room(db: &Database, code: &str) -> mongodb::error::Result<Option<Room>> {
+    rooms_collection(db).find_one(doc! { "_id": code }, None).await
+}
+
+/// Adds `player` to the room, or updates the name and locale of a player rejoining. New players
+/// cannot join a finished room; the room is returned without them.
+pub async fn join_room(
+    db: &Database,
+    code: &str,
+    player: &RoomPlayer,
+) -> mongodb::error::Result<Option<Room>> {
+    let options = FindOneAndUpdateOptions::builder().return_document(ReturnDocument::After).build();
+    let rejoin = doc! {
+        "$set": {
+            "players.$.name": &player.name,
+            "players.$.lang": &player.lang,
+            "updated_at": DateTime::now(),
+        }
+    };
+    let filter = doc! { "_id": code, "players.user_id": &player.user_id };
+    if let Some(room) = rooms_collection(db).find_one_and_update(filter, rejoin, options.clone()).await?
+    {
+        return Ok(Some(room));
+    }
+
+    let join = doc! {
+        "$push": { "players": bson::to_bson(player)? },
+        "$set": { "updated_at": DateTime::now() },
+    };
+    let filter = doc! {
+        "_id": code,
+        "players.user_id": { "$ne": &player.user_id },
+        "phase": { "$ne": RoomPhase::Finished.as_str() },
+    };
+    match rooms_collection(db).find_one_and_update(filter, join, options).await? {
+        Some(room) => Ok(Some(room)),
+        // Finished, or joined concurrently from another connection.
+        None => find_room(db, code).await,
+    }
+}
+
+/// Records a player's answer to the open question `index`. Returns `None` when the question is no
+/// longer open, the player is not in the room or already answered it.
+pub async fn record_answer(
+    db: &Database,
+    code: &str,
+    index: i32,
+    answer: &RoomAnswer,
+) -> mongodb::error::Result<Option<Room>> {
+    let filter = doc! {
+        "_id": code,
+        "phase": RoomPhase::Question.as_str(),
+        "current": index,
+        "players.user_id": &answer.user_id,
+        "answers": { "$not": { "$elemMatch": { "question_index": index, "user_id": &answer.user_id } } },
+    };
+    let update = doc! {
+        "$push": { "answers": bson::to_bson(answer)? },
+        "$set": { "updated_at": DateTime::now() },
+    };
+    let options = FindOneAndUpdateOptions::builder().return_document(ReturnDocument::After).build();
+    rooms_collection(db).find_one_and_update(filter, update, options).await
+}
+
+/// Moves the room from `from` to `to`, stamping the start of a newly opened question. Returns
+/// `None` when the room has already moved on, so two `next` messages cannot skip a question.
+pub async fn advance_room(
+    db: &Database,
+    code: &str,
+    from: (RoomPhase, Option<i32>),
+    to: (RoomPhase, i32),
+) -> mongodb::error::Result<Option<Room>> {
+    let now = DateTime::now();
+    let filter = doc! { "_id": code, "phase": from.0.as_str(), "current": from.1 };
+    let mut set = doc! { "phase": to.0.as_str(), "current": to.1, "updated_at": now };
+    if to.0 == RoomPhase::Question {
+        set.insert("started_at", now);
+    }
+    let options = FindOneAndUpdateOptions::builder().return_document(ReturnDocument::After).build();
+    rooms_collection(db).find_one_and_update(filter, doc! { "$set": set }, options).await
+}
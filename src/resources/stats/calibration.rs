@@ -0,0 +1,91 @@
+/// Starting rating for players and questions.
+pub const BASE_RATING: f64 = 1500.0;
+/// Rating gap at which the stronger side is expected to win ten times as often.
+const SCALE: f64 = 400.0;
+/// Step size for a newcomer; it shrinks as ratings settle.
+const K_MAX: f64 = 64.0;
+const K_MIN: f64 = 8.0;
+/// Answers after which the step size has halved.
+const K_HALF_LIFE: f64 = 25.0;
+
+/// An Elo rating for a player or a question. Each answer is a match between the two, won by the
+/// player when correct; a player rated like a question answers it correctly half the time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rating {
+    pub value: f64,
+    pub answers: i64,
+}
+
+impl Default for Rating {
+    fn default() -> Self {
+        Self {
+            value: BASE_RATING,
+            answers: 0,
+        }
+    }
+}
+
+impl Rating {
+    fn k(self) -> f64 {
+        (K_MAX / (1.0 + self.answers as f64 / K_HALF_LIFE)).max(K_MIN)
+    }
+}
+
+/// Chance that a player rated `player` answers a question rated `question` correctly.
+pub fn expected(player: f64, question: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf((question - player) / SCALE))
+}
+
+/// Moves both ratings towards the observed outcome.
+pub fn update(player: &mut Rating, question: &mut Rating, correct: bool) {
+    let surprise = f64::from(u8::from(correct)) - expected(player.value, question.value);
+    let (player_k, question_k) = (player.k(), question.k());
+    player.value += player_k * surprise;
+    question.value -= question_k * surprise;
+    player.answers += 1;
+    question.answers += 1;
+}
+
+/// The stage each question would have if stages followed measured difficulty: the `n`-th easiest
+/// question gets the `n`-th lowest of the manually assigned stages. Input is `(stage, difficulty)`
+/// pairs; output is in the same order.
+pub fn expected_stages(questions: &[(i32, f64)]) -> Vec<i32> {
+    let mut stages: Vec<i32> = questions.iter().map(|(stage, _)| *stage).collect();
+    stages.sort_unstable();
+    let mut by_difficulty: Vec<usize> = (0..questions.len()).collect();
+    by_difficulty.sort_by(|&a, &b| questions[a].1.total_cmp(&questions[b].1));
+
+    let mut expected = vec![0; questions.len()];
+    for (rank, idx) in by_difficulty.into_iter().enumerate() {
+        expected[idx] = stages[rank];
+    }
+    expected
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BASE_RATING, Rating, expected, expected_stages, update};
+
+    #[test]
+    fn misses_raise_difficulty_and_settle() {
+        assert_eq!(expected(BASE_RATING, BASE_RATING), 0.5);
+
+        let mut question = Rating::default();
+        for _ in 0..40 {
+            update(&mut Rating::default(), &mut question, false);
+        }
+        assert!(question.value > BASE_RATING + 200.0);
+        assert_eq!(question.answers, 40);
+
+        let before = question.value;
+        update(&mut Rating::default(), &mut question, true);
+        let drop = before - question.value;
+        assert!(drop > 0.0 && drop < 25.0, "settled ratings move slowly, moved {drop}");
+    }
+
+    #[test]
+    fn stages_follow_difficulty_rank() {
+        let questions = [(1, 1650.0), (2, 1400.0), (3, 1500.0), (3, 1700.0)];
+        assert_eq!(expected_stages(&questions), vec![3, 1, 2, 3]);
+    }
+}
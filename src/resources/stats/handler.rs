@@ -0,0 +1,240 @@
+use axum::routing::{MethodRouter, get as axum_get};
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use mongodb::bson::{DateTime, oid::ObjectId};
+use serde_json::json;
+use tracing::error;
+
+use crate::{
+    auth::Caller,
+    resources::{
+        achievements::handler as achievement_handler,
+        questions::{
+            model::{QuestionDto, localized},
+            queries as question_queries,
+        },
+        stats::{
+            calibration::{BASE_RATING, expected_stages},
+            model::{
+                AnswerEvent, AnswerOutcome, DifficultyItemDto, DifficultyReportDto,
+                DifficultyReportQuery, ReportItemDto, StatsReportDto, StatsReportQuery, summarize,
+            },
+            queries,
+        },
+        streaks::{handler as streak_handler, model::Activity},
+    },
+    routes::api::ApiState,
+};
+
+pub fn question() -> MethodRouter<ApiState> {
+    axum_get(question_stats)
+}
+
+pub fn report() -> MethodRouter<ApiState> {
+    axum_get(stats_report)
+}
+
+pub fn difficulty() -> MethodRouter<ApiState> {
+    axum_get(difficulty_report)
+}
+
+/// Attempts, correct rate, average time and option pick rates for one question, overall and per
+/// locale, with wrong options that draw unusually many picks in a single locale.
+pub async fn question_stats(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+    caller: Caller,
+) -> impl IntoResponse {
+    if let Err(forbidden) = caller.require_staff() {
+        return forbidden.into_response();
+    }
+
+    let question = match question_queries::find_question_by_id(&state.db, &id).await {
+        Ok(Some(question)) => question,
+        Ok(None) => return error_response(StatusCode::NOT_FOUND, "question not found"),
+        Err(err) => {
+            error!(error = ?err, "failed to fetch question");
+            return error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to load statistics");
+        }
+    };
+    match queries::list_stats(&state.db, std::slice::from_ref(&question.id)).await {
+        Ok(stats) => (StatusCode::OK, Json(summarize(&question, &stats))).into_response(),
+        Err(err) => {
+            error!(error = ?err, "failed to load question statistics");
+            error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to load statistics")
+        }
+    }
+}
+
+/// Answered questions sorted by `attempts`, `correct_rate` or `avg_time` (`order=asc|desc`),
+/// optionally only those with at least `min_attempts`.
+pub async fn stats_report(
+    State(state): State<ApiState>,
+    Query(params): Query<StatsReportQuery>,
+    caller: Caller,
+) -> impl IntoResponse {
+    if let Err(forbidden) = caller.require_staff() {
+        return forbidden.into_response();
+    }
+    let limit = params.limit.unwrap_or(50).clamp(1, 200);
+    let offset = params.offset.unwrap_or(0);
+    let min_attempts = params.min_attempts.unwrap_or(1);
+
+    let items = async {
+        let rows =
+            queries::report_rows(&state.db, params.sort, params.order, min_attempts, limit, offset)
+                .await?;
+        let ids: Vec<String> = rows.iter().map(|r| r.question_id.clone()).collect();
+        let stats = queries::list_stats(&state.db, &ids).await?;
+        let questions: Vec<QuestionDto> = question_queries::find_questions_by_ids(&state.db, &ids)
+            .await?
+            .into_iter()
+            .map(QuestionDto::from)
+            .collect();
+
+        let items: Vec<ReportItemDto> = rows
+            .into_iter()
+            .map(|row| {
+                let question = questions.iter().find(|q| q.id == row.question_id);
+                let suspect_options = question
+                    .map(|q| {
+                        summarize(q, stats.iter().filter(|s| s.question_id == q.id)).suspect_options
+                    })
+                    .unwrap_or_default();
+                ReportItemDto {
+                    prompt: question.map(|q| localized(&q.prompt, "en")),
+                    stage: question.map(|q| q.stage),
+                    difficulty: question.and_then(|q| q.difficulty),
+                    question_id: row.question_id,
+                    attempts: row.attempts,
+                    correct_rate: row.correct_rate,
+                    avg_time_ms: row.avg_time_ms,
+                    suspect_options,
+                }
+            })
+            .collect();
+        Ok::<_, mongodb::error::Error>(items)
+    };
+
+    match items.await {
+        Ok(items) => (
+            StatusCode::OK,
+            Json(StatsReportDto {
+                items,
+            }),
+        )
+            .into_response(),
+        Err(err) => {
+            error!(error = ?err, "failed to load statistics report");
+            error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to load statistics")
+        }
+    }
+}
+
+/// Calibrated questions whose manual stage disagrees most with their measured difficulty. Only
+/// questions with at least `min_answers` (default `CALIBRATION_MIN_ANSWERS`) answers take part.
+pub async fn difficulty_report(
+    State(state): State<ApiState>,
+    Query(params): Query<DifficultyReportQuery>,
+    caller: Caller,
+) -> impl IntoResponse {
+    if let Err(forbidden) = caller.require_staff() {
+        return forbidden.into_response();
+    }
+    let min_answers = params.min_answers.unwrap_or(state.config.calibration_min_answers).max(1);
+    let limit = params.limit.unwrap_or(50).clamp(1, 200);
+    let offset = params.offset.unwrap_or(0);
+
+    let questions = match question_queries::list_calibrated(&state.db, min_answers).await {
+        Ok(questions) => questions,
+        Err(err) => {
+            error!(error = ?err, "failed to load calibrated questions");
+            return error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "failed to load difficulty report",
+            );
+        }
+    };
+
+    let pairs: Vec<(i32, f64)> =
+        questions.iter().map(|q| (q.stage, q.difficulty.unwrap_or(BASE_RATING))).collect();
+    let expected = expected_stages(&pairs);
+    let mut items: Vec<DifficultyItemDto> = questions
+        .into_iter()
+        .zip(expected)
+        .map(|(q, expected_stage)| DifficultyItemDto {
+            prompt: localized(&q.prompt, "en"),
+            stage: q.stage,
+            difficulty: q.difficulty.unwrap_or(BASE_RATING),
+            difficulty_answers: q.difficulty_answers.unwrap_or(0),
+            expected_stage,
+            disagreement: q.stage - expected_stage,
+            question_id: q.id,
+        })
+        .collect();
+    items.sort_by(|a, b| {
+        b.disagreement.abs().cmp(&a.disagreement.abs()).then_with(|| a.question_id.cmp(&b.question_id))
+    });
+    let items = items.into_iter().skip(offset).take(limit).collect();
+
+    (
+        StatusCode::OK,
+        Json(DifficultyReportDto {
+            items,
+        }),
+    )
+        .into_response()
+}
+
+/// Feeds graded answers into the per-question statistics, the answer log used for difficulty
+/// calibration and the player's achievements. Failures are logged; the answer has already been
+/// graded.
+pub async fn record_answers(
+    state: &ApiState,
+    user_id: Option<&str>,
+    lang: &str,
+    outcomes: &[AnswerOutcome],
+) {
+    for outcome in outcomes {
+        if let Err(err) = queries::record_outcome(&state.db, lang, outcome).await {
+            error!(error = ?err, question_id = outcome.question_id, "failed to record answer statistics");
+        }
+    }
+
+    let now = DateTime::now();
+    let events: Vec<AnswerEvent> = outcomes
+        .iter()
+        .map(|outcome| AnswerEvent {
+            id: ObjectId::new(),
+            question_id: outcome.question_id.clone(),
+            user_id: user_id.map(str::to_string),
+            lang: lang.to_string(),
+            correct: outcome.correct,
+            answered_at: now,
+        })
+        .collect();
+    if let Err(err) = queries::insert_events(&state.db, &events).await {
+        error!(error = ?err, "failed to log answers");
+    }
+
+    if let Some(user_id) = user_id
+        && !outcomes.is_empty()
+    {
+        let correct = outcomes.iter().filter(|outcome| outcome.correct).count() as i64;
+        let answered = outcomes.len() as i64;
+        achievement_handler::record_answers(state, user_id, lang, answered, correct).await;
+        let activity = Activity {
+            answered,
+            episodes: 0,
+        };
+        streak_handler::record_activity(state, user_id, activity).await;
+    }
+}
+
+fn error_response(status: StatusCode, msg: &str) -> Response {
+    (status, Json(json!({ "error": msg }))).into_response()
+}
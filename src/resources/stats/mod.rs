@@ -0,0 +1,4 @@
+pub mod calibration;
+pub mod handler;
+pub mod model;
+pub mod queries;
@@ -0,0 +1,439 @@
+use std::collections::BTreeMap;
+
+use mongodb::bson::{DateTime, oid::ObjectId};
+use serde::{Deserialize, Serialize};
+
+use crate::resources::questions::{model::QuestionDto, types::Answer};
+
+/// Reported answer times above this are treated as the player walking away and left out.
+pub const MAX_TIME_MS: i64 = 10 * 60 * 1000;
+/// Locales with fewer attempts than this are not compared with the others.
+pub const SUSPECT_MIN_ATTEMPTS: i64 = 20;
+/// A wrong option picked at least this often in one locale...
+pub const SUSPECT_MIN_PICK_RATE: f64 = 0.3;
+/// ...and this many times as often as in all other locales together is flagged as suspect.
+pub const SUSPECT_RATIO: f64 = 2.0;
+
+/// Running answer totals for one question in one locale (collection `question_stats`, keyed by
+/// `<question_id>|<lang>`), updated as answers are graded.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct QuestionStat {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub question_id: String,
+    pub lang: String,
+    pub attempts: i64,
+    pub correct: i64,
+    /// Attempts that reported a time, and the sum of those times.
+    #[serde(default)]
+    pub timed: i64,
+    #[serde(default)]
+    pub time_ms: i64,
+    /// Attempts picking each option, keyed by its authored index. Choice types only.
+    #[serde(default)]
+    pub picks: BTreeMap<String, i64>,
+    pub updated_at: DateTime,
+}
+
+/// A graded answer as it feeds the statistics, with picks in authored positions.
+#[derive(Debug, Clone)]
+pub struct AnswerOutcome {
+    pub question_id: String,
+    pub correct: bool,
+    pub picks: Vec<usize>,
+    pub time_ms: Option<i64>,
+}
+
+impl AnswerOutcome {
+    pub fn new(question_id: &str, answer: &Answer, correct: bool, time_ms: Option<i64>) -> Self {
+        let mut picks = match answer {
+            Answer::Selected(picks) => picks.clone(),
+            _ => Vec::new(),
+        };
+        picks.sort_unstable();
+        picks.dedup();
+        Self {
+            question_id: question_id.to_string(),
+            correct,
+            picks,
+            time_ms: time_ms.filter(|ms| (0..=MAX_TIME_MS).contains(ms)),
+        }
+    }
+}
+
+/// Every graded answer, in order (collection `answer_log`). The calibration job replays it to
+/// rate questions against the players who answered them.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AnswerEvent {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    pub question_id: String,
+    pub user_id: Option<String>,
+    pub lang: String,
+    pub correct: bool,
+    pub answered_at: DateTime,
+}
+
+/// A player's ability on the question difficulty scale (collection `player_ratings`, keyed by
+/// user id), maintained by the calibration job.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PlayerRating {
+    #[serde(rename = "_id")]
+    pub user_id: String,
+    pub rating: f64,
+    pub answers: i64,
+    /// Last `answer_log` entry counted in `rating`, so a replayed batch is not counted twice.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub calibrated_through: Option<ObjectId>,
+    pub updated_at: DateTime,
+}
+
+/// How far the calibration job has replayed `answer_log` (collection `job_state`).
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JobState {
+    #[serde(rename = "_id")]
+    pub job: String,
+    pub last_id: ObjectId,
+    pub updated_at: DateTime,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OptionStatDto {
+    pub index: usize,
+    pub correct: bool,
+    pub picks: i64,
+    /// Share of attempts that picked this option.
+    pub pick_rate: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StatsDto {
+    pub attempts: i64,
+    pub correct_rate: Option<f64>,
+    pub avg_time_ms: Option<f64>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub options: Vec<OptionStatDto>,
+}
+
+/// A wrong option drawing far more picks in one locale than elsewhere; usually a translation
+/// problem.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct SuspectOptionDto {
+    pub lang: String,
+    pub index: usize,
+    pub pick_rate: f64,
+    pub elsewhere_pick_rate: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct QuestionStatsDto {
+    pub question_id: String,
+    #[serde(flatten)]
+    pub totals: StatsDto,
+    pub locales: BTreeMap<String, StatsDto>,
+    pub suspect_options: Vec<SuspectOptionDto>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportSort {
+    #[default]
+    Attempts,
+    CorrectRate,
+    AvgTime,
+}
+
+impl ReportSort {
+    pub fn field(self) -> &'static str {
+        match self {
+            ReportSort::Attempts => "attempts",
+            ReportSort::CorrectRate => "correct_rate",
+            ReportSort::AvgTime => "avg_time_ms",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortOrder {
+    Asc,
+    #[default]
+    Desc,
+}
+
+impl SortOrder {
+    pub fn direction(self) -> i32 {
+        match self {
+            SortOrder::Asc => 1,
+            SortOrder::Desc => -1,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StatsReportQuery {
+    #[serde(default)]
+    pub sort: ReportSort,
+    #[serde(default)]
+    pub order: SortOrder,
+    pub min_attempts: Option<i64>,
+    pub limit: Option<i64>,
+    pub offset: Option<u64>,
+}
+
+/// Raw aggregation row: all locales of one question.
+#[derive(Debug, Deserialize)]
+pub struct ReportRow {
+    pub question_id: String,
+    pub attempts: i64,
+    pub correct_rate: f64,
+    pub avg_time_ms: Option<f64>,
+}
+
+/// Raw aggregation row: one player's answers to one question.
+#[derive(Debug, Deserialize)]
+pub struct PlayerQuestionTotals {
+    pub question_id: String,
+    pub answered: i64,
+    pub correct: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReportItemDto {
+    pub question_id: String,
+    pub prompt: Option<String>,
+    pub stage: Option<i32>,
+    pub difficulty: Option<f64>,
+    pub attempts: i64,
+    pub correct_rate: f64,
+    pub avg_time_ms: Option<f64>,
+    pub suspect_options: Vec<SuspectOptionDto>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StatsReportDto {
+    pub items: Vec<ReportItemDto>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DifficultyReportQuery {
+    pub min_answers: Option<i64>,
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+}
+
+/// A calibrated question next to the stage its measured difficulty suggests. A positive
+/// `disagreement` means it is staged later than it plays, i.e. easier than its stage.
+#[derive(Debug, Serialize)]
+pub struct DifficultyItemDto {
+    pub question_id: String,
+    pub prompt: String,
+    pub stage: i32,
+    pub difficulty: f64,
+    pub difficulty_answers: i64,
+    pub expected_stage: i32,
+    pub disagreement: i32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DifficultyReportDto {
+    pub items: Vec<DifficultyItemDto>,
+}
+
+#[derive(Default)]
+struct Tally {
+    attempts: i64,
+    correct: i64,
+    timed: i64,
+    time_ms: i64,
+    picks: BTreeMap<usize, i64>,
+}
+
+impl Tally {
+    fn add(&mut self, stat: &QuestionStat) {
+        self.attempts += stat.attempts;
+        self.correct += stat.correct;
+        self.timed += stat.timed;
+        self.time_ms += stat.time_ms;
+        for (index, count) in &stat.picks {
+            if let Ok(index) = index.parse() {
+                *self.picks.entry(index).or_default() += count;
+            }
+        }
+    }
+
+    fn pick_rate(&self, index: usize) -> Option<f64> {
+        rate(self.picks.get(&index).copied().unwrap_or(0), self.attempts)
+    }
+
+    fn to_dto(&self, question: &QuestionDto) -> StatsDto {
+        let options = if question.question_type.is_choice() {
+            question
+                .options
+                .iter()
+                .enumerate()
+                .map(|(index, option)| OptionStatDto {
+                    index,
+                    correct: option.correct,
+                    picks: self.picks.get(&index).copied().unwrap_or(0),
+                    pick_rate: self.pick_rate(index),
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+        StatsDto {
+            attempts: self.attempts,
+            correct_rate: rate(self.correct, self.attempts),
+            avg_time_ms: rate(self.time_ms, self.timed),
+            options,
+        }
+    }
+}
+
+fn rate(count: i64, total: i64) -> Option<f64> {
+    (total > 0).then(|| count as f64 / total as f64)
+}
+
+/// Folds a question's per-locale totals into overall and per-locale figures and flags wrong
+/// options picked disproportionately often in one locale.
+pub fn summarize<'a>(
+    question: &QuestionDto,
+    stats: impl IntoIterator<Item = &'a QuestionStat>,
+) -> QuestionStatsDto {
+    let mut totals = Tally::default();
+    let mut locales: BTreeMap<&'a str, Tally> = BTreeMap::new();
+    for stat in stats {
+        totals.add(stat);
+        locales.entry(&stat.lang).or_default().add(stat);
+    }
+
+    let mut suspect_options = Vec::new();
+    if question.question_type.is_choice() {
+        for (lang, tally) in &locales {
+            let elsewhere_attempts = totals.attempts - tally.attempts;
+            if tally.attempts < SUSPECT_MIN_ATTEMPTS || elsewhere_attempts < SUSPECT_MIN_ATTEMPTS {
+                continue;
+            }
+            for (index, option) in question.options.iter().enumerate() {
+                if option.correct {
+                    continue;
+                }
+                let picks = tally.picks.get(&index).copied().unwrap_or(0);
+                let elsewhere_picks = totals.picks.get(&index).copied().unwrap_or(0) - picks;
+                let pick_rate = picks as f64 / tally.attempts as f64;
+                let elsewhere_pick_rate = elsewhere_picks as f64 / elsewhere_attempts as f64;
+                if pick_rate >= SUSPECT_MIN_PICK_RATE && pick_rate >= SUSPECT_RATIO * elsewhere_pick_rate
+                {
+                    suspect_options.push(SuspectOptionDto {
+                        lang: lang.to_string(),
+                        index,
+                        pick_rate,
+                        elsewhere_pick_rate,
+                    });
+                }
+            }
+        }
+    }
+
+    QuestionStatsDto {
+        question_id: question.id.clone(),
+        totals: totals.to_dto(question),
+        locales: locales
+            .iter()
+            .map(|(lang, tally)| (lang.to_string(), tally.to_dto(question)))
+            .collect(),
+        suspect_options,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use mongodb::bson::DateTime;
+
+    use super::{AnswerOutcome, QuestionStat, summarize};
+    use crate::resources::{
+        questions::{
+            model::{LocalizedText, OptionDto, QuestionDto},
+            types::{Answer, QuestionType},
+        },
+        workflow::model::ContentStatus,
+    };
+
+    fn question() -> QuestionDto {
+        let option = |correct: bool| OptionDto {
+            text: LocalizedText::new(),
+            correct,
+            explanation: None,
+        };
+        QuestionDto {
+            id: "q1".into(),
+            question_type: QuestionType::SingleChoice,
+            stage: 1,
+            stage_label: None,
+            prompt: LocalizedText::new(),
+            options: vec![option(true), option(false), option(false), option(false)],
+            pairs: Vec::new(),
+            answers: Vec::new(),
+            era: None,
+            level: None,
+            time_limit_secs: None,
+            difficulty: None,
+            difficulty_answers: None,
+            tags: Vec::new(),
+            image_url: None,
+            status: ContentStatus::Published,
+            pending_status: None,
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+
+    fn stat(lang: &str, attempts: i64, correct: i64, picks: &[(usize, i64)]) -> QuestionStat {
+        QuestionStat {
+            id: format!("q1|{lang}"),
+            question_id: "q1".into(),
+            lang: lang.into(),
+            attempts,
+            correct,
+            timed: attempts,
+            time_ms: attempts * 4000,
+            picks: picks
+                .iter()
+                .map(|(index, count)| (index.to_string(), *count))
+                .collect::<BTreeMap<_, _>>(),
+            updated_at: DateTime::now(),
+        }
+    }
+
+    #[test]
+    fn flags_a_distractor_popular_in_one_locale() {
+        let stats = vec![
+            stat("en", 50, 40, &[(0, 40), (1, 5), (2, 3), (3, 2)]),
+            stat("es", 40, 30, &[(0, 30), (1, 4), (2, 4), (3, 2)]),
+            stat("pt", 30, 12, &[(0, 12), (1, 15), (2, 2), (3, 1)]),
+        ];
+        let summary = summarize(&question(), &stats);
+
+        assert_eq!(summary.totals.attempts, 120);
+        assert_eq!(summary.totals.correct_rate, Some(82.0 / 120.0));
+        assert_eq!(summary.totals.avg_time_ms, Some(4000.0));
+        assert_eq!(summary.totals.options[1].picks, 24);
+        assert_eq!(summary.locales["pt"].options[1].pick_rate, Some(0.5));
+        assert_eq!(summary.suspect_options.len(), 1);
+        assert_eq!(summary.suspect_options[0].lang, "pt");
+        assert_eq!(summary.suspect_options[0].index, 1);
+    }
+
+    #[test]
+    fn outcomes_ignore_implausible_times() {
+        let outcome = AnswerOutcome::new("q1", &Answer::Selected(vec![2, 0, 2]), false, Some(-5));
+        assert_eq!(outcome.picks, vec![0, 2]);
+        assert_eq!(outcome.time_ms, None);
+        let outcome = AnswerOutcome::new("q1", &Answer::Text("wept".into()), true, Some(3000));
+        assert!(outcome.picks.is_empty());
+        assert_eq!(outcome.time_ms, Some(3000));
+    }
+}
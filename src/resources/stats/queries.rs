@@ -0,0 +1,209 @@
+use futures_util::TryStreamExt;
+use mongodb::{
+    Collection, Database, IndexModel,
+    bson::{self, DateTime, Document, doc, oid::ObjectId},
+    options::{FindOptions, ReplaceOptions, UpdateOptions},
+};
+
+use crate::resources::stats::model::{
+    AnswerEvent, AnswerOutcome, JobState, PlayerQuestionTotals, PlayerRating, QuestionStat, ReportRow,
+    ReportSort, SortOrder,
+};
+
+fn stats_collection(db: &Database) -> Collection<QuestionStat> {
+    db.collection("question_stats")
+}
+
+fn log_collection(db: &Database) -> Collection<AnswerEvent> {
+    db.collection("answer_log")
+}
+
+fn ratings_collection(db: &Database) -> Collection<PlayerRating> {
+    db.collection("player_ratings")
+}
+
+fn job_state_collection(db: &Database) -> Collection<JobState> {
+    db.collection("job_state")
+}
+
+/// Indexes backing per-question lookups and per-player totals.
+pub async fn ensure_indexes(db: &Database) -> mongodb::error::Result<()> {
+    let index = IndexModel::builder().keys(doc! { "question_id": 1 }).build();
+    stats_collection(db).create_index(index, None).await?;
+    let index = IndexModel::builder().keys(doc! { "user_id": 1 }).build();
+    log_collection(db).create_index(index, None).await?;
+    Ok(())
+}
+
+/// Adds one graded answer to its question's totals for `lang`.
+pub async fn record_outcome(
+    db: &Database,
+    lang: &str,
+    outcome: &AnswerOutcome,
+) -> mongodb::error::Result<()> {
+    let mut inc = doc! { "attempts": 1_i64, "correct": i64::from(outcome.correct) };
+    if let Some(time_ms) = outcome.time_ms {
+        inc.insert("timed", 1_i64);
+        inc.insert("time_ms", time_ms);
+    }
+    for index in &outcome.picks {
+        inc.insert(format!("picks.{index}"), 1_i64);
+    }
+    let update = doc! {
+        "$inc": inc,
+        "$set": { "updated_at": DateTime::now() },
+        "$setOnInsert": { "question_id": &outcome.question_id, "lang": lang },
+    };
+    let id = format!("{}|{}", outcome.question_id, lang);
+    let options = UpdateOptions::builder().upsert(true).build();
+    stats_collection(db).update_one(doc! { "_id": id }, update, options).await?;
+    Ok(())
+}
+
+/// Per-locale totals for the given questions.
+pub async fn list_stats(
+    db: &Database,
+    question_ids: &[String],
+) -> mongodb::error::Result<Vec<QuestionStat>> {
+    let options = FindOptions::builder().sort(doc! { "_id": 1 }).build();
+    let mut cursor =
+        stats_collection(db).find(doc! { "question_id": { "$in": question_ids } }, options).await?;
+    let mut stats = Vec::new();
+    while let Some(stat) = cursor.try_next().await? {
+        stats.push(stat);
+    }
+    Ok(stats)
+}
+
+/// One page of questions with their totals across locales, sorted by `sort`. Ties go by id so
+/// pages stay stable.
+pub async fn report_rows(
+    db: &Database,
+    sort: ReportSort,
+    order: SortOrder,
+    min_attempts: i64,
+    limit: i64,
+    offset: u64,
+) -> mongodb::error::Result<Vec<ReportRow>> {
+    let mut sort_doc = Document::new();
+    sort_doc.insert(sort.field(), order.direction());
+    sort_doc.insert("question_id", 1);
+
+    let pipeline = vec![
+        doc! {
+            "$group": {
+                "_id": "$question_id",
+                "attempts": { "$sum": "$attempts" },
+                "correct": { "$sum": "$correct" },
+                "timed": { "$sum": "$timed" },
+                "time_ms": { "$sum": "$time_ms" },
+            }
+        },
+        doc! { "$match": { "attempts": { "$gte": min_attempts.max(1) } } },
+        doc! {
+            "$project": {
+                "_id": 0,
+                "question_id": "$_id",
+                "attempts": 1,
+                "correct_rate": { "$divide": ["$correct", "$attempts"] },
+                "avg_time_ms": {
+                    "$cond": [{ "$gt": ["$timed", 0] }, { "$divide": ["$time_ms", "$timed"] }, null]
+                },
+            }
+        },
+        doc! { "$sort": sort_doc },
+        doc! { "$skip": offset as i64 },
+        doc! { "$limit": limit },
+    ];
+
+    let mut cursor = stats_collection(db).aggregate(pipeline, None).await?;
+    let mut rows = Vec::new();
+    while let Some(row) = cursor.try_next().await? {
+        rows.push(bson::from_document(row)?);
+    }
+    Ok(rows)
+}
+
+pub async fn insert_events(db: &Database, events: &[AnswerEvent]) -> mongodb::error::Result<()> {
+    if events.is_empty() {
+        return Ok(());
+    }
+    log_collection(db).insert_many(events, None).await?;
+    Ok(())
+}
+
+/// How often the player answered each question, and how often correctly.
+pub async fn player_question_totals(
+    db: &Database,
+    user_id: &str,
+) -> mongodb::error::Result<Vec<PlayerQuestionTotals>> {
+    let pipeline = vec![
+        doc! { "$match": { "user_id": user_id } },
+        doc! {
+            "$group": {
+                "_id": "$question_id",
+                "answered": { "$sum": 1 },
+                "correct": { "$sum": { "$cond": ["$correct", 1, 0] } },
+            }
+        },
+        doc! { "$project": { "_id": 0, "question_id": "$_id", "answered": 1, "correct": 1 } },
+    ];
+    let mut cursor = log_collection(db).aggregate(pipeline, None).await?;
+    let mut totals = Vec::new();
+    while let Some(row) = cursor.try_next().await? {
+        totals.push(bson::from_document(row)?);
+    }
+    Ok(totals)
+}
+
+/// Up to `limit` answers by signed-in players logged after `after`, oldest first.
+pub async fn events_after(
+    db: &Database,
+    after: Option<ObjectId>,
+    limit: i64,
+) -> mongodb::error::Result<Vec<AnswerEvent>> {
+    let mut filter = doc! { "user_id": { "$ne": null } };
+    if let Some(after) = after {
+        filter.insert("_id", doc! { "$gt": after });
+    }
+    let options = FindOptions::builder().sort(doc! { "_id": 1 }).limit(Some(limit)).build();
+    let mut cursor = log_collection(db).find(filter, options).await?;
+    let mut events = Vec::new();
+    while let Some(event) = cursor.try_next().await? {
+        events.push(event);
+    }
+    Ok(events)
+}
+
+pub async fn find_job_state(db: &Database, job: &str) -> mongodb::error::Result<Option<JobState>> {
+    job_state_collection(db).find_one(doc! { "_id": job }, None).await
+}
+
+pub async fn save_job_state(db: &Database, job: &str, last_id: ObjectId) -> mongodb::error::Result<()> {
+    let state = JobState {
+        job: job.to_string(),
+        last_id,
+        updated_at: DateTime::now(),
+    };
+    let options = ReplaceOptions::builder().upsert(true).build();
+    job_state_collection(db).replace_one(doc! { "_id": job }, &state, options).await?;
+    Ok(())
+}
+
+pub async fn find_player_ratings(
+    db: &Database,
+    user_ids: &[String],
+) -> mongodb::error::Result<Vec<PlayerRating>> {
+    let mut cursor = ratings_collection(db).find(doc! { "_id": { "$in": user_ids } }, None).await?;
+    let mut ratings = Vec::new();
+    while let Some(rating) = cursor.try_next().await? {
+        ratings.push(rating);
+    }
+    Ok(ratings)
+}
+
+pub async fn save_player_rating(db: &Database, rating: &PlayerRating) -> mongodb::error::Result<()> {
+    let options = ReplaceOptions::builder().upsert(true).build();
+    ratings_collection(db).replace_one(doc! { "_id": &rating.user_id }, rating, options).await?;
+    Ok(())
+}
@@ -0,0 +1,183 @@
+use axum::routing::{MethodRouter, get as axum_get};
+use axum::{
+    Json,
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use chrono::Utc;
+use chrono_tz::Tz;
+use serde_json::json;
+use tracing::error;
+
+use crate::{
+    auth::Caller,
+    resources::{
+        achievements::handler as achievement_handler,
+        challenges::model::DATE_FORMAT,
+        streaks::{
+            model::{
+                Activity, DayDto, FreezeRules, FreezesDto, HISTORY_DAYS, Streak, StreakDto,
+                UpdateStreakSettings, local_day, recent_days,
+            },
+            queries,
+        },
+    },
+    routes::api::ApiState,
+};
+
+pub fn mine() -> MethodRouter<ApiState> {
+    axum_get(my_streak).put(update_settings)
+}
+
+/// The caller's streak, today's progress towards the daily goal and the recent history.
+pub async fn my_streak(State(state): State<ApiState>, caller: Caller) -> impl IntoResponse {
+    let user_id = match caller.require_user() {
+        Ok(user_id) => user_id,
+        Err(forbidden) => return forbidden.into_response(),
+    };
+    match streak_dto(&state, user_id).await {
+        Ok(dto) => (StatusCode::OK, Json(dto)).into_response(),
+        Err(err) => {
+            error!(error = ?err, "failed to load streak");
+            error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to load streak")
+        }
+    }
+}
+
+/// Sets the caller's timezone and daily goal. Days already counted keep their dates.
+pub async fn update_settings(
+    State(state): State<ApiState>,
+    caller: Caller,
+    Json(payload): Json<UpdateStreakSettings>,
+) -> impl IntoResponse {
+    let user_id = match caller.require_user() {
+        Ok(user_id) => user_id,
+        Err(forbidden) => return forbidden.into_response(),
+    };
+    if let Err(msg) = payload.validate() {
+        return error_response(StatusCode::BAD_REQUEST, msg);
+    }
+    if let Err(err) =
+        queries::save_settings(&state.db, user_id, payload.timezone.as_deref(), payload.daily_goal).await
+    {
+        error!(error = ?err, "failed to save streak settings");
+        return error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to save streak settings");
+    }
+    match streak_dto(&state, user_id).await {
+        Ok(dto) => (StatusCode::OK, Json(dto)).into_response(),
+        Err(err) => {
+            error!(error = ?err, "failed to load streak");
+            error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to load streak")
+        }
+    }
+}
+
+/// Adds activity to the player's day, advancing the streak when it first meets the daily goal.
+/// Failures are logged; the activity itself is already recorded elsewhere.
+pub async fn record_activity(state: &ApiState, user_id: &str, activity: Activity) {
+    match advance(state, user_id, activity).await {
+        Ok(Some(longest)) => achievement_handler::record_streak(state, user_id, longest).await,
+        Ok(None) => {}
+        Err(err) => error!(error = ?err, user_id, "failed to record streak activity"),
+    }
+}
+
+/// Returns the longest streak when today's goal was just met.
+async fn advance(
+    state: &ApiState,
+    user_id: &str,
+    activity: Activity,
+) -> mongodb::error::Result<Option<i64>> {
+    let mut streak = load_streak(state, user_id).await?;
+    let today = local_day(Utc::now(), timezone(state, &streak));
+    let day = today.format(DATE_FORMAT).to_string();
+    let Some(totals) = queries::record_activity(&state.db, user_id, &day, activity).await? else {
+        return Ok(None);
+    };
+    // `advance` ignores days already counted, so only the first activity past the goal saves.
+    if totals.total() < daily_goal(state, &streak) || !streak.advance(today, freeze_rules(state)) {
+        return Ok(None);
+    }
+    queries::save_progress(&state.db, &streak).await?;
+    Ok(Some(streak.longest))
+}
+
+async fn streak_dto(state: &ApiState, user_id: &str) -> mongodb::error::Result<StreakDto> {
+    let streak = load_streak(state, user_id).await?;
+    let tz = timezone(state, &streak);
+    let goal = daily_goal(state, &streak);
+    let today = local_day(Utc::now(), tz);
+    let days = recent_days(today, HISTORY_DAYS);
+    let from = days.first().unwrap_or(&today).format(DATE_FORMAT).to_string();
+    let to = today.format(DATE_FORMAT).to_string();
+    let activity = queries::list_activity(&state.db, user_id, &from, &to).await?;
+
+    let history: Vec<DayDto> = days
+        .iter()
+        .map(|day| {
+            let day = day.format(DATE_FORMAT).to_string();
+            let totals = activity.iter().find(|a| a.day == day);
+            let (answered, episodes) = totals.map_or((0, 0), |a| (a.answered, a.episodes));
+            DayDto {
+                frozen: streak.frozen_days.contains(&day),
+                goal_met: answered + episodes >= goal,
+                day,
+                answered,
+                episodes,
+            }
+        })
+        .collect();
+    let standing = streak.standing(today);
+    Ok(StreakDto {
+        timezone: tz.name().to_string(),
+        daily_goal: goal,
+        today: history.last().cloned().unwrap_or(DayDto {
+            day: to,
+            answered: 0,
+            episodes: 0,
+            goal_met: false,
+            frozen: false,
+        }),
+        current: standing.current,
+        longest: streak.longest,
+        at_risk: standing.at_risk,
+        last_goal_day: streak.last_day,
+        freezes: FreezesDto {
+            available: streak.freezes,
+            max: state.config.streak_max_freezes,
+            needed: standing.freezes_needed,
+        },
+        history,
+    })
+}
+
+async fn load_streak(state: &ApiState, user_id: &str) -> mongodb::error::Result<Streak> {
+    Ok(queries::find_streak(&state.db, user_id).await?.unwrap_or_else(|| Streak {
+        user_id: user_id.to_string(),
+        ..Default::default()
+    }))
+}
+
+fn timezone(state: &ApiState, streak: &Streak) -> Tz {
+    streak
+        .timezone
+        .as_deref()
+        .and_then(|timezone| timezone.parse().ok())
+        .unwrap_or(state.config.challenge_timezone)
+}
+
+fn daily_goal(state: &ApiState, streak: &Streak) -> i64 {
+    streak.daily_goal.unwrap_or(state.config.streak_daily_goal)
+}
+
+fn freeze_rules(state: &ApiState) -> FreezeRules {
+    FreezeRules {
+        max: state.config.streak_max_freezes,
+        every_days: state.config.streak_freeze_every_days,
+    }
+}
+
+fn error_response(status: StatusCode, msg: &str) -> Response {
+    (status, Json(json!({ "error": msg }))).into_response()
+}
@@ -0,0 +1,276 @@
+use chrono::{Days, NaiveDate, Utc};
+use chrono_tz::Tz;
+use mongodb::bson::DateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::resources::challenges::model::DATE_FORMAT;
+
+pub const MIN_DAILY_GOAL: i64 = 1;
+pub const MAX_DAILY_GOAL: i64 = 200;
+/// Days of activity shown in the streak history, today included.
+pub const HISTORY_DAYS: u64 = 14;
+/// Frozen days remembered on the streak, most recent last.
+pub const FROZEN_DAYS_KEPT: usize = 30;
+
+/// What a player did on one day in their timezone (collection `daily_activity`, keyed by
+/// `<user_id>|<day>`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyActivity {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub user_id: String,
+    /// `YYYY-MM-DD`.
+    pub day: String,
+    #[serde(default)]
+    pub answered: i64,
+    #[serde(default)]
+    pub episodes: i64,
+    pub updated_at: DateTime,
+}
+
+impl DailyActivity {
+    /// Progress towards the daily goal: questions answered plus episodes read.
+    pub fn total(&self) -> i64 {
+        self.answered + self.episodes
+    }
+}
+
+pub fn activity_id(user_id: &str, day: &str) -> String {
+    format!("{user_id}|{day}")
+}
+
+/// Activity to add to today's totals.
+#[derive(Debug, Clone, Copy)]
+pub struct Activity {
+    pub answered: i64,
+    pub episodes: i64,
+}
+
+/// One episode read.
+pub const EPISODE_READ: Activity = Activity {
+    answered: 0,
+    episodes: 1,
+};
+
+/// A player's streak and settings (collection `streaks`, keyed by user id). The streak advances
+/// the first time each day's goal is met, so reading it never replays activity.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Streak {
+    #[serde(rename = "_id")]
+    pub user_id: String,
+    /// IANA timezone the player's days are counted in; the server default when unset.
+    pub timezone: Option<String>,
+    /// Questions plus episodes per day; the server default when unset.
+    pub daily_goal: Option<i64>,
+    #[serde(default)]
+    pub current: i64,
+    #[serde(default)]
+    pub longest: i64,
+    /// Last day the goal was met.
+    pub last_day: Option<String>,
+    /// Freezes held, each covering one missed day.
+    #[serde(default)]
+    pub freezes: i64,
+    /// Missed days covered by freezes.
+    #[serde(default)]
+    pub frozen_days: Vec<String>,
+    pub updated_at: Option<DateTime>,
+}
+
+/// How freezes are earned and capped.
+#[derive(Debug, Clone, Copy)]
+pub struct FreezeRules {
+    /// Most freezes a player can hold.
+    pub max: i64,
+    /// A freeze is earned every this many streak days (`0` never).
+    pub every_days: i64,
+}
+
+impl Streak {
+    fn last_day(&self) -> Option<NaiveDate> {
+        self.last_day.as_deref().and_then(|day| NaiveDate::parse_from_str(day, DATE_FORMAT).ok())
+    }
+
+    /// Counts `day` as a goal day. Missed days since the last goal day are covered by freezes when
+    /// enough are held; otherwise the streak starts over. Returns `false` when `day` was already
+    /// counted (or precedes the last goal day, after a timezone change).
+    pub fn advance(&mut self, day: NaiveDate, rules: FreezeRules) -> bool {
+        match self.last_day() {
+            Some(last) if day <= last => return false,
+            Some(last) => {
+                let missed = (day - last).num_days() - 1;
+                if missed == 0 {
+                    self.current += 1;
+                } else if missed <= self.freezes {
+                    self.freezes -= missed;
+                    self.frozen_days.extend(
+                        last.iter_days()
+                            .skip(1)
+                            .take(missed as usize)
+                            .map(|d| d.format(DATE_FORMAT).to_string()),
+                    );
+                    let excess = self.frozen_days.len().saturating_sub(FROZEN_DAYS_KEPT);
+                    self.frozen_days.drain(..excess);
+                    self.current += 1;
+                } else {
+                    self.current = 1;
+                }
+            }
+            None => self.current = 1,
+        }
+        self.longest = self.longest.max(self.current);
+        self.last_day = Some(day.format(DATE_FORMAT).to_string());
+        if rules.every_days > 0 && self.current % rules.every_days == 0 && self.freezes < rules.max {
+            self.freezes += 1;
+        }
+        true
+    }
+
+    /// Where the streak stands on `today`, before today's goal is met.
+    pub fn standing(&self, today: NaiveDate) -> Standing {
+        let Some(last) = self.last_day() else {
+            return Standing::default();
+        };
+        let missed = (today - last).num_days() - 1;
+        if missed < 0 {
+            Standing {
+                current: self.current,
+                at_risk: false,
+                freezes_needed: 0,
+            }
+        } else if missed <= self.freezes {
+            Standing {
+                current: self.current,
+                at_risk: true,
+                freezes_needed: missed,
+            }
+        } else {
+            Standing::default()
+        }
+    }
+}
+
+/// The streak as shown: `current` is `0` once it is broken beyond what freezes cover. An
+/// `at_risk` streak ends unless today's goal is met, spending `freezes_needed` freezes.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Standing {
+    pub current: i64,
+    pub at_risk: bool,
+    pub freezes_needed: i64,
+}
+
+/// The calendar day `now` falls on in `tz`.
+pub fn local_day(now: chrono::DateTime<Utc>, tz: Tz) -> NaiveDate {
+    now.with_timezone(&tz).date_naive()
+}
+
+/// The `count` days ending on `today`, oldest first.
+pub fn recent_days(today: NaiveDate, count: u64) -> Vec<NaiveDate> {
+    let first = today.checked_sub_days(Days::new(count.saturating_sub(1))).unwrap_or(today);
+    first.iter_days().take(count as usize).collect()
+}
+
+/// Body of `PUT /v1/me/streak`; fields left out are unchanged.
+#[derive(Deserialize)]
+pub struct UpdateStreakSettings {
+    pub timezone: Option<String>,
+    pub daily_goal: Option<i64>,
+}
+
+impl UpdateStreakSettings {
+    /// Checks the update, returning the timezone if one was given.
+    pub fn validate(&self) -> Result<Option<Tz>, &'static str> {
+        if let Some(goal) = self.daily_goal
+            && !(MIN_DAILY_GOAL..=MAX_DAILY_GOAL).contains(&goal)
+        {
+            return Err("daily_goal must be between 1 and 200");
+        }
+        match self.timezone.as_deref() {
+            Some(timezone) => timezone.parse().map(Some).map_err(|_| "unknown timezone"),
+            None => Ok(None),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct StreakDto {
+    pub timezone: String,
+    pub daily_goal: i64,
+    pub today: DayDto,
+    pub current: i64,
+    pub longest: i64,
+    pub at_risk: bool,
+    pub last_goal_day: Option<String>,
+    pub freezes: FreezesDto,
+    /// The last days, oldest first, today included.
+    pub history: Vec<DayDto>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FreezesDto {
+    pub available: i64,
+    pub max: i64,
+    /// Freezes today's goal would spend to keep the streak.
+    pub needed: i64,
+}
+
+/// One day's activity. `goal_met` is measured against the current goal.
+#[derive(Debug, Clone, Serialize)]
+pub struct DayDto {
+    pub day: String,
+    pub answered: i64,
+    pub episodes: i64,
+    pub goal_met: bool,
+    pub frozen: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::{FreezeRules, Standing, Streak, recent_days};
+
+    fn day(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, d).unwrap()
+    }
+
+    const RULES: FreezeRules = FreezeRules {
+        max: 2,
+        every_days: 3,
+    };
+
+    #[test]
+    fn streaks_grow_and_earn_freezes() {
+        let mut streak = Streak::default();
+        for d in 1..=3 {
+            assert!(streak.advance(day(d), RULES));
+        }
+        assert!(!streak.advance(day(3), RULES));
+        assert_eq!((streak.current, streak.longest, streak.freezes), (3, 3, 1));
+
+        // One missed day is covered by the freeze.
+        assert_eq!(
+            streak.standing(day(5)),
+            Standing {
+                current: 3,
+                at_risk: true,
+                freezes_needed: 1,
+            }
+        );
+        streak.advance(day(5), RULES);
+        assert_eq!((streak.current, streak.freezes), (4, 0));
+        assert_eq!(streak.frozen_days, vec!["2026-10-04".to_string()]);
+
+        // Two missed days without freezes break it.
+        assert_eq!(streak.standing(day(8)), Standing::default());
+        streak.advance(day(8), RULES);
+        assert_eq!((streak.current, streak.longest), (1, 4));
+        assert!(!streak.standing(day(8)).at_risk);
+    }
+
+    #[test]
+    fn history_ends_today() {
+        let days = recent_days(day(14), 3);
+        assert_eq!(days, vec![day(12), day(13), day(14)]);
+    }
+}
@@ -0,0 +1,101 @@
+use futures_util::TryStreamExt;
+use mongodb::{
+    Collection, Database, IndexModel,
+    bson::{DateTime, Document, doc},
+    options::{FindOneAndUpdateOptions, ReturnDocument, UpdateOptions},
+};
+
+use crate::resources::streaks::model::{Activity, DailyActivity, Streak, activity_id};
+
+fn activity_collection(db: &Database) -> Collection<DailyActivity> {
+    db.collection("daily_activity")
+}
+
+fn streaks_collection(db: &Database) -> Collection<Streak> {
+    db.collection("streaks")
+}
+
+/// Index backing the streak history.
+pub async fn ensure_indexes(db: &Database) -> mongodb::error::Result<()> {
+    let index = IndexModel::builder().keys(doc! { "user_id": 1, "day": 1 }).build();
+    activity_collection(db).create_index(index, None).await?;
+    Ok(())
+}
+
+pub async fn find_streak(db: &Database, user_id: &str) -> mongodb::error::Result<Option<Streak>> {
+    streaks_collection(db).find_one(doc! { "_id": user_id }, None).await
+}
+
+/// Adds `activity` to the player's totals for `day`, returning the updated totals.
+pub async fn record_activity(
+    db: &Database,
+    user_id: &str,
+    day: &str,
+    activity: Activity,
+) -> mongodb::error::Result<Option<DailyActivity>> {
+    let update = doc! {
+        "$inc": { "answered": activity.answered, "episodes": activity.episodes },
+        "$set": { "updated_at": DateTime::now() },
+        "$setOnInsert": { "user_id": user_id, "day": day },
+    };
+    let options =
+        FindOneAndUpdateOptions::builder().upsert(true).return_document(ReturnDocument::After).build();
+    activity_collection(db)
+        .find_one_and_update(doc! { "_id": activity_id(user_id, day) }, update, options)
+        .await
+}
+
+/// The player's activity from `from` to `to` (`YYYY-MM-DD`, inclusive), oldest first.
+pub async fn list_activity(
+    db: &Database,
+    user_id: &str,
+    from: &str,
+    to: &str,
+) -> mongodb::error::Result<Vec<DailyActivity>> {
+    let filter = doc! { "user_id": user_id, "day": { "$gte": from, "$lte": to } };
+    let mut cursor = activity_collection(db).find(filter, None).await?;
+    let mut days = Vec::new();
+    while let Some(day) = cursor.try_next().await? {
+        days.push(day);
+    }
+    days.sort_by(|a, b| a.day.cmp(&b.day));
+    Ok(days)
+}
+
+/// Stores the streak counters, leaving the player's settings alone.
+pub async fn save_progress(db: &Database, streak: &Streak) -> mongodb::error::Result<()> {
+    let update = doc! {
+        "$set": {
+            "current": streak.current,
+            "longest": streak.longest,
+            "last_day": &streak.last_day,
+            "freezes": streak.freezes,
+            "frozen_days": &streak.frozen_days,
+            "updated_at": DateTime::now(),
+        }
+    };
+    upsert(db, &streak.user_id, update).await
+}
+
+/// Sets the player's timezone and daily goal; `None` leaves a setting unchanged.
+pub async fn save_settings(
+    db: &Database,
+    user_id: &str,
+    timezone: Option<&str>,
+    daily_goal: Option<i64>,
+) -> mongodb::error::Result<()> {
+    let mut set = doc! { "updated_at": DateTime::now() };
+    if let Some(timezone) = timezone {
+        set.insert("timezone", timezone);
+    }
+    if let Some(daily_goal) = daily_goal {
+        set.insert("daily_goal", daily_goal);
+    }
+    upsert(db, user_id, doc! { "$set": set }).await
+}
+
+async fn upsert(db: &Database, user_id: &str, update: Document) -> mongodb::error::Result<()> {
+    let options = UpdateOptions::builder().upsert(true).build();
+    streaks_collection(db).update_one(doc! { "_id": user_id }, update, options).await?;
+    Ok(())
+}
@@ -0,0 +1,284 @@
+use axum::routing::{MethodRouter, get as axum_get, post as axum_post};
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::error;
+
+use crate::{
+    auth::Caller,
+    resources::{
+        questions::{
+            handler::{self as question_handler, WriteQuestion},
+            model::QuestionDto,
+            queries as question_queries,
+        },
+        revisions::model::ACTION_CREATE,
+        submissions::{
+            model::{
+                Decision, DecisionRequest, SubmissionDto, SubmissionStatus, SubmitQuestion,
+                validate_submission,
+            },
+            queries,
+        },
+    },
+    routes::api::ApiState,
+};
+
+pub fn collection() -> MethodRouter<ApiState> {
+    axum_get(list_own_submissions).post(create_submission)
+}
+
+pub fn submission() -> MethodRouter<ApiState> {
+    axum_get(get_submission).put(update_submission)
+}
+
+pub fn decision() -> MethodRouter<ApiState> {
+    axum_post(decide_submission)
+}
+
+pub fn queue() -> MethodRouter<ApiState> {
+    axum_get(moderation_queue)
+}
+
+#[derive(Deserialize)]
+pub struct ListQuery {
+    pub status: Option<SubmissionStatus>,
+    pub limit: Option<u64>,
+    pub offset: Option<u64>,
+}
+
+#[derive(Serialize)]
+pub struct SubmissionsList {
+    pub items: Vec<SubmissionDto>,
+}
+
+pub async fn create_submission(
+    State(state): State<ApiState>,
+    caller: Caller,
+    Json(payload): Json<SubmitQuestion>,
+) -> impl IntoResponse {
+    let submitter = match caller.require_user() {
+        Ok(user_id) => user_id,
+        Err(forbidden) => return forbidden.into_response(),
+    };
+    if let Err(msg) = validate_submission(&payload.question) {
+        return error_response(StatusCode::BAD_REQUEST, msg);
+    }
+
+    match queries::insert_submission(&state.db, submitter, payload.question, payload.note).await {
+        Ok(submission) => (StatusCode::CREATED, Json(SubmissionDto::from(submission))).into_response(),
+        Err(err) => {
+            error!(error = ?err, "failed to store submission");
+            error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to store submission")
+        }
+    }
+}
+
+/// The caller's own submissions, so submitters can follow their status.
+pub async fn list_own_submissions(
+    State(state): State<ApiState>,
+    Query(params): Query<ListQuery>,
+    caller: Caller,
+) -> impl IntoResponse {
+    let submitter = match caller.require_user() {
+        Ok(user_id) => user_id,
+        Err(forbidden) => return forbidden.into_response(),
+    };
+    list_response(&state, Some(submitter), params.status, &params).await
+}
+
+/// Submissions for moderators, defaulting to the pending queue.
+pub async fn moderation_queue(
+    State(state): State<ApiState>,
+    Query(params): Query<ListQuery>,
+    caller: Caller,
+) -> impl IntoResponse {
+    if let Err(forbidden) = caller.require_moderator() {
+        return forbidden.into_response();
+    }
+    let status = params.status.unwrap_or(SubmissionStatus::Pending);
+    list_response(&state, None, Some(status), &params).await
+}
+
+pub async fn get_submission(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+    caller: Caller,
+) -> impl IntoResponse {
+    match queries::find_submission(&state.db, &id).await {
+        Ok(Some(submission))
+            if caller.role.can_moderate()
+                || caller.user_id.as_deref() == Some(submission.submitter.as_str()) =>
+        {
+            (StatusCode::OK, Json(SubmissionDto::from(submission))).into_response()
+        }
+        Ok(_) => error_response(StatusCode::NOT_FOUND, "submission not found"),
+        Err(err) => {
+            error!(error = ?err, "failed to fetch submission");
+            error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to fetch submission")
+        }
+    }
+}
+
+/// Lets the submitter revise a submission that is still open; it goes back to the pending queue.
+pub async fn update_submission(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+    caller: Caller,
+    Json(payload): Json<SubmitQuestion>,
+) -> impl IntoResponse {
+    let submitter = match caller.require_user() {
+        Ok(user_id) => user_id,
+        Err(forbidden) => return forbidden.into_response(),
+    };
+    let Ok(oid) = ObjectId::parse_str(&id) else {
+        return error_response(StatusCode::NOT_FOUND, "submission not found");
+    };
+    if let Err(msg) = validate_submission(&payload.question) {
+        return error_response(StatusCode::BAD_REQUEST, msg);
+    }
+
+    match queries::resubmit(&state.db, oid, submitter, &payload.question, payload.note.as_deref()).await
+    {
+        Ok(Some(submission)) => (StatusCode::OK, Json(SubmissionDto::from(submission))).into_response(),
+        Ok(None) => error_response(StatusCode::CONFLICT, "submission is not open for changes"),
+        Err(err) => {
+            error!(error = ?err, "failed to update submission");
+            error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to update submission")
+        }
+    }
+}
+
+/// Approves (promoting the submission into a draft question), requests changes, or rejects a
+/// pending submission. Requesting changes and rejecting require a reason for the submitter.
+pub async fn decide_submission(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+    caller: Caller,
+    Json(payload): Json<DecisionRequest>,
+) -> impl IntoResponse {
+    if let Err(forbidden) = caller.require_moderator() {
+        return forbidden.into_response();
+    }
+    let submission = match queries::find_submission(&state.db, &id).await {
+        Ok(Some(submission)) => submission,
+        Ok(None) => return error_response(StatusCode::NOT_FOUND, "submission not found"),
+        Err(err) => {
+            error!(error = ?err, "failed to fetch submission");
+            return error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to decide submission");
+        }
+    };
+    if submission.status != SubmissionStatus::Pending {
+        return error_response(StatusCode::CONFLICT, "submission is not pending");
+    }
+
+    let reason = payload.reason.as_deref().map(str::trim).filter(|r| !r.is_empty());
+    if payload.decision != Decision::Approve && reason.is_none() {
+        return error_response(StatusCode::BAD_REQUEST, "a reason is required");
+    }
+
+    let question = match payload.decision {
+        Decision::Approve => {
+            let question = payload.question.unwrap_or(submission.question);
+            if let Err(msg) = question_handler::validate_create_question(&question) {
+                return (
+                    StatusCode::UNPROCESSABLE_ENTITY,
+                    Json(json!({
+                        "error": "submission is incomplete; approve with a completed question",
+                        "details": msg,
+                    })),
+                )
+                    .into_response();
+            }
+            Some(question)
+        }
+        Decision::RequestChanges | Decision::Reject => None,
+    };
+
+    let decided = match queries::decide(
+        &state.db,
+        submission.id,
+        payload.decision.status(),
+        caller.user_id.as_deref(),
+        reason,
+    )
+    .await
+    {
+        Ok(Some(decided)) => decided,
+        Ok(None) => return error_response(StatusCode::CONFLICT, "submission is not pending"),
+        Err(err) => {
+            error!(error = ?err, "failed to decide submission");
+            return error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to decide submission");
+        }
+    };
+    let Some(question) = question else {
+        return (StatusCode::OK, Json(SubmissionDto::from(decided))).into_response();
+    };
+
+    let question = question_queries::new_question(question);
+    let created = QuestionDto::from(question.clone());
+    let reason = format!("promoted from submission {id}");
+    let write = WriteQuestion {
+        id: &created.id,
+        action: ACTION_CREATE,
+        caller: &caller,
+        reason: Some(reason),
+        failure: "failed to promote submission",
+    };
+    let promoted = match mongodb::bson::to_document(&question) {
+        Ok(next) => question_handler::write_question(&state, None, &next, write).await,
+        Err(err) => {
+            error!(error = ?err, id, "failed to encode question");
+            Ok(false)
+        }
+    };
+    if !matches!(promoted, Ok(true)) {
+        if let Err(err) = queries::reopen(&state.db, submission.id).await {
+            error!(error = ?err, id, "failed to reopen submission");
+        }
+        return promoted.err().unwrap_or_else(|| {
+            error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to promote submission")
+        });
+    }
+    if let Err(err) = queries::set_question_id(&state.db, submission.id, &created.id).await {
+        error!(error = ?err, id, "failed to link submission to question");
+    }
+
+    let mut dto = SubmissionDto::from(decided);
+    dto.question_id = Some(created.id);
+    (StatusCode::OK, Json(dto)).into_response()
+}
+
+async fn list_response(
+    state: &ApiState,
+    submitter: Option<&str>,
+    status: Option<SubmissionStatus>,
+    params: &ListQuery,
+) -> Response {
+    let limit = params.limit.unwrap_or(50).min(100) as i64;
+    let offset = params.offset.unwrap_or(0);
+
+    match queries::list_submissions(&state.db, submitter, status, limit, offset).await {
+        Ok(items) => (
+            StatusCode::OK,
+            Json(SubmissionsList {
+                items: items.into_iter().map(SubmissionDto::from).collect(),
+            }),
+        )
+            .into_response(),
+        Err(err) => {
+            error!(error = ?err, "failed to list submissions");
+            error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to list submissions")
+        }
+    }
+}
+
+fn error_response(status: StatusCode, msg: &str) -> Response {
+    (status, Json(json!({ "error": msg }))).into_response()
+}
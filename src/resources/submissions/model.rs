@@ -0,0 +1,197 @@
+use chrono::Utc;
+use mongodb::bson::{DateTime, oid::ObjectId};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    lang::is_supported_lang,
+    resources::questions::{
+        model::{CreateQuestion, LocalizedText, validate_classification, validate_time_limit},
+        types::validate_shape,
+    },
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SubmissionStatus {
+    Pending,
+    ChangesRequested,
+    Approved,
+    Rejected,
+}
+
+impl SubmissionStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::ChangesRequested => "changes_requested",
+            Self::Approved => "approved",
+            Self::Rejected => "rejected",
+        }
+    }
+}
+
+/// A community question idea waiting in the moderation queue. The payload has the shape of a
+/// question but may cover any subset of locales.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Submission {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    pub submitter: String,
+    pub question: CreateQuestion,
+    pub note: Option<String>,
+    pub status: SubmissionStatus,
+    pub moderator: Option<String>,
+    pub reason: Option<String>,
+    /// Draft question created when the submission was approved.
+    pub question_id: Option<String>,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SubmitQuestion {
+    #[serde(flatten)]
+    pub question: CreateQuestion,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Decision {
+    Approve,
+    RequestChanges,
+    Reject,
+}
+
+impl Decision {
+    pub fn status(self) -> SubmissionStatus {
+        match self {
+            Self::Approve => SubmissionStatus::Approved,
+            Self::RequestChanges => SubmissionStatus::ChangesRequested,
+            Self::Reject => SubmissionStatus::Rejected,
+        }
+    }
+}
+
+/// Moderator verdict. Approving may supply a completed `question` (for example with the missing
+/// translations filled in); it must pass the same validation as `POST /v1/questions`.
+#[derive(Debug, Deserialize)]
+pub struct DecisionRequest {
+    pub decision: Decision,
+    pub reason: Option<String>,
+    pub question: Option<CreateQuestion>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SubmissionDto {
+    pub id: String,
+    pub status: SubmissionStatus,
+    pub question: CreateQuestion,
+    pub note: Option<String>,
+    pub submitter: String,
+    pub moderator: Option<String>,
+    pub reason: Option<String>,
+    pub question_id: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl From<Submission> for SubmissionDto {
+    fn from(s: Submission) -> Self {
+        Self {
+            id: s.id.to_hex(),
+            status: s.status,
+            question: s.question,
+            note: s.note,
+            submitter: s.submitter,
+            moderator: s.moderator,
+            reason: s.reason,
+            question_id: s.question_id,
+            created_at: s.created_at.to_chrono().with_timezone(&Utc).to_rfc3339(),
+            updated_at: s.updated_at.to_chrono().with_timezone(&Utc).to_rfc3339(),
+        }
+    }
+}
+
+/// Looser than question validation: any supported locale is enough, but whatever is present must
+/// be filled in and the options must still form a single-answer question.
+pub fn validate_submission(question: &CreateQuestion) -> Result<(), &'static str> {
+    validate_partial(&question.prompt, "prompt")?;
+    if let Some(label) = &question.stage_label {
+        validate_partial(label, "stage_label")?;
+    }
+    validate_classification(question)?;
+    validate_time_limit(question)?;
+    validate_shape(question, validate_partial)
+}
+
+fn validate_partial(text: &LocalizedText, field: &'static str) -> Result<(), &'static str> {
+    if text.keys().any(|lang| !is_supported_lang(lang)) {
+        return Err("unsupported locale; expected en, es, pt or sv");
+    }
+    if text.is_empty() || text.values().any(|value| value.trim().is_empty()) {
+        return Err(match field {
+            "prompt" => "prompt requires at least one non-empty locale",
+            "stage_label" => "stage_label locales must not be empty",
+            "option text" => "option text requires at least one non-empty locale",
+            "pair text" => "pair text requires at least one non-empty locale",
+            "answer text" => "answer text requires at least one non-empty locale",
+            _ => "option explanation locales must not be empty",
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::validate_submission;
+    use crate::resources::questions::{
+        model::{CreateQuestion, LocalizedText, OptionItem},
+        types::QuestionType,
+    };
+
+    fn text(lang: &str, value: &str) -> LocalizedText {
+        LocalizedText::from([(lang.to_string(), value.to_string())])
+    }
+
+    fn spanish_only() -> CreateQuestion {
+        CreateQuestion {
+            question_type: QuestionType::SingleChoice,
+            stage: 1,
+            stage_label: None,
+            prompt: text("es", "¿Quién construyó el arca?"),
+            options: ["Noé", "Moisés", "Abraham", "David"]
+                .iter()
+                .enumerate()
+                .map(|(idx, name)| OptionItem {
+                    text: text("es", name),
+                    correct: idx == 0,
+                    explanation: None,
+                })
+                .collect(),
+            pairs: vec![],
+            answers: vec![],
+            era: None,
+            level: None,
+            time_limit_secs: None,
+            tags: vec![],
+            image_url: None,
+        }
+    }
+
+    #[test]
+    fn accepts_a_single_locale() {
+        assert!(validate_submission(&spanish_only()).is_ok());
+    }
+
+    #[test]
+    fn rejects_unknown_locales_and_blank_text() {
+        let mut question = spanish_only();
+        question.prompt.insert("fr".into(), "Qui a construit l'arche ?".into());
+        assert!(validate_submission(&question).is_err());
+
+        let mut question = spanish_only();
+        question.options[2].text = text("es", "  ");
+        assert!(validate_submission(&question).is_err());
+    }
+}
@@ -0,0 +1,142 @@
+use futures_util::TryStreamExt;
+use mongodb::{
+    Collection, Database,
+    bson::{self, Bson, DateTime, Document, doc, oid::ObjectId},
+    options::{FindOneAndUpdateOptions, FindOptions, ReturnDocument},
+};
+
+use crate::resources::{
+    questions::model::CreateQuestion,
+    submissions::model::{Submission, SubmissionStatus},
+};
+
+fn submissions_collection(db: &Database) -> Collection<Submission> {
+    db.collection("submissions")
+}
+
+pub async fn insert_submission(
+    db: &Database,
+    submitter: &str,
+    question: CreateQuestion,
+    note: Option<String>,
+) -> mongodb::error::Result<Submission> {
+    let now = DateTime::now();
+    let submission = Submission {
+        id: ObjectId::new(),
+        submitter: submitter.to_string(),
+        question,
+        note,
+        status: SubmissionStatus::Pending,
+        moderator: None,
+        reason: None,
+        question_id: None,
+        created_at: now,
+        updated_at: now,
+    };
+    submissions_collection(db).insert_one(&submission, None).await?;
+    Ok(submission)
+}
+
+pub async fn find_submission(db: &Database, id: &str) -> mongodb::error::Result<Option<Submission>> {
+    let Ok(oid) = ObjectId::parse_str(id) else {
+        return Ok(None);
+    };
+    submissions_collection(db).find_one(doc! {"_id": oid}, None).await
+}
+
+/// Submissions filtered by submitter and/or status, oldest first so the queue is worked in order.
+pub async fn list_submissions(
+    db: &Database,
+    submitter: Option<&str>,
+    status: Option<SubmissionStatus>,
+    limit: i64,
+    offset: u64,
+) -> mongodb::error::Result<Vec<Submission>> {
+    let mut filter = Document::new();
+    if let Some(submitter) = submitter {
+        filter.insert("submitter", submitter);
+    }
+    if let Some(status) = status {
+        filter.insert("status", status.as_str());
+    }
+    let options = FindOptions::builder()
+        .sort(doc! {"created_at": 1, "_id": 1})
+        .skip(Some(offset))
+        .limit(Some(limit))
+        .build();
+
+    let mut cursor = submissions_collection(db).find(filter, options).await?;
+    let mut results = Vec::new();
+    while let Some(submission) = cursor.try_next().await? {
+        results.push(submission);
+    }
+    Ok(results)
+}
+
+/// Replaces the content of an open submission owned by `submitter` and puts it back in the queue.
+pub async fn resubmit(
+    db: &Database,
+    id: ObjectId,
+    submitter: &str,
+    question: &CreateQuestion,
+    note: Option<&str>,
+) -> mongodb::error::Result<Option<Submission>> {
+    let filter = doc! {
+        "_id": id,
+        "submitter": submitter,
+        "status": {"$in": [SubmissionStatus::Pending.as_str(), SubmissionStatus::ChangesRequested.as_str()]},
+    };
+    let update = doc! {
+        "$set": {
+            "question": bson::to_bson(question)?,
+            "note": note,
+            "status": SubmissionStatus::Pending.as_str(),
+            "updated_at": DateTime::now(),
+        }
+    };
+    let options = FindOneAndUpdateOptions::builder().return_document(ReturnDocument::After).build();
+    submissions_collection(db).find_one_and_update(filter, update, options).await
+}
+
+/// Records a moderator decision on a pending submission. Returns `None` if it was no longer
+/// pending (already decided or sent back to the submitter).
+pub async fn decide(
+    db: &Database,
+    id: ObjectId,
+    status: SubmissionStatus,
+    moderator: Option<&str>,
+    reason: Option<&str>,
+) -> mongodb::error::Result<Option<Submission>> {
+    let filter = doc! {"_id": id, "status": SubmissionStatus::Pending.as_str()};
+    let update = doc! {
+        "$set": {
+            "status": status.as_str(),
+            "moderator": moderator,
+            "reason": reason,
+            "updated_at": DateTime::now(),
+        }
+    };
+    let options = FindOneAndUpdateOptions::builder().return_document(ReturnDocument::After).build();
+    submissions_collection(db).find_one_and_update(filter, update, options).await
+}
+
+/// Links an approved submission to the draft question created from it.
+pub async fn set_question_id(
+    db: &Database,
+    id: ObjectId,
+    question_id: &str,
+) -> mongodb::error::Result<()> {
+    submissions_collection(db)
+        .update_one(doc! {"_id": id}, doc! {"$set": {"question_id": question_id}}, None)
+        .await?;
+    Ok(())
+}
+
+/// Puts a claimed submission back in the queue when promoting it failed.
+pub async fn reopen(db: &Database, id: ObjectId) -> mongodb::error::Result<()> {
+    let update = doc! {
+        "$set": {"status": SubmissionStatus::Pending.as_str(), "moderator": Bson::Null, "reason": Bson::Null}
+    };
+    submissions_collection(db).update_one(doc! {"_id": id}, update, None).await?;
+    Ok(())
+}
@@ -0,0 +1,205 @@
+use std::collections::BTreeSet;
+
+use axum::routing::{MethodRouter, get as axum_get};
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+};
+use mongodb::{Database, bson::DateTime};
+use serde::Deserialize;
+use serde_json::json;
+use tracing::error;
+
+use crate::{
+    lang::resolve_lang,
+    resources::{
+        eras::queries as era_queries,
+        questions::{model::LocalizedQuestionDto, queries as question_queries},
+        sync::{
+            model::{
+                Changes, ENTITY_EPISODE, ENTITY_ERA, ENTITY_QUESTION, EpisodeRef, SyncEpisode, SyncEra,
+                SyncResponse,
+            },
+            queries,
+        },
+    },
+    routes::api::ApiState,
+};
+
+const TOKEN_PREFIX: &str = "v1.";
+
+/// How far the next token reaches back before the time this sync started reading. `updated_at`
+/// and tombstones are stamped before they are committed, so a write can become visible with a
+/// timestamp slightly in the past; re-delivering the overlap keeps the next sync from skipping it.
+const TOKEN_OVERLAP_MS: i64 = 60_000;
+
+pub fn get() -> MethodRouter<ApiState> {
+    axum_get(sync)
+}
+
+#[derive(Deserialize)]
+pub struct SyncQuery {
+    pub since: Option<String>,
+    pub lang: Option<String>,
+}
+
+pub async fn sync(
+    State(state): State<ApiState>,
+    Query(params): Query<SyncQuery>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let lang = resolve_lang(params.lang.as_deref(), headers.get("accept-language"));
+    let since = match params.since.as_deref().map(decode_token) {
+        None => None,
+        Some(Some(since)) => Some(since),
+        Some(None) => {
+            return (StatusCode::BAD_REQUEST, Json(json!({ "error": "invalid sync token" })))
+                .into_response();
+        }
+    };
+
+    let now = DateTime::now();
+    // Tombstones older than the retention period are gone, so such clients start over.
+    let since = since.filter(|since| {
+        let retention = chrono::Duration::days(state.config.tombstone_retention_days.max(0));
+        since.to_chrono() >= now.to_chrono() - retention
+    });
+    let token = next_token(now);
+
+    match load_changes(&state.db, since, &lang).await {
+        Ok((questions, eras, episodes)) => (
+            StatusCode::OK,
+            Json(SyncResponse {
+                token,
+                full: since.is_none(),
+                lang,
+                questions,
+                eras,
+                episodes,
+            }),
+        )
+            .into_response(),
+        Err(err) => {
+            error!(error = ?err, "failed to load sync changes");
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "failed to load changes" })))
+                .into_response()
+        }
+    }
+}
+
+type ChangeSet =
+    (Changes<LocalizedQuestionDto, String>, Changes<SyncEra, String>, Changes<SyncEpisode, EpisodeRef>);
+
+async fn load_changes(
+    db: &Database,
+    since: Option<DateTime>,
+    lang: &str,
+) -> mongodb::error::Result<ChangeSet> {
+    let (questions, eras, tombstones) = match since {
+        Some(since) => (
+            question_queries::list_questions_updated_since(db, since).await?,
+            era_queries::list_eras_updated_since(db, since, lang).await?,
+            queries::tombstones_since(db, since).await?,
+        ),
+        None => (
+            question_queries::list_all_questions(db).await?,
+            era_queries::list_eras_full(db, lang).await?,
+            Vec::new(),
+        ),
+    };
+
+    let questions: Vec<_> = questions.into_iter().map(|q| q.localize(lang)).collect();
+    let mut era_headers = Vec::with_capacity(eras.len());
+    let mut episodes = Vec::new();
+    for era in eras {
+        let (header, era_episodes) = SyncEra::split(era);
+        era_headers.push(header);
+        episodes.extend(era_episodes);
+    }
+
+    let live_questions: BTreeSet<&str> = questions.iter().map(|q| q.id.as_str()).collect();
+    let live_eras: BTreeSet<&str> = era_headers.iter().map(|e| e.id.as_str()).collect();
+    let live_episodes: BTreeSet<(&str, &str)> =
+        episodes.iter().map(|e| (e.era_id.as_str(), e.episode.id.as_str())).collect();
+
+    let mut deleted_questions = BTreeSet::new();
+    let mut deleted_eras = BTreeSet::new();
+    let mut deleted_episodes = BTreeSet::new();
+    for tombstone in tombstones {
+        match tombstone.entity.as_str() {
+            ENTITY_QUESTION if !live_questions.contains(tombstone.entity_id.as_str()) => {
+                deleted_questions.insert(tombstone.entity_id);
+            }
+            ENTITY_ERA if !live_eras.contains(tombstone.entity_id.as_str()) => {
+                deleted_eras.insert(tombstone.entity_id);
+            }
+            ENTITY_EPISODE => {
+                let era_id = tombstone.era_id.unwrap_or_default();
+                if !live_episodes.contains(&(era_id.as_str(), tombstone.entity_id.as_str())) {
+                    deleted_episodes.insert(EpisodeRef {
+                        era_id,
+                        id: tombstone.entity_id,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok((
+        Changes {
+            upserted: questions,
+            deleted: deleted_questions.into_iter().collect(),
+        },
+        Changes {
+            upserted: era_headers,
+            deleted: deleted_eras.into_iter().collect(),
+        },
+        Changes {
+            upserted: episodes,
+            deleted: deleted_episodes.into_iter().collect(),
+        },
+    ))
+}
+
+/// Token for a sync that started reading at `now`.
+fn next_token(now: DateTime) -> String {
+    encode_token(DateTime::from_millis(now.timestamp_millis() - TOKEN_OVERLAP_MS))
+}
+
+fn encode_token(at: DateTime) -> String {
+    format!("{TOKEN_PREFIX}{}", at.timestamp_millis())
+}
+
+fn decode_token(token: &str) -> Option<DateTime> {
+    let millis = token.strip_prefix(TOKEN_PREFIX)?.parse::<i64>().ok()?;
+    Some(DateTime::from_millis(millis))
+}
+
+#[cfg(test)]
+mod tests {
+    use mongodb::bson::DateTime;
+
+    use super::{TOKEN_OVERLAP_MS, decode_token, encode_token, next_token};
+
+    #[test]
+    fn sync_token_round_trips() {
+        let at = DateTime::from_millis(1_760_000_000_123);
+        assert_eq!(decode_token(&encode_token(at)), Some(at));
+    }
+
+    #[test]
+    fn next_token_reaches_back_over_late_commits() {
+        let now = DateTime::from_millis(1_760_000_000_123);
+        let since = decode_token(&next_token(now)).unwrap();
+        assert_eq!(now.timestamp_millis() - since.timestamp_millis(), TOKEN_OVERLAP_MS);
+    }
+
+    #[test]
+    fn rejects_malformed_tokens() {
+        assert_eq!(decode_token("1760000000123"), None);
+        assert_eq!(decode_token("v1.abc"), None);
+    }
+}
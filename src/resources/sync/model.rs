@@ -0,0 +1,90 @@
+use mongodb::bson::{DateTime, oid::ObjectId};
+use serde::{Deserialize, Serialize};
+
+use crate::resources::{
+    eras::model::{EpisodeDto, EraDto},
+    questions::model::LocalizedQuestionDto,
+};
+
+pub const ENTITY_QUESTION: &str = "question";
+pub const ENTITY_ERA: &str = "era";
+pub const ENTITY_EPISODE: &str = "episode";
+
+/// Record of a hard or soft delete, kept so offline clients can learn what to drop.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Tombstone {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    pub entity: String,
+    pub entity_id: String,
+    /// Parent era for episode tombstones.
+    pub era_id: Option<String>,
+    pub deleted_at: DateTime,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SyncResponse {
+    /// Pass back as `since` on the next call.
+    pub token: String,
+    /// `true` when no `since` was given and this is a complete snapshot.
+    pub full: bool,
+    pub lang: String,
+    pub questions: Changes<LocalizedQuestionDto, String>,
+    pub eras: Changes<SyncEra, String>,
+    pub episodes: Changes<SyncEpisode, EpisodeRef>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Changes<T, D> {
+    pub upserted: Vec<T>,
+    pub deleted: Vec<D>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SyncEra {
+    pub id: String,
+    pub name: String,
+    pub label: String,
+    pub image_path: Option<String>,
+    pub order: i32,
+    #[serde(rename = "type")]
+    pub era_type: Option<String>,
+    pub books: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SyncEpisode {
+    pub era_id: String,
+    #[serde(flatten)]
+    pub episode: EpisodeDto,
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+pub struct EpisodeRef {
+    pub era_id: String,
+    pub id: String,
+}
+
+impl SyncEra {
+    /// Splits an era into its header and its episodes.
+    pub fn split(era: EraDto) -> (Self, Vec<SyncEpisode>) {
+        let episodes = era
+            .episodes
+            .into_iter()
+            .map(|episode| SyncEpisode {
+                era_id: era.id.clone(),
+                episode,
+            })
+            .collect();
+        let header = Self {
+            id: era.id,
+            name: era.name,
+            label: era.label,
+            image_path: era.image_path,
+            order: era.order,
+            era_type: era.era_type,
+            books: era.books,
+        };
+        (header, episodes)
+    }
+}
@@ -0,0 +1,77 @@
+use futures_util::TryStreamExt;
+use mongodb::{
+    Collection, Database, IndexModel,
+    bson::{DateTime, doc, oid::ObjectId},
+};
+
+use crate::resources::sync::model::Tombstone;
+
+fn tombstones_collection(db: &Database) -> Collection<Tombstone> {
+    db.collection("tombstones")
+}
+
+/// Serves `tombstones_since` and the retention purge.
+pub async fn ensure_indexes(db: &Database) -> mongodb::error::Result<()> {
+    let index = IndexModel::builder().keys(doc! {"deleted_at": 1}).build();
+    tombstones_collection(db).create_index(index, None).await?;
+    Ok(())
+}
+
+pub async fn record_tombstones(
+    db: &Database,
+    entity: &str,
+    entity_ids: &[String],
+    era_id: Option<&str>,
+) -> mongodb::error::Result<()> {
+    if entity_ids.is_empty() {
+        return Ok(());
+    }
+
+    let now = DateTime::now();
+    let docs = entity_ids.iter().map(|entity_id| Tombstone {
+        id: ObjectId::new(),
+        entity: entity.to_string(),
+        entity_id: entity_id.clone(),
+        era_id: era_id.map(str::to_owned),
+        deleted_at: now,
+    });
+    tombstones_collection(db).insert_many(docs, None).await?;
+    Ok(())
+}
+
+/// Removes tombstones for entities that were recreated, so a client never sees an id as both
+/// upserted and deleted.
+pub async fn clear_tombstones(
+    db: &Database,
+    entity: &str,
+    entity_ids: &[String],
+    era_id: Option<&str>,
+) -> mongodb::error::Result<()> {
+    if entity_ids.is_empty() {
+        return Ok(());
+    }
+
+    let mut filter = doc! {"entity": entity, "entity_id": {"$in": entity_ids}};
+    if let Some(era_id) = era_id {
+        filter.insert("era_id", era_id);
+    }
+    tombstones_collection(db).delete_many(filter, None).await?;
+    Ok(())
+}
+
+pub async fn tombstones_since(db: &Database, since: DateTime) -> mongodb::error::Result<Vec<Tombstone>> {
+    let mut cursor = tombstones_collection(db).find(doc! {"deleted_at": {"$gte": since}}, None).await?;
+
+    let mut tombstones = Vec::new();
+    while let Some(tombstone) = cursor.try_next().await? {
+        tombstones.push(tombstone);
+    }
+    Ok(tombstones)
+}
+
+/// Removes tombstones recorded before `cutoff`. Sync tokens older than that get a full snapshot.
+pub async fn purge_tombstones_before(db: &Database, cutoff: DateTime) -> mongodb::error::Result<u64> {
+    let result =
+        tombstones_collection(db).delete_many(doc! {"deleted_at": {"$lt": cutoff}}, None).await?;
+    Ok(result.deleted_count)
+}
@@ -0,0 +1,108 @@
+use serde_json::json;
+
+/// Badge catalog; rules are described on `achievements::model::Rule`.
+pub fn badges_config() -> serde_json::Value {
+    json!({
+        "badges": [
+            {
+                "id": "first_answer",
+                "rule": { "kind": "answers", "count": 1 },
+                "label": { "en": "First Steps", "es": "Primeros pasos", "pt": "Primeiros passos", "sv": "Första stegen" },
+                "description": {
+                    "en": "Answer your first question.",
+                    "es": "Responde tu primera pregunta.",
+                    "pt": "Responda sua primeira pergunta.",
+                    "sv": "Besvara din första fråga."
+                }
+            },
+            {
+                "id": "hundred_correct",
+                "rule": { "kind": "correct_answers", "count": 100 },
+                "label": { "en": "Faithful Student", "es": "Estudiante fiel", "pt": "Estudante fiel", "sv": "Trogen elev" },
+                "description": {
+                    "en": "Answer 100 questions correctly.",
+                    "es": "Responde correctamente 100 preguntas.",
+                    "pt": "Responda corretamente 100 perguntas.",
+                    "sv": "Besvara 100 frågor rätt."
+                }
+            },
+            {
+                "id": "week_streak",
+                "rule": { "kind": "streak_days", "days": 7 },
+                "label": { "en": "Seven Days", "es": "Siete días", "pt": "Sete dias", "sv": "Sju dagar" },
+                "description": {
+                    "en": "Study seven days in a row.",
+                    "es": "Estudia siete días seguidos.",
+                    "pt": "Estude sete dias seguidos.",
+                    "sv": "Studera sju dagar i rad."
+                }
+            },
+            {
+                "id": "polyglot",
+                "rule": { "kind": "languages", "count": 4 },
+                "label": { "en": "Pentecost", "es": "Pentecostés", "pt": "Pentecostes", "sv": "Pingst" },
+                "description": {
+                    "en": "Answer questions in all four languages.",
+                    "es": "Responde preguntas en los cuatro idiomas.",
+                    "pt": "Responda perguntas nos quatro idiomas.",
+                    "sv": "Besvara frågor på alla fyra språken."
+                }
+            },
+            {
+                "id": "reader",
+                "rule": { "kind": "episodes_read", "count": 10 },
+                "label": { "en": "Reader", "es": "Lector", "pt": "Leitor", "sv": "Läsare" },
+                "description": {
+                    "en": "Read ten episodes.",
+                    "es": "Lee diez episodios.",
+                    "pt": "Leia dez episódios.",
+                    "sv": "Läs tio episoder."
+                }
+            },
+            {
+                "id": "creation_complete",
+                "rule": { "kind": "era_complete", "era": "creation" },
+                "label": { "en": "In the Beginning", "es": "En el principio", "pt": "No princípio", "sv": "I begynnelsen" },
+                "description": {
+                    "en": "Read every episode of Creation.",
+                    "es": "Lee todos los episodios de la Creación.",
+                    "pt": "Leia todos os episódios da Criação.",
+                    "sv": "Läs alla episoder i Skapelsen."
+                }
+            },
+            {
+                "id": "prophets_complete",
+                "rule": { "kind": "era_complete", "era": "prophets" },
+                "label": { "en": "Voice of the Prophets", "es": "Voz de los profetas", "pt": "Voz dos profetas", "sv": "Profeternas röst" },
+                "description": {
+                    "en": "Read every episode of the Prophets.",
+                    "es": "Lee todos los episodios de los Profetas.",
+                    "pt": "Leia todos os episódios dos Profetas.",
+                    "sv": "Läs alla episoder om profeterna."
+                }
+            },
+            {
+                "id": "gospel_complete",
+                "rule": { "kind": "era_complete", "era": "gospel" },
+                "label": { "en": "Good News", "es": "Buena Nueva", "pt": "Boa Nova", "sv": "Glädjebudskapet" },
+                "description": {
+                    "en": "Read every episode of the Gospel.",
+                    "es": "Lee todos los episodios del Evangelio.",
+                    "pt": "Leia todos os episódios do Evangelho.",
+                    "sv": "Läs alla episoder i evangeliet."
+                }
+            },
+            {
+                "id": "psalms_perfect",
+                "rule": { "kind": "perfect_era", "era": "psalms" },
+                "label": { "en": "Psalmist", "es": "Salmista", "pt": "Salmista", "sv": "Psalmist" },
+                "description": {
+                    "en": "Get a perfect score on the Psalms.",
+                    "es": "Obtén una puntuación perfecta en los Salmos.",
+                    "pt": "Obtenha uma pontuação perfeita nos Salmos.",
+                    "sv": "Få fullt poäng på Psaltaren."
+                }
+            }
+        ]
+    })
+}
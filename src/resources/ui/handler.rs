@@ -1,12 +1,63 @@
-use axum::Json;
 use axum::routing::{MethodRouter, get as axum_get};
+use axum::{
+    Json,
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+use serde_json::json;
+use tracing::error;
 
-use super::{levels::levels_config, locales::locales_config};
+use super::{badges::badges_config, levels::levels_config, locales::locales_config};
+use crate::{
+    conditional::{self, Validators},
+    routes::api::ApiState,
+};
 
-pub fn get_locales() -> MethodRouter<crate::routes::api::ApiState> {
-    axum_get(|| async { Json(locales_config()) })
+pub fn get_locales() -> MethodRouter<ApiState> {
+    axum_get(locales)
 }
 
-pub fn get_levels() -> MethodRouter<crate::routes::api::ApiState> {
-    axum_get(|| async { Json(levels_config()) })
+pub fn get_levels() -> MethodRouter<ApiState> {
+    axum_get(levels)
+}
+
+pub fn get_badges() -> MethodRouter<ApiState> {
+    axum_get(badges)
+}
+
+pub async fn locales(State(state): State<ApiState>, headers: HeaderMap) -> impl IntoResponse {
+    catalog_response(&state, &headers, "ui:locales", locales_config)
+}
+
+pub async fn levels(State(state): State<ApiState>, headers: HeaderMap) -> impl IntoResponse {
+    catalog_response(&state, &headers, "ui:levels", levels_config)
+}
+
+pub async fn badges(State(state): State<ApiState>, headers: HeaderMap) -> impl IntoResponse {
+    catalog_response(&state, &headers, "ui:badges", badges_config)
+}
+
+fn catalog_response(
+    state: &ApiState,
+    headers: &HeaderMap,
+    key: &str,
+    build: fn() -> serde_json::Value,
+) -> Response {
+    let body = match state.cache.get(key) {
+        Some(body) => body,
+        None => match state.cache.store(key.to_string(), &build()) {
+            Ok(body) => body,
+            Err(err) => {
+                error!(error = ?err, key, "failed to encode catalog");
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(json!({ "error": "failed to encode catalog" })),
+                )
+                    .into_response();
+            }
+        },
+    };
+    let validators = Validators::for_body(&body, None);
+    conditional::respond(headers, body, validators)
 }
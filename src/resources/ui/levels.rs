@@ -18,3 +18,21 @@ pub fn levels_config() -> serde_json::Value {
         ]
     })
 }
+
+/// Position of a level in the catalog (`0` = easiest), or `None` for unknown ids.
+pub fn level_rank(id: &str) -> Option<usize> {
+    levels_config()["levels"].as_array()?.iter().position(|level| level["id"].as_str() == Some(id))
+}
+
+/// Id of the level at `rank` in the catalog.
+pub fn level_id(rank: usize) -> Option<String> {
+    levels_config()["levels"].get(rank)?["id"].as_str().map(str::to_string)
+}
+
+/// A level's label in `lang`, falling back to English.
+pub fn level_label(id: &str, lang: &str) -> Option<String> {
+    let config = levels_config();
+    let level = config["levels"].as_array()?.iter().find(|level| level["id"].as_str() == Some(id))?;
+    let label = &level["label"];
+    label[lang].as_str().or_else(|| label["en"].as_str()).map(str::to_string)
+}
@@ -1,3 +1,4 @@
+pub mod badges;
 pub mod handler;
 pub mod levels;
 pub mod locales;
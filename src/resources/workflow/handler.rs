@@ -0,0 +1,397 @@
+use axum::routing::{MethodRouter, get as axum_get, post as axum_post};
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+use mongodb::bson::Document;
+use serde::Serialize;
+use serde_json::json;
+use tracing::error;
+
+use crate::{
+    auth::Caller,
+    resources::{
+        eras::{
+            handler::{self as era_handler, WriteEra},
+            queries as era_queries,
+        },
+        questions::{
+            handler::{self as question_handler, WriteQuestion},
+            model::localized,
+            queries as question_queries,
+        },
+        revisions::model::{ACTION_STATUS, change_reason},
+        sync::{
+            model::{ENTITY_EPISODE, ENTITY_ERA, ENTITY_QUESTION},
+            queries as sync_queries,
+        },
+        workflow::{
+            model::{
+                ContentStatus, CreateComment, PENDING_FIELD, ReviewCommentDto, ReviewQueueItem,
+                StatusChangeDto, StatusChangeRequest, TransitionError, check_transition, transitioned,
+                working_status,
+            },
+            queries::{self, NewComment},
+        },
+    },
+    routes::api::ApiState,
+};
+
+const MAX_COMMENT_LEN: usize = 4000;
+
+pub fn question_status() -> MethodRouter<ApiState> {
+    axum_post(change_question_status)
+}
+
+pub fn era_status() -> MethodRouter<ApiState> {
+    axum_post(change_era_status)
+}
+
+pub fn question_comments() -> MethodRouter<ApiState> {
+    axum_get(list_question_comments).post(add_question_comment)
+}
+
+pub fn era_comments() -> MethodRouter<ApiState> {
+    axum_get(list_era_comments).post(add_era_comment)
+}
+
+pub fn queue() -> MethodRouter<ApiState> {
+    axum_get(review_queue)
+}
+
+pub async fn change_question_status(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+    caller: Caller,
+    headers: HeaderMap,
+    Json(payload): Json<StatusChangeRequest>,
+) -> impl IntoResponse {
+    if let Err(forbidden) = caller.require_staff() {
+        return forbidden.into_response();
+    }
+
+    let current = match question_queries::find_question_document(&state.db, &id).await {
+        Ok(Some(q)) if q.get_datetime("deleted_at").is_err() => q,
+        Ok(_) => return error_response(StatusCode::NOT_FOUND, "question not found"),
+        Err(err) => {
+            error!(error = ?err, "failed to fetch question");
+            return error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to change status");
+        }
+    };
+    let from = working_status(&current);
+    let comment = comment_text(payload.comment.as_deref());
+    if let Some(rejection) = rejection(from, payload.status, &caller, comment.is_some()) {
+        return rejection;
+    }
+
+    let next = transitioned(&current, payload.status);
+    let write = WriteQuestion {
+        id: &id,
+        action: ACTION_STATUS,
+        caller: &caller,
+        reason: comment.clone().or_else(|| change_reason(&headers)),
+        failure: "failed to change status",
+    };
+    match question_handler::write_question(&state, Some(&current), &next, write).await {
+        Ok(true) => {}
+        Ok(false) => {
+            return error_response(StatusCode::CONFLICT, "question status changed concurrently");
+        }
+        Err(response) => return response,
+    }
+    state.cache.invalidate_prefix("bundle:");
+
+    let ids = std::slice::from_ref(&id);
+    let tombstones = async {
+        if payload.status == ContentStatus::Published {
+            sync_queries::clear_tombstones(&state.db, ENTITY_QUESTION, ids, None).await?;
+        } else if from == ContentStatus::Published {
+            sync_queries::record_tombstones(&state.db, ENTITY_QUESTION, ids, None).await?;
+        }
+        Ok::<_, mongodb::error::Error>(())
+    };
+    if let Err(err) = tombstones.await {
+        error!(error = ?err, id, "failed to update question tombstones");
+    }
+
+    record_transition_comment(&state, ENTITY_QUESTION, &id, &caller, comment, (from, payload.status))
+        .await;
+
+    (
+        StatusCode::OK,
+        Json(StatusChangeDto {
+            id,
+            from,
+            status: payload.status,
+        }),
+    )
+        .into_response()
+}
+
+pub async fn change_era_status(
+    State(state): State<ApiState>,
+    Path(era_id): Path<String>,
+    caller: Caller,
+    headers: HeaderMap,
+    Json(payload): Json<StatusChangeRequest>,
+) -> impl IntoResponse {
+    if let Err(forbidden) = caller.require_staff() {
+        return forbidden.into_response();
+    }
+
+    let current = match era_queries::find_era_document(&state.db, &era_id).await {
+        Ok(Some(era)) => era,
+        Ok(None) => return error_response(StatusCode::NOT_FOUND, "era not found"),
+        Err(err) => {
+            error!(error = ?err, "failed to fetch era");
+            return error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to change status");
+        }
+    };
+    let from = working_status(&current);
+    let comment = comment_text(payload.comment.as_deref());
+    if let Some(rejection) = rejection(from, payload.status, &caller, comment.is_some()) {
+        return rejection;
+    }
+
+    let era = transitioned(&current, payload.status);
+    let write = WriteEra {
+        era_id: &era_id,
+        action: ACTION_STATUS,
+        caller: &caller,
+        reason: comment.clone().or_else(|| change_reason(&headers)),
+        failure: "failed to change status",
+    };
+    let stored = era_queries::write_era(&state.db, &era_id, Some(&current), &era);
+    match era_handler::write_era(&state, era.clone(), write, stored).await {
+        Ok(true) => {}
+        Ok(false) => return error_response(StatusCode::CONFLICT, "era status changed concurrently"),
+        Err(response) => return response,
+    }
+    state.cache.invalidate_prefix("eras:");
+    state.cache.invalidate_prefix("bundle:");
+
+    let episode_set = era_queries::episode_ids(&era);
+    let episodes: Vec<String> = episode_set.iter().cloned().collect();
+    // Publishing a pending edit replaces live episodes; those it dropped are deleted for sync.
+    let dropped: Vec<String> = if current.contains_key(PENDING_FIELD) {
+        era_queries::episode_ids(&current).difference(&episode_set).cloned().collect()
+    } else {
+        Vec::new()
+    };
+    let ids = std::slice::from_ref(&era_id);
+    let tombstones = async {
+        if payload.status == ContentStatus::Published {
+            sync_queries::clear_tombstones(&state.db, ENTITY_ERA, ids, None).await?;
+            sync_queries::clear_tombstones(&state.db, ENTITY_EPISODE, &episodes, Some(&era_id)).await?;
+            sync_queries::record_tombstones(&state.db, ENTITY_EPISODE, &dropped, Some(&era_id)).await?;
+        } else if from == ContentStatus::Published {
+            sync_queries::record_tombstones(&state.db, ENTITY_ERA, ids, None).await?;
+            sync_queries::record_tombstones(&state.db, ENTITY_EPISODE, &episodes, Some(&era_id)).await?;
+        }
+        Ok::<_, mongodb::error::Error>(())
+    };
+    if let Err(err) = tombstones.await {
+        error!(error = ?err, era_id, "failed to update era tombstones");
+    }
+
+    record_transition_comment(&state, ENTITY_ERA, &era_id, &caller, comment, (from, payload.status))
+        .await;
+
+    (
+        StatusCode::OK,
+        Json(StatusChangeDto {
+            id: era_id,
+            from,
+            status: payload.status,
+        }),
+    )
+        .into_response()
+}
+
+pub async fn list_question_comments(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+    caller: Caller,
+) -> impl IntoResponse {
+    list_comments_response(&state, &caller, ENTITY_QUESTION, &id).await
+}
+
+pub async fn add_question_comment(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+    caller: Caller,
+    Json(payload): Json<CreateComment>,
+) -> impl IntoResponse {
+    add_comment_response(&state, &caller, ENTITY_QUESTION, &id, payload).await
+}
+
+pub async fn list_era_comments(
+    State(state): State<ApiState>,
+    Path(era_id): Path<String>,
+    caller: Caller,
+) -> impl IntoResponse {
+    list_comments_response(&state, &caller, ENTITY_ERA, &era_id).await
+}
+
+pub async fn add_era_comment(
+    State(state): State<ApiState>,
+    Path(era_id): Path<String>,
+    caller: Caller,
+    Json(payload): Json<CreateComment>,
+) -> impl IntoResponse {
+    add_comment_response(&state, &caller, ENTITY_ERA, &era_id, payload).await
+}
+
+#[derive(Serialize)]
+pub struct ReviewQueue {
+    pub items: Vec<ReviewQueueItem>,
+}
+
+/// Questions and eras waiting for a reviewer, oldest submission first within each kind.
+pub async fn review_queue(State(state): State<ApiState>, caller: Caller) -> impl IntoResponse {
+    if let Err(forbidden) = caller.require_staff() {
+        return forbidden.into_response();
+    }
+
+    let items = async {
+        let questions = question_queries::list_in_review(&state.db).await?;
+        let eras = era_queries::list_eras_in_review(&state.db).await?;
+
+        let mut items: Vec<ReviewQueueItem> = questions
+            .into_iter()
+            .map(|q| ReviewQueueItem {
+                entity: ENTITY_QUESTION,
+                title: Some(localized(&q.prompt, "en")),
+                updated_at: Some(q.updated_at),
+                id: q.id,
+            })
+            .collect();
+        items.extend(eras.iter().map(era_queue_item));
+        Ok::<_, mongodb::error::Error>(items)
+    };
+
+    match items.await {
+        Ok(items) => (
+            StatusCode::OK,
+            Json(ReviewQueue {
+                items,
+            }),
+        )
+            .into_response(),
+        Err(err) => {
+            error!(error = ?err, "failed to load review queue");
+            error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to load review queue")
+        }
+    }
+}
+
+fn era_queue_item(era: &Document) -> ReviewQueueItem {
+    ReviewQueueItem {
+        entity: ENTITY_ERA,
+        id: era.get_str("_id").unwrap_or_default().to_string(),
+        title: era.get_document("en").ok().and_then(|en| en.get_str("name").ok()).map(str::to_owned),
+        updated_at: era.get_datetime("updated_at").ok().map(|dt| dt.to_chrono().to_rfc3339()),
+    }
+}
+
+async fn list_comments_response(state: &ApiState, caller: &Caller, entity: &str, id: &str) -> Response {
+    if let Err(forbidden) = caller.require_staff() {
+        return forbidden.into_response();
+    }
+
+    match queries::list_comments(&state.db, entity, id).await {
+        Ok(comments) => {
+            let items: Vec<ReviewCommentDto> =
+                comments.into_iter().map(ReviewCommentDto::from).collect();
+            (StatusCode::OK, Json(json!({ "items": items }))).into_response()
+        }
+        Err(err) => {
+            error!(error = ?err, entity, id, "failed to list review comments");
+            error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to list comments")
+        }
+    }
+}
+
+async fn add_comment_response(
+    state: &ApiState,
+    caller: &Caller,
+    entity: &str,
+    id: &str,
+    payload: CreateComment,
+) -> Response {
+    if let Err(forbidden) = caller.require_staff() {
+        return forbidden.into_response();
+    }
+    let Some(body) = comment_text(Some(&payload.body)) else {
+        return error_response(StatusCode::BAD_REQUEST, "comment body must not be empty");
+    };
+    if body.chars().count() > MAX_COMMENT_LEN {
+        return error_response(StatusCode::BAD_REQUEST, "comment body is too long");
+    }
+
+    let comment = NewComment {
+        entity,
+        entity_id: id,
+        author: caller.user_id.as_deref(),
+        body: &body,
+        transition: None,
+    };
+    match queries::insert_comment(&state.db, comment).await {
+        Ok(comment) => (StatusCode::CREATED, Json(ReviewCommentDto::from(comment))).into_response(),
+        Err(err) => {
+            error!(error = ?err, entity, id, "failed to add review comment");
+            error_response(StatusCode::INTERNAL_SERVER_ERROR, "failed to add comment")
+        }
+    }
+}
+
+async fn record_transition_comment(
+    state: &ApiState,
+    entity: &str,
+    id: &str,
+    caller: &Caller,
+    comment: Option<String>,
+    transition: (ContentStatus, ContentStatus),
+) {
+    let Some(body) = comment else {
+        return;
+    };
+    let comment = NewComment {
+        entity,
+        entity_id: id,
+        author: caller.user_id.as_deref(),
+        body: &body,
+        transition: Some(transition),
+    };
+    if let Err(err) = queries::insert_comment(&state.db, comment).await {
+        error!(error = ?err, entity, id, "failed to record review comment");
+    }
+}
+
+/// Error response for a transition the workflow does not allow, if any.
+fn rejection(
+    from: ContentStatus,
+    to: ContentStatus,
+    caller: &Caller,
+    has_comment: bool,
+) -> Option<Response> {
+    check_transition(from, to, caller.role, has_comment).err().map(|err| match err {
+        TransitionError::Invalid => {
+            error_response(StatusCode::CONFLICT, &format!("cannot move from {from} to {to}"))
+        }
+        TransitionError::Forbidden(msg) => crate::auth::Forbidden(msg).into_response(),
+        TransitionError::CommentRequired => {
+            error_response(StatusCode::BAD_REQUEST, "a comment is required when sending content back")
+        }
+    })
+}
+
+fn comment_text(comment: Option<&str>) -> Option<String> {
+    comment.map(str::trim).filter(|c| !c.is_empty()).map(str::to_owned)
+}
+
+fn error_response(status: StatusCode, msg: &str) -> Response {
+    (status, Json(json!({ "error": msg }))).into_response()
+}
@@ -0,0 +1,414 @@
+use std::fmt;
+
+use chrono::Utc;
+use mongodb::bson::{Bson, DateTime, Document, doc, oid::ObjectId};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    auth::{Caller, Forbidden, Role},
+    db::next_updated_at,
+};
+
+/// Publication state of a question or era. Documents stored before the workflow existed have no
+/// `status` and count as published.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContentStatus {
+    #[default]
+    Draft,
+    InReview,
+    Published,
+    Archived,
+}
+
+impl ContentStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Draft => "draft",
+            Self::InReview => "in_review",
+            Self::Published => "published",
+            Self::Archived => "archived",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "draft" => Some(Self::Draft),
+            "in_review" => Some(Self::InReview),
+            "published" => Some(Self::Published),
+            "archived" => Some(Self::Archived),
+            _ => None,
+        }
+    }
+
+    /// Reads the `status` field of a stored document, treating a missing value as published.
+    pub fn of_document(doc: &Document) -> Self {
+        doc.get_str("status").ok().and_then(Self::parse).unwrap_or(Self::Published)
+    }
+
+    /// Filter value matching documents in this status; published also matches legacy documents
+    /// without a `status` field.
+    pub fn filter_value(self) -> Bson {
+        match self {
+            Self::Published => Bson::Document(doc! { "$in": [self.as_str(), Bson::Null] }),
+            other => Bson::String(other.as_str().to_string()),
+        }
+    }
+}
+
+impl fmt::Display for ContentStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Which statuses a read may return. Public reads only ever see published content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Visibility {
+    Published,
+    Any,
+    Only(ContentStatus),
+}
+
+impl Visibility {
+    pub fn apply(self, filter: &mut Document) {
+        match self {
+            Self::Published => {
+                filter.insert("status", ContentStatus::Published.filter_value());
+            }
+            Self::Only(status) => {
+                filter.insert("status", status.filter_value());
+            }
+            Self::Any => {}
+        }
+    }
+
+    pub fn allows(self, status: ContentStatus) -> bool {
+        match self {
+            Self::Published => status == ContentStatus::Published,
+            Self::Only(only) => status == only,
+            Self::Any => true,
+        }
+    }
+}
+
+/// Resolves what a read may return. Plain requests get published content; `?preview=true` or a
+/// `status` filter require a staff role.
+pub fn visibility_for(
+    caller: &Caller,
+    preview: bool,
+    status: Option<ContentStatus>,
+) -> Result<Visibility, Forbidden> {
+    if !preview && status.is_none() {
+        return Ok(Visibility::Published);
+    }
+    caller.require_staff()?;
+    Ok(status.map(Visibility::Only).unwrap_or(Visibility::Any))
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum TransitionError {
+    /// The workflow has no edge between the two statuses.
+    Invalid,
+    /// The edge exists but the caller's role may not take it.
+    Forbidden(&'static str),
+    /// Reviewers sending content back must say why.
+    CommentRequired,
+}
+
+/// Checks a status change against the workflow:
+///
+/// - editors submit drafts for review, withdraw them, archive drafts and revive archived content;
+/// - reviewers publish or send back content under review, and unpublish or archive live content;
+/// - admins may take any edge.
+pub fn check_transition(
+    from: ContentStatus,
+    to: ContentStatus,
+    role: Role,
+    has_comment: bool,
+) -> Result<(), TransitionError> {
+    use ContentStatus::*;
+
+    let (editor, reviewer) = match (from, to) {
+        (Draft, InReview) | (Draft, Archived) | (Archived, Draft) => (true, false),
+        (InReview, Draft) => (true, true),
+        (InReview, Published) | (Published, Draft) | (Published, Archived) => (false, true),
+        _ => return Err(TransitionError::Invalid),
+    };
+
+    let allowed = role == Role::Admin || (editor && role.can_edit()) || (reviewer && role.can_review());
+    if !allowed {
+        return Err(TransitionError::Forbidden(if reviewer && !editor {
+            "reviewer role required"
+        } else {
+            "editor role required"
+        }));
+    }
+
+    if from == InReview && to == Draft && role == Role::Reviewer && !has_comment {
+        return Err(TransitionError::CommentRequired);
+    }
+    Ok(())
+}
+
+/// Field holding an edit to published content while it waits for review. The published fields
+/// keep serving players until the edit is published over them.
+pub const PENDING_FIELD: &str = "pending";
+
+/// The status edits and transitions act on: that of a pending edit, if any, else the document's.
+pub fn working_status(doc: &Document) -> ContentStatus {
+    match doc.get_document(PENDING_FIELD) {
+        Ok(pending) => ContentStatus::of_document(pending),
+        Err(_) => ContentStatus::of_document(doc),
+    }
+}
+
+/// `doc` as editors work on it: a pending edit's fields (a `null` removes the field) and status
+/// in place of the published ones.
+pub fn working_copy(doc: &Document) -> Document {
+    let mut working = doc.clone();
+    if let Some(Bson::Document(pending)) = working.remove(PENDING_FIELD) {
+        for (key, value) in pending {
+            match value {
+                Bson::Null => working.remove(&key),
+                value => working.insert(key, value),
+            };
+        }
+    }
+    working
+}
+
+/// `updated_at` for the next write to `previous`, past both its own and a pending edit's.
+pub fn next_write_at(previous: &Document) -> DateTime {
+    let own = next_updated_at(Some(previous));
+    let pending = next_updated_at(previous.get_document(PENDING_FIELD).ok());
+    own.max(pending)
+}
+
+/// `previous` after an edit setting `fields` (`null` removes one). Published content stays live
+/// and the edit is staged as a pending draft, replacing any earlier one; anything else is edited
+/// in place and goes back to draft.
+pub fn edited(previous: &Document, mut fields: Document) -> Document {
+    let at = next_write_at(previous);
+    fields.insert("status", ContentStatus::Draft.as_str());
+    fields.insert("updated_at", at);
+
+    let mut next = previous.clone();
+    if ContentStatus::of_document(previous) == ContentStatus::Published {
+        next.insert(PENDING_FIELD, fields);
+    } else {
+        next.remove(PENDING_FIELD);
+        for (key, value) in fields {
+            match value {
+                Bson::Null => next.remove(&key),
+                value => next.insert(key, value),
+            };
+        }
+    }
+    next
+}
+
+/// `previous` after its working status moves to `to`. Publishing a pending edit promotes it over
+/// the published fields; archiving one discards it, leaving the published content as it was.
+pub fn transitioned(previous: &Document, to: ContentStatus) -> Document {
+    let at = next_write_at(previous);
+    let Ok(pending) = previous.get_document(PENDING_FIELD) else {
+        let mut next = previous.clone();
+        next.insert("status", to.as_str());
+        next.insert("updated_at", at);
+        return next;
+    };
+
+    let mut next = match to {
+        ContentStatus::Published => working_copy(previous),
+        ContentStatus::Archived => {
+            let mut next = previous.clone();
+            next.remove(PENDING_FIELD);
+            return next;
+        }
+        _ => {
+            let mut pending = pending.clone();
+            pending.insert("status", to.as_str());
+            pending.insert("updated_at", at);
+            let mut next = previous.clone();
+            next.insert(PENDING_FIELD, pending);
+            return next;
+        }
+    };
+    next.insert("status", to.as_str());
+    next.insert("updated_at", at);
+    next
+}
+
+/// Narrows a write filter to documents whose pending edit, if any, is still the one in `previous`.
+pub fn guard_pending(filter: &mut Document, previous: &Document) {
+    let key = format!("{PENDING_FIELD}.updated_at");
+    match previous.get_document(PENDING_FIELD).and_then(|pending| pending.get_datetime("updated_at")) {
+        Ok(updated_at) => filter.insert(key, *updated_at),
+        Err(_) => filter.insert(key, doc! { "$exists": false }),
+    };
+}
+
+/// Matches documents whose working status is `status`, so pending edits under review are found
+/// alongside content that is itself in review.
+pub fn working_status_filter(status: ContentStatus) -> Document {
+    let pending_status = format!("{PENDING_FIELD}.status");
+    doc! { "$or": [
+        { "status": status.filter_value(), PENDING_FIELD: { "$exists": false } },
+        { pending_status: status.as_str() },
+    ] }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StatusChangeRequest {
+    pub status: ContentStatus,
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StatusChangeDto {
+    pub id: String,
+    pub from: ContentStatus,
+    pub status: ContentStatus,
+}
+
+/// Reviewer or editor note attached to a question or era, optionally recording the status change
+/// it accompanied.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReviewComment {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    pub entity: String,
+    pub entity_id: String,
+    pub author: Option<String>,
+    pub body: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_status: Option<ContentStatus>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to_status: Option<ContentStatus>,
+    pub created_at: DateTime,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateComment {
+    pub body: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReviewCommentDto {
+    pub id: String,
+    pub author: Option<String>,
+    pub body: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from_status: Option<ContentStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to_status: Option<ContentStatus>,
+    pub created_at: String,
+}
+
+impl From<ReviewComment> for ReviewCommentDto {
+    fn from(c: ReviewComment) -> Self {
+        Self {
+            id: c.id.to_hex(),
+            author: c.author,
+            body: c.body,
+            from_status: c.from_status,
+            to_status: c.to_status,
+            created_at: c.created_at.to_chrono().with_timezone(&Utc).to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReviewQueueItem {
+    pub entity: &'static str,
+    pub id: String,
+    pub title: Option<String>,
+    pub updated_at: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use mongodb::bson::doc;
+
+    use super::{
+        ContentStatus, PENDING_FIELD, TransitionError, check_transition, edited, transitioned,
+        working_copy, working_status,
+    };
+    use crate::auth::Role;
+
+    #[test]
+    fn missing_status_counts_as_published() {
+        assert_eq!(ContentStatus::of_document(&doc! {"_id": "creation"}), ContentStatus::Published);
+        assert_eq!(ContentStatus::of_document(&doc! {"status": "draft"}), ContentStatus::Draft);
+    }
+
+    #[test]
+    fn only_reviewers_publish() {
+        use ContentStatus::*;
+        assert_eq!(check_transition(Draft, InReview, Role::Editor, false), Ok(()));
+        assert_eq!(
+            check_transition(InReview, Published, Role::Editor, false),
+            Err(TransitionError::Forbidden("reviewer role required"))
+        );
+        assert_eq!(check_transition(InReview, Published, Role::Reviewer, false), Ok(()));
+        assert_eq!(check_transition(InReview, Published, Role::Admin, false), Ok(()));
+        assert_eq!(
+            check_transition(Draft, Published, Role::Admin, false),
+            Err(TransitionError::Invalid)
+        );
+    }
+
+    #[test]
+    fn reviewers_must_explain_rejections() {
+        use ContentStatus::*;
+        assert_eq!(
+            check_transition(InReview, Draft, Role::Reviewer, false),
+            Err(TransitionError::CommentRequired)
+        );
+        assert_eq!(check_transition(InReview, Draft, Role::Reviewer, true), Ok(()));
+        assert_eq!(check_transition(InReview, Draft, Role::Editor, false), Ok(()));
+        assert_eq!(
+            check_transition(InReview, Draft, Role::Player, true),
+            Err(TransitionError::Forbidden("editor role required"))
+        );
+    }
+
+    #[test]
+    fn edits_to_published_content_are_staged() {
+        let live = doc! {"_id": "q", "prompt": "old", "era": "exodus", "status": "published"};
+        let staged = edited(&live, doc! {"prompt": "new", "era": null});
+        assert_eq!(staged.get_str("prompt"), Ok("old"));
+        assert_eq!(staged.get_str("status"), Ok("published"));
+        assert_eq!(working_status(&staged), ContentStatus::Draft);
+
+        let working = working_copy(&staged);
+        assert_eq!(working.get_str("prompt"), Ok("new"));
+        assert!(!working.contains_key("era"));
+        assert!(!working.contains_key(PENDING_FIELD));
+
+        let draft = doc! {"_id": "q", "prompt": "old", "era": "exodus", "status": "in_review"};
+        let edited_draft = edited(&draft, doc! {"prompt": "new", "era": null});
+        assert_eq!(edited_draft.get_str("prompt"), Ok("new"));
+        assert_eq!(edited_draft.get_str("status"), Ok("draft"));
+        assert!(!edited_draft.contains_key("era"));
+    }
+
+    #[test]
+    fn pending_edits_are_promoted_or_discarded() {
+        let live = doc! {"_id": "q", "prompt": "old", "status": "published"};
+        let staged = edited(&live, doc! {"prompt": "new"});
+
+        let submitted = transitioned(&staged, ContentStatus::InReview);
+        assert_eq!(submitted.get_str("status"), Ok("published"));
+        assert_eq!(working_status(&submitted), ContentStatus::InReview);
+
+        let published = transitioned(&submitted, ContentStatus::Published);
+        assert_eq!(published.get_str("prompt"), Ok("new"));
+        assert_eq!(published.get_str("status"), Ok("published"));
+        assert!(!published.contains_key(PENDING_FIELD));
+
+        let discarded = transitioned(&staged, ContentStatus::Archived);
+        assert_eq!(discarded, live);
+    }
+}
@@ -0,0 +1,55 @@
+use futures_util::TryStreamExt;
+use mongodb::{
+    Collection, Database,
+    bson::{DateTime, doc, oid::ObjectId},
+    options::FindOptions,
+};
+
+use crate::resources::workflow::model::{ContentStatus, ReviewComment};
+
+fn comments_collection(db: &Database) -> Collection<ReviewComment> {
+    db.collection("review_comments")
+}
+
+pub struct NewComment<'a> {
+    pub entity: &'a str,
+    pub entity_id: &'a str,
+    pub author: Option<&'a str>,
+    pub body: &'a str,
+    pub transition: Option<(ContentStatus, ContentStatus)>,
+}
+
+pub async fn insert_comment(
+    db: &Database,
+    new: NewComment<'_>,
+) -> mongodb::error::Result<ReviewComment> {
+    let comment = ReviewComment {
+        id: ObjectId::new(),
+        entity: new.entity.to_string(),
+        entity_id: new.entity_id.to_string(),
+        author: new.author.map(str::to_owned),
+        body: new.body.to_string(),
+        from_status: new.transition.map(|(from, _)| from),
+        to_status: new.transition.map(|(_, to)| to),
+        created_at: DateTime::now(),
+    };
+    comments_collection(db).insert_one(&comment, None).await?;
+    Ok(comment)
+}
+
+/// Comments on one question or era, oldest first.
+pub async fn list_comments(
+    db: &Database,
+    entity: &str,
+    entity_id: &str,
+) -> mongodb::error::Result<Vec<ReviewComment>> {
+    let options = FindOptions::builder().sort(doc! {"created_at": 1, "_id": 1}).build();
+    let mut cursor =
+        comments_collection(db).find(doc! {"entity": entity, "entity_id": entity_id}, options).await?;
+
+    let mut comments = Vec::new();
+    while let Some(comment) = cursor.try_next().await? {
+        comments.push(comment);
+    }
+    Ok(comments)
+}
@@ -1,6 +1,8 @@
+use std::sync::Arc;
+
 use axum::{
     Json, Router,
-    http::{Method, StatusCode},
+    http::{Method, StatusCode, header},
     response::IntoResponse,
 };
 use mongodb::Database;
@@ -10,21 +12,61 @@ use tower_http::{
     trace::TraceLayer,
 };
 
-use crate::resources::{
-    eras::handler as era_handler, health::handler as health_handler,
-    questions::handler as question_handler, ui::handler as ui_handler,
+use crate::{
+    cache::ResponseCache,
+    config::AppConfig,
+    resources::{
+        achievements::handler as achievement_handler,
+        admin::handler as admin_handler,
+        bookmarks::handler as bookmark_handler,
+        bundle::handler as bundle_handler,
+        challenges::handler as challenge_handler,
+        eras::handler as era_handler,
+        groups::handler as group_handler,
+        health::handler as health_handler,
+        leaderboards::handler as leaderboard_handler,
+        placement::handler as placement_handler,
+        play::handler as play_handler,
+        questions::handler as question_handler,
+        recommendations::handler as recommendation_handler,
+        repetition::handler as repetition_handler,
+        reports::handler as report_handler,
+        revisions::handler as revision_handler,
+        rooms::{handler as room_handler, hub::RoomHub},
+        stats::handler as stats_handler,
+        streaks::handler as streak_handler,
+        submissions::handler as submission_handler,
+        sync::handler as sync_handler,
+        ui::handler as ui_handler,
+        workflow::handler as workflow_handler,
+    },
 };
 
 #[derive(Clone)]
 pub struct ApiState {
     pub db: Database,
+    pub cache: ResponseCache,
+    pub config: Arc<AppConfig>,
+    pub rooms: RoomHub,
+}
+
+impl ApiState {
+    pub fn new(db: Database, cfg: &AppConfig) -> Self {
+        Self {
+            db,
+            cache: ResponseCache::from_config(cfg),
+            config: Arc::new(cfg.clone()),
+            rooms: RoomHub::default(),
+        }
+    }
 }
 
 pub fn router(state: ApiState) -> Router {
     let cors = CorsLayer::new()
         .allow_origin(Any)
-        .allow_methods([Method::GET, Method::POST, Method::DELETE])
-        .allow_headers(Any);
+        .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
+        .allow_headers(Any)
+        .expose_headers([header::ETAG, header::LAST_MODIFIED]);
 
     Router::new()
         // Health routes
@@ -33,8 +75,26 @@ pub fn router(state: ApiState) -> Router {
         // Config routes
         .route("/v1/ui/locales", ui_handler::get_locales())
         .route("/v1/ui/levels", ui_handler::get_levels())
+        .route("/v1/ui/badges", ui_handler::get_badges())
         // Questions routes
-        .route("/v1/questions/:id", question_handler::get().delete(question_handler::delete_question))
+        .route(
+            "/v1/questions/:id",
+            question_handler::get()
+                .put(question_handler::update_question)
+                .delete(question_handler::delete_question),
+        )
+        .route("/v1/questions/random", play_handler::random())
+        .route("/v1/questions/:id/restore", question_handler::restore())
+        .route("/v1/questions/:id/answer", question_handler::answer())
+        .route("/v1/questions/:id/stats", stats_handler::question())
+        .route("/v1/questions/:id/revisions", revision_handler::question_revisions())
+        .route("/v1/questions/:id/revisions/diff", revision_handler::question_diff())
+        .route("/v1/questions/:id/revisions/:number", revision_handler::question_revision())
+        .route("/v1/questions/:id/revisions/:number/revert", question_handler::revert())
+        .route("/v1/questions/:id/status", workflow_handler::question_status())
+        .route("/v1/questions/:id/comments", workflow_handler::question_comments())
+        .route("/v1/questions/:id/reports", report_handler::question_reports())
+        .route("/v1/questions/:id/reports/resolve", report_handler::resolve())
         .route("/v1/questions", question_handler::collection())
         // Eras routes
         .route("/eras", era_handler::collection())
@@ -48,6 +108,83 @@ pub fn router(state: ApiState) -> Router {
         .route("/v1/eras/:era_id/episodes", era_handler::episodes_collection())
         .route("/v1/eras/:era_id/episodes/:episode_id", era_handler::episode())
         .route("/v1/episodes", era_handler::episodes_search())
+        .route("/v1/eras/:era_id/revisions", revision_handler::era_revisions())
+        .route("/v1/eras/:era_id/revisions/diff", revision_handler::era_diff())
+        .route("/v1/eras/:era_id/revisions/:number", revision_handler::era_revision())
+        .route("/v1/eras/:era_id/revisions/:number/revert", era_handler::revert())
+        .route("/v1/eras/:era_id/status", workflow_handler::era_status())
+        .route("/v1/eras/:era_id/comments", workflow_handler::era_comments())
+        // Play routes
+        .route("/v1/play/questions/:id", play_handler::question())
+        .route("/v1/play/questions/:id/answer", play_handler::answer())
+        .route("/v1/quizzes", play_handler::quizzes())
+        .route("/v1/quizzes/results", play_handler::quiz_results())
+        .route("/v1/quizzes/:session/questions/:id/start", play_handler::start())
+        // Placement routes
+        .route("/v1/placement", placement_handler::start())
+        .route("/v1/placement/:session/answers", placement_handler::answer())
+        .route("/v1/me/placement", placement_handler::mine())
+        // Spaced repetition routes
+        .route("/v1/me/reviews/due", repetition_handler::due())
+        .route("/v1/me/reviews/stats", repetition_handler::stats())
+        .route("/v1/me/reviews/:question_id", repetition_handler::record())
+        // Achievement routes
+        .route("/v1/me/achievements", achievement_handler::mine())
+        .route("/v1/me/episodes/:era_id/:episode_id", achievement_handler::episode())
+        // Streak routes
+        .route("/v1/me/streak", streak_handler::mine())
+        // Recommendation routes
+        .route("/v1/me/recommendations", recommendation_handler::mine())
+        // Bookmark and note routes
+        .route("/v1/me/bookmarks", bookmark_handler::bookmarks())
+        .route("/v1/me/bookmarks/:bookmark_id", bookmark_handler::bookmark())
+        .route("/v1/me/notes", bookmark_handler::notes())
+        .route("/v1/me/notes/:note_id", bookmark_handler::note())
+        // Live room routes
+        .route("/v1/rooms", room_handler::create())
+        .route("/v1/rooms/:code", room_handler::get())
+        .route("/v1/rooms/:code/ws", room_handler::socket())
+        // Group routes
+        .route("/v1/groups", group_handler::collection())
+        .route("/v1/groups/join", group_handler::join())
+        .route("/v1/groups/:group_id", group_handler::group())
+        .route("/v1/groups/:group_id/members/:user_id", group_handler::member())
+        .route("/v1/groups/:group_id/assignments", group_handler::assignments())
+        .route("/v1/groups/:group_id/assignments/:assignment_id", group_handler::assignment())
+        .route(
+            "/v1/groups/:group_id/assignments/:assignment_id/questions/:question_id/answer",
+            group_handler::answer(),
+        )
+        .route(
+            "/v1/groups/:group_id/assignments/:assignment_id/episodes/:episode_id",
+            group_handler::episode(),
+        )
+        .route("/v1/groups/:group_id/assignments/:assignment_id/report", group_handler::report())
+        // Leaderboard routes
+        .route("/v1/leaderboards", leaderboard_handler::board())
+        // Daily challenge routes
+        .route("/v1/challenges/today", challenge_handler::today())
+        .route("/v1/challenges/:date", challenge_handler::by_date())
+        .route("/v1/challenges/:date/results", challenge_handler::results())
+        .route("/v1/challenges/:date/leaderboard", challenge_handler::leaderboard())
+        // Review workflow routes
+        .route("/v1/review/queue", workflow_handler::queue())
+        // Community submission routes
+        .route("/v1/submissions", submission_handler::collection())
+        .route("/v1/submissions/:id", submission_handler::submission())
+        .route("/v1/submissions/:id/decision", submission_handler::decision())
+        .route("/v1/moderation/submissions", submission_handler::queue())
+        // Offline bundle routes
+        .route("/v1/bundle", bundle_handler::get())
+        .route("/v1/bundle/version", bundle_handler::version())
+        // Delta sync routes
+        .route("/v1/sync", sync_handler::get())
+        // Admin routes
+        .route("/v1/admin/cache", admin_handler::cache())
+        .route("/v1/admin/trash", question_handler::trash())
+        .route("/v1/admin/reports", report_handler::triage())
+        .route("/v1/admin/question-stats", stats_handler::report())
+        .route("/v1/admin/difficulty", stats_handler::difficulty())
         .layer(cors)
         .layer(TraceLayer::new_for_http())
         .fallback(fallback_invalid_path)
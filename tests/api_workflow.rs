@@ -1,114 +1,2227 @@
-use std::env;
+use std::net::SocketAddr;
 
-use mongodb::Client;
+use mongodb::{Client, Database};
 use reqwest::StatusCode;
 use serde_json::Value;
 use uuid::Uuid;
 
-use verbumdei_api::{config::AppConfig, db, routes};
+use verbumdei_api::{config::AppConfig, db, jobs, routes};
 
 #[tokio::test]
 async fn question_workflow_smoke() -> Result<(), Box<dyn std::error::Error>> {
-    // Unique DB per test run to avoid collisions.
-    let test_db = format!("verbumdei_test_{}", Uuid::new_v4());
-    unsafe {
-        env::set_var("MONGO_DB", &test_db);
-        env::set_var("API_HOST", "127.0.0.1");
-    }
-
-    // Bind to an ephemeral port.
-    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
-    let addr = listener.local_addr()?;
-    unsafe {
-        env::set_var("API_PORT", addr.port().to_string());
-    }
-
-    let cfg = AppConfig::from_env();
-    let _guard = DbGuard::new(cfg.mongo_uri.clone(), test_db.clone());
-    let db = db::init_mongo(&cfg).await?;
-    let state = routes::api::ApiState {
-        db,
-    };
-    let app = routes::api::router(state);
-
-    // Start server in background.
-    let server_handle = tokio::spawn(async move {
-        axum::serve(listener, app).await.expect("server error");
-    });
-
-    let client = reqwest::Client::new();
-    let base = format!("http://{}/v1", addr);
+    let app = spawn_app().await?;
+    let (client, base) = (&app.client, &app.base);
 
     // Create
     let valid_question = include_str!("fixtures/question_valid.json");
 
     let valid_payload: Value = serde_json::from_str(valid_question).expect("valid fixture");
-    let create_res = client.post(format!("{}/questions", base)).json(&valid_payload).send().await?;
+    let forbidden_res = client.post(format!("{}/questions", base)).json(&valid_payload).send().await?;
+    assert_eq!(forbidden_res.status(), StatusCode::FORBIDDEN);
+    let create_res = client
+        .post(format!("{}/questions", base))
+        .header("X-User-Role", "editor")
+        .json(&valid_payload)
+        .send()
+        .await?;
 
     assert_eq!(create_res.status(), StatusCode::CREATED);
     let created = create_res.json::<serde_json::Value>().await?;
     let id = created.get("id").and_then(|v| v.as_str()).ok_or("missing id")?.to_string();
+    assert_eq!(created.get("status").and_then(|v| v.as_str()), Some("draft"));
+
+    // Drafts are hidden from players until reviewed and published
+    let draft_res = client.get(format!("{}/questions/{}", base, id)).send().await?;
+    assert_eq!(draft_res.status(), StatusCode::NOT_FOUND);
+    let preview_res = client
+        .get(format!("{}/questions/{}?preview=true", base, id))
+        .header("X-User-Role", "editor")
+        .send()
+        .await?;
+    assert_eq!(preview_res.status(), StatusCode::OK);
 
-    // Get
-    let get_res = client.get(format!("{}/questions/{}", base, id)).send().await?;
+    let early_publish_res = client
+        .post(format!("{}/questions/{}/status", base, id))
+        .header("X-User-Role", "editor")
+        .json(&serde_json::json!({ "status": "published" }))
+        .send()
+        .await?;
+    assert_eq!(early_publish_res.status(), StatusCode::CONFLICT);
+    let submit_res = client
+        .post(format!("{}/questions/{}/status", base, id))
+        .header("X-User-Role", "editor")
+        .json(&serde_json::json!({ "status": "in_review" }))
+        .send()
+        .await?;
+    assert_eq!(submit_res.status(), StatusCode::OK);
+    let editor_publish_res = client
+        .post(format!("{}/questions/{}/status", base, id))
+        .header("X-User-Role", "editor")
+        .json(&serde_json::json!({ "status": "published" }))
+        .send()
+        .await?;
+    assert_eq!(editor_publish_res.status(), StatusCode::FORBIDDEN);
+
+    let queue_res =
+        client.get(format!("{}/review/queue", base)).header("X-User-Role", "reviewer").send().await?;
+    let queue = queue_res.json::<serde_json::Value>().await?;
+    let queued = queue.get("items").and_then(|v| v.as_array()).ok_or("missing items")?;
+    assert_eq!(queued.len(), 1);
+
+    let publish_res = client
+        .post(format!("{}/questions/{}/status", base, id))
+        .header("X-User-Role", "reviewer")
+        .header("X-User-Id", "fr-tomas")
+        .json(&serde_json::json!({ "status": "published", "comment": "Doctrinally sound." }))
+        .send()
+        .await?;
+    assert_eq!(publish_res.status(), StatusCode::OK);
+    let comments_res = client
+        .get(format!("{}/questions/{}/comments", base, id))
+        .header("X-User-Role", "editor")
+        .send()
+        .await?;
+    let comments = comments_res.json::<serde_json::Value>().await?;
+    assert_eq!(comments.pointer("/items/0/author").and_then(|v| v.as_str()), Some("fr-tomas"));
+
+    // Get: players never see the solution; staff get the full question to edit
+    let public_res =
+        client.get(format!("{}/questions/{}", base, id)).header("X-User-Id", "ana").send().await?;
+    assert_eq!(public_res.status(), StatusCode::OK);
+    let public = public_res.json::<serde_json::Value>().await?;
+    assert_eq!(public.pointer("/options/0/text/en").and_then(|v| v.as_str()), Some("Yes"));
+    let options = public.get("options").and_then(|v| v.as_array()).ok_or("missing options")?;
+    assert!(options.iter().all(|o| o.get("correct").is_none() && o.get("explanation").is_none()));
+    assert!(public.get("answers").is_none());
+
+    let get_res =
+        client.get(format!("{}/questions/{}", base, id)).header("X-User-Role", "editor").send().await?;
     assert_eq!(get_res.status(), StatusCode::OK);
+    let etag =
+        get_res.headers().get("etag").and_then(|v| v.to_str().ok()).ok_or("missing etag")?.to_string();
+    assert!(get_res.headers().contains_key("last-modified"));
+    assert_eq!(get_res.headers().get("cache-control").and_then(|v| v.to_str().ok()), Some("private"));
+
+    // Conditional get
+    let cached_res = client
+        .get(format!("{}/questions/{}", base, id))
+        .header("X-User-Role", "editor")
+        .header("If-None-Match", &etag)
+        .send()
+        .await?;
+    assert_eq!(cached_res.status(), StatusCode::NOT_MODIFIED);
 
-    // List
+    // Update guarded by If-Match; a published question stays live while the edit waits for review
+    let mut edited_payload = valid_payload.clone();
+    edited_payload["prompt"]["en"] = Value::from("Edited prompt?");
+    let update_res = client
+        .put(format!("{}/questions/{}", base, id))
+        .header("X-User-Role", "editor")
+        .header("If-Match", &etag)
+        .json(&edited_payload)
+        .send()
+        .await?;
+    assert_eq!(update_res.status(), StatusCode::OK);
+    let updated = update_res.json::<serde_json::Value>().await?;
+    assert_eq!(updated.get("status").and_then(|v| v.as_str()), Some("draft"));
+    assert_eq!(updated.pointer("/prompt/en").and_then(|v| v.as_str()), Some("Edited prompt?"));
+    let stale_res = client
+        .put(format!("{}/questions/{}", base, id))
+        .header("X-User-Role", "editor")
+        .header("If-Match", &etag)
+        .json(&valid_payload)
+        .send()
+        .await?;
+    assert_eq!(stale_res.status(), StatusCode::PRECONDITION_FAILED);
+
+    let live = client.get(format!("{}/questions/{}", base, id)).send().await?.json::<Value>().await?;
+    assert_eq!(live.pointer("/prompt/en"), public.pointer("/prompt/en"));
+    let staff_live = client
+        .get(format!("{}/questions/{}", base, id))
+        .header("X-User-Role", "editor")
+        .send()
+        .await?
+        .json::<Value>()
+        .await?;
+    assert_eq!(staff_live.get("status").and_then(|v| v.as_str()), Some("published"));
+    assert_eq!(staff_live.get("pending_status").and_then(|v| v.as_str()), Some("draft"));
+
+    // List: the live version keeps being served; the pending edit is not a draft question
     let list_res = client.get(format!("{}/questions?limit=5&offset=0", base)).send().await?;
     assert_eq!(list_res.status(), StatusCode::OK);
+    assert!(!list_res.headers().contains_key("last-modified"));
+    let list = list_res.json::<serde_json::Value>().await?;
+    assert_eq!(list.get("items").and_then(|v| v.as_array()).map(Vec::len), Some(1));
+    let drafts_res = client
+        .get(format!("{}/questions?status=draft", base))
+        .header("X-User-Role", "editor")
+        .send()
+        .await?;
+    let drafts = drafts_res.json::<serde_json::Value>().await?;
+    assert_eq!(drafts.get("items").and_then(|v| v.as_array()).map(Vec::len), Some(0));
+
+    // The edit goes through review and replaces the live wording once published
+    for (role, status) in [("editor", "in_review"), ("reviewer", "published")] {
+        let res = client
+            .post(format!("{}/questions/{}/status", base, id))
+            .header("X-User-Role", role)
+            .json(&serde_json::json!({ "status": status }))
+            .send()
+            .await?;
+        assert_eq!(res.status(), StatusCode::OK);
+        let change = res.json::<Value>().await?;
+        assert_eq!(change.get("status").and_then(|v| v.as_str()), Some(status));
+    }
+    let promoted =
+        client.get(format!("{}/questions/{}", base, id)).send().await?.json::<Value>().await?;
+    assert_eq!(promoted.pointer("/prompt/en").and_then(|v| v.as_str()), Some("Edited prompt?"));
+    let staff_promoted = client
+        .get(format!("{}/questions/{}", base, id))
+        .header("X-User-Role", "editor")
+        .send()
+        .await?
+        .json::<Value>()
+        .await?;
+    assert!(staff_promoted.get("pending_status").is_none());
 
     // Delete
-    let delete_res = client.delete(format!("{}/questions/{}", base, id)).send().await?;
+    let delete_res = client
+        .delete(format!("{}/questions/{}", base, id))
+        .header("X-User-Role", "editor")
+        .send()
+        .await?;
     assert_eq!(delete_res.status(), StatusCode::NO_CONTENT);
+    let deleted_res = client
+        .get(format!("{}/questions/{}?preview=true", base, id))
+        .header("X-User-Role", "editor")
+        .send()
+        .await?;
+    assert_eq!(deleted_res.status(), StatusCode::NOT_FOUND);
+
+    // Trash and restore
+    let forbidden_res = client.get(format!("{}/admin/trash", base)).send().await?;
+    assert_eq!(forbidden_res.status(), StatusCode::FORBIDDEN);
+    let trash_res =
+        client.get(format!("{}/admin/trash", base)).header("X-User-Role", "editor").send().await?;
+    assert_eq!(trash_res.status(), StatusCode::OK);
+    let trash = trash_res.json::<serde_json::Value>().await?;
+    let trashed = trash.get("items").and_then(|v| v.as_array()).ok_or("missing items")?;
+    assert_eq!(trashed.len(), 1);
+    assert_eq!(trashed[0].get("id").and_then(|v| v.as_str()), Some(id.as_str()));
+
+    let restore_res = client
+        .post(format!("{}/questions/{}/restore", base, id))
+        .header("X-User-Role", "editor")
+        .send()
+        .await?;
+    assert_eq!(restore_res.status(), StatusCode::OK);
+    let restored_res = client
+        .get(format!("{}/questions/{}?preview=true", base, id))
+        .header("X-User-Role", "editor")
+        .send()
+        .await?;
+    assert_eq!(restored_res.status(), StatusCode::OK);
+
+    // Revision history: create, two status changes, update, two more to publish it, delete, restore
+    let revisions_res = client
+        .get(format!("{}/questions/{}/revisions", base, id))
+        .header("X-User-Role", "reviewer")
+        .send()
+        .await?;
+    assert_eq!(revisions_res.status(), StatusCode::OK);
+    let revisions = revisions_res.json::<serde_json::Value>().await?;
+    let items = revisions.get("items").and_then(|v| v.as_array()).ok_or("missing items")?;
+    let actions: Vec<_> =
+        items.iter().filter_map(|r| r.get("action").and_then(|v| v.as_str())).collect();
+    assert_eq!(
+        actions,
+        ["restore", "delete", "status", "status", "update", "status", "status", "create"]
+    );
+
+    let diff_res = client
+        .get(format!("{}/questions/{}/revisions/diff?from=1&to=3", base, id))
+        .header("X-User-Role", "editor")
+        .send()
+        .await?;
+    assert_eq!(diff_res.status(), StatusCode::OK);
+
+    let revert_res = client
+        .post(format!("{}/questions/{}/revisions/1/revert", base, id))
+        .header("X-User-Role", "editor")
+        .json(&serde_json::json!({ "reason": "undo edit" }))
+        .send()
+        .await?;
+    assert_eq!(revert_res.status(), StatusCode::OK);
+    let reverted = revert_res.json::<serde_json::Value>().await?;
+    let latest_res = client
+        .get(format!("{}/questions/{}/revisions/9", base, id))
+        .header("X-User-Role", "editor")
+        .send()
+        .await?;
+    let latest = latest_res.json::<serde_json::Value>().await?;
+    assert_eq!(latest.get("action").and_then(|v| v.as_str()), Some("revert"));
+    assert_eq!(latest.get("reason").and_then(|v| v.as_str()), Some("undo edit"));
+    // The revision holds exactly the document the revert stored: the live question, with the
+    // reverted wording staged as its pending edit.
+    let timestamp = |value: Option<&Value>| {
+        value.and_then(|v| v.as_str()).and_then(|v| chrono::DateTime::parse_from_rfc3339(v).ok())
+    };
+    assert_eq!(
+        timestamp(latest.pointer("/snapshot/pending/updated_at/$date")),
+        timestamp(reverted.get("updated_at"))
+    );
 
-    server_handle.abort();
     Ok(())
 }
 
 #[tokio::test]
 async fn question_create_rejects_invalid_payload() -> Result<(), Box<dyn std::error::Error>> {
-    // Unique DB per test run to avoid collisions.
-    let test_db = format!("verbumdei_test_invalid_{}", Uuid::new_v4());
-    unsafe {
-        env::set_var("MONGO_DB", &test_db);
-        env::set_var("API_HOST", "127.0.0.1");
+    let app = spawn_app().await?;
+    let (client, base) = (&app.client, &app.base);
+
+    // Invalid payload: missing pt locale in prompt and only 3 options.
+    let invalid_question = include_str!("fixtures/question_invalid.json");
+
+    let invalid_payload: Value = serde_json::from_str(invalid_question).expect("invalid fixture");
+    let create_res = client
+        .post(format!("{}/questions", base))
+        .header("X-User-Role", "editor")
+        .json(&invalid_payload)
+        .send()
+        .await?;
+
+    assert_eq!(create_res.status(), StatusCode::BAD_REQUEST);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn submissions_are_moderated_into_draft_questions() -> Result<(), Box<dyn std::error::Error>> {
+    let app = spawn_app().await?;
+    let (client, base) = (&app.client, &app.base);
+
+    let spanish_only = serde_json::json!({
+        "stage": 1,
+        "stage_label": null,
+        "prompt": {"es": "¿Quién construyó el arca?"},
+        "options": [
+            {"text": {"es": "Noé"}, "correct": true, "explanation": null},
+            {"text": {"es": "Moisés"}, "correct": false, "explanation": null},
+            {"text": {"es": "Abraham"}, "correct": false, "explanation": null},
+            {"text": {"es": "David"}, "correct": false, "explanation": null}
+        ],
+        "tags": ["genesis"],
+        "image_url": null,
+        "note": "From the Sunday school class"
+    });
+
+    let anonymous_res = client.post(format!("{}/submissions", base)).json(&spanish_only).send().await?;
+    assert_eq!(anonymous_res.status(), StatusCode::FORBIDDEN);
+    let submit_res = client
+        .post(format!("{}/submissions", base))
+        .header("X-User-Id", "maria")
+        .json(&spanish_only)
+        .send()
+        .await?;
+    assert_eq!(submit_res.status(), StatusCode::CREATED);
+    let submission = submit_res.json::<Value>().await?;
+    let id = submission.get("id").and_then(|v| v.as_str()).ok_or("missing id")?.to_string();
+    assert_eq!(submission.get("status").and_then(|v| v.as_str()), Some("pending"));
+
+    // Incomplete translations cannot be promoted as-is
+    let incomplete_res = client
+        .post(format!("{}/submissions/{}/decision", base, id))
+        .header("X-User-Role", "moderator")
+        .json(&serde_json::json!({ "decision": "approve" }))
+        .send()
+        .await?;
+    assert_eq!(incomplete_res.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let changes_res = client
+        .post(format!("{}/submissions/{}/decision", base, id))
+        .header("X-User-Role", "moderator")
+        .json(&serde_json::json!({ "decision": "request_changes", "reason": "Please add translations" }))
+        .send()
+        .await?;
+    assert_eq!(changes_res.status(), StatusCode::OK);
+
+    let mine_res =
+        client.get(format!("{}/submissions", base)).header("X-User-Id", "maria").send().await?;
+    let mine = mine_res.json::<Value>().await?;
+    assert_eq!(mine.pointer("/items/0/status").and_then(|v| v.as_str()), Some("changes_requested"));
+    assert_eq!(
+        mine.pointer("/items/0/reason").and_then(|v| v.as_str()),
+        Some("Please add translations")
+    );
+
+    let complete: Value = serde_json::from_str(include_str!("fixtures/question_valid.json"))?;
+    let resubmit_res = client
+        .put(format!("{}/submissions/{}", base, id))
+        .header("X-User-Id", "maria")
+        .json(&complete)
+        .send()
+        .await?;
+    assert_eq!(resubmit_res.status(), StatusCode::OK);
+
+    let approve_res = client
+        .post(format!("{}/submissions/{}/decision", base, id))
+        .header("X-User-Role", "moderator")
+        .json(&serde_json::json!({ "decision": "approve" }))
+        .send()
+        .await?;
+    assert_eq!(approve_res.status(), StatusCode::OK);
+    let approved = approve_res.json::<Value>().await?;
+    let question_id =
+        approved.get("question_id").and_then(|v| v.as_str()).ok_or("missing question_id")?;
+
+    let draft_res = client
+        .get(format!("{}/questions/{}?preview=true", base, question_id))
+        .header("X-User-Role", "editor")
+        .send()
+        .await?;
+    let draft = draft_res.json::<Value>().await?;
+    assert_eq!(draft.get("status").and_then(|v| v.as_str()), Some("draft"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn reports_flag_questions_until_resolved() -> Result<(), Box<dyn std::error::Error>> {
+    let app = spawn_app_with(|cfg| cfg.report_flag_threshold = 2).await?;
+    let (client, base) = (&app.client, &app.base);
+
+    let payload: Value = serde_json::from_str(include_str!("fixtures/question_valid.json"))?;
+    let created = client
+        .post(format!("{}/questions", base))
+        .header("X-User-Role", "editor")
+        .json(&payload)
+        .send()
+        .await?
+        .json::<Value>()
+        .await?;
+    let id = created.get("id").and_then(|v| v.as_str()).ok_or("missing id")?.to_string();
+    for status in ["in_review", "published"] {
+        let res = client
+            .post(format!("{}/questions/{}/status", base, id))
+            .header("X-User-Role", "admin")
+            .json(&serde_json::json!({ "status": status }))
+            .send()
+            .await?;
+        assert_eq!(res.status(), StatusCode::OK);
     }
 
-    // Bind to an ephemeral port.
-    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
-    let addr = listener.local_addr()?;
-    unsafe {
-        env::set_var("API_PORT", addr.port().to_string());
+    let report = serde_json::json!({ "category": "translation_error", "lang": "es", "text": "Typo" });
+    for user in ["ana", "ben"] {
+        let res = client
+            .post(format!("{}/questions/{}/reports", base, id))
+            .header("X-User-Id", user)
+            .json(&report)
+            .send()
+            .await?;
+        assert_eq!(res.status(), StatusCode::CREATED);
     }
+    let anonymous_res =
+        client.post(format!("{}/questions/{}/reports", base, id)).json(&report).send().await?;
+    assert_eq!(anonymous_res.status(), StatusCode::FORBIDDEN);
+    let duplicate_res = client
+        .post(format!("{}/questions/{}/reports", base, id))
+        .header("X-User-Id", "ana")
+        .json(&report)
+        .send()
+        .await?;
+    assert_eq!(duplicate_res.status(), StatusCode::CONFLICT);
 
-    let cfg = AppConfig::from_env();
-    let _guard = DbGuard::new(cfg.mongo_uri.clone(), test_db.clone());
-    let db = db::init_mongo(&cfg).await?;
-    let state = routes::api::ApiState {
-        db,
-    };
-    let app = routes::api::router(state);
+    let triage_res =
+        client.get(format!("{}/admin/reports", base)).header("X-User-Role", "editor").send().await?;
+    assert_eq!(triage_res.status(), StatusCode::OK);
+    let triage = triage_res.json::<Value>().await?;
+    assert_eq!(triage.pointer("/items/0/open_count").and_then(|v| v.as_u64()), Some(2));
+    assert_eq!(triage.pointer("/items/0/flagged").and_then(|v| v.as_bool()), Some(true));
+    assert_eq!(
+        triage.pointer("/items/0/categories/translation_error").and_then(|v| v.as_u64()),
+        Some(2)
+    );
 
-    // Start server in background.
-    let server_handle = tokio::spawn(async move {
-        axum::serve(listener, app).await.expect("server error");
+    let resolve_res = client
+        .post(format!("{}/questions/{}/reports/resolve", base, id))
+        .header("X-User-Role", "editor")
+        .json(&serde_json::json!({ "resolution": "Fixed the Spanish prompt" }))
+        .send()
+        .await?;
+    let resolved = resolve_res.json::<Value>().await?;
+    assert_eq!(resolved.get("resolved").and_then(|v| v.as_u64()), Some(2));
+    assert_eq!(resolved.get("flagged").and_then(|v| v.as_bool()), Some(false));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn sync_reports_deletes_and_restarts_expired_tokens() -> Result<(), Box<dyn std::error::Error>> {
+    let app = spawn_app_with(|cfg| cfg.tombstone_retention_days = 30).await?;
+    let (client, base) = (&app.client, &app.base);
+
+    let payload: Value = serde_json::from_str(include_str!("fixtures/question_valid.json"))?;
+    let created = client
+        .post(format!("{}/questions", base))
+        .header("X-User-Role", "editor")
+        .json(&payload)
+        .send()
+        .await?
+        .json::<Value>()
+        .await?;
+    let id = created.get("id").and_then(|v| v.as_str()).ok_or("missing id")?.to_string();
+    for status in ["in_review", "published"] {
+        let res = client
+            .post(format!("{}/questions/{}/status", base, id))
+            .header("X-User-Role", "admin")
+            .json(&serde_json::json!({ "status": status }))
+            .send()
+            .await?;
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+
+    let full = client.get(format!("{}/sync?lang=en", base)).send().await?.json::<Value>().await?;
+    assert_eq!(full.get("full").and_then(|v| v.as_bool()), Some(true));
+    assert_eq!(full.pointer("/questions/upserted/0/id").and_then(|v| v.as_str()), Some(id.as_str()));
+    let token = full.get("token").and_then(|v| v.as_str()).ok_or("missing token")?.to_string();
+
+    let deleted = client
+        .delete(format!("{}/questions/{}", base, id))
+        .header("X-User-Role", "editor")
+        .send()
+        .await?;
+    assert_eq!(deleted.status(), StatusCode::NO_CONTENT);
+    let delta = client
+        .get(format!("{}/sync?lang=en&since={}", base, token))
+        .send()
+        .await?
+        .json::<Value>()
+        .await?;
+    assert_eq!(delta.get("full").and_then(|v| v.as_bool()), Some(false));
+    assert_eq!(delta.pointer("/questions/deleted/0").and_then(|v| v.as_str()), Some(id.as_str()));
+
+    // A token from before the tombstone retention period cannot be trusted to see every delete.
+    let expired =
+        client.get(format!("{}/sync?lang=en&since=v1.0", base)).send().await?.json::<Value>().await?;
+    assert_eq!(expired.get("full").and_then(|v| v.as_bool()), Some(true));
+    assert_eq!(expired.pointer("/questions/deleted").and_then(|v| v.as_array()).map(Vec::len), Some(0));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn typed_questions_are_graded_server_side() -> Result<(), Box<dyn std::error::Error>> {
+    let app = spawn_app().await?;
+    let (client, base) = (&app.client, &app.base);
+
+    let locales = |en: &str, es: &str, pt: &str, sv: &str| serde_json::json!({ "en": en, "es": es, "pt": pt, "sv": sv });
+    let payload = serde_json::json!({
+        "type": "fill_blank",
+        "stage": 1,
+        "prompt": locales("Jesus ___.", "Jesús ___.", "Jesus ___.", "Jesus ___."),
+        "answers": [locales("wept", "lloró", "chorou", "grät")],
+        "tags": ["verse"],
     });
 
-    let client = reqwest::Client::new();
-    let base = format!("http://{}/v1", addr);
+    let mut missing_answers = payload.clone();
+    missing_answers["answers"] = serde_json::json!([]);
+    let missing_answers_res = client
+        .post(format!("{}/questions", base))
+        .header("X-User-Role", "editor")
+        .json(&missing_answers)
+        .send()
+        .await?;
+    assert_eq!(missing_answers_res.status(), StatusCode::BAD_REQUEST);
 
-    // Invalid payload: missing pt locale in prompt and only 3 options.
-    let invalid_question = include_str!("fixtures/question_invalid.json");
+    let created = client
+        .post(format!("{}/questions", base))
+        .header("X-User-Role", "editor")
+        .json(&payload)
+        .send()
+        .await?
+        .json::<Value>()
+        .await?;
+    assert_eq!(created.get("type").and_then(|v| v.as_str()), Some("fill_blank"));
+    let id = created.get("id").and_then(|v| v.as_str()).ok_or("missing id")?.to_string();
 
-    let invalid_payload: Value = serde_json::from_str(invalid_question).expect("invalid fixture");
-    let create_res = client.post(format!("{}/questions", base)).json(&invalid_payload).send().await?;
+    let draft_res = client
+        .post(format!("{}/questions/{}/answer", base, id))
+        .header("X-User-Role", "editor")
+        .json(&serde_json::json!({ "text": "wept" }))
+        .send()
+        .await?;
+    assert_eq!(draft_res.status(), StatusCode::NOT_FOUND);
 
-    assert_eq!(create_res.status(), StatusCode::BAD_REQUEST);
+    for status in ["in_review", "published"] {
+        let res = client
+            .post(format!("{}/questions/{}/status", base, id))
+            .header("X-User-Role", "admin")
+            .json(&serde_json::json!({ "status": status }))
+            .send()
+            .await?;
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+
+    // The raw answer endpoint is for staff; players answer through play and never see it.
+    let player_res = client
+        .post(format!("{}/questions/{}/answer?lang=es", base, id))
+        .header("X-User-Id", "ana")
+        .json(&serde_json::json!({ "text": "lloró" }))
+        .send()
+        .await?;
+    assert_eq!(player_res.status(), StatusCode::FORBIDDEN);
+    let public = client.get(format!("{}/questions/{}", base, id)).send().await?.json::<Value>().await?;
+    assert!(public.get("answers").is_none());
+
+    let answer_res = client
+        .post(format!("{}/questions/{}/answer?lang=es", base, id))
+        .header("X-User-Role", "editor")
+        .json(&serde_json::json!({ "text": "  Lloró. " }))
+        .send()
+        .await?;
+    assert_eq!(answer_res.status(), StatusCode::OK);
+    let answer = answer_res.json::<Value>().await?;
+    assert_eq!(answer.get("correct").and_then(|v| v.as_bool()), Some(true));
+    assert_eq!(answer.pointer("/solution/text/0").and_then(|v| v.as_str()), Some("lloró"));
+
+    let wrong_shape_res = client
+        .post(format!("{}/questions/{}/answer", base, id))
+        .header("X-User-Role", "editor")
+        .json(&serde_json::json!({ "selected": [0] }))
+        .send()
+        .await?;
+    assert_eq!(wrong_shape_res.status(), StatusCode::BAD_REQUEST);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn quizzes_do_not_repeat_within_a_session() -> Result<(), Box<dyn std::error::Error>> {
+    let app = spawn_app().await?;
+    let (client, base) = (&app.client, &app.base);
+
+    let fixture: Value = serde_json::from_str(include_str!("fixtures/question_valid.json"))?;
+    for (era, level) in [("prophets", "lay"), ("prophets", "saint"), ("kings", "lay")] {
+        let mut payload = fixture.clone();
+        payload["era"] = Value::from(era);
+        payload["level"] = Value::from(level);
+        let created = client
+            .post(format!("{}/questions", base))
+            .header("X-User-Role", "editor")
+            .json(&payload)
+            .send()
+            .await?
+            .json::<Value>()
+            .await?;
+        let id = created.get("id").and_then(|v| v.as_str()).ok_or("missing id")?.to_string();
+        for status in ["in_review", "published"] {
+            let res = client
+                .post(format!("{}/questions/{}/status", base, id))
+                .header("X-User-Role", "admin")
+                .json(&serde_json::json!({ "status": status }))
+                .send()
+                .await?;
+            assert_eq!(res.status(), StatusCode::OK);
+        }
+    }
+
+    let random = client
+        .get(format!("{}/questions/random?era=prophets&count=5", base))
+        .send()
+        .await?
+        .json::<Value>()
+        .await?;
+    assert_eq!(random.get("items").and_then(|v| v.as_array()).map(Vec::len), Some(2));
+
+    let first = client
+        .post(format!("{}/quizzes", base))
+        .json(&serde_json::json!({ "count": 2 }))
+        .send()
+        .await?
+        .json::<Value>()
+        .await?;
+    let session = first.get("session").and_then(|v| v.as_str()).ok_or("missing session")?;
+    let first_items = first.get("items").and_then(|v| v.as_array()).ok_or("missing items")?;
+    assert_eq!(first_items.len(), 2);
+    assert!(first_items.iter().any(|item| item.get("era") == Some(&Value::from("kings"))));
+
+    let second = client
+        .post(format!("{}/quizzes", base))
+        .json(&serde_json::json!({ "count": 2, "session": session }))
+        .send()
+        .await?
+        .json::<Value>()
+        .await?;
+    let second_items = second.get("items").and_then(|v| v.as_array()).ok_or("missing items")?;
+    assert_eq!(second_items.len(), 1);
+    assert!(first_items.iter().all(|item| item.get("id") != second_items[0].get("id")));
+
+    // A session started signed in belongs to that player.
+    let owned = client
+        .post(format!("{}/quizzes", base))
+        .header("X-User-Id", "ana")
+        .json(&serde_json::json!({ "count": 1 }))
+        .send()
+        .await?
+        .json::<Value>()
+        .await?;
+    let owned_session = owned.get("session").and_then(|v| v.as_str()).ok_or("missing session")?;
+    for user in [None, Some("ben")] {
+        let mut request = client
+            .post(format!("{}/quizzes", base))
+            .json(&serde_json::json!({ "count": 1, "session": owned_session }));
+        if let Some(user) = user {
+            request = request.header("X-User-Id", user);
+        }
+        assert_eq!(request.send().await?.status(), StatusCode::NOT_FOUND);
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn daily_challenge_records_one_result_per_player() -> Result<(), Box<dyn std::error::Error>> {
+    let app = spawn_app_with(|cfg| cfg.challenge_size = 2).await?;
+    let (client, base) = (&app.client, &app.base);
+
+    let payload: Value = serde_json::from_str(include_str!("fixtures/question_valid.json"))?;
+    for _ in 0..3 {
+        let created = client
+            .post(format!("{}/questions", base))
+            .header("X-User-Role", "editor")
+            .json(&payload)
+            .send()
+            .await?
+            .json::<Value>()
+            .await?;
+        let id = created.get("id").and_then(|v| v.as_str()).ok_or("missing id")?.to_string();
+        for status in ["in_review", "published"] {
+            let res = client
+                .post(format!("{}/questions/{}/status", base, id))
+                .header("X-User-Role", "admin")
+                .json(&serde_json::json!({ "status": status }))
+                .send()
+                .await?;
+            assert_eq!(res.status(), StatusCode::OK);
+        }
+    }
+
+    let today = client
+        .get(format!("{}/challenges/today?lang=en", base))
+        .header("X-User-Id", "ana")
+        .send()
+        .await?
+        .json::<Value>()
+        .await?;
+    let date = today.get("date").and_then(|v| v.as_str()).ok_or("missing date")?.to_string();
+    let items = today.get("items").and_then(|v| v.as_array()).ok_or("missing items")?.clone();
+    assert_eq!(items.len(), 2);
+
+    let by_date =
+        client.get(format!("{}/challenges/{}", base, date)).send().await?.json::<Value>().await?;
+    let ids = |items: &[Value]| items.iter().map(|item| item.get("id").cloned()).collect::<Vec<_>>();
+    assert_eq!(
+        ids(by_date.get("items").and_then(|v| v.as_array()).ok_or("missing items")?),
+        ids(&items)
+    );
+
+    let future_res = client.get(format!("{}/challenges/2999-01-01", base)).send().await?;
+    assert_eq!(future_res.status(), StatusCode::NOT_FOUND);
+    // Past days are never drawn after the fact, and their results cannot be posted.
+    let past_res = client.get(format!("{}/challenges/1900-01-01", base)).send().await?;
+    assert_eq!(past_res.status(), StatusCode::NOT_FOUND);
+    let backfill = serde_json::json!({ "answers": [{ "selected": [0] }, { "selected": [0] }] });
+    let backfill_res = client
+        .post(format!("{}/challenges/1900-01-01/results", base))
+        .header("X-User-Id", "ben")
+        .json(&backfill)
+        .send()
+        .await?;
+    assert_eq!(backfill_res.status(), StatusCode::FORBIDDEN);
+    let board = client
+        .get(format!("{}/leaderboards?period=all_time", base))
+        .header("X-User-Id", "ben")
+        .send()
+        .await?
+        .json::<Value>()
+        .await?;
+    assert_eq!(board.get("items").and_then(|v| v.as_array()).map(Vec::len), Some(0));
+
+    // The shared session cannot be used to look up solutions ahead of submitting.
+    let session = today.get("session").and_then(|v| v.as_str()).ok_or("missing session")?;
+    let item_id = items[0].get("id").and_then(|v| v.as_str()).ok_or("missing id")?;
+    let peek_res = client
+        .post(format!("{}/play/questions/{}/answer?session={}", base, item_id, session))
+        .header("X-User-Id", "ana")
+        .json(&serde_json::json!({ "selected": [0] }))
+        .send()
+        .await?;
+    assert_eq!(peek_res.status(), StatusCode::FORBIDDEN);
+    // Nor can a free-play session of its own, while the challenge is open.
+    let free_play_res = client
+        .post(format!("{}/play/questions/{}/answer?session=mine", base, item_id))
+        .json(&serde_json::json!({ "selected": [0] }))
+        .send()
+        .await?;
+    assert_eq!(free_play_res.status(), StatusCode::FORBIDDEN);
+
+    // Pick the correct option ("Yes") wherever the shuffle put it for this player.
+    let answers: Vec<Value> = items
+        .iter()
+        .map(|item| {
+            let options = item.get("options").and_then(|v| v.as_array()).cloned().unwrap_or_default();
+            let position = options.iter().position(|o| o.as_str() == Some("Yes")).unwrap_or(0);
+            serde_json::json!({ "selected": [position] })
+        })
+        .collect();
+    let submit = serde_json::json!({ "answers": answers, "duration_ms": 42000 });
+
+    let result_res = client
+        .post(format!("{}/challenges/{}/results", base, date))
+        .header("X-User-Id", "ana")
+        .json(&submit)
+        .send()
+        .await?;
+    assert_eq!(result_res.status(), StatusCode::CREATED);
+    let result = result_res.json::<Value>().await?;
+    assert_eq!(result.get("score").and_then(|v| v.as_i64()), Some(2));
+    // Timed by the server from ana's first fetch; the client's claim is ignored.
+    let duration_ms = result.get("duration_ms").and_then(|v| v.as_i64()).ok_or("missing duration")?;
+    assert!((0..42000).contains(&duration_ms));
+
+    let again_res = client
+        .post(format!("{}/challenges/{}/results", base, date))
+        .header("X-User-Id", "ana")
+        .json(&submit)
+        .send()
+        .await?;
+    assert_eq!(again_res.status(), StatusCode::CONFLICT);
+
+    let board = client
+        .get(format!("{}/challenges/{}/leaderboard", base, date))
+        .send()
+        .await?
+        .json::<Value>()
+        .await?;
+    assert_eq!(board.pointer("/items/0/user_id").and_then(|v| v.as_str()), Some("ana"));
+    assert_eq!(board.pointer("/items/0/rank").and_then(|v| v.as_u64()), Some(1));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn leaderboards_rank_quiz_results() -> Result<(), Box<dyn std::error::Error>> {
+    let app = spawn_app().await?;
+    let (client, base) = (&app.client, &app.base);
+
+    let mut payload: Value = serde_json::from_str(include_str!("fixtures/question_valid.json"))?;
+    payload["era"] = Value::from("prophets");
+    for _ in 0..2 {
+        let created = client
+            .post(format!("{}/questions", base))
+            .header("X-User-Role", "editor")
+            .json(&payload)
+            .send()
+            .await?
+            .json::<Value>()
+            .await?;
+        let id = created.get("id").and_then(|v| v.as_str()).ok_or("missing id")?.to_string();
+        for status in ["in_review", "published"] {
+            let res = client
+                .post(format!("{}/questions/{}/status", base, id))
+                .header("X-User-Role", "admin")
+                .json(&serde_json::json!({ "status": status }))
+                .send()
+                .await?;
+            assert_eq!(res.status(), StatusCode::OK);
+        }
+    }
+
+    // ana answers both questions correctly, ben only the first.
+    for (user, correct_answers) in [("ana", 2), ("ben", 1)] {
+        let quiz = client
+            .post(format!("{}/quizzes?lang=en", base))
+            .header("X-User-Id", user)
+            .json(&serde_json::json!({ "count": 2 }))
+            .send()
+            .await?
+            .json::<Value>()
+            .await?;
+        let session = quiz.get("session").and_then(|v| v.as_str()).ok_or("missing session")?;
+        let items = quiz.get("items").and_then(|v| v.as_array()).ok_or("missing items")?;
+        let answers: Vec<Value> = items
+            .iter()
+            .enumerate()
+            .map(|(idx, item)| {
+                let options =
+                    item.get("options").and_then(|v| v.as_array()).cloned().unwrap_or_default();
+                let yes = options.iter().position(|o| o.as_str() == Some("Yes")).unwrap_or(0);
+                let pick = if idx < correct_answers {
+                    yes
+                } else {
+                    (yes + 1) % options.len()
+                };
+                serde_json::json!({ "question_id": item.get("id"), "answer": { "selected": [pick] } })
+            })
+            .collect();
+        let submit = serde_json::json!({ "session": session, "answers": answers });
+
+        // Ranked quizzes are only graded through their submission.
+        let first_id = items[0].get("id").and_then(|v| v.as_str()).ok_or("missing id")?;
+        let peek_res = client
+            .post(format!("{}/play/questions/{}/answer?session={}", base, first_id, session))
+            .header("X-User-Id", user)
+            .json(&serde_json::json!({ "selected": [0] }))
+            .send()
+            .await?;
+        assert_eq!(peek_res.status(), StatusCode::FORBIDDEN);
+
+        let result_res = client
+            .post(format!("{}/quizzes/results", base))
+            .header("X-User-Id", user)
+            .json(&submit)
+            .send()
+            .await?;
+        assert_eq!(result_res.status(), StatusCode::CREATED);
+        let result = result_res.json::<Value>().await?;
+        assert_eq!(result.get("score").and_then(|v| v.as_i64()), Some(correct_answers as i64));
+
+        let repeat_res = client
+            .post(format!("{}/quizzes/results", base))
+            .header("X-User-Id", user)
+            .json(&submit)
+            .send()
+            .await?;
+        assert_eq!(repeat_res.status(), StatusCode::BAD_REQUEST);
+    }
+
+    // A quiz started signed out cannot be claimed by whoever submits it.
+    let anonymous = client
+        .post(format!("{}/quizzes?lang=en", base))
+        .json(&serde_json::json!({ "count": 1 }))
+        .send()
+        .await?
+        .json::<Value>()
+        .await?;
+    let claim = serde_json::json!({
+        "session": anonymous.get("session"),
+        "answers": [{ "question_id": anonymous.pointer("/items/0/id"), "answer": { "selected": [0] } }],
+    });
+    let claim_res = client
+        .post(format!("{}/quizzes/results", base))
+        .header("X-User-Id", "ben")
+        .json(&claim)
+        .send()
+        .await?;
+    assert_eq!(claim_res.status(), StatusCode::FORBIDDEN);
+
+    for query in ["period=all_time", "period=week", "period=day", "era=prophets"] {
+        let board = client
+            .get(format!("{}/leaderboards?{}&limit=1", base, query))
+            .header("X-User-Id", "ben")
+            .send()
+            .await?
+            .json::<Value>()
+            .await?;
+        assert_eq!(board.pointer("/items/0/user_id").and_then(|v| v.as_str()), Some("ana"));
+        assert_eq!(board.pointer("/items/0/score").and_then(|v| v.as_i64()), Some(2));
+        assert_eq!(board.pointer("/me/rank").and_then(|v| v.as_u64()), Some(2));
+        assert_eq!(board.pointer("/me/score").and_then(|v| v.as_i64()), Some(1));
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn missed_questions_come_back_for_review() -> Result<(), Box<dyn std::error::Error>> {
+    let app = spawn_app().await?;
+    let (client, base) = (&app.client, &app.base);
+
+    let payload: Value = serde_json::from_str(include_str!("fixtures/question_valid.json"))?;
+    let created = client
+        .post(format!("{}/questions", base))
+        .header("X-User-Role", "editor")
+        .json(&payload)
+        .send()
+        .await?
+        .json::<Value>()
+        .await?;
+    let id = created.get("id").and_then(|v| v.as_str()).ok_or("missing id")?.to_string();
+    for status in ["in_review", "published"] {
+        let res = client
+            .post(format!("{}/questions/{}/status", base, id))
+            .header("X-User-Role", "admin")
+            .json(&serde_json::json!({ "status": status }))
+            .send()
+            .await?;
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+
+    let anonymous = client
+        .post(format!("{}/me/reviews/{}", base, id))
+        .json(&serde_json::json!({ "correct": true }))
+        .send()
+        .await?;
+    assert_eq!(anonymous.status(), StatusCode::FORBIDDEN);
+
+    let passed = client
+        .post(format!("{}/me/reviews/{}", base, id))
+        .header("X-User-Id", "ana")
+        .json(&serde_json::json!({ "correct": true }))
+        .send()
+        .await?
+        .json::<Value>()
+        .await?;
+    assert_eq!(passed.get("interval_days").and_then(|v| v.as_i64()), Some(1));
+
+    let due = client
+        .get(format!("{}/me/reviews/due?lang=en", base))
+        .header("X-User-Id", "ana")
+        .send()
+        .await?
+        .json::<Value>()
+        .await?;
+    assert_eq!(due.pointer("/items").and_then(|v| v.as_array()).map(Vec::len), Some(0));
+
+    let missed = client
+        .post(format!("{}/me/reviews/{}", base, id))
+        .header("X-User-Id", "ana")
+        .json(&serde_json::json!({ "correct": false }))
+        .send()
+        .await?
+        .json::<Value>()
+        .await?;
+    assert_eq!(missed.get("interval_days").and_then(|v| v.as_i64()), Some(0));
+    assert_eq!(missed.get("lapses").and_then(|v| v.as_i64()), Some(1));
+
+    let stats = client
+        .get(format!("{}/me/reviews/stats", base))
+        .header("X-User-Id", "ana")
+        .send()
+        .await?
+        .json::<Value>()
+        .await?;
+    assert_eq!(stats.get("cards").and_then(|v| v.as_u64()), Some(1));
+    assert_eq!(stats.get("retention").and_then(|v| v.as_f64()), Some(0.5));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn answers_feed_question_statistics() -> Result<(), Box<dyn std::error::Error>> {
+    let app = spawn_app().await?;
+    let (client, base) = (&app.client, &app.base);
+
+    let payload: Value = serde_json::from_str(include_str!("fixtures/question_valid.json"))?;
+    let created = client
+        .post(format!("{}/questions", base))
+        .header("X-User-Role", "editor")
+        .json(&payload)
+        .send()
+        .await?
+        .json::<Value>()
+        .await?;
+    let id = created.get("id").and_then(|v| v.as_str()).ok_or("missing id")?.to_string();
+    for status in ["in_review", "published"] {
+        let res = client
+            .post(format!("{}/questions/{}/status", base, id))
+            .header("X-User-Role", "admin")
+            .json(&serde_json::json!({ "status": status }))
+            .send()
+            .await?;
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+
+    // Option 0 ("Yes") is correct; the Spanish player picks option 1 ("No").
+    for (lang, pick, time_ms) in [("en", "Yes", 4000), ("en", "Yes", 6000), ("es", "No", 8000)] {
+        assert_eq!(play_answer(&app, &id, None, lang, pick, time_ms).await?, StatusCode::OK);
+    }
+
+    let forbidden = client.get(format!("{}/questions/{}/stats", base, id)).send().await?;
+    assert_eq!(forbidden.status(), StatusCode::FORBIDDEN);
+
+    let stats = client
+        .get(format!("{}/questions/{}/stats", base, id))
+        .header("X-User-Role", "editor")
+        .send()
+        .await?
+        .json::<Value>()
+        .await?;
+    assert_eq!(stats.get("attempts").and_then(|v| v.as_i64()), Some(3));
+    assert_eq!(stats.get("avg_time_ms").and_then(|v| v.as_f64()), Some(6000.0));
+    assert_eq!(stats.pointer("/options/1/picks").and_then(|v| v.as_i64()), Some(1));
+    assert_eq!(stats.pointer("/locales/en/correct_rate").and_then(|v| v.as_f64()), Some(1.0));
+    assert_eq!(stats.pointer("/locales/es/correct_rate").and_then(|v| v.as_f64()), Some(0.0));
+
+    let report = client
+        .get(format!("{}/admin/question-stats?sort=correct_rate&order=asc", base))
+        .header("X-User-Role", "editor")
+        .send()
+        .await?
+        .json::<Value>()
+        .await?;
+    assert_eq!(report.pointer("/items/0/question_id").and_then(|v| v.as_str()), Some(id.as_str()));
+    assert_eq!(report.pointer("/items/0/attempts").and_then(|v| v.as_i64()), Some(3));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn calibration_rates_questions_from_answers() -> Result<(), Box<dyn std::error::Error>> {
+    let app = spawn_app().await?;
+    let (client, base, db) = (&app.client, &app.base, &app.db);
+
+    // The hard question is staged first, the easy one second.
+    let mut ids = Vec::new();
+    for stage in [1, 2] {
+        let mut payload: Value = serde_json::from_str(include_str!("fixtures/question_valid.json"))?;
+        payload["stage"] = Value::from(stage);
+        let created = client
+            .post(format!("{}/questions", base))
+            .header("X-User-Role", "editor")
+            .json(&payload)
+            .send()
+            .await?
+            .json::<Value>()
+            .await?;
+        let id = created.get("id").and_then(|v| v.as_str()).ok_or("missing id")?.to_string();
+        for status in ["in_review", "published"] {
+            let res = client
+                .post(format!("{}/questions/{}/status", base, id))
+                .header("X-User-Role", "admin")
+                .json(&serde_json::json!({ "status": status }))
+                .send()
+                .await?;
+            assert_eq!(res.status(), StatusCode::OK);
+        }
+        ids.push(id);
+    }
+    let (hard, easy) = (&ids[0], &ids[1]);
+
+    for user in ["ana", "ben", "cai", "dov"] {
+        for (id, pick) in [(hard, "No"), (easy, "Yes")] {
+            assert_eq!(play_answer(&app, id, Some(user), "en", pick, 0).await?, StatusCode::OK);
+        }
+    }
+
+    assert_eq!(jobs::calibrate_difficulty(db).await?, 8);
+    assert_eq!(jobs::calibrate_difficulty(db).await?, 0);
+    // Losing the saved progress, as a crash before it is written would, replays the answers
+    // without counting them twice.
+    db.collection::<mongodb::bson::Document>("job_state")
+        .delete_many(mongodb::bson::doc! {}, None)
+        .await?;
+    assert_eq!(jobs::calibrate_difficulty(db).await?, 8);
+
+    let report = client
+        .get(format!("{}/admin/difficulty?min_answers=4", base))
+        .header("X-User-Role", "editor")
+        .send()
+        .await?
+        .json::<Value>()
+        .await?;
+    let items = report.get("items").and_then(|v| v.as_array()).ok_or("missing items")?;
+    assert_eq!(items.len(), 2);
+    let item =
+        |id: &str| items.iter().find(|i| i.get("question_id").and_then(|v| v.as_str()) == Some(id));
+    let hard_item = item(hard).ok_or("hard question missing")?;
+    let easy_item = item(easy).ok_or("easy question missing")?;
+    assert_eq!(hard_item.get("difficulty_answers").and_then(|v| v.as_i64()), Some(4));
+    assert!(hard_item["difficulty"].as_f64() > easy_item["difficulty"].as_f64());
+    assert_eq!(hard_item.get("expected_stage").and_then(|v| v.as_i64()), Some(2));
+    assert_eq!(easy_item.get("disagreement").and_then(|v| v.as_i64()), Some(1));
+
+    let threshold = hard_item["difficulty"].as_f64().ok_or("missing difficulty")? - 1.0;
+    let listed = client
+        .get(format!("{}/questions?min_difficulty={}", base, threshold))
+        .send()
+        .await?
+        .json::<Value>()
+        .await?;
+    let listed = listed.get("items").and_then(|v| v.as_array()).ok_or("missing items")?;
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0].get("id").and_then(|v| v.as_str()), Some(hard.as_str()));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn placement_quiz_maps_ability_to_a_level() -> Result<(), Box<dyn std::error::Error>> {
+    let app = spawn_app_with(|cfg| cfg.calibration_min_answers = 1).await?;
+    let (client, base, db) = (&app.client, &app.base, &app.db);
+
+    let start = client.post(format!("{}/placement", base)).header("X-User-Id", "ana").send().await?;
+    assert_eq!(start.status(), StatusCode::CONFLICT);
+
+    // Calibrated questions at three levels, difficulty set as the calibration job would.
+    let questions = db.collection::<mongodb::bson::Document>("questions");
+    for (level, difficulty) in [
+        ("lay", 1200.0),
+        ("lay", 1250.0),
+        ("convert", 1400.0),
+        ("convert", 1450.0),
+        ("teacher", 1900.0),
+        ("teacher", 1950.0),
+    ] {
+        let mut payload: Value = serde_json::from_str(include_str!("fixtures/question_valid.json"))?;
+        payload["level"] = Value::from(level);
+        let created = client
+            .post(format!("{}/questions", base))
+            .header("X-User-Role", "editor")
+            .json(&payload)
+            .send()
+            .await?
+            .json::<Value>()
+            .await?;
+        let id = created.get("id").and_then(|v| v.as_str()).ok_or("missing id")?.to_string();
+        for status in ["in_review", "published"] {
+            let res = client
+                .post(format!("{}/questions/{}/status", base, id))
+                .header("X-User-Role", "admin")
+                .json(&serde_json::json!({ "status": status }))
+                .send()
+                .await?;
+            assert_eq!(res.status(), StatusCode::OK);
+        }
+        questions
+            .update_one(
+                mongodb::bson::doc! { "_id": mongodb::bson::oid::ObjectId::parse_str(&id)? },
+                mongodb::bson::doc! { "$set": { "difficulty": difficulty, "difficulty_answers": 50_i64 } },
+                None,
+            )
+            .await?;
+    }
+
+    // ana answers everything correctly, ben everything wrong.
+    let mut levels = Vec::new();
+    for (user, correct) in [("ana", true), ("ben", false)] {
+        let mut step = client
+            .post(format!("{}/placement?lang=en", base))
+            .header("X-User-Id", user)
+            .send()
+            .await?
+            .json::<Value>()
+            .await?;
+        let session = step.get("session").and_then(|v| v.as_str()).ok_or("missing session")?.to_string();
+        while let Some(question) = step.get("question") {
+            let options =
+                question.get("options").and_then(|v| v.as_array()).cloned().unwrap_or_default();
+            let yes = options.iter().position(|o| o.as_str() == Some("Yes")).unwrap_or(0);
+            let pick = if correct {
+                yes
+            } else {
+                (yes + 1) % options.len()
+            };
+            step = client
+                .post(format!("{}/placement/{}/answers?lang=en", base, session))
+                .header("X-User-Id", user)
+                .json(&serde_json::json!({ "selected": [pick] }))
+                .send()
+                .await?
+                .json::<Value>()
+                .await?;
+            assert_eq!(step.get("correct").and_then(|v| v.as_bool()), Some(correct));
+        }
+        let level = step.pointer("/result/level").and_then(|v| v.as_str()).ok_or("missing result")?;
+        levels.push(level.to_string());
+
+        let mine = client
+            .get(format!("{}/me/placement", base))
+            .header("X-User-Id", user)
+            .send()
+            .await?
+            .json::<Value>()
+            .await?;
+        assert_eq!(mine.get("level").and_then(|v| v.as_str()), Some(level));
+    }
+    assert_ne!(levels[0], "lay");
+    assert_eq!(levels[1], "lay");
 
-    server_handle.abort();
     Ok(())
 }
 
+#[tokio::test]
+async fn timed_quizzes_score_speed_and_reject_late_answers() -> Result<(), Box<dyn std::error::Error>> {
+    let app = spawn_app().await?;
+    let (client, base) = (&app.client, &app.base);
+
+    let mut payload: Value = serde_json::from_str(include_str!("fixtures/question_valid.json"))?;
+    payload["time_limit_secs"] = Value::from(1);
+    let res = client
+        .post(format!("{}/questions", base))
+        .header("X-User-Role", "editor")
+        .json(&payload)
+        .send()
+        .await?;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    payload["time_limit_secs"] = Value::from(30);
+    for _ in 0..2 {
+        let created = client
+            .post(format!("{}/questions", base))
+            .header("X-User-Role", "editor")
+            .json(&payload)
+            .send()
+            .await?
+            .json::<Value>()
+            .await?;
+        let id = created.get("id").and_then(|v| v.as_str()).ok_or("missing id")?.to_string();
+        for status in ["in_review", "published"] {
+            let res = client
+                .post(format!("{}/questions/{}/status", base, id))
+                .header("X-User-Role", "admin")
+                .json(&serde_json::json!({ "status": status }))
+                .send()
+                .await?;
+            assert_eq!(res.status(), StatusCode::OK);
+        }
+    }
+
+    let quiz = client
+        .post(format!("{}/quizzes?lang=en", base))
+        .header("X-User-Id", "ana")
+        .json(&serde_json::json!({ "count": 2, "mode": "competitive" }))
+        .send()
+        .await?
+        .json::<Value>()
+        .await?;
+    assert_eq!(quiz.get("mode").and_then(|v| v.as_str()), Some("competitive"));
+    let session = quiz.get("session").and_then(|v| v.as_str()).ok_or("missing session")?.to_string();
+    let items = quiz.get("items").and_then(|v| v.as_array()).cloned().ok_or("missing items")?;
+    assert_eq!(items.len(), 2);
+    assert!(items.iter().all(|item| item.get("time_limit_secs").and_then(|v| v.as_i64()) == Some(30)));
+    let answer = |item: &Value| {
+        let options = item.get("options").and_then(|v| v.as_array()).cloned().unwrap_or_default();
+        let yes = options.iter().position(|o| o.as_str() == Some("Yes")).unwrap_or(0);
+        serde_json::json!({ "question_id": item.get("id"), "answer": { "selected": [yes] } })
+    };
+
+    // The first question is started by the server and answered in time.
+    let first = items[0].get("id").and_then(|v| v.as_str()).ok_or("missing id")?;
+    let start_url = format!("{}/quizzes/{}/questions/{}/start", base, session, first);
+    let started = client.post(&start_url).header("X-User-Id", "ana").send().await?;
+    assert_eq!(started.status(), StatusCode::OK);
+    let started = started.json::<Value>().await?;
+    assert!(started.get("deadline").and_then(|v| v.as_str()).is_some());
+    let restarted =
+        client.post(&start_url).header("X-User-Id", "ana").send().await?.json::<Value>().await?;
+    assert_eq!(restarted.get("started_at"), started.get("started_at"));
+    let stranger = client.post(&start_url).header("X-User-Id", "ben").send().await?;
+    assert_eq!(stranger.status(), StatusCode::NOT_FOUND);
+
+    let result = client
+        .post(format!("{}/quizzes/results", base))
+        .header("X-User-Id", "ana")
+        .json(&serde_json::json!({ "session": session, "answers": [answer(&items[0])] }))
+        .send()
+        .await?
+        .json::<Value>()
+        .await?;
+    assert_eq!(result.get("score").and_then(|v| v.as_i64()), Some(1));
+    let points = result.get("points").and_then(|v| v.as_i64()).ok_or("missing points")?;
+    assert!(points > 500 && points <= 1000, "speed bonus applied, got {points}");
+    assert!(result.pointer("/answers/0/elapsed_ms").and_then(|v| v.as_i64()).is_some());
+
+    // The second was never started, so its correct answer cannot be timed and scores nothing.
+    let result = client
+        .post(format!("{}/quizzes/results", base))
+        .header("X-User-Id", "ana")
+        .json(&serde_json::json!({ "session": session, "answers": [answer(&items[1])] }))
+        .send()
+        .await?
+        .json::<Value>()
+        .await?;
+    assert_eq!(result.get("score").and_then(|v| v.as_i64()), Some(0));
+    assert_eq!(result.get("points").and_then(|v| v.as_i64()), Some(0));
+    assert_eq!(result.pointer("/answers/0/late").and_then(|v| v.as_bool()), Some(true));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn live_rooms_broadcast_answers_and_survive_reconnects() -> Result<(), Box<dyn std::error::Error>>
+{
+    use futures_util::{SinkExt, StreamExt};
+    use tokio_tungstenite::tungstenite::{Message, client::IntoClientRequest};
+
+    type Socket =
+        tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>;
+
+    async fn connect(url: String, user: &str) -> Result<Socket, Box<dyn std::error::Error>> {
+        let mut request = url.into_client_request()?;
+        request.headers_mut().insert("X-User-Id", user.parse()?);
+        Ok(tokio_tungstenite::connect_async(request).await?.0)
+    }
+
+    // Reads room states until one satisfies `done`.
+    async fn wait_for(
+        socket: &mut Socket,
+        done: impl Fn(&Value) -> bool,
+    ) -> Result<Value, Box<dyn std::error::Error>> {
+        loop {
+            let message = tokio::time::timeout(std::time::Duration::from_secs(5), socket.next())
+                .await?
+                .ok_or("socket closed")??;
+            if let Message::Text(text) = message {
+                let state: Value = serde_json::from_str(&text)?;
+                if done(&state) {
+                    return Ok(state);
+                }
+            }
+        }
+    }
+
+    fn pick(state: &Value, text: &str) -> usize {
+        let options =
+            state.pointer("/question/options").and_then(|v| v.as_array()).cloned().unwrap_or_default();
+        options.iter().position(|o| o.as_str() == Some(text)).unwrap_or(0)
+    }
+
+    let app = spawn_app().await?;
+    let (client, base) = (&app.client, &app.base);
+
+    let payload: Value = serde_json::from_str(include_str!("fixtures/question_valid.json"))?;
+    for _ in 0..2 {
+        let created = client
+            .post(format!("{}/questions", base))
+            .header("X-User-Role", "editor")
+            .json(&payload)
+            .send()
+            .await?
+            .json::<Value>()
+            .await?;
+        let id = created.get("id").and_then(|v| v.as_str()).ok_or("missing id")?.to_string();
+        for status in ["in_review", "published"] {
+            let res = client
+                .post(format!("{}/questions/{}/status", base, id))
+                .header("X-User-Role", "admin")
+                .json(&serde_json::json!({ "status": status }))
+                .send()
+                .await?;
+            assert_eq!(res.status(), StatusCode::OK);
+        }
+    }
+
+    let created = client
+        .post(format!("{}/rooms", base))
+        .header("X-User-Id", "host")
+        .json(&serde_json::json!({ "count": 2 }))
+        .send()
+        .await?;
+    assert_eq!(created.status(), StatusCode::CREATED);
+    let created = created.json::<Value>().await?;
+    assert_eq!(created.get("phase").and_then(|v| v.as_str()), Some("lobby"));
+    assert_eq!(created.get("total").and_then(|v| v.as_u64()), Some(2));
+    let code = created.get("code").and_then(|v| v.as_str()).ok_or("missing code")?.to_string();
+    let ws = |lang: &str| {
+        format!("ws://{}/v1/rooms/{}/ws?lang={}&name=Player", app.addr, code.to_lowercase(), lang)
+    };
+
+    let mut ana = connect(ws("en"), "ana").await?;
+    wait_for(&mut ana, |s| s["scores"].as_array().is_some_and(|p| p.len() == 1)).await?;
+    let mut ben = connect(ws("es"), "ben").await?;
+    wait_for(&mut ana, |s| s["scores"].as_array().is_some_and(|p| p.len() == 2)).await?;
+    let mut host = connect(ws("en"), "host").await?;
+    wait_for(&mut host, |s| s["host"] == true).await?;
+
+    // Only the host advances.
+    ben.send(Message::Text(r#"{"type":"next"}"#.into())).await?;
+    wait_for(&mut ben, |s| s["type"] == "error").await?;
+    host.send(Message::Text(r#"{"type":"next"}"#.into())).await?;
+
+    // Each player sees the question in their own locale.
+    let shown = wait_for(&mut ana, |s| s["phase"] == "question").await?;
+    assert_eq!(shown.pointer("/question/prompt").and_then(|v| v.as_str()), Some("Sample prompt?"));
+    assert!(shown.get("deadline").and_then(|v| v.as_str()).is_some());
+    let answer =
+        serde_json::json!({ "type": "answer", "answer": { "selected": [pick(&shown, "Yes")] } });
+    ana.send(Message::Text(answer.to_string())).await?;
+    let answered = wait_for(&mut ana, |s| s.get("you").is_some()).await?;
+    assert_eq!(answered.pointer("/you/correct").and_then(|v| v.as_bool()), Some(true));
+    assert!(answered.pointer("/you/points").and_then(|v| v.as_i64()).is_some_and(|p| p > 500));
+    ana.send(Message::Text(answer.to_string())).await?;
+    wait_for(&mut ana, |s| s["type"] == "error").await?;
+    wait_for(&mut host, |s| s["answered"] == 1).await?;
+
+    // ben drops and reconnects mid-question, then answers wrong.
+    ben.close(None).await?;
+    let mut ben = connect(ws("es"), "ben").await?;
+    let shown = wait_for(&mut ben, |s| s["phase"] == "question").await?;
+    assert_eq!(shown.pointer("/question/prompt").and_then(|v| v.as_str()), Some("Pregunta de ejemplo?"));
+    assert_eq!(shown.get("answered").and_then(|v| v.as_u64()), Some(1));
+    let answer = serde_json::json!({ "type": "answer", "answer": { "selected": [pick(&shown, "No")] } });
+    ben.send(Message::Text(answer.to_string())).await?;
+    wait_for(&mut host, |s| s["answered"] == 2).await?;
+
+    host.send(Message::Text(r#"{"type":"next"}"#.into())).await?;
+    let revealed = wait_for(&mut ben, |s| s["phase"] == "reveal").await?;
+    assert_eq!(revealed.pointer("/reveal/correct").and_then(|v| v.as_bool()), Some(false));
+    assert_eq!(
+        revealed.pointer("/reveal/solution/selected/0").and_then(|v| v.as_u64()),
+        Some(pick(&shown, "Sí") as u64)
+    );
+    assert_eq!(revealed.pointer("/scores/0/user_id").and_then(|v| v.as_str()), Some("ana"));
+    assert_eq!(revealed.pointer("/scores/1/score").and_then(|v| v.as_i64()), Some(0));
+
+    // The REST view matches what the sockets see.
+    let view = client
+        .get(format!("{}/rooms/{}", base, code))
+        .header("X-User-Id", "ana")
+        .send()
+        .await?
+        .json::<Value>()
+        .await?;
+    assert_eq!(view.get("phase").and_then(|v| v.as_str()), Some("reveal"));
+    assert_eq!(view.pointer("/you/correct").and_then(|v| v.as_bool()), Some(true));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn groups_assign_work_and_report_progress() -> Result<(), Box<dyn std::error::Error>> {
+    let app = spawn_app().await?;
+    let (client, base) = (&app.client, &app.base);
+
+    let payload: Value = serde_json::from_str(include_str!("fixtures/question_valid.json"))?;
+    let mut question_ids = Vec::new();
+    for _ in 0..2 {
+        let created = client
+            .post(format!("{}/questions", base))
+            .header("X-User-Role", "editor")
+            .json(&payload)
+            .send()
+            .await?
+            .json::<Value>()
+            .await?;
+        let id = created.get("id").and_then(|v| v.as_str()).ok_or("missing id")?.to_string();
+        for status in ["in_review", "published"] {
+            client
+                .post(format!("{}/questions/{}/status", base, id))
+                .header("X-User-Role", "admin")
+                .json(&serde_json::json!({ "status": status }))
+                .send()
+                .await?;
+        }
+        question_ids.push(id);
+    }
+
+    let group = client
+        .post(format!("{}/groups", base))
+        .header("X-User-Id", "teacher")
+        .json(&serde_json::json!({ "name": "Confirmation class" }))
+        .send()
+        .await?
+        .json::<Value>()
+        .await?;
+    let group_id = group.get("id").and_then(|v| v.as_str()).ok_or("missing id")?.to_string();
+    let code = group.get("invite_code").and_then(|v| v.as_str()).ok_or("missing code")?.to_lowercase();
+    for member in ["ana", "ben"] {
+        let joined = client
+            .post(format!("{}/groups/join", base))
+            .header("X-User-Id", member)
+            .json(&serde_json::json!({ "code": code }))
+            .send()
+            .await?;
+        assert_eq!(joined.status(), StatusCode::OK);
+        let joined = joined.json::<Value>().await?;
+        assert!(joined.get("invite_code").is_none());
+    }
+    let outsider =
+        client.get(format!("{}/groups/{}", base, group_id)).header("X-User-Id", "cy").send().await?;
+    assert_eq!(outsider.status(), StatusCode::NOT_FOUND);
+
+    let assignments_url = format!("{}/groups/{}/assignments", base, group_id);
+    let due_at = "2999-01-01T00:00:00Z";
+    let forbidden = client
+        .post(&assignments_url)
+        .header("X-User-Id", "ana")
+        .json(&serde_json::json!({ "title": "Week 1", "target": { "questions": question_ids }, "due_at": due_at }))
+        .send()
+        .await?;
+    assert_eq!(forbidden.status(), StatusCode::FORBIDDEN);
+    let unknown = client
+        .post(&assignments_url)
+        .header("X-User-Id", "teacher")
+        .json(&serde_json::json!({ "title": "Week 1", "target": { "questions": ["missing"] }, "due_at": due_at }))
+        .send()
+        .await?;
+    assert_eq!(unknown.status(), StatusCode::NOT_FOUND);
+    let assignment = client
+        .post(&assignments_url)
+        .header("X-User-Id", "teacher")
+        .json(&serde_json::json!({ "title": "Week 1", "target": { "questions": question_ids }, "due_at": due_at }))
+        .send()
+        .await?;
+    assert_eq!(assignment.status(), StatusCode::CREATED);
+    let assignment = assignment.json::<Value>().await?;
+    let assignment_id = assignment.get("id").and_then(|v| v.as_str()).ok_or("missing id")?.to_string();
+    let assignment_url = format!("{}/{}", assignments_url, assignment_id);
+
+    // Ana answers both questions correctly in the order the assignment serves them.
+    let detail = client
+        .get(format!("{}?lang=en", assignment_url))
+        .header("X-User-Id", "ana")
+        .send()
+        .await?
+        .json::<Value>()
+        .await?;
+    let questions =
+        detail.get("questions").and_then(|v| v.as_array()).cloned().ok_or("missing questions")?;
+    assert_eq!(questions.len(), 2);
+    assert_eq!(detail.pointer("/progress/done").and_then(|v| v.as_u64()), Some(0));
+    let mut last = Value::Null;
+    for question in &questions {
+        let id = question.get("id").and_then(|v| v.as_str()).ok_or("missing id")?;
+        let options = question.get("options").and_then(|v| v.as_array()).cloned().unwrap_or_default();
+        let yes = options.iter().position(|o| o.as_str() == Some("Yes")).unwrap_or(0);
+        let url = format!("{}/questions/{}/answer?lang=en", assignment_url, id);
+        let res = client
+            .post(&url)
+            .header("X-User-Id", "ana")
+            .json(&serde_json::json!({ "selected": [yes] }))
+            .send()
+            .await?;
+        assert_eq!(res.status(), StatusCode::OK);
+        last = res.json::<Value>().await?;
+        assert_eq!(last.get("correct").and_then(|v| v.as_bool()), Some(true));
+
+        let again = client
+            .post(&url)
+            .header("X-User-Id", "ana")
+            .json(&serde_json::json!({ "selected": [yes] }))
+            .send()
+            .await?;
+        assert_eq!(again.status(), StatusCode::CONFLICT);
+    }
+    assert_eq!(last.pointer("/progress/completion").and_then(|v| v.as_f64()), Some(1.0));
+    assert!(last.pointer("/progress/completed_at").and_then(|v| v.as_str()).is_some());
+
+    let report = client
+        .get(format!("{}/report", assignment_url))
+        .header("X-User-Id", "teacher")
+        .send()
+        .await?
+        .json::<Value>()
+        .await?;
+    let members = report.get("members").and_then(|v| v.as_array()).cloned().ok_or("missing members")?;
+    let progress: Vec<(&str, u64, u64)> = members
+        .iter()
+        .map(|m| {
+            (
+                m.get("user_id").and_then(|v| v.as_str()).unwrap_or_default(),
+                m.get("done").and_then(|v| v.as_u64()).unwrap_or_default(),
+                m.get("correct").and_then(|v| v.as_u64()).unwrap_or_default(),
+            )
+        })
+        .collect();
+    assert_eq!(progress, vec![("ben", 0, 0), ("ana", 2, 2)]);
+    assert_eq!(report.get("completed").and_then(|v| v.as_u64()), Some(1));
+    let member_report =
+        client.get(format!("{}/report", assignment_url)).header("X-User-Id", "ana").send().await?;
+    assert_eq!(member_report.status(), StatusCode::FORBIDDEN);
+
+    // Ben leaves; the group board only ranks the remaining members.
+    let left = client
+        .delete(format!("{}/groups/{}/members/ben", base, group_id))
+        .header("X-User-Id", "ben")
+        .send()
+        .await?;
+    assert_eq!(left.status(), StatusCode::NO_CONTENT);
+    let board = client
+        .get(format!("{}/leaderboards?group={}", base, group_id))
+        .header("X-User-Id", "teacher")
+        .send()
+        .await?
+        .json::<Value>()
+        .await?;
+    assert_eq!(board.get("group").and_then(|v| v.as_str()), Some(group_id.as_str()));
+    assert!(board.get("me").is_some_and(Value::is_null));
+    let outsider_board = client
+        .get(format!("{}/leaderboards?group={}", base, group_id))
+        .header("X-User-Id", "ben")
+        .send()
+        .await?;
+    assert_eq!(outsider_board.status(), StatusCode::NOT_FOUND);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn achievements_award_localized_badges_from_activity() -> Result<(), Box<dyn std::error::Error>> {
+    let app = spawn_app().await?;
+    let (client, base, db) = (&app.client, &app.base, &app.db);
+    db.collection::<mongodb::bson::Document>("eras")
+        .insert_one(
+            mongodb::bson::doc! {
+                "_id": "creation",
+                "status": "published",
+                "en": {
+                    "id": "creation",
+                    "name": "Creation",
+                    "label": "Creation",
+                    "order": 10,
+                    "books": ["Genesis"],
+                    "episodes": [
+                        { "id": "world", "name": "World", "label": "Creation of the World", "order": 10, "references": [] }
+                    ]
+                }
+            },
+            None,
+        )
+        .await?;
+
+    let catalog = client.get(format!("{}/ui/badges", base)).send().await?.json::<Value>().await?;
+    assert!(catalog.get("badges").and_then(|v| v.as_array()).is_some_and(|b| !b.is_empty()));
+
+    let fresh = client
+        .get(format!("{}/me/achievements?lang=es", base))
+        .header("X-User-Id", "ana")
+        .send()
+        .await?
+        .json::<Value>()
+        .await?;
+    assert_eq!(fresh.get("earned").and_then(|v| v.as_array()).map(Vec::len), Some(0));
+    let locked = fresh.get("locked").and_then(|v| v.as_array()).cloned().ok_or("missing locked")?;
+    let first = locked.iter().find(|b| b.get("id").and_then(|v| v.as_str()) == Some("first_answer"));
+    assert_eq!(first.and_then(|b| b.get("label")).and_then(|v| v.as_str()), Some("Primeros pasos"));
+    assert_eq!(first.and_then(|b| b.pointer("/progress/target")).and_then(|v| v.as_u64()), Some(1));
+
+    // Reading the era's only episode completes it.
+    let read = client
+        .post(format!("{}/me/episodes/creation/world", base))
+        .header("X-User-Id", "ana")
+        .send()
+        .await?;
+    assert_eq!(read.status(), StatusCode::OK);
+    let read = read.json::<Value>().await?;
+    let earned: Vec<&str> = read
+        .get("earned")
+        .and_then(|v| v.as_array())
+        .map(|badges| badges.iter().filter_map(|b| b.get("id").and_then(|v| v.as_str())).collect())
+        .unwrap_or_default();
+    assert_eq!(earned, vec!["creation_complete"]);
+    let missing = client
+        .post(format!("{}/me/episodes/creation/flood", base))
+        .header("X-User-Id", "ana")
+        .send()
+        .await?;
+    assert_eq!(missing.status(), StatusCode::NOT_FOUND);
+
+    // Answering a question earns the first-answer badge.
+    let payload: Value = serde_json::from_str(include_str!("fixtures/question_valid.json"))?;
+    let created = client
+        .post(format!("{}/questions", base))
+        .header("X-User-Role", "editor")
+        .json(&payload)
+        .send()
+        .await?;
+    let id = created
+        .json::<Value>()
+        .await?
+        .get("id")
+        .and_then(|v| v.as_str())
+        .ok_or("missing id")?
+        .to_string();
+    for status in ["in_review", "published"] {
+        client
+            .post(format!("{}/questions/{}/status", base, id))
+            .header("X-User-Role", "admin")
+            .json(&serde_json::json!({ "status": status }))
+            .send()
+            .await?;
+    }
+    let res = client
+        .post(format!("{}/play/questions/{}/answer?session=s1&lang=en", base, id))
+        .header("X-User-Id", "ana")
+        .json(&serde_json::json!({ "selected": [0] }))
+        .send()
+        .await?;
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let mine = client
+        .get(format!("{}/me/achievements?lang=en", base))
+        .header("X-User-Id", "ana")
+        .send()
+        .await?
+        .json::<Value>()
+        .await?;
+    let earned: Vec<&str> = mine
+        .get("earned")
+        .and_then(|v| v.as_array())
+        .map(|badges| badges.iter().filter_map(|b| b.get("id").and_then(|v| v.as_str())).collect())
+        .unwrap_or_default();
+    assert!(earned.contains(&"first_answer") && earned.contains(&"creation_complete"));
+    let polyglot = mine
+        .get("locked")
+        .and_then(|v| v.as_array())
+        .and_then(|badges| {
+            badges.iter().find(|b| b.get("id").and_then(|v| v.as_str()) == Some("polyglot"))
+        })
+        .cloned()
+        .ok_or("missing polyglot")?;
+    assert_eq!(polyglot.pointer("/progress/current").and_then(|v| v.as_u64()), Some(1));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn streaks_count_daily_goals_in_the_players_timezone() -> Result<(), Box<dyn std::error::Error>> {
+    let app = spawn_app().await?;
+    let (client, base, db) = (&app.client, &app.base, &app.db);
+    db.collection::<mongodb::bson::Document>("eras")
+        .insert_one(
+            mongodb::bson::doc! {
+                "_id": "creation",
+                "status": "published",
+                "en": {
+                    "id": "creation",
+                    "name": "Creation",
+                    "label": "Creation",
+                    "order": 10,
+                    "books": ["Genesis"],
+                    "episodes": [
+                        { "id": "world", "name": "World", "label": "Creation of the World", "order": 10, "references": [] }
+                    ]
+                }
+            },
+            None,
+        )
+        .await?;
+
+    let fresh = client
+        .get(format!("{}/me/streak", base))
+        .header("X-User-Id", "ben")
+        .send()
+        .await?
+        .json::<Value>()
+        .await?;
+    assert_eq!(fresh.get("current").and_then(|v| v.as_i64()), Some(0));
+    assert_eq!(fresh.get("history").and_then(|v| v.as_array()).map(Vec::len), Some(14));
+
+    for bad in
+        [serde_json::json!({ "timezone": "Mars/Olympus" }), serde_json::json!({ "daily_goal": 0 })]
+    {
+        let res = client
+            .put(format!("{}/me/streak", base))
+            .header("X-User-Id", "ben")
+            .json(&bad)
+            .send()
+            .await?;
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    }
+    let settings = client
+        .put(format!("{}/me/streak", base))
+        .header("X-User-Id", "ben")
+        .json(&serde_json::json!({ "timezone": "Pacific/Auckland", "daily_goal": 1 }))
+        .send()
+        .await?;
+    assert_eq!(settings.status(), StatusCode::OK);
+    let settings = settings.json::<Value>().await?;
+    assert_eq!(settings.get("timezone").and_then(|v| v.as_str()), Some("Pacific/Auckland"));
+    assert_eq!(settings.get("at_risk").and_then(|v| v.as_bool()), Some(false));
+
+    // Reading an episode meets the goal of one.
+    let read = client
+        .post(format!("{}/me/episodes/creation/world", base))
+        .header("X-User-Id", "ben")
+        .send()
+        .await?;
+    assert_eq!(read.status(), StatusCode::OK);
+
+    let streak = client
+        .get(format!("{}/me/streak", base))
+        .header("X-User-Id", "ben")
+        .send()
+        .await?
+        .json::<Value>()
+        .await?;
+    assert_eq!(streak.get("current").and_then(|v| v.as_i64()), Some(1));
+    assert_eq!(streak.get("longest").and_then(|v| v.as_i64()), Some(1));
+    assert_eq!(streak.pointer("/today/episodes").and_then(|v| v.as_i64()), Some(1));
+    assert_eq!(streak.pointer("/today/goal_met").and_then(|v| v.as_bool()), Some(true));
+    assert_eq!(streak.get("last_goal_day"), streak.pointer("/today/day"));
+    assert_eq!(streak.pointer("/freezes/available").and_then(|v| v.as_i64()), Some(0));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn recommendations_point_at_weak_eras_unread_episodes_and_due_reviews()
+-> Result<(), Box<dyn std::error::Error>> {
+    let app = spawn_app().await?;
+    let (client, base, db) = (&app.client, &app.base, &app.db);
+    let eras = db.collection::<mongodb::bson::Document>("eras");
+    eras.insert_one(
+        mongodb::bson::doc! {
+            "_id": "creation",
+            "status": "published",
+            "es": {
+                "id": "creation",
+                "name": "Creación",
+                "label": "Creación",
+                "order": 10,
+                "books": ["Génesis"],
+                "episodes": [
+                    { "id": "world", "name": "Mundo", "label": "El mundo", "order": 10, "references": [] },
+                    { "id": "eden", "name": "Edén", "label": "El Edén", "order": 20, "references": [] }
+                ]
+            }
+        },
+        None,
+    )
+    .await?;
+    eras.insert_one(
+        mongodb::bson::doc! {
+            "_id": "kings",
+            "status": "published",
+            "es": {
+                "id": "kings",
+                "name": "Reyes",
+                "label": "Reyes",
+                "order": 20,
+                "books": ["1 Samuel"],
+                "episodes": [
+                    { "id": "saul", "name": "Saúl", "label": "Saúl", "order": 10, "references": [] }
+                ]
+            }
+        },
+        None,
+    )
+    .await?;
+    let cards = db.collection::<mongodb::bson::Document>("review_cards");
+
+    let mut payload: Value = serde_json::from_str(include_str!("fixtures/question_valid.json"))?;
+    payload["era"] = serde_json::json!("creation");
+    let created = client
+        .post(format!("{}/questions", base))
+        .header("X-User-Role", "editor")
+        .json(&payload)
+        .send()
+        .await?;
+    let id = created
+        .json::<Value>()
+        .await?
+        .get("id")
+        .and_then(|v| v.as_str())
+        .ok_or("missing id")?
+        .to_string();
+    for status in ["in_review", "published"] {
+        client
+            .post(format!("{}/questions/{}/status", base, id))
+            .header("X-User-Role", "admin")
+            .json(&serde_json::json!({ "status": status }))
+            .send()
+            .await?;
+    }
+
+    // Three wrong answers make Creation a weak era.
+    for _ in 0..3 {
+        assert_eq!(play_answer(&app, &id, Some("eva"), "en", "No", 0).await?, StatusCode::OK);
+    }
+    let read = client
+        .post(format!("{}/me/episodes/creation/world?lang=es", base))
+        .header("X-User-Id", "eva")
+        .send()
+        .await?;
+    assert_eq!(read.status(), StatusCode::OK);
+    cards
+        .insert_one(
+            mongodb::bson::doc! {
+                "_id": format!("eva:{id}"),
+                "user_id": "eva",
+                "question_id": &id,
+                "ease": 2.5,
+                "interval_days": 1_i64,
+                "repetitions": 1,
+                "lapses": 0,
+                "due_at": mongodb::bson::DateTime::from_millis(0),
+                "last_reviewed_at": mongodb::bson::DateTime::from_millis(0),
+            },
+            None,
+        )
+        .await?;
+
+    let res = client
+        .get(format!("{}/me/recommendations?lang=es", base))
+        .header("X-User-Id", "eva")
+        .send()
+        .await?;
+    assert_eq!(res.status(), StatusCode::OK);
+    let body = res.json::<Value>().await?;
+    let items = body.get("recommendations").and_then(|v| v.as_array()).cloned().unwrap_or_default();
+    let summary: Vec<(&str, Option<&str>)> = items
+        .iter()
+        .map(|item| {
+            (
+                item.get("reason_code").and_then(|v| v.as_str()).unwrap_or(""),
+                item.get("episode_id").and_then(|v| v.as_str()),
+            )
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("due_reviews", None),
+            ("weak_era", None),
+            ("weak_era_episode", Some("eden")),
+            ("unvisited_episode", Some("saul")),
+        ]
+    );
+    assert_eq!(items[0].pointer("/question_ids/0").and_then(|v| v.as_str()), Some(id.as_str()));
+    assert_eq!(items[1].get("reason").and_then(|v| v.as_str()), Some("Acertaste el 0% en Creación."));
+
+    let anonymous = client.get(format!("{}/me/recommendations", base)).send().await?;
+    assert!(anonymous.status().is_client_error());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn bookmarks_and_notes_are_private_and_marked_on_eras() -> Result<(), Box<dyn std::error::Error>> {
+    let app = spawn_app().await?;
+    let (client, base, db) = (&app.client, &app.base, &app.db);
+    db.collection::<mongodb::bson::Document>("eras")
+        .insert_one(
+            mongodb::bson::doc! {
+                "_id": "creation",
+                "status": "published",
+                "en": {
+                    "id": "creation",
+                    "name": "Creation",
+                    "label": "Creation",
+                    "order": 10,
+                    "books": ["Genesis"],
+                    "episodes": [
+                        { "id": "world", "name": "World", "label": "Creation of the World", "order": 10, "references": [] },
+                        { "id": "eden", "name": "Eden", "label": "The Garden", "order": 20, "references": [] }
+                    ]
+                }
+            },
+            None,
+        )
+        .await?;
+
+    let payload: Value = serde_json::from_str(include_str!("fixtures/question_valid.json"))?;
+    let created = client
+        .post(format!("{}/questions", base))
+        .header("X-User-Role", "editor")
+        .json(&payload)
+        .send()
+        .await?;
+    let question_id = created
+        .json::<Value>()
+        .await?
+        .get("id")
+        .and_then(|v| v.as_str())
+        .ok_or("missing id")?
+        .to_string();
+    let draft = client
+        .post(format!("{}/me/bookmarks", base))
+        .header("X-User-Id", "ruth")
+        .json(&serde_json::json!({ "kind": "question", "question_id": &question_id }))
+        .send()
+        .await?;
+    assert_eq!(draft.status(), StatusCode::NOT_FOUND);
+
+    let episode = serde_json::json!({ "kind": "episode", "era_id": "creation", "episode_id": "world" });
+    let first = client
+        .post(format!("{}/me/bookmarks", base))
+        .header("X-User-Id", "ruth")
+        .json(&episode)
+        .send()
+        .await?;
+    assert_eq!(first.status(), StatusCode::CREATED);
+    let first = first.json::<Value>().await?;
+    let again = client
+        .post(format!("{}/me/bookmarks", base))
+        .header("X-User-Id", "ruth")
+        .json(&episode)
+        .send()
+        .await?;
+    assert_eq!(again.status(), StatusCode::OK);
+    assert_eq!(again.json::<Value>().await?.get("id"), first.get("id"));
+    let missing = client
+        .post(format!("{}/me/bookmarks", base))
+        .header("X-User-Id", "ruth")
+        .json(&serde_json::json!({ "kind": "episode", "era_id": "creation", "episode_id": "flood" }))
+        .send()
+        .await?;
+    assert_eq!(missing.status(), StatusCode::NOT_FOUND);
+
+    // Era responses carry the caller's flags; anonymous ones stay unmarked.
+    let era_res =
+        client.get(format!("{}/eras/creation?lang=en", base)).header("X-User-Id", "ruth").send().await?;
+    assert_eq!(era_res.headers().get("cache-control").and_then(|v| v.to_str().ok()), Some("private"));
+    assert!(era_res.headers().get_all("vary").iter().any(|v| v == "x-user-id"));
+    let era = era_res.json::<Value>().await?;
+    assert_eq!(era.get("bookmarked").and_then(|v| v.as_bool()), Some(false));
+    assert_eq!(era.pointer("/episodes/0/bookmarked").and_then(|v| v.as_bool()), Some(true));
+    assert_eq!(era.pointer("/episodes/1/bookmarked").and_then(|v| v.as_bool()), Some(false));
+    let anonymous_res = client.get(format!("{}/eras/creation?lang=en", base)).send().await?;
+    assert!(!anonymous_res.headers().contains_key("cache-control"));
+    assert!(anonymous_res.headers().get_all("vary").iter().any(|v| v == "x-user-id"));
+    let anonymous = anonymous_res.json::<Value>().await?;
+    assert!(anonymous.get("bookmarked").is_none());
+    let other = client
+        .get(format!("{}/eras/creation/episodes/world?lang=en", base))
+        .header("X-User-Id", "boaz")
+        .send()
+        .await?
+        .json::<Value>()
+        .await?;
+    assert_eq!(other.get("bookmarked").and_then(|v| v.as_bool()), Some(false));
+
+    // Notes are searchable and only their author can change them.
+    let note = client
+        .post(format!("{}/me/notes", base))
+        .header("X-User-Id", "ruth")
+        .json(
+            &serde_json::json!({ "kind": "era", "era_id": "creation", "text": "Light before the sun" }),
+        )
+        .send()
+        .await?;
+    assert_eq!(note.status(), StatusCode::CREATED);
+    let note_id =
+        note.json::<Value>().await?.get("id").and_then(|v| v.as_str()).ok_or("missing id")?.to_string();
+    let empty = client
+        .post(format!("{}/me/notes", base))
+        .header("X-User-Id", "ruth")
+        .json(&serde_json::json!({ "kind": "era", "era_id": "creation", "text": "  " }))
+        .send()
+        .await?;
+    assert_eq!(empty.status(), StatusCode::BAD_REQUEST);
+    let stolen = client
+        .put(format!("{}/me/notes/{}", base, note_id))
+        .header("X-User-Id", "boaz")
+        .json(&serde_json::json!({ "text": "mine now" }))
+        .send()
+        .await?;
+    assert_eq!(stolen.status(), StatusCode::NOT_FOUND);
+    let edited = client
+        .put(format!("{}/me/notes/{}", base, note_id))
+        .header("X-User-Id", "ruth")
+        .json(&serde_json::json!({ "text": "Light before the SUN, day one" }))
+        .send()
+        .await?;
+    assert_eq!(edited.status(), StatusCode::OK);
+    // Words are matched literally, so regex metacharacters neither widen nor break the search.
+    for (query, expected) in [("sun%20light", 1), ("moon", 0), ("sun.", 0), ("day%20(one", 0)] {
+        let found = client
+            .get(format!("{}/me/notes?q={}", base, query))
+            .header("X-User-Id", "ruth")
+            .send()
+            .await?
+            .json::<Value>()
+            .await?;
+        assert_eq!(found.get("total").and_then(|v| v.as_u64()), Some(expected));
+    }
+
+    let listed = client
+        .get(format!("{}/me/bookmarks?kind=episode&era=creation", base))
+        .header("X-User-Id", "ruth")
+        .send()
+        .await?
+        .json::<Value>()
+        .await?;
+    assert_eq!(listed.pointer("/items/0/episode_id").and_then(|v| v.as_str()), Some("world"));
+    let bookmark_id = first.get("id").and_then(|v| v.as_str()).ok_or("missing id")?;
+    let removed = client
+        .delete(format!("{}/me/bookmarks/{}", base, bookmark_id))
+        .header("X-User-Id", "ruth")
+        .send()
+        .await?;
+    assert_eq!(removed.status(), StatusCode::NO_CONTENT);
+    let episodes = client
+        .get(format!("{}/eras/creation/episodes?lang=en", base))
+        .header("X-User-Id", "ruth")
+        .send()
+        .await?
+        .json::<Value>()
+        .await?;
+    assert_eq!(episodes.pointer("/0/bookmarked").and_then(|v| v.as_bool()), Some(false));
+
+    Ok(())
+}
+
+/// Answers a published choice question through free play, picking the option displayed as `pick`.
+async fn play_answer(
+    app: &TestApp,
+    id: &str,
+    user: Option<&str>,
+    lang: &str,
+    pick: &str,
+    time_ms: i64,
+) -> Result<StatusCode, Box<dyn std::error::Error>> {
+    let as_user = |request: reqwest::RequestBuilder| match user {
+        Some(user) => request.header("X-User-Id", user),
+        None => request,
+    };
+    let served = as_user(app.client.get(format!("{}/play/questions/{}?lang={}", app.base, id, lang)))
+        .send()
+        .await?
+        .json::<Value>()
+        .await?;
+    let session = served.get("session").and_then(|v| v.as_str()).ok_or("missing session")?;
+    let options = served.get("options").and_then(|v| v.as_array()).ok_or("missing options")?;
+    let position = options.iter().position(|o| o.as_str() == Some(pick)).ok_or("missing option")?;
+    let res =
+        as_user(app.client.post(format!(
+            "{}/play/questions/{}/answer?session={}&lang={}",
+            app.base, id, session, lang
+        )))
+        .json(&serde_json::json!({ "selected": [position], "time_ms": time_ms }))
+        .send()
+        .await?;
+    Ok(res.status())
+}
+
+struct TestApp {
+    addr: SocketAddr,
+    base: String,
+    client: reqwest::Client,
+    db: Database,
+    _db_guard: DbGuard,
+    server_handle: tokio::task::JoinHandle<()>,
+}
+
+impl Drop for TestApp {
+    fn drop(&mut self) {
+        self.server_handle.abort();
+    }
+}
+
+async fn spawn_app() -> Result<TestApp, Box<dyn std::error::Error>> {
+    spawn_app_with(|_| {}).await
+}
+
+/// Serves the API on an ephemeral port against a fresh database. The config is built here and
+/// handed to the server directly, so tests running in parallel never share env-derived settings.
+async fn spawn_app_with(
+    configure: impl FnOnce(&mut AppConfig),
+) -> Result<TestApp, Box<dyn std::error::Error>> {
+    let mut cfg = AppConfig::from_env();
+    cfg.mongo_db = format!("verbumdei_test_{}", Uuid::new_v4());
+    configure(&mut cfg);
+    let _db_guard = DbGuard::new(cfg.mongo_uri.clone(), cfg.mongo_db.clone());
+    let db = db::init_mongo(&cfg).await?;
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+    let app = routes::api::router(routes::api::ApiState::new(db.clone(), &cfg));
+    let server_handle = tokio::spawn(async move {
+        axum::serve(listener, app).await.expect("server error");
+    });
+
+    Ok(TestApp {
+        addr,
+        base: format!("http://{}/v1", addr),
+        client: reqwest::Client::new(),
+        db,
+        _db_guard,
+        server_handle,
+    })
+}
+
 struct DbGuard {
     uri: String,
     name: String,
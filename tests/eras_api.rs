@@ -7,7 +7,7 @@ use mongodb::{
 use reqwest::StatusCode;
 use uuid::Uuid;
 
-use verbumdei_api::routes;
+use verbumdei_api::{config::AppConfig, routes};
 
 #[tokio::test]
 async fn eras_endpoints_return_seeded_data() -> Result<(), Box<dyn std::error::Error>> {
@@ -100,6 +100,351 @@ async fn episodes_search_requires_book_and_filters_by_book() -> Result<(), Box<d
     Ok(())
 }
 
+#[tokio::test]
+async fn eras_endpoints_support_conditional_requests() -> Result<(), Box<dyn std::error::Error>> {
+    let test_app = TestApp::spawn().await?;
+
+    let header_res = test_app
+        .client
+        .get(format!("{}/v1/eras", test_app.base))
+        .header("Accept-Language", "es-MX")
+        .send()
+        .await?;
+    assert_eq!(header_res.status(), StatusCode::OK);
+    assert_eq!(header_res.headers().get("vary").and_then(|v| v.to_str().ok()), Some("Accept-Language"));
+    let es_etag =
+        header_res.headers().get("etag").and_then(|v| v.to_str().ok()).ok_or("missing etag")?;
+
+    let not_modified_res = test_app
+        .client
+        .get(format!("{}/v1/eras?lang=es", test_app.base))
+        .header("If-None-Match", es_etag)
+        .send()
+        .await?;
+    assert_eq!(not_modified_res.status(), StatusCode::NOT_MODIFIED);
+    assert!(not_modified_res.headers().get("vary").is_none());
+
+    let other_lang_res = test_app
+        .client
+        .get(format!("{}/v1/eras?lang=pt", test_app.base))
+        .header("If-None-Match", es_etag)
+        .send()
+        .await?;
+    assert_eq!(other_lang_res.status(), StatusCode::OK);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn bundle_snapshot_is_versioned_by_content() -> Result<(), Box<dyn std::error::Error>> {
+    let test_app = TestApp::spawn().await?;
+
+    let bundle_res = test_app.client.get(format!("{}/v1/bundle?lang=es", test_app.base)).send().await?;
+    assert_eq!(bundle_res.status(), StatusCode::OK);
+    let etag = bundle_res
+        .headers()
+        .get("etag")
+        .and_then(|v| v.to_str().ok())
+        .ok_or("missing etag")?
+        .to_string();
+    let bundle = bundle_res.json::<serde_json::Value>().await?;
+    let version = bundle.get("version").and_then(|v| v.as_str()).ok_or("missing version")?.to_string();
+    assert_eq!(bundle.get("lang").and_then(|v| v.as_str()), Some("es"));
+    let eras = bundle.get("eras").and_then(|v| v.as_array()).ok_or("missing eras")?;
+    assert_eq!(eras.len(), 2);
+    assert_eq!(eras[0].get("name").and_then(|v| v.as_str()), Some("Creación"));
+    assert!(bundle.get("levels").is_some());
+    assert!(bundle.get("locales").is_some());
+
+    let version_res =
+        test_app.client.get(format!("{}/v1/bundle/version?lang=es", test_app.base)).send().await?;
+    assert_eq!(version_res.status(), StatusCode::OK);
+    let summary = version_res.json::<serde_json::Value>().await?;
+    assert_eq!(summary.get("version").and_then(|v| v.as_str()), Some(version.as_str()));
+    assert_eq!(summary.get("episode_count").and_then(|v| v.as_u64()), Some(3));
+
+    let not_modified_res = test_app
+        .client
+        .get(format!("{}/v1/bundle?lang=es", test_app.base))
+        .header("If-None-Match", &etag)
+        .send()
+        .await?;
+    assert_eq!(not_modified_res.status(), StatusCode::NOT_MODIFIED);
+
+    let gzip_res =
+        test_app.client.get(format!("{}/v1/bundle?lang=es&compress=gzip", test_app.base)).send().await?;
+    assert_eq!(gzip_res.status(), StatusCode::OK);
+    assert_eq!(gzip_res.headers().get("content-encoding").and_then(|v| v.to_str().ok()), Some("gzip"));
+    assert!(gzip_res.headers().get_all("vary").iter().any(|v| v == "Accept-Encoding"));
+    let gzip_etag =
+        gzip_res.headers().get("etag").and_then(|v| v.to_str().ok()).ok_or("missing etag")?;
+    assert_eq!(gzip_etag, format!("\"{version}-gzip\""));
+
+    let identity_res = test_app
+        .client
+        .get(format!("{}/v1/bundle?lang=es&compress=none", test_app.base))
+        .header("If-None-Match", gzip_etag)
+        .send()
+        .await?;
+    assert_eq!(identity_res.status(), StatusCode::OK);
+    assert_eq!(
+        identity_res.headers().get("etag").and_then(|v| v.to_str().ok()),
+        Some(format!("\"{version}\"").as_str())
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn sync_reports_era_writes_and_deleted_episodes() -> Result<(), Box<dyn std::error::Error>> {
+    let test_app = TestApp::spawn().await?;
+
+    let full_res = test_app.client.get(format!("{}/v1/sync?lang=en", test_app.base)).send().await?;
+    assert_eq!(full_res.status(), StatusCode::OK);
+    let full = full_res.json::<serde_json::Value>().await?;
+    assert_eq!(full.get("full").and_then(|v| v.as_bool()), Some(true));
+    assert_eq!(full.pointer("/eras/upserted").and_then(|v| v.as_array()).map(Vec::len), Some(2));
+    assert_eq!(full.pointer("/episodes/upserted").and_then(|v| v.as_array()).map(Vec::len), Some(3));
+    let token = full.get("token").and_then(|v| v.as_str()).ok_or("missing token")?.to_string();
+
+    let era = serde_json::json!({
+        "en": {
+            "id": "exodus",
+            "name": "Exodus",
+            "label": "Exodus and Sinai Covenant",
+            "order": 20,
+            "books": ["Exodus"],
+            "episodes": [
+                {"id": "moses", "name": "Moses", "label": "Moses and His Calling", "order": 10, "references": []}
+            ]
+        }
+    });
+    let forbidden_res =
+        test_app.client.put(format!("{}/v1/eras/exodus", test_app.base)).json(&era).send().await?;
+    assert_eq!(forbidden_res.status(), StatusCode::FORBIDDEN);
+    let put_res = test_app
+        .client
+        .put(format!("{}/v1/eras/exodus", test_app.base))
+        .header("X-User-Role", "editor")
+        .json(&era)
+        .send()
+        .await?;
+    assert_eq!(put_res.status(), StatusCode::OK);
+
+    // A published era keeps serving its current content until a reviewer publishes the edit.
+    let live_res = test_app.client.get(format!("{}/v1/eras/exodus", test_app.base)).send().await?;
+    assert_eq!(live_res.status(), StatusCode::OK);
+    let live = live_res.json::<serde_json::Value>().await?;
+    assert_eq!(live.get("episodes").and_then(|v| v.as_array()).map(Vec::len), Some(2));
+    let preview_res = test_app
+        .client
+        .get(format!("{}/v1/eras/exodus?preview=true", test_app.base))
+        .header("X-User-Role", "reviewer")
+        .send()
+        .await?;
+    assert_eq!(preview_res.status(), StatusCode::OK);
+    let preview = preview_res.json::<serde_json::Value>().await?;
+    assert_eq!(preview.get("episodes").and_then(|v| v.as_array()).map(Vec::len), Some(1));
+    let unchanged =
+        test_app.client.get(format!("{}/v1/sync?lang=en&since={}", test_app.base, token)).send().await?;
+    let unchanged = unchanged.json::<serde_json::Value>().await?;
+    assert_eq!(unchanged.pointer("/eras/deleted").and_then(|v| v.as_array()).map(Vec::len), Some(0));
+    assert_eq!(unchanged.pointer("/episodes/deleted").and_then(|v| v.as_array()).map(Vec::len), Some(0));
+    for (role, status) in [("editor", "in_review"), ("reviewer", "published")] {
+        let status_res = test_app
+            .client
+            .post(format!("{}/v1/eras/exodus/status", test_app.base))
+            .header("X-User-Role", role)
+            .json(&serde_json::json!({ "status": status }))
+            .send()
+            .await?;
+        assert_eq!(status_res.status(), StatusCode::OK);
+    }
+
+    let delta_res =
+        test_app.client.get(format!("{}/v1/sync?lang=en&since={}", test_app.base, token)).send().await?;
+    assert_eq!(delta_res.status(), StatusCode::OK);
+    let delta = delta_res.json::<serde_json::Value>().await?;
+    assert_eq!(delta.get("full").and_then(|v| v.as_bool()), Some(false));
+    let eras = delta.pointer("/eras/upserted").and_then(|v| v.as_array()).ok_or("missing eras")?;
+    assert_eq!(eras.len(), 1);
+    assert_eq!(eras[0].get("id").and_then(|v| v.as_str()), Some("exodus"));
+    let deleted =
+        delta.pointer("/episodes/deleted").and_then(|v| v.as_array()).ok_or("missing deleted")?;
+    assert_eq!(deleted.len(), 1);
+    assert_eq!(deleted[0].get("id").and_then(|v| v.as_str()), Some("sinai"));
+    assert_eq!(deleted[0].get("era_id").and_then(|v| v.as_str()), Some("exodus"));
+
+    let bad_token_res =
+        test_app.client.get(format!("{}/v1/sync?since=yesterday", test_app.base)).send().await?;
+    assert_eq!(bad_token_res.status(), StatusCode::BAD_REQUEST);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn era_writes_honour_if_match() -> Result<(), Box<dyn std::error::Error>> {
+    let test_app = TestApp::spawn().await?;
+    let url = format!("{}/v1/eras/exodus", test_app.base);
+
+    let read_res = test_app
+        .client
+        .get(format!("{}?preview=true", url))
+        .header("X-User-Role", "editor")
+        .send()
+        .await?;
+    assert_eq!(read_res.status(), StatusCode::OK);
+    let read_etag = read_res.headers().get("etag").ok_or("missing etag")?.to_str()?.to_string();
+
+    let era =
+        serde_json::json!({ "en": { "id": "exodus", "name": "Exodus", "order": 20, "episodes": [] } });
+    let stale_res = test_app
+        .client
+        .put(&url)
+        .header("X-User-Role", "editor")
+        .header("If-Match", "\"stale\"")
+        .json(&era)
+        .send()
+        .await?;
+    assert_eq!(stale_res.status(), StatusCode::PRECONDITION_FAILED);
+
+    let put_res = test_app
+        .client
+        .put(&url)
+        .header("X-User-Role", "editor")
+        .header("If-Match", &read_etag)
+        .json(&era)
+        .send()
+        .await?;
+    assert_eq!(put_res.status(), StatusCode::OK);
+    let write_etag = put_res.headers().get("etag").ok_or("missing etag")?.to_str()?.to_string();
+    assert_ne!(write_etag, read_etag);
+
+    // A second editor still holding the first read loses the race instead of overwriting.
+    let lost_res = test_app
+        .client
+        .delete(&url)
+        .header("X-User-Role", "editor")
+        .header("If-Match", &read_etag)
+        .send()
+        .await?;
+    assert_eq!(lost_res.status(), StatusCode::PRECONDITION_FAILED);
+    let delete_res = test_app
+        .client
+        .delete(&url)
+        .header("X-User-Role", "editor")
+        .header("If-Match", &write_etag)
+        .send()
+        .await?;
+    assert_eq!(delete_res.status(), StatusCode::NO_CONTENT);
+
+    let missing_res = test_app
+        .client
+        .put(&url)
+        .header("X-User-Role", "editor")
+        .header("If-Match", "*")
+        .json(&era)
+        .send()
+        .await?;
+    assert_eq!(missing_res.status(), StatusCode::PRECONDITION_FAILED);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn era_revisions_can_be_reverted() -> Result<(), Box<dyn std::error::Error>> {
+    let test_app = TestApp::spawn().await?;
+
+    for name in ["Exodus", "Exodus Renamed"] {
+        let era =
+            serde_json::json!({ "en": { "id": "exodus", "name": name, "order": 20, "episodes": [] } });
+        let put_res = test_app
+            .client
+            .put(format!("{}/v1/eras/exodus", test_app.base))
+            .header("X-User-Role", "editor")
+            .json(&era)
+            .send()
+            .await?;
+        assert_eq!(put_res.status(), StatusCode::OK);
+    }
+
+    let revert_url = format!("{}/v1/eras/exodus/revisions/1/revert", test_app.base);
+    let forbidden_res = test_app.client.post(&revert_url).send().await?;
+    assert_eq!(forbidden_res.status(), StatusCode::FORBIDDEN);
+    let revert_res = test_app
+        .client
+        .post(&revert_url)
+        .header("X-User-Role", "editor")
+        .header("X-User-Id", "editor-1")
+        .json(&serde_json::json!({ "reason": "undo rename" }))
+        .send()
+        .await?;
+    assert_eq!(revert_res.status(), StatusCode::OK);
+    let reverted = revert_res.json::<serde_json::Value>().await?;
+    assert_eq!(reverted.get("name").and_then(|v| v.as_str()), Some("Exodus"));
+
+    let revisions = test_app
+        .client
+        .get(format!("{}/v1/eras/exodus/revisions", test_app.base))
+        .header("X-User-Role", "editor")
+        .send()
+        .await?
+        .json::<serde_json::Value>()
+        .await?;
+    assert_eq!(revisions.pointer("/items/0/number").and_then(|v| v.as_i64()), Some(3));
+    assert_eq!(revisions.pointer("/items/0/action").and_then(|v| v.as_str()), Some("revert"));
+    assert_eq!(revisions.pointer("/items/0/reason").and_then(|v| v.as_str()), Some("undo rename"));
+
+    let missing_res = test_app
+        .client
+        .post(format!("{}/v1/eras/exodus/revisions/9/revert", test_app.base))
+        .header("X-User-Role", "editor")
+        .send()
+        .await?;
+    assert_eq!(missing_res.status(), StatusCode::NOT_FOUND);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn eras_responses_are_cached_per_lang() -> Result<(), Box<dyn std::error::Error>> {
+    let test_app = TestApp::spawn().await?;
+
+    for _ in 0..2 {
+        let res = test_app.client.get(format!("{}/v1/eras?lang=es", test_app.base)).send().await?;
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+    let res = test_app.client.get(format!("{}/v1/eras?lang=pt", test_app.base)).send().await?;
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let anonymous_res = test_app.client.get(format!("{}/v1/admin/cache", test_app.base)).send().await?;
+    assert_eq!(anonymous_res.status(), StatusCode::FORBIDDEN);
+
+    let stats_res = test_app
+        .client
+        .get(format!("{}/v1/admin/cache", test_app.base))
+        .header("X-User-Role", "admin")
+        .send()
+        .await?;
+    assert_eq!(stats_res.status(), StatusCode::OK);
+    let stats = stats_res.json::<serde_json::Value>().await?;
+    assert_eq!(stats.get("hits").and_then(|v| v.as_u64()), Some(1));
+    assert_eq!(stats.get("misses").and_then(|v| v.as_u64()), Some(2));
+    assert_eq!(stats.get("entries").and_then(|v| v.as_u64()), Some(2));
+
+    let invalidate_res = test_app
+        .client
+        .delete(format!("{}/v1/admin/cache?prefix=eras:", test_app.base))
+        .header("X-User-Role", "admin")
+        .send()
+        .await?;
+    assert_eq!(invalidate_res.status(), StatusCode::OK);
+    let invalidated = invalidate_res.json::<serde_json::Value>().await?;
+    assert_eq!(invalidated.get("removed").and_then(|v| v.as_u64()), Some(2));
+
+    Ok(())
+}
+
 struct TestApp {
     base: String,
     client: reqwest::Client,
@@ -121,9 +466,7 @@ impl TestApp {
         let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
         let addr = listener.local_addr()?;
 
-        let state = routes::api::ApiState {
-            db,
-        };
+        let state = routes::api::ApiState::new(db, &AppConfig::from_env());
         let app = routes::api::router(state);
 
         let server_handle = tokio::spawn(async move {